@@ -5,6 +5,15 @@ use std::{fmt::Display, marker::PhantomData};
 /// Use the [`Countable`](crate::Countable) trait to obtain a `Count` for a supported type,
 /// or use [`Count::from`](Count::from) or [`Count::new`](Count::new) to create a `Count` directly.
 #[derive(Debug)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(
+        zerocopy::FromBytes,
+        zerocopy::IntoBytes,
+        zerocopy::Immutable,
+        zerocopy::KnownLayout
+    )
+)]
 #[repr(transparent)]
 pub struct Count<T: ?Sized> {
     count: usize,
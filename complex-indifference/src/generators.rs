@@ -0,0 +1,146 @@
+//! Integrations with property-testing and fuzzing libraries, so that
+//! [`Index`], [`Count`], and [`Span`] values can be generated directly,
+//! rather than being built from raw numbers.
+//!
+//! Each integration is enabled by a feature: `arbitrary`, `proptest`, or `bolero`.
+//! Generated spans are always valid; that is, they never end before they start.
+
+use crate::{Count, Index, Span};
+
+/// Makes a span between two positions, whichever order they are in.
+#[allow(dead_code)] // unused if no integrations are enabled
+fn ordered_span<T: ?Sized>(a: usize, b: usize) -> Span<T> {
+    Span::new(Index::new(a.min(b)), Count::new(a.abs_diff(b)))
+}
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary {
+    use arbitrary::{Arbitrary, Result, Unstructured};
+
+    use super::ordered_span;
+    use crate::{Count, Index, Span};
+
+    impl<'a, T: ?Sized> Arbitrary<'a> for Index<T> {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            usize::arbitrary(u).map(Index::new)
+        }
+
+        fn size_hint(depth: usize) -> (usize, Option<usize>) {
+            usize::size_hint(depth)
+        }
+    }
+
+    impl<'a, T: ?Sized> Arbitrary<'a> for Count<T> {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            usize::arbitrary(u).map(Count::new)
+        }
+
+        fn size_hint(depth: usize) -> (usize, Option<usize>) {
+            usize::size_hint(depth)
+        }
+    }
+
+    impl<'a, T: ?Sized> Arbitrary<'a> for Span<T> {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let (a, b) = <(usize, usize)>::arbitrary(u)?;
+            Ok(ordered_span(a, b))
+        }
+
+        fn size_hint(depth: usize) -> (usize, Option<usize>) {
+            <(usize, usize)>::size_hint(depth)
+        }
+    }
+}
+
+#[cfg(feature = "proptest")]
+mod proptest {
+    use std::fmt::Debug;
+
+    use proptest::{
+        arbitrary::{Arbitrary, any},
+        num,
+        strategy::{Map, Strategy},
+    };
+
+    use super::ordered_span;
+    use crate::{Count, Index, Span};
+
+    impl<T: ?Sized + Debug> Arbitrary for Index<T> {
+        type Parameters = ();
+        type Strategy = Map<num::usize::Any, fn(usize) -> Self>;
+
+        fn arbitrary_with((): ()) -> Self::Strategy {
+            any::<usize>().prop_map(Index::new)
+        }
+    }
+
+    impl<T: ?Sized + Debug> Arbitrary for Count<T> {
+        type Parameters = ();
+        type Strategy = Map<num::usize::Any, fn(usize) -> Self>;
+
+        fn arbitrary_with((): ()) -> Self::Strategy {
+            any::<usize>().prop_map(Count::new)
+        }
+    }
+
+    impl<T: ?Sized + Debug> Arbitrary for Span<T> {
+        type Parameters = ();
+        type Strategy = Map<(num::usize::Any, num::usize::Any), fn((usize, usize)) -> Self>;
+
+        fn arbitrary_with((): ()) -> Self::Strategy {
+            any::<(usize, usize)>().prop_map(|(a, b)| ordered_span(a, b))
+        }
+    }
+}
+
+#[cfg(feature = "bolero")]
+mod bolero {
+    use bolero_generator::{TypeGenerator, driver::Driver};
+
+    use super::ordered_span;
+    use crate::{Count, Index, Span};
+
+    impl<T: ?Sized + 'static> TypeGenerator for Index<T> {
+        fn generate<D: Driver>(driver: &mut D) -> Option<Self> {
+            usize::generate(driver).map(Index::new)
+        }
+    }
+
+    impl<T: ?Sized + 'static> TypeGenerator for Count<T> {
+        fn generate<D: Driver>(driver: &mut D) -> Option<Self> {
+            usize::generate(driver).map(Count::new)
+        }
+    }
+
+    impl<T: ?Sized + 'static> TypeGenerator for Span<T> {
+        fn generate<D: Driver>(driver: &mut D) -> Option<Self> {
+            let (a, b) = <(usize, usize)>::generate(driver)?;
+            Some(ordered_span(a, b))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn spans_are_ordered() {
+        let span = Span::new(Index::new(2), Count::new(3));
+        assert_eq!(span, ordered_span::<u8>(2, 5));
+        assert_eq!(span, ordered_span::<u8>(5, 2));
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_span() {
+        use ::arbitrary::{Arbitrary, Unstructured};
+
+        let data: Vec<u8> = (0..=255).collect();
+        let mut u = Unstructured::new(&data);
+        for _ in 0..16 {
+            let span = Span::<u8>::arbitrary(&mut u).unwrap();
+            assert!(span.start() <= span.end());
+        }
+    }
+}
@@ -1,10 +1,19 @@
-use std::marker::PhantomData;
+use std::{collections::VecDeque, marker::PhantomData};
 
-use crate::{Count, Span};
+use crate::{Count, Offset, Span};
 
 /// An index into a sequence of things of type `T`
 /// (i.e. a finite [Ordinal number](https://en.wikipedia.org/wiki/Ordinal_number)).
 #[derive(Debug)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(
+        zerocopy::FromBytes,
+        zerocopy::IntoBytes,
+        zerocopy::Immutable,
+        zerocopy::KnownLayout
+    )
+)]
 #[repr(transparent)]
 pub struct Index<T: ?Sized> {
     index: usize,
@@ -40,6 +49,18 @@ impl<T: ?Sized> Index<T> {
     pub fn span_until(&self, ix: Index<T>) -> Option<Span<T>> {
         Span::try_from_indices(*self, ix)
     }
+
+    /// The signed offset from `origin` to this index, or `None`
+    /// if it does not fit into an [`Offset`].
+    #[inline(always)]
+    pub fn offset_from(&self, origin: Index<T>) -> Option<Offset<T>> {
+        let offset = match self.index.checked_sub(origin.index) {
+            Some(forwards) => isize::try_from(forwards).ok()?,
+            None => 0isize.checked_sub_unsigned(origin.index - self.index)?,
+        };
+
+        Some(Offset::new(offset))
+    }
 }
 
 impl<T: ?Sized> PartialOrd<Index<T>> for Index<T> {
@@ -134,6 +155,20 @@ impl<T> std::ops::IndexMut<Index<T>> for [T] {
     }
 }
 
+impl<T> std::ops::Index<Index<T>> for VecDeque<T> {
+    type Output = T;
+
+    fn index(&self, index: Index<T>) -> &T {
+        &self[index.as_usize()]
+    }
+}
+
+impl<T> std::ops::IndexMut<Index<T>> for VecDeque<T> {
+    fn index_mut(&mut self, index: Index<T>) -> &mut T {
+        &mut self[index.as_usize()]
+    }
+}
+
 impl std::ops::Index<Index<u8>> for str {
     type Output = u8;
 
@@ -176,4 +211,15 @@ mod test {
         let y = Index::<NoCmp>::new(2);
         assert!(x > y);
     }
+
+    #[cfg(feature = "zerocopy")]
+    #[test]
+    pub fn zerocopy_round_trip() {
+        use zerocopy::{FromBytes, IntoBytes};
+
+        let indices = [Index::<u8>::new(3), Index::new(5)];
+        let bytes = indices.as_bytes();
+        assert_eq!(bytes.len(), 2 * size_of::<usize>());
+        assert_eq!(<[Index<u8>]>::ref_from_bytes(bytes).unwrap(), indices);
+    }
 }
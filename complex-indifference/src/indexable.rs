@@ -1,3 +1,7 @@
+use std::collections::VecDeque;
+#[cfg(not(feature = "no-unsafe"))]
+use std::{ffi::OsStr, path::Path};
+
 use crate::{Index, Span};
 
 /// A trait for things that can be sliced by a [`Span`] or an [`Index`].
@@ -24,6 +28,15 @@ where
     fn find_spans(&self, other: &Self) -> impl Iterator<Item = Span<T>>;
 }
 
+/// A trait for things which cannot be sliced, because they are not
+/// stored contiguously, but which can still be iterated over by [`Span`].
+pub trait SpanIterable<T> {
+    /// Iterate over the items in the given span.
+    fn iter_span<'a>(&'a self, span: Span<T>) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a;
+}
+
 pub trait IndexableMut<T>: Indexable<T> {
     fn slice_mut(&mut self, span: Span<T>) -> &mut Self;
     fn slice_until_mut(&mut self, ix: Index<T>) -> &mut Self;
@@ -99,3 +112,170 @@ impl<T> IndexableMut<T> for [T] {
         &mut self[ix.as_usize()..]
     }
 }
+
+impl<T> SpanIterable<T> for VecDeque<T> {
+    #[inline(always)]
+    fn iter_span<'a>(&'a self, span: Span<T>) -> impl Iterator<Item = &'a T>
+    where
+        T: 'a,
+    {
+        self.range(span.start().as_usize()..span.end().as_usize())
+    }
+}
+
+/// Slicing an [`OsStr`] is only permitted next to valid UTF-8 (or at either end),
+/// since that is where the platform encoding can be split; this panics otherwise,
+/// just as slicing a `str` does when not on a character boundary.
+#[cfg(not(feature = "no-unsafe"))]
+impl Indexable<u8> for OsStr {
+    fn slice(&self, span: Span<u8>) -> &OsStr {
+        os_str_slice(self, span.start().as_usize(), span.end().as_usize())
+    }
+
+    fn slice_until(&self, ix: Index<u8>) -> &OsStr {
+        os_str_slice(self, 0, ix.as_usize())
+    }
+
+    fn slice_from(&self, ix: Index<u8>) -> &OsStr {
+        os_str_slice(self, ix.as_usize(), self.len())
+    }
+}
+
+#[cfg(not(feature = "no-unsafe"))]
+impl Findable<u8> for OsStr {
+    fn find_span(&self, other: &Self) -> Option<Span<u8>> {
+        self.find_spans(other).next()
+    }
+
+    fn find_spans(&self, other: &Self) -> impl Iterator<Item = Span<u8>> {
+        let needle = other.as_encoded_bytes();
+        // an empty match would not be a whole number of characters
+        let windows = (!needle.is_empty())
+            .then(|| self.as_encoded_bytes().windows(needle.len()))
+            .into_iter()
+            .flatten();
+
+        windows
+            .enumerate()
+            .filter(move |(_, window)| *window == needle)
+            .map(move |(start, _)| Span::new(start.into(), needle.len().into()))
+    }
+}
+
+#[cfg(not(feature = "no-unsafe"))]
+impl Indexable<u8> for Path {
+    #[inline(always)]
+    fn slice(&self, span: Span<u8>) -> &Path {
+        Path::new(self.as_os_str().slice(span))
+    }
+
+    #[inline(always)]
+    fn slice_until(&self, ix: Index<u8>) -> &Path {
+        Path::new(self.as_os_str().slice_until(ix))
+    }
+
+    #[inline(always)]
+    fn slice_from(&self, ix: Index<u8>) -> &Path {
+        Path::new(self.as_os_str().slice_from(ix))
+    }
+}
+
+#[cfg(not(feature = "no-unsafe"))]
+impl Findable<u8> for Path {
+    #[inline(always)]
+    fn find_span(&self, other: &Self) -> Option<Span<u8>> {
+        self.as_os_str().find_span(other.as_os_str())
+    }
+
+    #[inline(always)]
+    fn find_spans(&self, other: &Self) -> impl Iterator<Item = Span<u8>> {
+        self.as_os_str().find_spans(other.as_os_str())
+    }
+}
+
+#[cfg(not(feature = "no-unsafe"))]
+fn os_str_slice(s: &OsStr, start: usize, end: usize) -> &OsStr {
+    let bytes = s.as_encoded_bytes();
+    assert!(
+        start <= end && end <= bytes.len(),
+        "span {start}..{end} is out of bounds of OsStr of length {}",
+        bytes.len()
+    );
+    assert!(
+        is_os_str_boundary(bytes, start) && is_os_str_boundary(bytes, end),
+        "span {start}..{end} does not split OsStr next to valid UTF-8"
+    );
+
+    // SAFETY: the bytes are only split next to valid UTF-8, as required
+    unsafe { OsStr::from_encoded_bytes_unchecked(&bytes[start..end]) }
+}
+
+/// Whether the encoded bytes of an `OsStr` can be split at `ix`:
+/// either end, or directly before or after a valid UTF-8 character.
+#[cfg(not(feature = "no-unsafe"))]
+fn is_os_str_boundary(bytes: &[u8], ix: usize) -> bool {
+    if ix == 0 || ix == bytes.len() {
+        return true;
+    }
+
+    let before = (1..=ix.min(4)).any(|len| std::str::from_utf8(&bytes[ix - len..ix]).is_ok());
+    let after = bytes[ix..]
+        .utf8_chunks()
+        .next()
+        .is_some_and(|chunk| !chunk.valid().is_empty());
+
+    before || after
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::Count;
+
+    #[test]
+    fn vec_deque_span() {
+        let mut deque: VecDeque<u32> = (3..6).collect();
+        deque.push_front(2);
+        deque.push_front(1);
+
+        let span = Span::new(Index::new(1), Count::new(3));
+        assert_eq!(vec![&2, &3, &4], deque.iter_span(span).collect::<Vec<_>>());
+        assert_eq!(5, deque[Index::new(4)]);
+    }
+
+    #[test]
+    fn btree_map_range() {
+        let map: BTreeMap<Index<char>, char> = "abcdef"
+            .chars()
+            .enumerate()
+            .map(|(ix, c)| (Index::new(ix), c))
+            .collect();
+
+        let span = Span::new(Index::new(2), Count::new(3));
+        assert_eq!("cde", map.range(span).map(|(_, c)| c).collect::<String>());
+    }
+
+    #[cfg(not(feature = "no-unsafe"))]
+    #[test]
+    fn os_str_find_and_slice() {
+        let s = OsStr::new("résumé.txt");
+        let span = s.find_span(OsStr::new("é")).unwrap();
+        assert_eq!(Span::new(Index::new(1), Count::new(2)), span);
+        assert_eq!(OsStr::new("é"), s.slice(span));
+        assert_eq!(OsStr::new("r"), s.slice_until(span.start()));
+        assert_eq!(2, s.find_spans(OsStr::new("é")).count());
+
+        let path = Path::new("/home/user/file.txt");
+        let span = path.find_span(Path::new("user")).unwrap();
+        assert_eq!(Path::new("user/file.txt"), path.slice_from(span.start()));
+    }
+
+    #[cfg(not(feature = "no-unsafe"))]
+    #[test]
+    #[should_panic(expected = "does not split OsStr next to valid UTF-8")]
+    fn os_str_slice_inside_character() {
+        OsStr::new("é").slice_until(Index::new(1));
+    }
+}
@@ -15,17 +15,28 @@
 //! - `Count + Count → Count`
 //! - `Count - Count → Option<Count>`
 //!
+//! Signed distances are represented by an [`Offset`]:
+//! - `Index::offset_from(Index) → Option<Offset>`
+//! - `Index + Offset → Option<Index>`
+//! - `Index - Offset → Option<Index>`
+//! - `Offset + Offset → Offset` (or `Offset::checked_add(Offset) → Option<Offset>`)
+//! - `Offset::checked_neg() → Option<Offset>`
+//!
 //! However, you cannot perform the following operations:
 //! - `Index + Index`
 //!
-//! [`Span`]s are also provided, which are a (possibly empty) range of Indices.
+//! [`Span`]s are also provided, which are a (possibly empty) range of Indices;
+//! a [`RelativeSpan`] is a span within some enclosing span.
 
 mod count;
 mod countable;
+mod generators;
 mod index;
 mod indexable;
 mod internals;
+mod offset;
 mod rate;
+mod relative_span;
 mod span;
 
 pub use count::Count;
@@ -33,6 +44,8 @@ pub use countable::{ByteCount, CharCount, Countable};
 #[cfg(feature = "unicode-width")]
 pub use countable::{UnicodeWidth, UnicodeWidthCount};
 pub use index::Index;
-pub use indexable::{Findable, Indexable, IndexableMut};
+pub use indexable::{Findable, Indexable, IndexableMut, SpanIterable};
+pub use offset::{NegativeOffsetError, Offset};
 pub use rate::Rate;
+pub use relative_span::RelativeSpan;
 pub use span::Span;
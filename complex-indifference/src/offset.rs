@@ -0,0 +1,320 @@
+use std::{fmt::Display, marker::PhantomData};
+
+use crate::{Count, Index};
+
+/// A signed distance between two [`Index`]es of things of type `T`.
+///
+/// A [`Count`] can only ever move an `Index` forwards; an `Offset` can also
+/// move it backwards, which is what is needed for (e.g.) moving a cursor
+/// around. The `Offset` between two indices is given by [`Index::offset_from`].
+#[derive(Debug)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(
+        zerocopy::FromBytes,
+        zerocopy::IntoBytes,
+        zerocopy::Immutable,
+        zerocopy::KnownLayout
+    )
+)]
+#[repr(transparent)]
+pub struct Offset<T: ?Sized> {
+    offset: isize,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: ?Sized> Display for Offset<T> {
+    #[inline(always)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.offset.fmt(f)
+    }
+}
+
+impl<T: ?Sized> Default for Offset<T> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<T: ?Sized> Clone for Offset<T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for Offset<T> {}
+
+impl<T: ?Sized> PartialOrd<Offset<T>> for Offset<T> {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Offset<T>) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: ?Sized> Ord for Offset<T> {
+    #[inline(always)]
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.offset.cmp(&other.offset)
+    }
+}
+
+impl<T: ?Sized> PartialEq<Offset<T>> for Offset<T> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.offset == other.offset
+    }
+}
+
+impl<T: ?Sized> Eq for Offset<T> {}
+
+impl<T: ?Sized> Offset<T> {
+    pub const ZERO: Self = Self::new(0);
+
+    #[inline(always)]
+    pub const fn new(offset: isize) -> Self {
+        Self { offset, _phantom: PhantomData }
+    }
+
+    #[inline(always)]
+    pub const fn as_isize(&self) -> isize {
+        self.offset
+    }
+
+    /// The size of the offset, ignoring its direction.
+    #[inline(always)]
+    pub const fn magnitude(&self) -> Count<T> {
+        Count::new(self.offset.unsigned_abs())
+    }
+
+    /// The offset as a [`Count`], if it is not negative.
+    #[inline(always)]
+    pub const fn as_count(&self) -> Option<Count<T>> {
+        if self.offset < 0 {
+            None
+        } else {
+            Some(Count::new(self.offset as usize))
+        }
+    }
+
+    /// The offset in the opposite direction, or `None` if it
+    /// does not fit (i.e. for `isize::MIN`).
+    #[inline(always)]
+    pub const fn checked_neg(&self) -> Option<Self> {
+        match self.offset.checked_neg() {
+            Some(offset) => Some(Self::new(offset)),
+            None => None,
+        }
+    }
+
+    /// The sum of the offsets, or `None` if it overflows.
+    #[inline(always)]
+    pub const fn checked_add(&self, rhs: Self) -> Option<Self> {
+        match self.offset.checked_add(rhs.offset) {
+            Some(offset) => Some(Self::new(offset)),
+            None => None,
+        }
+    }
+
+    /// The difference of the offsets, or `None` if it overflows.
+    #[inline(always)]
+    pub const fn checked_sub(&self, rhs: Self) -> Option<Self> {
+        match self.offset.checked_sub(rhs.offset) {
+            Some(offset) => Some(Self::new(offset)),
+            None => None,
+        }
+    }
+}
+
+/// The error when converting a negative [`Offset`] to a [`Count`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegativeOffsetError;
+
+impl Display for NegativeOffsetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a negative offset cannot be converted to a count")
+    }
+}
+
+impl std::error::Error for NegativeOffsetError {}
+
+impl<T: ?Sized> From<isize> for Offset<T> {
+    #[inline(always)]
+    fn from(offset: isize) -> Self {
+        Self::new(offset)
+    }
+}
+
+impl<T: ?Sized> From<Offset<T>> for isize {
+    #[inline(always)]
+    fn from(offset: Offset<T>) -> Self {
+        offset.as_isize()
+    }
+}
+
+impl<T: ?Sized> From<Count<T>> for Offset<T> {
+    /// Slices never have more than `isize::MAX` bytes, so any count
+    /// of items in memory fits into an offset.
+    #[inline(always)]
+    fn from(count: Count<T>) -> Self {
+        Self::new(count.as_usize() as isize)
+    }
+}
+
+impl<T: ?Sized> TryFrom<Offset<T>> for Count<T> {
+    type Error = NegativeOffsetError;
+
+    #[inline(always)]
+    fn try_from(offset: Offset<T>) -> Result<Self, NegativeOffsetError> {
+        offset.as_count().ok_or(NegativeOffsetError)
+    }
+}
+
+impl<T: ?Sized> std::ops::Add for Offset<T> {
+    type Output = Self;
+
+    /// Overflows as `isize` addition does (as for [`Count`]);
+    /// use [`Offset::checked_add`] if the offsets might be large.
+    #[inline(always)]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.offset + rhs.offset)
+    }
+}
+
+impl<T: ?Sized> std::ops::AddAssign for Offset<T> {
+    #[inline(always)]
+    fn add_assign(&mut self, rhs: Self) {
+        self.offset += rhs.offset;
+    }
+}
+
+impl<T: ?Sized> std::ops::Sub for Offset<T> {
+    type Output = Self;
+
+    /// Overflows as `isize` subtraction does (as for [`Count`]);
+    /// use [`Offset::checked_sub`] if the offsets might be large.
+    #[inline(always)]
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.offset - rhs.offset)
+    }
+}
+
+impl<T: ?Sized> std::ops::SubAssign for Offset<T> {
+    #[inline(always)]
+    fn sub_assign(&mut self, rhs: Self) {
+        self.offset -= rhs.offset;
+    }
+}
+
+impl<T: ?Sized> std::ops::Add<Offset<T>> for Index<T> {
+    type Output = Option<Index<T>>;
+
+    #[inline(always)]
+    fn add(self, rhs: Offset<T>) -> Self::Output {
+        Some(Index::new(self.as_usize().checked_add_signed(rhs.offset)?))
+    }
+}
+
+impl<T: ?Sized> std::ops::Sub<Offset<T>> for Index<T> {
+    type Output = Option<Index<T>>;
+
+    #[inline(always)]
+    fn sub(self, rhs: Offset<T>) -> Self::Output {
+        // negating the offset would overflow for `isize::MIN`,
+        // so subtract its magnitude directly instead
+        let magnitude = rhs.offset.unsigned_abs();
+        let index = if rhs.offset < 0 {
+            self.as_usize().checked_add(magnitude)?
+        } else {
+            self.as_usize().checked_sub(magnitude)?
+        };
+
+        Some(Index::new(index))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn index_difference() {
+        let x = Index::<u8>::new(3);
+        let y = Index::<u8>::new(5);
+        assert_eq!(y.offset_from(x), Some(Offset::new(2)));
+        assert_eq!(x.offset_from(y), Some(Offset::new(-2)));
+        assert_eq!(x.offset_from(x), Some(Offset::ZERO));
+        assert_eq!(y - x, Some(Count::new(2)));
+        assert_eq!(x - y, None);
+    }
+
+    #[test]
+    pub fn index_difference_overflow() {
+        let start = Index::<u8>::new(0);
+        let end = Index::<u8>::new(usize::MAX);
+        assert_eq!(end.offset_from(start), None);
+        assert_eq!(start.offset_from(end), None);
+
+        let min = Index::<u8>::new(isize::MAX as usize + 1);
+        assert_eq!(start.offset_from(min), Some(Offset::new(isize::MIN)));
+    }
+
+    #[test]
+    pub fn negation() {
+        assert_eq!(Offset::<u8>::new(2).checked_neg(), Some(Offset::new(-2)));
+        assert_eq!(Offset::<u8>::new(isize::MIN).checked_neg(), None);
+        assert_eq!(
+            Index::<u8>::new(3) - Offset::new(isize::MIN),
+            Some(Index::new(3 + isize::MIN.unsigned_abs()))
+        );
+        assert_eq!(Index::<u8>::new(usize::MAX) - Offset::new(isize::MIN), None);
+    }
+
+    #[test]
+    pub fn index_plus_offset() {
+        let x = Index::<u8>::new(3);
+        assert_eq!(x + Offset::new(2), Some(Index::new(5)));
+        assert_eq!(x + Offset::new(-3), Some(Index::new(0)));
+        assert_eq!(x + Offset::new(-4), None);
+        assert_eq!(x - Offset::new(-2), Some(Index::new(5)));
+        assert_eq!(x - Offset::new(3), Some(Index::new(0)));
+        assert_eq!(x - Offset::new(4), None);
+    }
+
+    #[test]
+    pub fn round_trip() {
+        let x = Index::<u8>::new(7);
+        let y = Index::<u8>::new(2);
+        assert_eq!(y + x.offset_from(y).unwrap(), Some(x));
+        assert_eq!(x + y.offset_from(x).unwrap(), Some(y));
+    }
+
+    #[test]
+    pub fn counts() {
+        assert_eq!(Offset::<u8>::new(4).as_count(), Some(Count::new(4)));
+        assert_eq!(Offset::<u8>::new(-4).as_count(), None);
+        assert_eq!(Offset::<u8>::new(-4).magnitude(), Count::new(4));
+        assert_eq!(Offset::from(Count::<u8>::new(4)), Offset::new(4));
+        assert_eq!(
+            Count::try_from(Offset::<u8>::new(-4)),
+            Err(NegativeOffsetError)
+        );
+    }
+
+    #[test]
+    pub fn checked_arithmetic() {
+        let max = Offset::<u8>::new(isize::MAX);
+        let min = Offset::<u8>::new(isize::MIN);
+        assert_eq!(
+            max.checked_add(Offset::new(-1)),
+            Some(Offset::new(isize::MAX - 1))
+        );
+        assert_eq!(max.checked_add(Offset::new(1)), None);
+        assert_eq!(
+            min.checked_sub(Offset::new(-1)),
+            Some(Offset::new(isize::MIN + 1))
+        );
+        assert_eq!(min.checked_sub(Offset::new(1)), None);
+    }
+}
@@ -18,6 +18,13 @@ impl<T: ?Sized> std::ops::Div<Duration> for Count<T> {
     }
 }
 
+impl<T: ?Sized> Rate<T> {
+    #[inline(always)]
+    pub fn per_second(&self) -> f64 {
+        self.count_per_second
+    }
+}
+
 impl<T: ?Sized> std::fmt::Display for Rate<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:.2} /s", self.count_per_second)
@@ -0,0 +1,125 @@
+use crate::{Count, Index, Span};
+
+/// A [`Span`] which is relative to the start of an enclosing span, rather
+/// than to the start of the whole sequence (for example, a span within one
+/// line of some text, rather than within the whole text).
+///
+/// Relative spans are made with [`Span::rebase`] or [`Span::relative_to`],
+/// and turned back into absolute spans with [`RelativeSpan::absolute`]
+/// or [`RelativeSpan::within`].
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(zerocopy::Immutable, zerocopy::KnownLayout)
+)]
+#[repr(transparent)]
+pub struct RelativeSpan<T: ?Sized> {
+    span: Span<T>,
+}
+
+impl<T: ?Sized> Default for RelativeSpan<T> {
+    fn default() -> Self {
+        Self { span: Span::default() }
+    }
+}
+
+impl<T: ?Sized> Copy for RelativeSpan<T> {}
+
+impl<T: ?Sized> Clone for RelativeSpan<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> RelativeSpan<T> {
+    /// A span starting `start` items after the start of the enclosing span.
+    pub fn new(start: Count<T>, len: Count<T>) -> Self {
+        Self { span: Span::new(Index::new(start.as_usize()), len) }
+    }
+
+    /// How far after the start of the enclosing span this span starts.
+    #[inline(always)]
+    pub fn start(&self) -> Count<T> {
+        Count::new(self.span.start().as_usize())
+    }
+
+    /// How far after the start of the enclosing span this span ends (exclusive).
+    #[inline(always)]
+    pub fn end(&self) -> Count<T> {
+        Count::new(self.span.end().as_usize())
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> Count<T> {
+        self.span.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.span.is_empty()
+    }
+
+    /// The absolute span, given where the enclosing span starts.
+    pub fn absolute(self, parent_start: Index<T>) -> Span<T> {
+        Span::new(parent_start + self.start(), self.len())
+    }
+
+    /// The absolute span, given the enclosing span, or `None`
+    /// if this span would extend past the end of it.
+    pub fn within(self, parent: Span<T>) -> Option<Span<T>> {
+        if self.end() > parent.len() {
+            return None;
+        }
+
+        Some(self.absolute(parent.start()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn span(start: usize, len: usize) -> Span<u8> {
+        Span::new(Index::new(start), Count::new(len))
+    }
+
+    #[test]
+    fn round_trip() {
+        let line = span(10, 8);
+        let word = span(12, 3);
+
+        let relative = word.relative_to(line).unwrap();
+        assert_eq!(RelativeSpan::new(Count::new(2), Count::new(3)), relative);
+        assert_eq!(Some(word), relative.within(line));
+        assert_eq!(word, relative.absolute(line.start()));
+    }
+
+    #[test]
+    fn rebase() {
+        let word = span(12, 3);
+        assert_eq!(
+            Some(RelativeSpan::new(Count::new(12), Count::new(3))),
+            word.rebase(Index::new(0))
+        );
+        assert_eq!(
+            Some(RelativeSpan::new(Count::new(0), Count::new(3))),
+            word.rebase(Index::new(12))
+        );
+        assert_eq!(None, word.rebase(Index::new(13)));
+    }
+
+    #[test]
+    fn outside_parent() {
+        let line = span(10, 8);
+        assert_eq!(None, span(8, 3).relative_to(line));
+        assert_eq!(None, span(16, 3).relative_to(line));
+        assert_eq!(
+            None,
+            RelativeSpan::new(Count::new(6), Count::new(3)).within(line)
+        );
+        assert_eq!(
+            Some(span(18, 0)),
+            RelativeSpan::new(Count::new(8), Count::ZERO).within(line)
+        );
+    }
+}
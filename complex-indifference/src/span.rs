@@ -1,11 +1,21 @@
 // cSpell: ignore excl
 
-use std::ops::Range;
+use std::ops::{Bound, Range, RangeBounds};
 
-use crate::{Count, Index, internals};
+use crate::{Count, Index, RelativeSpan, internals};
 
 /// A range of [`Index`]es.
+///
+/// A span is laid out as its start followed by its end. Not every pair of
+/// indices is a valid span, so spans cannot be read directly from bytes;
+/// instead, tables of spans can be stored as `[Index<T>; 2]` (which, with
+/// the `zerocopy` feature, can be) and checked as they are read.
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(zerocopy::Immutable, zerocopy::KnownLayout)
+)]
+#[repr(C)]
 pub struct Span<T: ?Sized> {
     start: Index<T>,
     end_excl: Index<T>,
@@ -42,6 +52,21 @@ impl<T: ?Sized> TryFrom<(Index<T>, Index<T>)> for Span<T> {
     }
 }
 
+impl<T: ?Sized> TryFrom<[Index<T>; 2]> for Span<T> {
+    type Error = ();
+    #[inline(always)]
+    fn try_from([start, end]: [Index<T>; 2]) -> Result<Self, ()> {
+        Self::try_from_indices(start, end).ok_or(())
+    }
+}
+
+impl<T: ?Sized> From<Span<T>> for [Index<T>; 2] {
+    #[inline(always)]
+    fn from(span: Span<T>) -> Self {
+        [span.start, span.end_excl]
+    }
+}
+
 impl<T: ?Sized> TryFrom<Range<usize>> for Span<T> {
     type Error = ();
     #[inline(always)]
@@ -126,6 +151,22 @@ impl<T: ?Sized> Span<T> {
         }
     }
 
+    /// This span, relative to `parent_start`, or `None` if it starts before that.
+    pub fn rebase(self, parent_start: Index<T>) -> Option<RelativeSpan<T>> {
+        let start = (self.start - parent_start)?;
+        Some(RelativeSpan::new(start, self.len()))
+    }
+
+    /// This span, relative to the enclosing span `parent`,
+    /// or `None` if it is not within it.
+    pub fn relative_to(self, parent: Span<T>) -> Option<RelativeSpan<T>> {
+        if !parent.contains(self) {
+            return None;
+        }
+
+        self.rebase(parent.start())
+    }
+
     #[inline(always)]
     fn invariant(&self) {
         internals::invariant!(self.start() <= self.end());
@@ -139,6 +180,18 @@ impl Span<u8> {
     }
 }
 
+/// This allows a span to be used to find the keys (or items)
+/// of a `BTreeMap` (or `BTreeSet`) which are within it.
+impl<T: ?Sized> RangeBounds<Index<T>> for Span<T> {
+    fn start_bound(&self) -> Bound<&Index<T>> {
+        Bound::Included(&self.start)
+    }
+
+    fn end_bound(&self) -> Bound<&Index<T>> {
+        Bound::Excluded(&self.end_excl)
+    }
+}
+
 impl<T> std::ops::Index<Span<T>> for [T] {
     type Output = [T];
 
@@ -224,4 +277,22 @@ mod test {
 
         assert!(span_outer.contains(span_inner));
     }
+
+    #[test]
+    fn layout() {
+        assert_eq!(size_of::<Span<u8>>(), 2 * size_of::<usize>());
+        assert_eq!(size_of::<RelativeSpan<u8>>(), size_of::<Span<u8>>());
+    }
+
+    #[cfg(feature = "zerocopy")]
+    #[test]
+    fn zerocopy_span_table() {
+        use zerocopy::{FromBytes, IntoBytes};
+
+        let span = Span::<u8>::new(Index::new(3), Count::new(2));
+        let table: [[Index<u8>; 2]; 2] = [span.into(), [Index::new(5), Index::new(3)]];
+        let read = <[[Index<u8>; 2]]>::ref_from_bytes(table.as_bytes()).unwrap();
+        assert_eq!(Span::try_from(read[0]), Ok(span));
+        assert_eq!(Span::try_from(read[1]), Err(()));
+    }
 }
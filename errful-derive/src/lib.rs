@@ -1,11 +1,18 @@
-use darling::{ast, FromDeriveInput, FromField, FromMeta, FromVariant};
+use darling::{
+    ast,
+    util::{Flag, Override, SpannedValue},
+    FromDeriveInput, FromField, FromMeta, FromVariant,
+};
 use proc_macro2::{Ident, Span, TokenStream};
-use quote::{format_ident, quote};
-use syn::{parse_macro_input, DeriveInput, Type};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{parse_macro_input, spanned::Spanned, DeriveInput};
 
 #[derive(FromDeriveInput, Debug)]
 #[darling(attributes(error), supports(struct_any, enum_any))]
 struct Opts {
+    ident: syn::Ident,
+    generics: syn::Generics,
+
     // actual options
     display: Option<String>,
 
@@ -21,6 +28,10 @@ struct BasicOptions {
     /// Exit code to use if this is returned from `main`
     exit_code: Option<u8>,
 
+    /// HTTP status code to respond with
+    #[cfg(feature = "http")]
+    status: Option<u16>,
+
     /// URL to show for this error
     url: Option<String>,
 
@@ -30,7 +41,11 @@ struct BasicOptions {
     /// The severity of the error
     severity: Option<syn::Path>,
 
-    /// Don’t show the error message for this error when printing a chain
+    /// Advice on how to resolve the error
+    help: Option<SpannedValue<String>>,
+
+    /// Forward everything (display, source, and errful information)
+    /// to the single field of this struct or variant
     #[darling(default)]
     transparent: bool,
 }
@@ -44,20 +59,17 @@ pub fn derive_errful(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 
     let res = move || -> Result<proc_macro::TokenStream, darling::Error> {
         let opts = Opts::from_derive_input(&input)?;
-        let source_method = generate_source_function(&opts.data)?;
+        check_enum_help(&opts)?;
+        let delegates = transparent_patterns(&opts)?;
+        let source_method = generate_source_function(&opts.data, &delegates)?;
         let labels_fn = generate_labels_function(&opts.data)?;
+        let related_fn = generate_related_function(&opts.data)?;
+        let location_fn = generate_location_function(&opts.data)?;
+        let from_impls = generate_from_impls(&opts)?;
 
         let DeriveInput { ident, .. } = input;
-        let display_impl = opts.display.as_deref().map(|display| {
-            quote! {
-                #[automatically_derived]
-                impl ::core::fmt::Display for #ident {
-                    fn fmt(&self, __formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-                        write!(__formatter, #display)
-                    }
-                }
-            }
-        });
+        let (impl_generics, ty_generics, where_clause) = opts.generics.split_for_impl();
+        let display_impl = generate_display_impl(&ident, &opts, &delegates);
 
         let request_ident = format_ident!("__request");
 
@@ -69,6 +81,17 @@ pub fn derive_errful(input: proc_macro::TokenStream) -> proc_macro::TokenStream
             |exit_code| quote! { ::std::process::ExitCode::from(#exit_code) },
         );
 
+        // HTTP status is provided
+        #[cfg(feature = "http")]
+        let provide_status = provide_value(
+            &opts,
+            &request_ident,
+            |o| o.status.as_ref(),
+            |status| quote! { ::errful::http::HttpStatus(#status) },
+        );
+        #[cfg(not(feature = "http"))]
+        let provide_status: Option<TokenStream> = None;
+
         // TODO: backtrace is provided
 
         let url_fn = generate_value_function(
@@ -92,49 +115,69 @@ pub fn derive_errful(input: proc_macro::TokenStream) -> proc_macro::TokenStream
             Ident::new("severity", Span::call_site()),
             quote! { &dyn ::errful::protocol::PrintableSeverity },
             |o| o.severity.as_ref(),
-            |severity| quote! { &#severity },
+            |severity| quote_spanned! {severity.span()=> &#severity },
         );
 
-        let transparent_fn = generate_required_value_function(
+        let help_fn = generate_variant_value_function(
             &opts,
-            Ident::new("transparent", Span::call_site()),
-            quote! { bool },
-            |o| o.transparent,
-            |transparent| quote! { #transparent },
+            Ident::new("help", Span::call_site()),
+            quote! { ::std::borrow::Cow<'_, str> },
+            |o| o.help.as_ref().map(|help| help.as_str()),
+            |help: &str| quote_help(help, display_arguments(help, &opts.data)),
+            |help: &str, variant| {
+                let (bindings, args) = variant_arguments(help, variant);
+                (bindings, quote_help(help, args))
+            },
         );
 
-        let source_code = find_source_code(&opts.data)?;
-        let source_code = source_code.map(|source_code| {
+        let source_code = generate_source_code_functions(&opts.data)?;
+
+        let errful_fns = delegate_errful_functions(
+            &delegates,
+            [
+                url_fn,
+                code_fn,
+                severity_fn,
+                help_fn,
+                labels_fn,
+                related_fn,
+                location_fn,
+                Some(source_code),
+            ],
+        )?;
+
+        let provide_delegate = (!delegates.is_empty()).then(|| {
             quote! {
-                fn source_code(&self) -> Option<&str> {
-                    Some(#source_code)
+                #[allow(unreachable_patterns)]
+                match self {
+                    #(#delegates => ::core::error::Error::provide(__inner, #request_ident),)*
+                    _ => {}
                 }
             }
         });
 
         let output = quote! {
             #[automatically_derived]
-            impl ::core::error::Error for #ident {
+            impl #impl_generics ::core::error::Error for #ident #ty_generics #where_clause {
                 #source_method
 
                 fn provide<'a>(&'a self, #request_ident: &mut ::core::error::Request<'a>) {
                     use ::std::borrow::Borrow;
                     #request_ident.provide_ref::<dyn ::errful::Errful>(self);
+                    #provide_delegate
                     #provide_exit_code
+                    #provide_status
                 }
             }
 
             #[automatically_derived]
-            impl ::errful::Errful for #ident {
-                #url_fn
-                #code_fn
-                #severity_fn
-                #transparent_fn
-                #labels_fn
-                #source_code
+            impl #impl_generics ::errful::Errful for #ident #ty_generics #where_clause {
+                #(#errful_fns)*
             }
 
             #display_impl
+
+            #(#from_impls)*
         };
 
         Ok(output.into())
@@ -172,15 +215,33 @@ struct StructField {
     /* Actual options: */
     // is this the source of the error?
     #[darling(default)]
-    source: bool,
+    source: Flag,
 
-    // labels
+    // is this the source of the error, which it can be created `From`?
+    #[darling(default)]
+    from: Flag,
+
+    // labels, optionally with a method which finds the span when the label is rendered
     label: Option<LabelTarget>,
+    label_with: Option<SpannedValue<syn::Ident>>,
     source_id: Option<String>,
+    severity: Option<syn::Path>,
+
+    // source code, optionally with an ID that labels can refer to
+    source_code: Option<SpannedValue<Override<SourceCodeOptions>>>,
+
+    // a collection of related errors
+    #[darling(default)]
+    related: bool,
 
-    // source code
+    // where the error was raised
     #[darling(default)]
-    source_code: bool,
+    location: bool,
+}
+
+#[derive(Debug, FromMeta)]
+struct SourceCodeOptions {
+    id: Option<String>,
 }
 
 /// The source of an errful label can either be
@@ -199,34 +260,308 @@ impl FromMeta for LabelTarget {
     }
 }
 
-fn generate_source_function(data: &Data) -> darling::Result<TokenStream> {
-    let read_source_field = |target: &mut dyn FnMut(TokenStream) -> TokenStream,
-                             fields: &[StructField]| {
-        if fields.len() == 1 {
-            // TODO, newtype
+/// Produces a pattern binding `__inner` to the single field
+/// of each transparent struct or variant.
+fn transparent_patterns(opts: &Opts) -> darling::Result<Vec<TokenStream>> {
+    let single_field = |fields: &[StructField], span: &syn::Ident| match fields {
+        [field] => Ok(name_for_field((0, field))),
+        _ => Err(
+            darling::Error::custom("transparent errors must have exactly one field")
+                .with_span(span),
+        ),
+    };
+
+    match &opts.data {
+        ast::Data::Struct(fields) if opts.basic.transparent => {
+            let field_name = single_field(&fields.fields, &opts.ident)?;
+            Ok(vec![quote! { Self { #field_name: __inner, .. } }])
         }
+        ast::Data::Struct(_) => Ok(Vec::new()),
+        ast::Data::Enum(variants) => {
+            if opts.basic.transparent {
+                return Err(darling::Error::custom(
+                    "transparent must be applied to individual variants of an enum",
+                )
+                .with_span(&opts.ident));
+            }
 
-        // TODO: need to check for more than one source field
-        // and produce an error
-        for (ix, field) in fields.iter().enumerate() {
-            let field_name = name_for_field((ix, field));
-            if field.source
+            let mut errors = darling::Error::accumulator();
+            let patterns = Vec::from_iter(
+                variants
+                    .iter()
+                    .filter(|v| v.basic.transparent)
+                    .filter_map(|v| {
+                        let ident = &v.ident;
+                        let field_name = errors.handle(single_field(&v.fields.fields, ident))?;
+                        Some(quote! { Self::#ident { #field_name: __inner, .. } })
+                    }),
+            );
+
+            errors.finish_with(patterns)
+        }
+    }
+}
+
+fn generate_display_impl(
+    ident: &Ident,
+    opts: &Opts,
+    delegates: &[TokenStream],
+) -> Option<TokenStream> {
+    let display = opts.display.as_deref().map(|display| {
+        let args = display_arguments(display, &opts.data);
+        quote! { write!(__formatter, #display #(, #args)*) }
+    });
+
+    let body = match (display, delegates.is_empty()) {
+        (display, true) => display?,
+        (Some(display), false) => quote! {
+            #[allow(unreachable_patterns)]
+            match self {
+                #(#delegates => ::core::fmt::Display::fmt(__inner, __formatter),)*
+                _ => #display,
+            }
+        },
+        // without a display string, only a transparent struct can be displayed
+        (None, false) if matches!(opts.data, ast::Data::Struct(_)) => quote! {
+            let #(#delegates)* = self;
+            ::core::fmt::Display::fmt(__inner, __formatter)
+        },
+        (None, false) => return None,
+    };
+
+    let (impl_generics, ty_generics, where_clause) = opts.generics.split_for_impl();
+    Some(quote! {
+        #[automatically_derived]
+        impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+            fn fmt(&self, __formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
+
+/// Wraps the generated `Errful` functions so that transparent structs and variants
+/// forward to their inner error. Functions which were not generated at all are
+/// created, so that they can forward as well.
+fn delegate_errful_functions(
+    delegates: &[TokenStream],
+    generated: impl IntoIterator<Item = Option<TokenStream>>,
+) -> darling::Result<Vec<TokenStream>> {
+    let generated = generated.into_iter().flatten();
+    if delegates.is_empty() {
+        return Ok(Vec::from_iter(generated));
+    }
+
+    let mut fns = Vec::new();
+    for tokens in generated {
+        // a generator may produce more than one function
+        let item: syn::ItemImpl = syn::parse2(quote! { impl X { #tokens } })?;
+        fns.extend(item.items.into_iter().filter_map(|i| match i {
+            syn::ImplItem::Fn(f) => Some(f),
+            _ => None,
+        }));
+    }
+
+    // the methods which are not provided through `Error::provide`
+    let required: [syn::ImplItemFn; 9] = [
+        syn::parse_quote! { fn code(&self) -> Option<&str> { None } },
+        syn::parse_quote! { fn severity(&self) -> Option<&dyn ::errful::protocol::PrintableSeverity> { None } },
+        syn::parse_quote! { fn help(&self) -> Option<::std::borrow::Cow<'_, str>> { None } },
+        syn::parse_quote! { fn source_code(&self) -> Option<&str> { None } },
+        syn::parse_quote! { fn source_code_by_id(&self, id: &str) -> Option<&str> { None } },
+        syn::parse_quote! { fn labels(&self) -> Option<::std::vec::Vec<::errful::protocol::Label<'_>>> { None } },
+        syn::parse_quote! { fn related(&self) -> Option<::std::vec::Vec<&(dyn ::core::error::Error + 'static)>> { None } },
+        syn::parse_quote! { fn location(&self) -> Option<&'static ::std::panic::Location<'static>> { None } },
+        syn::parse_quote! { fn transparent(&self) -> bool { false } },
+    ];
+
+    for f in required {
+        if !fns.iter().any(|g| g.sig.ident == f.sig.ident) {
+            fns.push(f);
+        }
+    }
+
+    Ok(Vec::from_iter(fns.into_iter().map(|mut f| {
+        let name = &f.sig.ident;
+        let args = Vec::from_iter(f.sig.inputs.iter().filter_map(|arg| match arg {
+            syn::FnArg::Typed(arg) => Some(&arg.pat),
+            syn::FnArg::Receiver(_) => None,
+        }));
+
+        let call = quote! { #name(#(#args),*) };
+        let original = &f.block;
+        let block = quote! {
+            {
+                #[allow(unreachable_patterns)]
+                match self {
+                    #(#delegates => ::errful::AsErrful::errful(__inner).#call,)*
+                    _ => #original,
+                }
+            }
+        };
+
+        // UNWRAP: the block is always valid
+        f.block = syn::parse2(block).unwrap();
+        quote! { #f }
+    })))
+}
+
+/// Finds the arguments referenced by a format string, and produces
+/// the arguments needed to resolve them to fields of the struct.
+///
+/// Named arguments (`{path}`) which match a field become `path = self.path`,
+/// and positional arguments (`{0}`) are resolved to the fields of a tuple struct.
+/// Anything else is left to be resolved by `write!` itself.
+fn display_arguments(display: &str, data: &Data) -> Vec<TokenStream> {
+    let ast::Data::Struct(struct_data) = data else {
+        return Vec::new();
+    };
+
+    Vec::from_iter(
+        referenced_fields(display, &struct_data.fields)
+            .into_iter()
+            .map(|(ix, field)| {
+                let field_name = name_for_field((ix, field));
+                match &field.ident {
+                    Some(ident) => quote! { #ident = self.#field_name },
+                    None => quote! { self.#field_name },
+                }
+            }),
+    )
+}
+
+/// Like [`display_arguments`], but for a format string on an enum variant,
+/// where there is no `self` to read the fields from.
+///
+/// Produces the bindings to add to the pattern matching the variant
+/// (`path: __field_0`), and the arguments which refer to those bindings.
+fn variant_arguments(display: &str, variant: &EnumVariant) -> (Vec<TokenStream>, Vec<TokenStream>) {
+    let mut bindings = Vec::new();
+    let mut args = Vec::new();
+    for (ix, field) in referenced_fields(display, &variant.fields.fields) {
+        let field_name = name_for_field((ix, field));
+        let binding = format_ident!("__field_{ix}");
+        bindings.push(quote! { #field_name: #binding });
+        args.push(match &field.ident {
+            Some(ident) => quote! { #ident = #binding },
+            None => quote! { #binding },
+        });
+    }
+
+    (bindings, args)
+}
+
+/// The help on an enum is used for any variant without its own help,
+/// so it cannot refer to fields, which differ between the variants.
+fn check_enum_help(opts: &Opts) -> darling::Result<()> {
+    match &opts.basic.help {
+        Some(help) if matches!(opts.data, ast::Data::Enum(_)) => {
+            let (positional, named) = format_arguments(help);
+            if positional > 0 || !named.is_empty() {
+                return Err(darling::Error::custom(
+                    "help on an enum cannot refer to fields; put it on the variants instead",
+                )
+                .with_span(&help.span()));
+            }
+
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Finds the fields referenced by a format string: first the positional
+/// fields of a tuple struct (in order), and then the named fields.
+fn referenced_fields<'a>(
+    display: &str,
+    fields: &'a [StructField],
+) -> Vec<(usize, &'a StructField)> {
+    let (positional, named) = format_arguments(display);
+
+    let mut result = Vec::new();
+    for (ix, field) in fields.iter().enumerate().take(positional) {
+        if field.ident.is_none() {
+            result.push((ix, field));
+        }
+    }
+
+    for name in named {
+        if let Some(found) = fields
+            .iter()
+            .enumerate()
+            .find(|(_, f)| f.ident.as_ref().is_some_and(|i| *i == name))
+        {
+            result.push(found);
+        }
+    }
+
+    result
+}
+
+/// Finds the arguments of a format string: the number
+/// of positional arguments, and the names of the others.
+fn format_arguments(display: &str) -> (usize, Vec<String>) {
+    let mut named = Vec::new();
+    let mut positional = 0;
+    let mut implicit = 0;
+
+    let mut chars = display.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '{' {
+            continue;
+        }
+
+        if chars.next_if_eq(&'{').is_some() {
+            continue; // escaped brace
+        }
+
+        let inner = String::from_iter(chars.by_ref().take_while(|c| *c != '}'));
+        let arg = inner.split(':').next().unwrap_or_default().trim();
+        if arg.is_empty() {
+            implicit += 1;
+            positional = positional.max(implicit);
+        } else if let Ok(ix) = arg.parse::<usize>() {
+            positional = positional.max(ix + 1);
+        } else if !named.iter().any(|n| n == arg) {
+            named.push(arg.to_string());
+        }
+    }
+
+    (positional, named)
+}
+
+fn generate_source_function(
+    data: &Data,
+    delegates: &[TokenStream],
+) -> darling::Result<TokenStream> {
+    let mut errors = darling::Error::accumulator();
+    let mut read_source_field = |target: &mut dyn FnMut(TokenStream) -> TokenStream,
+                                 fields: &[StructField]| {
+        let mut sources = fields.iter().enumerate().filter(|(_, field)| {
+            field.source.is_present()
+                || field.from.is_present()
                 || field
                     .ident
                     .as_ref()
                     .map(|i| i == "source")
                     .unwrap_or_default()
-            {
-                let target = target(field_name);
-                if is_optional(&field.ty) {
-                    return quote! { #target.map(|s| s.borrow()) };
-                } else {
-                    return quote! { Some(#target.borrow()) };
-                }
-            }
+        });
+
+        let Some(source) = sources.next() else {
+            return quote! { None };
+        };
+
+        for (_, field) in sources {
+            let error = darling::Error::custom("an error can only have one source field");
+            errors.push(match &field.ident {
+                _ if field.from.is_present() => error.with_span(&field.from.span()),
+                Some(ident) if !field.source.is_present() => error.with_span(ident),
+                _ => error.with_span(&field.source.span()),
+            });
         }
 
-        quote! { None }
+        let target = target(name_for_field(source));
+        quote! { (#target).errful_source() }
     };
 
     let contents = match data {
@@ -237,13 +572,14 @@ fn generate_source_function(data: &Data) -> darling::Result<TokenStream> {
                 let source = read_source_field(
                     &mut |name| {
                         field = Some(name.clone());
-                        quote! { #name }
+                        quote! { __source }
                     },
                     &v.fields.fields,
                 );
                 if let Some(field) = field {
+                    // binds to a new name, since tuple fields cannot be bound by their index
                     quote! {
-                        Self::#name {#field, ..} => #source,
+                        Self::#name {#field: __source, ..} => #source,
                     }
                 } else {
                     quote! {
@@ -259,16 +595,32 @@ fn generate_source_function(data: &Data) -> darling::Result<TokenStream> {
             }
         }
         ast::Data::Struct(fields) => {
-            let result = read_source_field(&mut |name| quote! { self.#name }, &fields.fields);
+            let result = read_source_field(&mut |name| quote! { &self.#name }, &fields.fields);
             quote! {
                 #result
             }
         }
     };
 
+    errors.finish()?;
+
+    // transparent errors skip over their inner error, since they display as it
+    let contents = if delegates.is_empty() {
+        contents
+    } else {
+        quote! {
+            #[allow(unreachable_patterns)]
+            match self {
+                #(#delegates => ::core::error::Error::source(__inner),)*
+                _ => { #contents }
+            }
+        }
+    };
+
     let result = quote! {
         fn source(&self) -> Option<&(dyn ::core::error::Error + 'static)> {
-            use std::borrow::Borrow;
+            #[allow(unused_imports)]
+            use ::errful::protocol::source::{OptionalDerefSource, OptionalSource, ValueSource};
             #contents
         }
     };
@@ -281,10 +633,36 @@ fn generate_labels_function(data: &Data) -> darling::Result<Option<TokenStream>>
         return Ok(None);
     };
 
+    let mut errors = darling::Error::accumulator();
     let mut labels = Vec::new();
 
     for (ix, field) in struct_data.fields.iter().enumerate() {
-        let Some(label) = &field.label else { continue };
+        let Some(label) = &field.label else {
+            if let Some(method) = &field.label_with {
+                errors.push(
+                    darling::Error::custom("label_with requires a label message")
+                        .with_span(&method.span()),
+                );
+            }
+
+            continue;
+        };
+
+        if let LabelTarget::Field(ident) = label {
+            if !struct_data
+                .fields
+                .iter()
+                .any(|f| f.ident.as_ref() == Some(ident))
+            {
+                errors.push(
+                    darling::Error::custom(format!(
+                        "no field named `{ident}` to use as the label message"
+                    ))
+                    .with_span(ident),
+                );
+                continue;
+            }
+        }
 
         let field_name = name_for_field((ix, field));
 
@@ -293,62 +671,309 @@ fn generate_labels_function(data: &Data) -> darling::Result<Option<TokenStream>>
             None => quote! { None },
         };
 
-        let value = match label {
+        // the span is either the field itself or, with label_with, computed by
+        // a method of the error; in which case the label may be missing
+        let (span, label_span) = match &field.label_with {
+            Some(method) => {
+                let method = &**method;
+                (
+                    Some(quote! { self.#method() }),
+                    quote! { ::errful::protocol::LabelSpan::to_span(&__span) },
+                )
+            }
+            None => (
+                None,
+                quote! { ::errful::protocol::LabelSpan::to_span(&self.#field_name) },
+            ),
+        };
+
+        let label = match label {
             LabelTarget::Field(ident) => {
                 quote! {
                    ::errful::protocol::Label::new_error(
                        #source_id,
-                       self.#ident.borrow(),
-                       self.#field_name)
+                       self.#ident.as_dyn_error(),
+                       #label_span)
                 }
             }
             LabelTarget::Literal(label) => {
                 quote! {
-                    ::errful::protocol::Label::new_literal(#source_id, #label, self.#field_name)
+                    ::errful::protocol::Label::new_literal(
+                        #source_id,
+                        #label,
+                        #label_span)
                 }
             }
         };
 
-        labels.push(value);
+        let value = match &field.severity {
+            Some(severity) => {
+                let severity = quote_spanned! {severity.span()=> &#severity };
+                quote! { #label.with_severity(#severity) }
+            }
+            None => label,
+        };
+
+        labels.push(match span {
+            Some(span) => quote! {
+                if let ::core::option::Option::Some(__span) = #span {
+                    __labels.push(#value);
+                }
+            },
+            None => quote! { __labels.push(#value); },
+        });
     }
 
+    errors.finish()?;
+
     if labels.is_empty() {
         return Ok(None);
     }
 
     let result = Some(quote! {
         fn labels(&self) -> Option<::std::vec::Vec<::errful::protocol::Label>> {
-            use ::std::borrow::Borrow;
-            Some(vec![
-                #(#labels),*
-            ])
+            use ::errful::protocol::AsDynError;
+            let mut __labels = ::std::vec::Vec::new();
+            #(#labels)*
+            Some(__labels)
+        }
+    });
+
+    Ok(result)
+}
+
+fn generate_related_function(data: &Data) -> darling::Result<Option<TokenStream>> {
+    let ast::Data::Struct(struct_data) = data else {
+        return Ok(None);
+    };
+
+    let related = Vec::from_iter(
+        struct_data
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| field.related)
+            .map(|(ix, field)| {
+                let field_name = name_for_field((ix, field));
+                quote! {
+                    result.extend(self.#field_name.iter().map(
+                        |e| -> &(dyn ::core::error::Error + 'static) { e.as_dyn_error() }
+                    ));
+                }
+            }),
+    );
+
+    if related.is_empty() {
+        return Ok(None);
+    }
+
+    let result = Some(quote! {
+        fn related(&self) -> Option<::std::vec::Vec<&(dyn ::core::error::Error + 'static)>> {
+            use ::errful::protocol::AsDynError;
+            let mut result = ::std::vec::Vec::new();
+            #(#related)*
+            Some(result)
         }
     });
 
     Ok(result)
 }
 
-fn find_source_code(data: &Data) -> darling::Result<Option<TokenStream>> {
-    if let ast::Data::Struct(struct_data) = data {
-        // TODO error if specified more than once
-        Ok(field_ref(&struct_data.fields, |f| f.source_code))
+fn generate_location_function(data: &Data) -> darling::Result<Option<TokenStream>> {
+    let ast::Data::Struct(struct_data) = data else {
+        return Ok(None);
+    };
+
+    let Some((ix, field)) = struct_data
+        .fields
+        .iter()
+        .enumerate()
+        .find(|(_, f)| f.location)
+    else {
+        return Ok(None);
+    };
+
+    let field_name = name_for_field((ix, field));
+    // works for both `&Location` and `Option<&Location>`
+    Ok(Some(quote! {
+        fn location(&self) -> Option<&'static ::std::panic::Location<'static>> {
+            ::std::convert::From::from(self.#field_name)
+        }
+    }))
+}
+
+/// Generates `From` implementations for each struct or variant
+/// with a `#[error(from)]` field.
+fn generate_from_impls(opts: &Opts) -> darling::Result<Vec<TokenStream>> {
+    let constructors: Vec<(TokenStream, &[StructField])> = match &opts.data {
+        ast::Data::Struct(fields) => vec![(quote! { Self }, &fields.fields)],
+        ast::Data::Enum(variants) => Vec::from_iter(variants.iter().map(|v| {
+            let ident = &v.ident;
+            (quote! { Self::#ident }, v.fields.fields.as_slice())
+        })),
+    };
+
+    let mut errors = darling::Error::accumulator();
+    let mut result = Vec::new();
+    let ident = &opts.ident;
+    let (impl_generics, ty_generics, where_clause) = opts.generics.split_for_impl();
+    for (constructor, fields) in constructors {
+        let Some((from_ix, from_field)) =
+            fields.iter().enumerate().find(|(_, f)| f.from.is_present())
+        else {
+            continue;
+        };
+
+        // the only other fields which can be filled in are locations
+        let mut others = Vec::new();
+        for (ix, field) in fields.iter().enumerate() {
+            if ix == from_ix {
+                continue;
+            }
+
+            if !field.location {
+                errors.push(
+                    darling::Error::custom(
+                        "a From impl can only be generated if all other fields are #[error(location)]",
+                    )
+                    .with_span(&from_field.from.span()),
+                );
+                break;
+            }
+
+            let field_name = name_for_field((ix, field));
+            // works for both `&Location` and `Option<&Location>`
+            others.push(quote! {
+                #field_name: ::std::convert::From::from(::std::panic::Location::caller())
+            });
+        }
+
+        let from_name = name_for_field((from_ix, from_field));
+        for (from_ty, value) in from_conversions(&from_field.ty) {
+            result.push(quote! {
+                #[automatically_derived]
+                impl #impl_generics ::core::convert::From<#from_ty> for #ident #ty_generics #where_clause {
+                    #[track_caller]
+                    fn from(__source: #from_ty) -> Self {
+                        #constructor { #from_name: #value, #(#others),* }
+                    }
+                }
+            });
+        }
+    }
+
+    errors.finish_with(result)
+}
+
+/// The types which a `#[error(from)]` field of type `ty` can be created from,
+/// along with the expressions which convert `__source` to the field type.
+///
+/// As well as the type of the field itself, a field of type `Option<T>`
+/// can be created from `T`, and a field of type `Box<T>` from `T` (when
+/// it is not a trait object).
+fn from_conversions(ty: &syn::Type) -> Vec<(TokenStream, TokenStream)> {
+    let source = quote! { __source };
+    let mut result = Vec::new();
+    if let Some(inner) = wrapped_type(ty, "Option") {
+        result.extend(
+            from_conversions(inner)
+                .into_iter()
+                .map(|(from_ty, value)| (from_ty, quote! { ::core::option::Option::Some(#value) })),
+        );
     } else {
-        Ok(None)
+        result.push((quote! { #ty }, source.clone()));
+        if let Some(inner) = wrapped_type(ty, "Box") {
+            if !matches!(inner, syn::Type::TraitObject(_)) {
+                result.push((
+                    quote! { #inner },
+                    quote! { ::std::boxed::Box::new(#source) },
+                ));
+            }
+        }
     }
+
+    result
 }
 
-fn field_ref<'a>(
-    fields: impl IntoIterator<Item = &'a StructField>,
-    predicate: impl Fn(&StructField) -> bool,
-) -> Option<TokenStream> {
-    for (field_index, field) in fields.into_iter().enumerate() {
-        if predicate(field) {
-            let field_name = name_for_field((field_index, field));
-            return Some(quote! { &self.#field_name });
+/// If `ty` is `wrapper<T>` then returns `T`.
+fn wrapped_type<'t>(ty: &'t syn::Type, wrapper: &str) -> Option<&'t syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+
+    match Vec::from_iter(args.args.iter()).as_slice() {
+        [syn::GenericArgument::Type(inner)] => Some(inner),
+        _ => None,
+    }
+}
+
+fn generate_source_code_functions(data: &Data) -> darling::Result<TokenStream> {
+    let ast::Data::Struct(struct_data) = data else {
+        return Ok(TokenStream::new());
+    };
+
+    let mut errors = darling::Error::accumulator();
+    let mut default = None;
+    let mut named: Vec<(String, TokenStream)> = Vec::new();
+    for (ix, field) in struct_data.fields.iter().enumerate() {
+        let Some(options) = &field.source_code else {
+            continue;
+        };
+
+        let field_name = name_for_field((ix, field));
+        let field_ref = quote! { &self.#field_name };
+        let id = (**options).as_ref().explicit().and_then(|o| o.id.clone());
+        match id {
+            None if default.is_some() => errors.push(darling::Error::custom(
+                "only one source_code field may be unnamed; use #[error(source_code(id = \"...\"))]",
+            ).with_span(&options.span())),
+            None => default = Some(field_ref),
+            Some(id) if named.iter().any(|(n, _)| *n == id) => {
+                errors.push(darling::Error::custom(format!(
+                    "more than one source_code field has the id \"{id}\""
+                )).with_span(&options.span()))
+            }
+            Some(id) => named.push((id, field_ref)),
         }
     }
 
-    None
+    errors.finish()?;
+
+    let default_fn = default.map(|source_code| {
+        quote! {
+            fn source_code(&self) -> Option<&str> {
+                Some(#source_code)
+            }
+        }
+    });
+
+    let named_fn = (!named.is_empty()).then(|| {
+        let cases = named
+            .iter()
+            .map(|(id, field_ref)| quote! { #id => Some(#field_ref), });
+        quote! {
+            fn source_code_by_id(&self, id: &str) -> Option<&str> {
+                match id {
+                    #(#cases)*
+                    _ => None,
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        #default_fn
+        #named_fn
+    })
 }
 
 fn name_for_field(field: (usize, &StructField)) -> TokenStream {
@@ -360,16 +985,13 @@ fn name_for_field(field: (usize, &StructField)) -> TokenStream {
     }
 }
 
-fn is_optional(ty: &Type) -> bool {
-    // TODO: bad
-    // instead use a trait for source_code
-    // maybe also castaway
-    let Type::Path(p) = ty else { return false };
-    let Some(last) = p.path.segments.last() else {
-        return false;
-    };
-
-    last.ident == "Option"
+/// Produces the help message, formatting it only if it has any arguments.
+fn quote_help(help: &str, args: Vec<TokenStream>) -> TokenStream {
+    if help.contains(['{', '}']) {
+        quote! { ::std::borrow::Cow::Owned(format!(#help #(, #args)*)) }
+    } else {
+        quote! { ::std::borrow::Cow::Borrowed(#help) }
+    }
 }
 
 fn generate_value_function<'a, T: ?Sized + 'a>(
@@ -378,12 +1000,27 @@ fn generate_value_function<'a, T: ?Sized + 'a>(
     result_t: TokenStream,
     proj: impl Fn(&'a BasicOptions) -> Option<&'a T>,
     quote_t: impl Fn(&'a T) -> TokenStream,
+) -> Option<TokenStream> {
+    generate_variant_value_function(opts, name, result_t, proj, &quote_t, |value, _| {
+        (Vec::new(), quote_t(value))
+    })
+}
+
+/// Like [`generate_value_function`], but the value of a variant can use
+/// its fields, by producing the bindings needed in the pattern matching it.
+fn generate_variant_value_function<'a, T: ?Sized + 'a>(
+    opts: &'a Opts,
+    name: proc_macro2::Ident,
+    result_t: TokenStream,
+    proj: impl Fn(&'a BasicOptions) -> Option<&'a T>,
+    quote_t: impl Fn(&'a T) -> TokenStream,
+    quote_variant: impl Fn(&'a T, &'a EnumVariant) -> (Vec<TokenStream>, TokenStream),
 ) -> Option<TokenStream> {
     if let ast::Data::Enum(variants) = &opts.data {
         let cases = Vec::from_iter(variants.iter().filter_map(|v| {
             let ident = &v.ident;
-            let quoted = quote_t(proj(&v.basic)?);
-            Some(quote! { Self::#ident{..} => Some(#quoted), })
+            let (bindings, quoted) = quote_variant(proj(&v.basic)?, v);
+            Some(quote! { Self::#ident{ #(#bindings,)* .. } => Some(#quoted), })
         }));
 
         if !cases.is_empty() {
@@ -416,45 +1053,6 @@ fn generate_value_function<'a, T: ?Sized + 'a>(
     })
 }
 
-fn generate_required_value_function<'a, T: 'a>(
-    opts: &'a Opts,
-    name: proc_macro2::Ident,
-    result_t: TokenStream,
-    proj: impl Fn(&'a BasicOptions) -> T,
-    quote_t: impl Fn(T) -> TokenStream,
-) -> TokenStream {
-    if let ast::Data::Enum(variants) = &opts.data {
-        let cases = Vec::from_iter(variants.iter().map(|v| {
-            let ident = &v.ident;
-            let quoted = quote_t(proj(&v.basic));
-            quote! { Self::#ident{..} => #quoted, }
-        }));
-
-        if !cases.is_empty() {
-            let base_case = {
-                let quoted = quote_t(proj(&opts.basic));
-                quote! { _ => #quoted, }
-            };
-
-            return quote! {
-                fn #name(&self) -> #result_t {
-                    match self {
-                        #(#cases)*
-                        #base_case
-                    }
-                }
-            };
-        }
-    }
-
-    let quoted = quote_t(proj(&opts.basic));
-    quote! {
-        fn #name(&self) -> #result_t {
-            #quoted
-        }
-    }
-}
-
 fn provide_value<'a, T: ?Sized + 'a>(
     opts: &'a Opts,
     request: &Ident,
@@ -465,14 +1063,14 @@ fn provide_value<'a, T: ?Sized + 'a>(
         let cases = Vec::from_iter(variants.iter().filter_map(|v| {
             let ident = &v.ident;
             let quoted = quote_t(proj(&v.basic)?);
-            Some(quote! { Self::#ident{..} => #request.provide_value(#quoted), })
+            Some(quote! { Self::#ident{..} => { #request.provide_value(#quoted); } })
         }));
 
         if !cases.is_empty() {
             let base_case = match proj(&opts.basic) {
                 Some(base_value) => {
                     let quoted = quote_t(base_value);
-                    quote! { _ => #request.provide_value(#quoted), }
+                    quote! { _ => { #request.provide_value(#quoted); } }
                 }
                 _ => quote! { _ => {}, },
             };
@@ -0,0 +1,147 @@
+//! Collecting many errors into one, e.g. when validating many files in parallel.
+
+use std::{borrow::Cow, error::Error, fmt::Display, process::ExitCode};
+
+use crate::{
+    protocol::{AsErrful, Errful, PrintableSeverity},
+    termination::{diagnostics_exit_code, is_failure},
+};
+
+/// How the exit code of an [`Aggregate`] is chosen.
+///
+/// In either case, an aggregate without any failing errors
+/// (for example, one which only contains warnings) exits successfully.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ExitPolicy {
+    /// Use the exit code of the first failing error.
+    #[default]
+    FirstFailure,
+    /// Use the given exit code if any error is failing.
+    Fixed(ExitCode),
+}
+
+/// An error made up of many errors, none of which is the cause of the others.
+///
+/// The errors are provided as [related errors](Errful::related), so they are
+/// each rendered (with their labels) inside the aggregate.
+#[derive(Debug)]
+pub struct Aggregate<E> {
+    errors: Vec<E>,
+    message: Option<Cow<'static, str>>,
+    exit_policy: ExitPolicy,
+}
+
+impl<E> Default for Aggregate<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> Aggregate<E> {
+    pub fn new() -> Self {
+        Aggregate {
+            errors: Vec::new(),
+            message: None,
+            exit_policy: ExitPolicy::default(),
+        }
+    }
+
+    /// Sets the message of the aggregate; by default this is the number of errors.
+    pub fn with_message(self, message: impl Into<Cow<'static, str>>) -> Self {
+        Self { message: Some(message.into()), ..self }
+    }
+
+    pub fn with_exit_policy(self, exit_policy: ExitPolicy) -> Self {
+        Self { exit_policy, ..self }
+    }
+
+    pub fn push(&mut self, error: impl Into<E>) {
+        self.errors.push(error.into());
+    }
+
+    pub fn errors(&self) -> &[E] {
+        &self.errors
+    }
+
+    pub fn into_errors(self) -> Vec<E> {
+        self.errors
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns the aggregate as an error only if it contains any errors.
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() { Ok(()) } else { Err(self) }
+    }
+}
+
+impl<E: Error> Aggregate<E> {
+    /// Whether any of the errors has a failing severity.
+    pub fn has_failures(&self) -> bool {
+        self.errors.iter().any(is_failure)
+    }
+
+    /// The exit code chosen by the [`ExitPolicy`].
+    pub fn exit_code(&self) -> ExitCode {
+        match self.exit_policy {
+            ExitPolicy::FirstFailure => diagnostics_exit_code(&self.errors),
+            ExitPolicy::Fixed(code) if self.has_failures() => code,
+            ExitPolicy::Fixed(_) => ExitCode::SUCCESS,
+        }
+    }
+}
+
+impl<E> FromIterator<E> for Aggregate<E> {
+    fn from_iter<I: IntoIterator<Item = E>>(iter: I) -> Self {
+        Aggregate { errors: Vec::from_iter(iter), ..Self::new() }
+    }
+}
+
+impl<E> Extend<E> for Aggregate<E> {
+    fn extend<I: IntoIterator<Item = E>>(&mut self, iter: I) {
+        self.errors.extend(iter);
+    }
+}
+
+impl<E> Display for Aggregate<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.message, self.errors.len()) {
+            (Some(message), _) => f.write_str(message),
+            (None, 1) => f.write_str("1 error occurred"),
+            (None, n) => write!(f, "{n} errors occurred"),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for Aggregate<E> {
+    fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
+        request.provide_ref::<dyn Errful>(self);
+        request.provide_value(self.exit_code());
+    }
+}
+
+impl<E: Error + 'static> Errful for Aggregate<E> {
+    /// The severity of the first failing error, or of the first error
+    /// if none of them are failing.
+    fn severity(&self) -> Option<&dyn PrintableSeverity> {
+        let error = self
+            .errors
+            .iter()
+            .find(|e| is_failure(*e))
+            .or(self.errors.first())?;
+
+        error.errful().severity()
+    }
+
+    fn related(&self) -> Option<Vec<&(dyn Error + 'static)>> {
+        Some(Vec::from_iter(
+            self.errors.iter().map(|e| e as &(dyn Error + 'static)),
+        ))
+    }
+}
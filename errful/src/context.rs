@@ -0,0 +1,85 @@
+//! Adding context to errors, in the style of `anyhow::Context`.
+
+use std::{
+    error::Error,
+    fmt::{Debug, Display},
+};
+
+use crate::protocol::{AsErrful, Errful, PrintableSeverity};
+
+/// An error which wraps another error with a message describing
+/// what was happening when it occurred.
+///
+/// The inner error is the source of this one, so its labels and
+/// source code are still shown when the chain is rendered.
+/// The severity and exit code of the inner error are preserved.
+#[derive(Debug)]
+pub struct ContextError<C, E> {
+    context: C,
+    source: E,
+}
+
+impl<C, E> ContextError<C, E> {
+    pub fn context(&self) -> &C {
+        &self.context
+    }
+
+    pub fn into_inner(self) -> E {
+        self.source
+    }
+}
+
+impl<C: Display, E> Display for ContextError<C, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.context.fmt(f)
+    }
+}
+
+impl<C: Display + Debug + 'static, E: Error + 'static> Error for ContextError<C, E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+
+    fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
+        request.provide_ref::<dyn Errful>(self);
+        // forward any other values, such as the exit code
+        self.source.provide(request);
+    }
+}
+
+impl<C: Display + Debug + 'static, E: Error + 'static> Errful for ContextError<C, E> {
+    fn severity(&self) -> Option<&dyn PrintableSeverity> {
+        self.source.errful().severity()
+    }
+}
+
+/// Extension methods for adding context to the error of a [`Result`].
+pub trait Context<T, E> {
+    fn context<C>(self, context: C) -> Result<T, ContextError<C, E>>
+    where
+        C: Display + Debug + 'static;
+
+    /// Like [`Context::context`], but the context is only
+    /// constructed if there is an error.
+    fn with_context<C, F>(self, context: F) -> Result<T, ContextError<C, E>>
+    where
+        C: Display + Debug + 'static,
+        F: FnOnce() -> C;
+}
+
+impl<T, E: Error + 'static> Context<T, E> for Result<T, E> {
+    fn context<C>(self, context: C) -> Result<T, ContextError<C, E>>
+    where
+        C: Display + Debug + 'static,
+    {
+        self.map_err(|source| ContextError { context, source })
+    }
+
+    fn with_context<C, F>(self, context: F) -> Result<T, ContextError<C, E>>
+    where
+        C: Display + Debug + 'static,
+        F: FnOnce() -> C,
+    {
+        self.map_err(|source| ContextError { context: context(), source })
+    }
+}
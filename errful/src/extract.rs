@@ -0,0 +1,64 @@
+//! Extracting structured information from any error, without knowing its type.
+//!
+//! These are intended for frameworks (such as CLIs or web handlers) which are
+//! given a boxed error and want to know, for example, what exit code or status
+//! to use. Each function looks at the error and then each of its sources in turn,
+//! and returns what the first one to provide anything provides:
+//!
+//! ```
+//! # #![feature(error_generic_member_access)]
+//! use std::process::ExitCode;
+//!
+//! #[derive(Debug, errful::Error)]
+//! #[error(display = "not found", exit_code = 2)]
+//! struct NotFound {}
+//!
+//! let err: Box<dyn std::error::Error + Send + Sync> = Box::new(NotFound {});
+//! assert_eq!(errful::extract::<ExitCode>(&*err), Some(ExitCode::from(2)));
+//! ```
+
+use std::error::{Error, request_ref, request_value};
+
+use crate::protocol::{AsDynError, Errful, Label};
+
+/// The errors in the chain starting at `err`, outermost first.
+fn chain<'a>(err: &'a (dyn Error + 'static)) -> impl Iterator<Item = &'a (dyn Error + 'static)> {
+    std::iter::successors(Some(err), |&err| err.source())
+}
+
+/// A value of type `T` provided by the error or any of its sources;
+/// e.g. an [`ExitCode`](std::process::ExitCode).
+pub fn extract<T: 'static>(err: &(impl AsDynError<'static> + ?Sized)) -> Option<T> {
+    chain(err.as_dyn_error()).find_map(request_value::<T>)
+}
+
+/// A reference to a `T` provided by the error or any of its sources;
+/// e.g. a [`Backtrace`](std::backtrace::Backtrace).
+pub fn extract_ref<T: ?Sized + 'static>(err: &(impl AsDynError<'static> + ?Sized)) -> Option<&T> {
+    chain(err.as_dyn_error()).find_map(request_ref::<T>)
+}
+
+/// The labels of the first error in the chain which has any.
+///
+/// The labels point into the [source code](Errful::source_code)
+/// of the same error, which is returned with them.
+pub fn extract_labels(
+    err: &(impl AsDynError<'static> + ?Sized),
+) -> Option<(Option<&str>, Vec<Label<'_>>)> {
+    chain(err.as_dyn_error()).find_map(|err| {
+        let errful = request_ref::<dyn Errful>(err)?;
+        let labels = errful.labels().filter(|labels| !labels.is_empty())?;
+        Some((errful.source_code(), labels))
+    })
+}
+
+/// The URL of the first error in the chain which has one.
+///
+/// Derived errors return their URL through [`Errful::url`] rather
+/// than providing it, so use this rather than `extract::<Url>`.
+pub fn extract_url(err: &(impl AsDynError<'static> + ?Sized)) -> Option<url::Url> {
+    chain(err.as_dyn_error()).find_map(|err| match request_ref::<dyn Errful>(err) {
+        Some(errful) => errful.url(),
+        None => request_value(err),
+    })
+}
@@ -0,0 +1,142 @@
+use std::fmt::{Display, Formatter, Write};
+
+use crate::{
+    Severity,
+    protocol::{AsErrful, Errful, LabelMessage},
+};
+
+/// Renders an error as a single-line JSON document, for logging.
+///
+/// The document has the following schema:
+///
+/// ```text
+/// Document = { "chain": [Entry] }
+/// Entry = {
+///     "message": string,
+///     "code": string | null,
+///     "severity": string,
+///     "url": string | null,
+///     "help": string | null,
+///     "location": string | null,
+///     "labels": [{ "message": string, "offset": number, "length": number, "source_id": string | null, "severity": string | null }],
+///     "related": [Document],
+/// }
+/// ```
+///
+/// The chain contains the error followed by its sources, omitting any
+/// which are transparent, as with [`PrettyDisplay`](crate::PrettyDisplay).
+/// Offsets and lengths are in bytes.
+pub struct JsonDisplay<'e> {
+    err: &'e dyn Errful,
+}
+
+impl<'e> From<&'e dyn Errful> for JsonDisplay<'e> {
+    fn from(err: &'e dyn Errful) -> Self {
+        Self { err }
+    }
+}
+
+impl Display for JsonDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write_document(f, self.err)
+    }
+}
+
+fn write_document(f: &mut impl Write, err: &dyn std::error::Error) -> std::fmt::Result {
+    f.write_str("{\"chain\":[")?;
+
+    let mut first = true;
+    let mut next: Option<&dyn std::error::Error> = Some(err);
+    while let Some(err) = next {
+        let enhanced = err.errful();
+        if !enhanced.transparent() {
+            if !first {
+                f.write_char(',')?;
+            }
+
+            write_entry(f, enhanced)?;
+            first = false;
+        }
+
+        next = err.source();
+    }
+
+    f.write_str("]}")
+}
+
+fn write_entry(f: &mut impl Write, err: &dyn Errful) -> std::fmt::Result {
+    let severity = err.severity().unwrap_or(&Severity::Error);
+
+    f.write_str("{\"message\":")?;
+    write_string(f, &err.to_string())?;
+    f.write_str(",\"code\":")?;
+    write_optional_string(f, err.code())?;
+    f.write_str(",\"severity\":")?;
+    write_string(f, severity.name())?;
+    f.write_str(",\"url\":")?;
+    write_optional_string(f, err.url().as_ref().map(|u| u.as_str()))?;
+    f.write_str(",\"help\":")?;
+    write_optional_string(f, err.help().as_deref())?;
+    f.write_str(",\"location\":")?;
+    write_optional_string(f, err.location().map(|l| l.to_string()).as_deref())?;
+
+    f.write_str(",\"labels\":[")?;
+    for (ix, label) in err.labels().unwrap_or_default().iter().enumerate() {
+        if ix > 0 {
+            f.write_char(',')?;
+        }
+
+        let message = match label.message() {
+            LabelMessage::Error(e) => e.to_string(),
+            LabelMessage::String(s) => s.to_string(),
+        };
+
+        f.write_str("{\"message\":")?;
+        write_string(f, &message)?;
+        write!(
+            f,
+            ",\"offset\":{},\"length\":{},\"source_id\":",
+            label.span().start().as_usize(),
+            label.span().len().as_usize(),
+        )?;
+        write_optional_string(f, label.source_id())?;
+        f.write_str(",\"severity\":")?;
+        write_optional_string(f, label.severity().map(|s| s.name()))?;
+        f.write_char('}')?;
+    }
+
+    f.write_str("],\"related\":[")?;
+    for (ix, related) in err.related().unwrap_or_default().into_iter().enumerate() {
+        if ix > 0 {
+            f.write_char(',')?;
+        }
+
+        write_document(f, related)?;
+    }
+
+    f.write_str("]}")
+}
+
+pub(super) fn write_optional_string(f: &mut impl Write, value: Option<&str>) -> std::fmt::Result {
+    match value {
+        Some(value) => write_string(f, value),
+        None => f.write_str("null"),
+    }
+}
+
+pub(super) fn write_string(f: &mut impl Write, value: &str) -> std::fmt::Result {
+    f.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+
+    f.write_char('"')
+}
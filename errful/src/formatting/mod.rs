@@ -1,3 +1,9 @@
+mod json;
 mod pretty;
+#[cfg(feature = "http")]
+mod problem;
 
-pub use pretty::PrettyDisplay;
+pub use json::JsonDisplay;
+pub use pretty::{Glyphs, PrettyDisplay};
+#[cfg(feature = "http")]
+pub use problem::ProblemJsonDisplay;
@@ -1,4 +1,4 @@
-use std::fmt::{Display, Formatter};
+use std::fmt::{Display, Formatter, Write};
 
 use crate::{
     Severity,
@@ -10,6 +10,53 @@ pub struct PrettyDisplay<'e> {
     err: &'e dyn Errful,
     color: bool,
     width: Option<usize>, // None = use termwidth
+    theme: Option<snippets::Theme>,
+    glyphs: Glyphs,
+    max_depth: usize,
+}
+
+/// The characters used to draw the error chain.
+#[derive(Debug, Clone, Copy)]
+pub struct Glyphs {
+    /// Follows the severity symbol of the first error in the chain.
+    pub first: &'static str,
+    /// Precedes each cause in the chain; must be two columns wide.
+    pub cause: &'static str,
+    /// Lengthens the cause glyph to indent deeper causes; must be one column wide.
+    pub depth: &'static str,
+    /// Runs down the left of the details of each error.
+    pub body: &'static str,
+    /// Terminates the chain.
+    pub end: &'static str,
+}
+
+impl Default for Glyphs {
+    fn default() -> Self {
+        Self::unicode()
+    }
+}
+
+impl Glyphs {
+    pub const fn unicode() -> Self {
+        Glyphs {
+            first: "┐",
+            cause: "├▷",
+            depth: "─",
+            body: "│",
+            end: "┷",
+        }
+    }
+
+    /// Glyphs for output which may not support Unicode, such as some log files.
+    pub const fn ascii() -> Self {
+        Glyphs {
+            first: "+",
+            cause: "|>",
+            depth: "-",
+            body: "|",
+            end: "=",
+        }
+    }
 }
 
 impl PrettyDisplay<'_> {
@@ -17,6 +64,29 @@ impl PrettyDisplay<'_> {
         Self { color, ..self }
     }
 
+    /// Enables color only if the environment supports it;
+    /// this respects `NO_COLOR` (see [`snippets::ColorLevel::detect`]).
+    pub fn with_detected_color(self) -> Self {
+        let color = snippets::ColorLevel::detect() != snippets::ColorLevel::None;
+        Self { color, ..self }
+    }
+
+    /// Sets the theme used for severity colors and source snippets.
+    /// A theme at [`snippets::ColorLevel::None`] disables color.
+    pub fn with_theme(self, theme: snippets::Theme) -> Self {
+        Self { theme: Some(theme), ..self }
+    }
+
+    pub fn with_glyphs(self, glyphs: Glyphs) -> Self {
+        Self { glyphs, ..self }
+    }
+
+    /// Sets the maximum number of causes shown for each error;
+    /// any further causes are summarized.
+    pub fn with_max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+
     pub fn with_terminal_width(self) -> Self {
         Self { width: None, ..self }
     }
@@ -27,13 +97,19 @@ impl PrettyDisplay<'_> {
 
     pub fn use_color(&self) -> bool {
         self.color
+            && self
+                .theme
+                .is_none_or(|theme| theme.level != snippets::ColorLevel::None)
     }
 
     fn styles(&self, severity: &dyn PrintableSeverity) -> Styles {
-        if self.color {
-            Styles::new(severity.base_colour())
-        } else {
-            Styles::no_color()
+        if !self.use_color() {
+            return Styles::no_color();
+        }
+
+        match &self.theme {
+            Some(theme) => Styles::new(severity.theme_style(theme)),
+            None => Styles::new(owo_colors::Style::new().color(severity.base_colour())),
         }
     }
 
@@ -42,38 +118,63 @@ impl PrettyDisplay<'_> {
         prefix: &str,
         err: &dyn Errful,
         highlight: &mut impl FnMut(&Label) -> owo_colors::Style,
-        f: &mut Formatter<'_>,
+        f: &mut impl Write,
     ) -> std::fmt::Result {
-        if let Some(labels) = err.labels() {
-            if let Some(source_code) = err.source_code() {
-                let labels = Vec::from_iter(labels.into_iter().map(|label| {
-                    let highlight = highlight(&label);
-                    snippets::Label::new(
-                        label.span(),
-                        match label.message {
-                            // TODO: inner errors
-                            LabelMessage::Error(e) => format!("{e}").into(),
-                            LabelMessage::String(l) => l,
-                        },
-                        highlight,
-                    )
-                }));
+        let Some(labels) = err.labels() else {
+            return Ok(());
+        };
+
+        // labels are grouped by the source they point into,
+        // in the order that each source is first referenced
+        let mut sources: Vec<(Option<&'static str>, Vec<snippets::Label>)> = Vec::new();
+        for label in labels {
+            let highlight = highlight(&label);
+            let source_id = label.source_id();
+            let label = snippets::Label::new(
+                label.span(),
+                match label.message {
+                    // TODO: inner errors
+                    LabelMessage::Error(e) => format!("{e}").into(),
+                    LabelMessage::String(l) => l,
+                },
+                highlight,
+            );
+
+            match sources.iter_mut().find(|(id, _)| *id == source_id) {
+                Some((_, labels)) => labels.push(label),
+                None => sources.push((source_id, vec![label])),
+            }
+        }
 
-                let source_name = None; // TODO: source name
+        for (source_id, labels) in sources {
+            let source_code = match source_id {
+                Some(id) => err.source_code_by_id(id),
+                None => err.source_code(),
+            };
+
+            let Some(source_code) = source_code else {
+                let message = match source_id {
+                    Some(id) => format!(
+                        "! errful issue: no source code with id \"{id}\" provided to render labels\n\
+                         !               (use #[error(source_code(id = \"{id}\"))] to mark an appropriate field)"
+                    ),
+                    None => "! errful issue: no source code provided to render labels\n\
+                             !               (use #[error(source_code)] to mark an appropriate field)"
+                        .to_string(),
+                };
 
-                if let Ok(labels) = labels.try_into() {
-                    let rendered =
-                        snippets::render_labels_to_string(source_code, source_name, labels);
-                    write!(f, "{}", textwrap::indent(&rendered, prefix))?;
-                }
-            } else {
-                let message = textwrap::indent(
-                    "! errful issue: no source code provided to render labels\n\
-                     !               (use #[error(source_code)] to mark an appropriate field)",
-                    prefix,
-                );
+                writeln!(f, "{}", textwrap::indent(&message, prefix))?;
+                continue;
+            };
 
-                writeln!(f, "{message}")?;
+            if let Ok(labels) = labels.try_into() {
+                let renderer = snippets::LabelRenderer::new(source_code, source_id);
+                let rendered = match self.theme.filter(|_| self.use_color()) {
+                    Some(theme) => renderer.with_theme(theme).render_to_string(labels),
+                    None => renderer.render_to_string(labels),
+                };
+
+                write!(f, "{}", textwrap::indent(&rendered, prefix))?;
             }
         }
 
@@ -82,29 +183,173 @@ impl PrettyDisplay<'_> {
 
     fn print_chain_entry(
         &self,
-        f: &mut Formatter<'_>,
+        f: &mut impl Write,
         message_wrap_opts: textwrap::Options,
         body_indent: &str,
         err: &dyn Errful,
+        show_code: bool,
         colors: &mut impl FnMut(&Label) -> owo_colors::Style,
     ) -> std::fmt::Result {
+        // related errors are nested inside the body, which is 5 columns wide
+        let related_width = message_wrap_opts.width.saturating_sub(5);
+
         // output the message for the error
-        let message = format!("{err}");
+        let message = match err.code().filter(|_| show_code) {
+            Some(code) => format!("[{code}] {err}"),
+            None => format!("{err}"),
+        };
         let wrapped = textwrap::wrap(&message, message_wrap_opts);
         for line in wrapped {
             writeln!(f, "{line}")?;
         }
 
         // output any additional information
+        if let Some(location) = err.location() {
+            writeln!(f, "{body_indent}raised at {location}")?;
+        }
+
         self.render_sourcelabels(body_indent, err, colors, f)?;
 
+        // output any related errors as nested chains
+        if let Some(related) = err.related() {
+            for related in related {
+                let mut nested = String::new();
+                let wrap_opts = textwrap::Options::new(related_width);
+                self.print_chain(&mut nested, related, wrap_opts, true, colors)?;
+                write!(f, "{}", textwrap::indent(&nested, body_indent))?;
+            }
+        }
+
         Ok(())
     }
+
+    fn print_chain(
+        &self,
+        f: &mut impl Write,
+        err: &dyn std::error::Error,
+        wrap_opts: textwrap::Options,
+        nested: bool,
+        colors: &mut impl FnMut(&Label) -> owo_colors::Style,
+    ) -> std::fmt::Result {
+        let severity = err.errful().severity().unwrap_or(&Severity::Error);
+        let styles = self.styles(severity);
+
+        let glyphs = &self.glyphs;
+        let body_indent = format!("{}", styles.base_style(format_args!("   {} ", glyphs.body)));
+        let message_indent = format!(
+            "{}",
+            styles.base_style(format_args!("   {}  ", glyphs.body))
+        );
+
+        let mut index = 0;
+        let mut next: Option<&dyn std::error::Error> = Some(err);
+        while let Some(err) = next {
+            let enhanced = err.errful();
+            if !enhanced.transparent() {
+                if index > self.max_depth {
+                    break;
+                }
+
+                // each cause is indented two columns further than the one it caused
+                let depth = index.saturating_sub(1) * 2;
+
+                let first_indent = if index == 0 {
+                    format!(
+                        " {} {} ",
+                        styles.base_style(severity.symbol()),
+                        styles.base_style(glyphs.first)
+                    )
+                } else {
+                    // a cause with a severity of its own is marked in its color
+                    let cause_styles = enhanced.severity().map(|sev| self.styles(sev));
+                    let styles = cause_styles.as_ref().unwrap_or(&styles);
+
+                    let (head, tail) = split_first_char(glyphs.cause);
+                    format!(
+                        "{:2} {} ",
+                        styles.base_style_dim(index),
+                        styles
+                            .base_style(format_args!("{head}{}{tail}", glyphs.depth.repeat(depth)))
+                    )
+                };
+
+                let body_indent = format!("{body_indent}{:depth$}", "");
+                let message_indent = format!("{message_indent}{:depth$}", "");
+
+                self.print_chain_entry(
+                    f,
+                    if index == 0 {
+                        wrap_opts
+                            .clone()
+                            .initial_indent(&first_indent)
+                            .subsequent_indent(&body_indent)
+                    } else {
+                        // message must be indented one more level than the body
+                        wrap_opts
+                            .clone()
+                            .initial_indent(&first_indent)
+                            .subsequent_indent(&message_indent)
+                    },
+                    &body_indent,
+                    enhanced,
+                    // the code of the outermost error is shown in the header
+                    nested || index > 0,
+                    colors,
+                )?;
+
+                index += 1;
+            }
+
+            // proceed
+            next = err.source().map(look_through);
+        }
+
+        // summarize any causes beyond the maximum depth,
+        // counting them in the same way as they would be shown
+        let hidden = std::iter::successors(next, |err| err.source().map(look_through))
+            .filter(|err| !err.errful().transparent())
+            .count();
+
+        if hidden > 0 {
+            let causes = if hidden == 1 { "cause" } else { "causes" };
+            writeln!(f, "{body_indent}… and {hidden} more {causes}")?;
+        }
+
+        // terminate the chain
+        writeln!(f, "   {}", styles.base_style(glyphs.end))
+    }
+}
+
+/// Splits off the first character of a glyph, so that it can be lengthened.
+fn split_first_char(glyph: &str) -> (&str, &str) {
+    glyph.split_at(glyph.chars().next().map_or(0, char::len_utf8))
+}
+
+/// An `io::Error` which wraps another error displays as that error,
+/// but provides nothing from it (and skips it as a source); so, show
+/// the wrapped error in its place, with any code, severity, or labels.
+fn look_through<'a>(
+    err: &'a (dyn std::error::Error + 'static),
+) -> &'a (dyn std::error::Error + 'static) {
+    match err
+        .downcast_ref::<std::io::Error>()
+        .and_then(|err| err.get_ref())
+    {
+        Some(inner) => inner,
+        None => err,
+    }
 }
 
 impl<'e> From<&'e dyn Errful> for PrettyDisplay<'e> {
     fn from(err: &'e dyn Errful) -> Self {
-        Self { err, color: true, width: Some(usize::MAX) }
+        Self {
+            err,
+            color: true,
+            width: Some(usize::MAX),
+            theme: None,
+            glyphs: Glyphs::unicode(),
+            max_depth: usize::MAX,
+        }
     }
 }
 
@@ -127,8 +372,7 @@ impl Styles {
         }
     }
 
-    fn new(base: owo_colors::AnsiColors) -> Self {
-        let base = owo_colors::Style::new().color(base);
+    fn new(base: owo_colors::Style) -> Self {
         Self {
             base,
             base_dim: base.dimmed(),
@@ -171,11 +415,13 @@ impl<'a, T: Display> std::fmt::Display for AppliedStyle<'a, T> {
 impl Display for PrettyDisplay<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut colorgen = ColorGenerator::new();
-        let mut colors = |_: &Label| {
-            if self.use_color() {
-                owo_colors::Style::new().color(colorgen.next())
-            } else {
+        let mut colors = |label: &Label| {
+            if !self.use_color() {
                 owo_colors::Style::new()
+            } else if let Some(severity) = label.severity() {
+                self.styles(severity).base
+            } else {
+                owo_colors::Style::new().color(colorgen.next())
             }
         };
 
@@ -200,62 +446,24 @@ impl Display for PrettyDisplay<'_> {
 
         writeln!(f, "\n{}", styles.only_bold_style("Details:"))?;
 
-        let body_indent = format!("{}", styles.base_style("   │ "));
-        let message_indent = format!("{}", styles.base_style("   │  "));
         let wrap_opts = if let Some(width) = self.width {
             textwrap::Options::new(width)
         } else {
             textwrap::Options::with_termwidth()
         };
 
-        let mut index = 0;
-        let mut next: Option<&dyn std::error::Error> = Some(self.err);
-        while let Some(err) = next {
-            let enhanced = err.errful();
-            if !enhanced.transparent() {
-                let first_indent = if index == 0 {
-                    format!(
-                        " {} {} ",
-                        styles.base_style(severity.symbol()),
-                        styles.base_style("┐")
-                    )
-                } else {
-                    format!(
-                        "{:2} {} ",
-                        styles.base_style_dim(index),
-                        styles.base_style("├▷")
-                    )
-                };
-
-                self.print_chain_entry(
-                    f,
-                    if index == 0 {
-                        wrap_opts
-                            .clone()
-                            .initial_indent(&first_indent)
-                            .subsequent_indent(&body_indent)
-                    } else {
-                        // message must be indented one more level than the body
-                        wrap_opts
-                            .clone()
-                            .initial_indent(&first_indent)
-                            .subsequent_indent(&message_indent)
-                    },
-                    &body_indent,
-                    enhanced,
-                    &mut colors,
-                )?;
+        self.print_chain(f, self.err, wrap_opts.clone(), false, &mut colors)?;
 
-                index += 1;
+        if let Some(help) = err.help() {
+            writeln!(f, "\n{}", styles.only_bold_style("Help:"))?;
+            for line in textwrap::wrap(
+                &help,
+                wrap_opts.initial_indent("  ").subsequent_indent("  "),
+            ) {
+                writeln!(f, "{line}")?;
             }
-
-            // proceed
-            next = err.source();
         }
 
-        // terminate the chain
-        writeln!(f, "   {}", styles.base_style("┷"))?;
-
         Ok(())
     }
 }
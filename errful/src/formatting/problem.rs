@@ -0,0 +1,120 @@
+use std::{
+    error::{Error, request_ref, request_value},
+    fmt::{Display, Formatter, Write},
+};
+
+use super::json::{write_optional_string, write_string};
+use crate::{
+    http::HttpStatus,
+    protocol::{AsErrful, Errful, LabelMessage},
+};
+
+/// Renders an error as an [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807)
+/// problem document, to be sent with the `application/problem+json` media type.
+///
+/// The document has the following members:
+///
+/// ```text
+/// Problem = {
+///     "type": string,        (the URL of the error, or "about:blank")
+///     "title": string,       (the message of the error)
+///     "status": number,      (see ProblemJsonDisplay::status)
+///     "detail": string,      (the messages of its causes, if it has any)
+///     "code": string,        (if the error or one of its sources has a code)
+///     "labels": [{ "message": string, "offset": number, "length": number, "source_id": string | null }],
+/// }
+/// ```
+///
+/// Like the status, the code and labels are taken from the first error in
+/// the chain which has them. Labels usually point into the body of the request,
+/// so they are included (with offsets and lengths in bytes) so that clients can
+/// show where the problems are.
+pub struct ProblemJsonDisplay<'e> {
+    err: &'e dyn Errful,
+}
+
+impl<'e> From<&'e dyn Errful> for ProblemJsonDisplay<'e> {
+    fn from(err: &'e dyn Errful) -> Self {
+        Self { err }
+    }
+}
+
+impl<'e> ProblemJsonDisplay<'e> {
+    fn chain(&self) -> impl Iterator<Item = &'e dyn Error> {
+        std::iter::successors(Some(self.err as &dyn Error), |&err| err.source())
+    }
+
+    /// The status to respond with: that provided by the error or the first of
+    /// its sources which provides one, or otherwise `500 Internal Server Error`.
+    pub fn status(&self) -> HttpStatus {
+        self.chain()
+            .find_map(request_value::<HttpStatus>)
+            .unwrap_or(HttpStatus::INTERNAL_SERVER_ERROR)
+    }
+}
+
+impl Display for ProblemJsonDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let err = self.err;
+
+        f.write_str("{\"type\":")?;
+        write_string(f, err.url().as_ref().map_or("about:blank", |u| u.as_str()))?;
+        f.write_str(",\"title\":")?;
+        write_string(f, &err.to_string())?;
+        write!(f, ",\"status\":{}", self.status().as_u16())?;
+
+        let causes = Vec::from_iter(
+            std::iter::successors(err.source(), |&err| err.source())
+                .filter(|err| !err.errful().transparent())
+                .map(|err| err.to_string()),
+        );
+
+        if !causes.is_empty() {
+            f.write_str(",\"detail\":")?;
+            write_string(f, &causes.join(": "))?;
+        }
+
+        let code = self
+            .chain()
+            .find_map(|err| request_ref::<dyn Errful>(err)?.code());
+        if let Some(code) = code {
+            f.write_str(",\"code\":")?;
+            write_string(f, code)?;
+        }
+
+        let labels = self.chain().find_map(|err| {
+            request_ref::<dyn Errful>(err)?
+                .labels()
+                .filter(|labels| !labels.is_empty())
+        });
+
+        if let Some(labels) = labels {
+            f.write_str(",\"labels\":[")?;
+            for (ix, label) in labels.iter().enumerate() {
+                if ix > 0 {
+                    f.write_char(',')?;
+                }
+
+                let message = match label.message() {
+                    LabelMessage::Error(e) => e.to_string(),
+                    LabelMessage::String(s) => s.to_string(),
+                };
+
+                f.write_str("{\"message\":")?;
+                write_string(f, &message)?;
+                write!(
+                    f,
+                    ",\"offset\":{},\"length\":{},\"source_id\":",
+                    label.span().start().as_usize(),
+                    label.span().len().as_usize(),
+                )?;
+                write_optional_string(f, label.source_id())?;
+                f.write_char('}')?;
+            }
+
+            f.write_char(']')?;
+        }
+
+        f.write_char('}')
+    }
+}
@@ -0,0 +1,22 @@
+//! Support for reporting errors from web services.
+//!
+//! Errors can declare the status code to respond with, using
+//! `#[error(status = 404)]`, and can be rendered as a problem document
+//! with [`AsErrful::display_problem_json`](crate::AsErrful::display_problem_json).
+
+/// The media type of the documents produced by
+/// [`ProblemJsonDisplay`](crate::formatting::ProblemJsonDisplay).
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+/// An HTTP status code, as provided by errors with a `status` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HttpStatus(pub u16);
+
+impl HttpStatus {
+    /// The status used for errors which do not provide one.
+    pub const INTERNAL_SERVER_ERROR: Self = Self(500);
+
+    pub fn as_u16(self) -> u16 {
+        self.0
+    }
+}
@@ -2,15 +2,30 @@
 #![feature(try_trait_v2)]
 #![doc = include_str!("../README.md")]
 
+pub mod aggregate;
 mod colors;
-mod formatting;
+pub mod context;
+pub mod extract;
+pub mod formatting;
+#[cfg(feature = "http")]
+pub mod http;
 pub mod protocol;
 pub mod severity;
 pub mod termination;
+#[cfg(feature = "tracing")]
+mod trace;
 
+pub use aggregate::Aggregate;
 pub use complex_indifference::Span;
+pub use context::Context;
 pub use errful_derive::Error;
-pub use formatting::PrettyDisplay;
+pub use extract::{extract, extract_labels, extract_ref, extract_url};
+pub use formatting::{JsonDisplay, PrettyDisplay};
 pub use protocol::{AsErrful, Errful};
 pub use severity::Severity;
-pub use termination::ExitResult;
+pub use termination::{DiagnosticCollector, ExitResult};
+#[cfg(feature = "tracing")]
+pub use trace::trace;
+
+/// The version of the `errful` crate.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
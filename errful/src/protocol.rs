@@ -43,7 +43,7 @@ use complex_indifference::Span;
 use owo_colors::AnsiColors;
 pub use url_macro::url;
 
-use crate::PrettyDisplay;
+use crate::{JsonDisplay, PrettyDisplay};
 
 pub trait AsErrful: Error + Sized {
     fn errful(&self) -> &dyn Errful {
@@ -107,6 +107,21 @@ pub trait AsErrful: Error + Sized {
     {
         self.display_pretty().with_color(false)
     }
+
+    fn display_json(&self) -> JsonDisplay<'_>
+    where
+        Self: Sized,
+    {
+        self.display_errful()
+    }
+
+    #[cfg(feature = "http")]
+    fn display_problem_json(&self) -> crate::formatting::ProblemJsonDisplay<'_>
+    where
+        Self: Sized,
+    {
+        self.display_errful()
+    }
 }
 
 impl<E: Error> AsErrful for E {}
@@ -136,14 +151,37 @@ pub trait Errful: Error {
         None
     }
 
+    /// Advice on how to resolve the error.
+    fn help(&self) -> Option<Cow<'_, str>> {
+        None
+    }
+
     fn source_code(&self) -> Option<&str> {
         None
     }
 
+    /// Source code with the given ID, for labels which refer to
+    /// a source other than the default one; see [`Label::source_id`].
+    fn source_code_by_id(&self, _id: &str) -> Option<&str> {
+        None
+    }
+
     fn labels(&self) -> Option<Vec<Label<'_>>> {
         None
     }
 
+    /// Further errors which are related to this one, but are not its cause;
+    /// e.g. other errors found in the same input.
+    fn related(&self) -> Option<Vec<&(dyn Error + 'static)>> {
+        None
+    }
+
+    /// Where the error was raised; typically captured with
+    /// [`Location::caller`](std::panic::Location::caller) in a `#[track_caller]` constructor.
+    fn location(&self) -> Option<&'static std::panic::Location<'static>> {
+        None
+    }
+
     /// Whether or not this error should be skipped when printing
     /// cause chains.
     fn transparent(&self) -> bool {
@@ -153,7 +191,9 @@ pub trait Errful: Error {
 
 pub struct Label<'a> {
     pub(crate) message: LabelMessage<'a>,
+    source_id: Option<&'static str>,
     span: Span<u8>,
+    severity: Option<&'a dyn PrintableSeverity>,
 }
 
 pub enum LabelMessage<'a> {
@@ -163,35 +203,194 @@ pub enum LabelMessage<'a> {
 
 impl<'a> Label<'a> {
     pub fn new_error(
-        _source_id: Option<&'static str>,
+        source_id: Option<&'static str>,
         message: &'a dyn Error,
         span: Span<u8>,
     ) -> Self {
-        Label { message: LabelMessage::Error(message), span }
+        Label {
+            message: LabelMessage::Error(message),
+            source_id,
+            span,
+            severity: None,
+        }
     }
 
     pub fn new_literal(
-        _source_id: Option<&'static str>,
+        source_id: Option<&'static str>,
         message: &'static str,
         span: Span<u8>,
     ) -> Self {
         Label {
             message: LabelMessage::String(message.into()),
+            source_id,
             span,
+            severity: None,
         }
     }
 
+    /// The ID of the source code that the label points into;
+    /// if `None`, the label refers to [`Errful::source_code`].
+    pub fn source_id(&self) -> Option<&'static str> {
+        self.source_id
+    }
+
     pub fn span(&self) -> Span<u8> {
         self.span
     }
 
+    pub fn with_severity(self, severity: &'a dyn PrintableSeverity) -> Self {
+        Self { severity: Some(severity), ..self }
+    }
+
+    /// The severity used to style this label; if `None`,
+    /// the label is given a color of its own.
+    pub fn severity(&self) -> Option<&'a dyn PrintableSeverity> {
+        self.severity
+    }
+
     pub fn message(&self) -> &LabelMessage<'_> {
         &self.message
     }
 }
 
+/// Types which can be viewed as a `dyn Error`: any (sized) error type,
+/// including generic parameters and `Arc<E>`, as well as the
+/// `dyn Error` trait objects which are usually held in a `Box`.
+///
+/// This is how the derive macro turns source fields and label errors into
+/// `&dyn Error`; boxes are dereferenced automatically.
+pub trait AsDynError<'a> {
+    fn as_dyn_error(&self) -> &(dyn Error + 'a);
+}
+
+impl<'a, E: Error + 'a> AsDynError<'a> for E {
+    fn as_dyn_error(&self) -> &(dyn Error + 'a) {
+        self
+    }
+}
+
+impl<'a> AsDynError<'a> for dyn Error + 'a {
+    fn as_dyn_error(&self) -> &(dyn Error + 'a) {
+        self
+    }
+}
+
+impl<'a> AsDynError<'a> for dyn Error + Send + 'a {
+    fn as_dyn_error(&self) -> &(dyn Error + 'a) {
+        self
+    }
+}
+
+impl<'a> AsDynError<'a> for dyn Error + Send + Sync + 'a {
+    fn as_dyn_error(&self) -> &(dyn Error + 'a) {
+        self
+    }
+}
+
+/// Finding the source of an error.
+///
+/// The derive macro calls `errful_source` on a reference to the source field,
+/// with all of these traits in scope. Method resolution picks the first
+/// one that applies as it auto-references and dereferences the receiver:
+/// - [`OptionalSource`] for `Option<E>`,
+/// - [`OptionalDerefSource`] (one auto-reference later) for `Option<Box<dyn Error>>` and similar,
+/// - [`ValueSource`] for anything which is, or dereferences to, an error.
+#[doc(hidden)]
+pub mod source {
+    use std::{error::Error, ops::Deref};
+
+    use super::AsDynError;
+
+    pub trait ValueSource {
+        fn errful_source(&self) -> Option<&(dyn Error + 'static)>;
+    }
+
+    impl<E: AsDynError<'static> + ?Sized> ValueSource for E {
+        fn errful_source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(self.as_dyn_error())
+        }
+    }
+
+    pub trait OptionalSource {
+        fn errful_source(&self) -> Option<&(dyn Error + 'static)>;
+    }
+
+    impl<E: AsDynError<'static>> OptionalSource for Option<E> {
+        fn errful_source(&self) -> Option<&(dyn Error + 'static)> {
+            self.as_ref().map(AsDynError::as_dyn_error)
+        }
+    }
+
+    pub trait OptionalDerefSource<'a> {
+        fn errful_source(&self) -> Option<&'a (dyn Error + 'static)>;
+    }
+
+    impl<'a, P: Deref<Target: AsDynError<'static>>> OptionalDerefSource<'a> for &'a Option<P> {
+        fn errful_source(&self) -> Option<&'a (dyn Error + 'static)> {
+            let option: &'a Option<P> = self;
+            option.as_deref().map(AsDynError::as_dyn_error)
+        }
+    }
+}
+
+/// Types which can be used as the location of a label.
+///
+/// This is implemented for [`Span<u8>`], for `Range<usize>`, for `(usize, usize)`
+/// tuples of offset and length, and (with the `miette` feature) for `miette::SourceSpan`.
+pub trait LabelSpan {
+    fn to_span(&self) -> Span<u8>;
+}
+
+impl LabelSpan for Span<u8> {
+    fn to_span(&self) -> Span<u8> {
+        *self
+    }
+}
+
+impl LabelSpan for std::ops::Range<usize> {
+    fn to_span(&self) -> Span<u8> {
+        // a reversed range is treated as empty
+        Span::new(self.start.into(), self.len().into())
+    }
+}
+
+impl LabelSpan for (usize, usize) {
+    fn to_span(&self) -> Span<u8> {
+        let (offset, len) = *self;
+        Span::new(offset.into(), len.into())
+    }
+}
+
+#[cfg(feature = "miette")]
+impl LabelSpan for miette::SourceSpan {
+    fn to_span(&self) -> Span<u8> {
+        Span::new(self.offset().into(), self.len().into())
+    }
+}
+
 pub trait PrintableSeverity {
     fn symbol(&self) -> &'static str;
     fn name(&self) -> &'static str;
     fn base_colour(&self) -> AnsiColors;
+
+    /// The style to use for this severity when a theme has been selected.
+    fn theme_style(&self, _theme: &snippets::Theme) -> owo_colors::Style {
+        owo_colors::Style::new().color(self.base_colour())
+    }
+
+    /// Whether a diagnostic with this severity should cause
+    /// the program to fail; see [`crate::termination::DiagnosticCollector`].
+    fn is_failure(&self) -> bool {
+        true
+    }
+
+    /// The level of the event emitted by [`crate::trace`].
+    #[cfg(feature = "tracing")]
+    fn tracing_level(&self) -> tracing::Level {
+        if self.is_failure() {
+            tracing::Level::ERROR
+        } else {
+            tracing::Level::WARN
+        }
+    }
 }
@@ -32,4 +32,25 @@ impl PrintableSeverity for Severity {
             Severity::Error => AnsiColors::Red,
         }
     }
+
+    fn theme_style(&self, theme: &snippets::Theme) -> owo_colors::Style {
+        match self {
+            Severity::Info => theme.info,
+            Severity::Warning => theme.warning,
+            Severity::Error => theme.error,
+        }
+    }
+
+    fn is_failure(&self) -> bool {
+        matches!(self, Severity::Error)
+    }
+
+    #[cfg(feature = "tracing")]
+    fn tracing_level(&self) -> tracing::Level {
+        match self {
+            Severity::Info => tracing::Level::INFO,
+            Severity::Warning => tracing::Level::WARN,
+            Severity::Error => tracing::Level::ERROR,
+        }
+    }
 }
@@ -1,12 +1,17 @@
 use std::{
     convert::Infallible,
-    error::{request_value, Error},
+    error::{Error, request_value},
     process::{ExitCode, Termination},
 };
 
+use crate::AsErrful;
+
 pub enum ExitResult<E> {
     Code(ExitCode),
     Err(E),
+    /// Diagnostics which are all reported; the program only fails
+    /// if at least one of them has a failing severity.
+    Diagnostics(Vec<E>),
 }
 
 impl<E> ExitResult<E> {
@@ -21,6 +26,10 @@ impl<E> ExitResult<E> {
     pub fn error(err: E) -> Self {
         ExitResult::Err(err)
     }
+
+    pub fn diagnostics(diagnostics: Vec<E>) -> Self {
+        ExitResult::Diagnostics(diagnostics)
+    }
 }
 
 impl<E: Error> ExitResult<E> {
@@ -81,14 +90,104 @@ impl<E: Error> Termination for ExitResult<E> {
         match self {
             ExitResult::Code(exit_code) => exit_code,
             ExitResult::Err(err) => {
-                use crate::AsErrful;
                 _ = write!(
                     std::io::stderr(),
                     "{}",
-                    err.display_pretty().with_terminal_width()
+                    err.display_pretty()
+                        .with_terminal_width()
+                        .with_detected_color()
                 );
                 request_value(&err).unwrap_or(ExitCode::FAILURE)
             }
+            ExitResult::Diagnostics(diagnostics) => {
+                for diagnostic in &diagnostics {
+                    _ = write!(
+                        std::io::stderr(),
+                        "{}",
+                        diagnostic
+                            .display_pretty()
+                            .with_terminal_width()
+                            .with_detected_color()
+                    );
+                }
+
+                diagnostics_exit_code(&diagnostics)
+            }
+        }
+    }
+}
+
+pub(crate) fn is_failure<E: Error>(err: &E) -> bool {
+    // errors without a severity are treated as errors
+    err.errful().severity().is_none_or(|s| s.is_failure())
+}
+
+/// The exit code of the first failing diagnostic, if any.
+pub(crate) fn diagnostics_exit_code<E: Error>(diagnostics: &[E]) -> ExitCode {
+    match diagnostics.iter().find(|d| is_failure(*d)) {
+        Some(failure) => request_value(failure).unwrap_or(ExitCode::FAILURE),
+        None => ExitCode::SUCCESS,
+    }
+}
+
+/// Accumulates diagnostics while a program runs, so that they can all
+/// be reported at the end (by converting into an [`ExitResult`]).
+///
+/// Warnings and other non-failing diagnostics do not cause the program to fail,
+/// but any error-severity diagnostic does.
+pub struct DiagnosticCollector<E> {
+    diagnostics: Vec<E>,
+}
+
+impl<E> Default for DiagnosticCollector<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> DiagnosticCollector<E> {
+    pub fn new() -> Self {
+        DiagnosticCollector { diagnostics: Vec::new() }
+    }
+
+    pub fn push(&mut self, diagnostic: impl Into<E>) {
+        self.diagnostics.push(diagnostic.into());
+    }
+
+    /// Collects the error from a result, if any, returning the success value.
+    pub fn collect<T, EIn: Into<E>>(&mut self, result: Result<T, EIn>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.push(err);
+                None
+            }
         }
     }
+
+    pub fn diagnostics(&self) -> &[E] {
+        &self.diagnostics
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
+
+impl<E: Error> DiagnosticCollector<E> {
+    /// Whether any of the collected diagnostics has a failing severity.
+    pub fn has_failures(&self) -> bool {
+        self.diagnostics.iter().any(is_failure)
+    }
+
+    /// The exit code that the program will finish with.
+    pub fn exit_code(&self) -> ExitCode {
+        diagnostics_exit_code(&self.diagnostics)
+    }
+}
+
+impl<E> From<DiagnosticCollector<E>> for ExitResult<E> {
+    fn from(collector: DiagnosticCollector<E>) -> Self {
+        ExitResult::Diagnostics(collector.diagnostics)
+    }
 }
@@ -0,0 +1,71 @@
+use std::error::Error;
+
+use tracing::{Level, event};
+
+use crate::{AsErrful, Severity, protocol::LabelMessage};
+
+/// Emits a `tracing` event for the error.
+///
+/// Rather than formatting the whole error into the message, the details
+/// are recorded as separate fields so that they can be indexed:
+/// - `error.code`, `error.severity`, and `error.url`,
+/// - `error.labels`, the labels as `start..end: message`, separated by `; `,
+/// - `error.label_count`,
+/// - `error.sources`, the messages of the error's sources, separated by `: `.
+///
+/// The level of the event is chosen by [`PrintableSeverity::tracing_level`](crate::protocol::PrintableSeverity::tracing_level).
+pub fn trace(err: &(dyn Error + 'static)) {
+    let errful = err.errful();
+    let severity = errful.severity().unwrap_or(&Severity::Error);
+
+    let labels = errful.labels().unwrap_or_default();
+    let label_count = labels.len();
+    let labels = Vec::from_iter(labels.iter().map(|label| {
+        let span = label.span();
+        let message = match label.message() {
+            LabelMessage::Error(e) => e.to_string(),
+            LabelMessage::String(s) => s.to_string(),
+        };
+
+        format!(
+            "{}..{}: {message}",
+            span.start().as_usize(),
+            span.end().as_usize()
+        )
+    }))
+    .join("; ");
+
+    let mut sources = Vec::new();
+    let mut next = err.source();
+    while let Some(source) = next {
+        sources.push(source.to_string());
+        next = source.source();
+    }
+
+    let sources = sources.join(": ");
+    let url = errful.url();
+
+    // the level must be a constant in each event
+    macro_rules! emit {
+        ($level:expr) => {
+            event!(
+                $level,
+                error.code = errful.code(),
+                error.severity = severity.name(),
+                error.url = url.as_ref().map(|url| url.as_str()),
+                error.labels = (!labels.is_empty()).then_some(labels.as_str()),
+                error.label_count = label_count,
+                error.sources = (!sources.is_empty()).then_some(sources.as_str()),
+                "{err}"
+            )
+        };
+    }
+
+    match severity.tracing_level() {
+        Level::ERROR => emit!(Level::ERROR),
+        Level::WARN => emit!(Level::WARN),
+        Level::INFO => emit!(Level::INFO),
+        Level::DEBUG => emit!(Level::DEBUG),
+        Level::TRACE => emit!(Level::TRACE),
+    }
+}
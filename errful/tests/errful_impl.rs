@@ -60,6 +60,21 @@ fn variant_overrides_exit_code() {
     assert_eq!(over.exit_code(), Some(34.into()));
 }
 
+#[test]
+fn variant_exit_code_without_base() {
+    #[derive(errful_derive::Error, Debug)]
+    #[error(display = "some error")]
+    enum SomeError {
+        Base,
+
+        #[error(exit_code = 34)]
+        Override,
+    }
+
+    assert_eq!(SomeError::Base.errful().exit_code(), None);
+    assert_eq!(SomeError::Override.errful().exit_code(), Some(34.into()));
+}
+
 #[test]
 fn enum_source() {
     #[derive(errful_derive::Error, Debug)]
@@ -86,3 +101,26 @@ fn enum_source() {
         Some("Custom { kind: Other, error: \"something bad\" }")
     );
 }
+
+#[test]
+fn diagnostic_collector() {
+    #[derive(errful_derive::Error, Debug)]
+    #[error(display = "diagnostic", exit_code = 3)]
+    enum Diagnostic {
+        #[error(severity = errful::Severity::Warning)]
+        Warning,
+        #[error(severity = errful::Severity::Error)]
+        Error,
+    }
+
+    let mut collector = errful::DiagnosticCollector::<Diagnostic>::new();
+    assert_eq!(collector.collect(Ok::<_, Diagnostic>(1)), Some(1));
+    collector.push(Diagnostic::Warning);
+    assert!(!collector.has_failures());
+    assert_eq!(collector.exit_code(), std::process::ExitCode::SUCCESS);
+
+    assert_eq!(collector.collect(Err::<(), _>(Diagnostic::Error)), None);
+    assert!(collector.has_failures());
+    assert_eq!(collector.exit_code(), std::process::ExitCode::from(3));
+    assert_eq!(collector.diagnostics().len(), 2);
+}
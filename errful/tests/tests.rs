@@ -98,9 +98,7 @@ fn label() {
         span: Span<u8>,
     }
 
-    let value = E {
-        span: Span::new(0.into(), 1.into()),
-    };
+    let value = E { span: Span::new(0.into(), 1.into()) };
 
     assert_snapshot!(value.display_pretty_nocolor(), @r##"
     × Error: label-haver
@@ -149,3 +147,889 @@ fn label_with_field() {
        ┷
     "#);
 }
+
+#[test]
+fn related() {
+    #[derive(Debug, errful::Error)]
+    #[error(display = "unexpected character")]
+    struct Unexpected {
+        #[error(label = "here")]
+        span: Span<u8>,
+
+        #[error(source_code)]
+        code: &'static str,
+    }
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "failed to parse")]
+    struct Outer {
+        #[error(related)]
+        errors: Vec<Unexpected>,
+
+        #[error(source)]
+        inner: Inner,
+    }
+
+    let value = Outer {
+        errors: vec![
+            Unexpected { span: Span::new(1.into(), 1.into()), code: "a!b" },
+            Unexpected { span: Span::new(2.into(), 1.into()), code: "cd?" },
+        ],
+        inner: Inner {},
+    };
+
+    assert_snapshot!(value.display_pretty_nocolor(), @r#"
+    × Error: failed to parse
+
+    Details:
+     × ┐ failed to parse
+       │  × ┐ unexpected character
+       │    │   ┌
+       │    │ 1 │ a!b
+       │    │   │  ╿
+       │    │   │  └╴here
+       │    │   └
+       │    ┷
+       │  × ┐ unexpected character
+       │    │   ┌
+       │    │ 1 │ cd?
+       │    │   │   ╿
+       │    │   │   └╴here
+       │    │   └
+       │    ┷
+     1 ├▷ inner
+       ┷
+    "#);
+}
+
+#[test]
+fn display_fields() {
+    #[derive(Debug, errful::Error)]
+    #[error(display = "failed at {path}: {value:?} {{literal}}")]
+    struct Named {
+        path: String,
+        value: u32,
+    }
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "{1} then {0}")]
+    struct Tuple(&'static str, &'static str);
+
+    let named = Named { path: "a/b".to_string(), value: 12 };
+    assert_eq!(named.to_string(), "failed at a/b: 12 {literal}");
+
+    assert_eq!(Tuple("first", "second").to_string(), "second then first");
+}
+
+#[test]
+fn help() {
+    #[derive(Debug, errful::Error)]
+    #[error(display = "missing file", help = "create {path} and try again")]
+    struct E {
+        path: &'static str,
+    }
+
+    let value = E { path: "config.toml" };
+
+    assert_snapshot!(value.display_pretty_nocolor(), @r#"
+    × Error: missing file
+
+    Details:
+     × ┐ missing file
+       ┷
+
+    Help:
+      create config.toml and try again
+    "#);
+}
+
+#[test]
+fn help_variants() {
+    #[derive(Debug, errful::Error)]
+    #[error(display = "some error", help = "try again later")]
+    enum E {
+        Base,
+
+        #[error(help = "check the {{input}}")]
+        Override,
+
+        #[error(help = "check {input} for {count} mistakes")]
+        Named {
+            input: String,
+            count: usize,
+        },
+
+        #[error(help = "found {1} mistakes in {0}")]
+        Positional(String, usize),
+    }
+
+    assert_eq!(E::Base.errful().help().as_deref(), Some("try again later"));
+    assert_eq!(
+        E::Override.errful().help().as_deref(),
+        Some("check the {input}")
+    );
+
+    let named = E::Named { input: "config.toml".to_string(), count: 2 };
+    assert_eq!(
+        named.errful().help().as_deref(),
+        Some("check config.toml for 2 mistakes")
+    );
+
+    let positional = E::Positional("config.toml".to_string(), 2);
+    assert_eq!(
+        positional.errful().help().as_deref(),
+        Some("found 2 mistakes in config.toml")
+    );
+}
+
+#[test]
+fn multiple_sources() {
+    #[derive(Debug, errful::Error)]
+    #[error(display = "type mismatch")]
+    struct E {
+        #[error(source_code(id = "main.rs"))]
+        main: String,
+
+        #[error(source_code(id = "lib.rs"))]
+        lib: String,
+
+        #[error(label = "expected because of this", source_id = "lib.rs")]
+        declared: Span<u8>,
+
+        #[error(label = "found a string", source_id = "main.rs")]
+        used: Span<u8>,
+    }
+
+    let value = E {
+        main: "f(\"x\")".to_string(),
+        lib: "fn f(x: u32) {}".to_string(),
+        declared: Span::new(8.into(), 3.into()),
+        used: Span::new(2.into(), 3.into()),
+    };
+
+    assert_snapshot!(value.display_pretty_nocolor(), @r#"
+    × Error: type mismatch
+
+    Details:
+     × ┐ type mismatch
+       │   ┌────────┐
+       │   │ lib.rs │
+       │   ├────────╯
+       │ 1 │ fn f(x: u32) {}
+       │   │         ├─┘
+       │   │         └╴expected because of this
+       │   └
+       │   ┌─────────┐
+       │   │ main.rs │
+       │   ├─────────╯
+       │ 1 │ f("x")
+       │   │   ├─┘
+       │   │   └╴found a string
+       │   └
+       ┷
+    "#);
+}
+
+#[test]
+fn label_span_types() {
+    #[derive(Debug, errful::Error)]
+    #[error(display = "span types")]
+    struct E {
+        #[error(source_code)]
+        code: &'static str,
+
+        #[error(label = "a range")]
+        range: std::ops::Range<usize>,
+
+        #[error(label = "an offset and length")]
+        offset_len: (usize, usize),
+    }
+
+    let value = E {
+        code: "one two three",
+        range: 0..3,
+        offset_len: (8, 5),
+    };
+
+    assert_snapshot!(value.display_pretty_nocolor(), @r#"
+    × Error: span types
+
+    Details:
+     × ┐ span types
+       │   ┌
+       │ 1 │ one two three
+       │   │ ├─┘     ├───┘
+       │   │ └╴a range
+       │   │         └╴an offset and length
+       │   └
+       ┷
+    "#);
+}
+
+#[test]
+fn transparent() {
+    #[derive(Debug, errful::Error)]
+    #[error(display = "bad digit", code = "E001")]
+    struct Detailed {
+        #[error(source_code)]
+        code: &'static str,
+
+        #[error(label = "here")]
+        span: Span<u8>,
+
+        #[error(source)]
+        inner: Inner,
+    }
+
+    #[derive(Debug, errful::Error)]
+    #[error(transparent)]
+    struct Wrapper(Detailed);
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "something else")]
+    enum Either {
+        #[error(transparent)]
+        Detailed {
+            detailed: Detailed,
+        },
+        Other,
+    }
+
+    let detailed = || Detailed {
+        code: "12x",
+        span: Span::new(2.into(), 1.into()),
+        inner: Inner {},
+    };
+
+    let wrapper = Wrapper(detailed());
+    assert_eq!(wrapper.to_string(), "bad digit");
+    assert_eq!(wrapper.errful().code(), Some("E001"));
+    assert_eq!(
+        wrapper.display_pretty_nocolor().to_string(),
+        detailed().display_pretty_nocolor().to_string()
+    );
+
+    let either = Either::Detailed { detailed: detailed() };
+    assert_eq!(either.errful().code(), Some("E001"));
+    assert_snapshot!(either.display_pretty_nocolor(), @r#"
+    × Error [E001]
+    bad digit
+
+    Details:
+     × ┐ bad digit
+       │   ┌
+       │ 1 │ 12x
+       │   │   ╿
+       │   │   └╴here
+       │   └
+     1 ├▷ inner
+       ┷
+    "#);
+    assert_eq!(Either::Other.to_string(), "something else");
+}
+
+#[test]
+fn json() {
+    #[derive(Debug, errful::Error)]
+    #[error(display = "bad \"digit\"", code = "E001", url = "https://example.com")]
+    struct E {
+        #[error(source_code)]
+        code: &'static str,
+
+        #[error(label = "here")]
+        span: Span<u8>,
+
+        #[error(source)]
+        inner: Inner,
+    }
+
+    let value = E {
+        code: "12x",
+        span: Span::new(2.into(), 1.into()),
+        inner: Inner {},
+    };
+
+    assert_snapshot!(value.display_json(), @r#"
+    {"chain":[{"message":"bad \"digit\"","code":"E001","severity":"Error","url":"https://example.com/","help":null,"location":null,"labels":[{"message":"here","offset":2,"length":1,"source_id":null,"severity":null}],"related":[]},{"message":"inner","code":null,"severity":"Error","url":null,"help":null,"location":null,"labels":[],"related":[]}]}
+    "#);
+}
+
+#[test]
+fn ascii_glyphs_and_no_color_theme() {
+    #[derive(Debug, errful::Error)]
+    #[error(display = "outer")]
+    struct Outer {
+        #[error(source)]
+        inner: Inner,
+    }
+
+    let value = Outer { inner: Inner {} };
+
+    assert_snapshot!(
+        value
+            .display_pretty()
+            .with_theme(snippets::Theme::no_color())
+            .with_glyphs(errful::formatting::Glyphs::ascii()),
+        @r#"
+    × Error: outer
+
+    Details:
+     × + outer
+     1 |> inner
+       =
+    "#
+    );
+}
+
+#[test]
+fn context() {
+    use errful::Context;
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "bad digit", severity = errful::Severity::Warning, exit_code = 7)]
+    struct E {
+        #[error(source_code)]
+        code: &'static str,
+
+        #[error(label = "here")]
+        span: Span<u8>,
+    }
+
+    let result: Result<(), E> = Err(E { code: "12x", span: Span::new(2.into(), 1.into()) });
+    let value = result.context("while parsing header").unwrap_err();
+
+    assert_eq!(value.errful().exit_code(), Some(7.into()));
+    assert_snapshot!(value.display_pretty_nocolor(), @r#"
+    ⚠ Warning: while parsing header
+
+    Details:
+     ⚠ ┐ while parsing header
+     1 ├▷ bad digit
+       │   ┌
+       │ 1 │ 12x
+       │   │   ╿
+       │   │   └╴here
+       │   └
+       ┷
+    "#);
+}
+
+#[test]
+fn location() {
+    use std::panic::Location;
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "something went wrong")]
+    struct E {
+        #[error(location)]
+        location: &'static Location<'static>,
+    }
+
+    impl E {
+        #[track_caller]
+        fn new() -> Self {
+            E { location: Location::caller() }
+        }
+    }
+
+    let expected = Location::caller();
+    let value = E::new();
+    let location = value.errful().location().unwrap();
+    assert_eq!(location.file(), expected.file());
+    assert_eq!(location.line(), expected.line() + 1);
+
+    let pretty = value.display_pretty_nocolor().to_string();
+    assert!(pretty.contains(&format!("│ raised at {location}\n")));
+}
+
+#[test]
+fn label_severity() {
+    #[derive(Debug, errful::Error)]
+    #[error(display = "duplicate key")]
+    struct E {
+        #[error(source_code)]
+        input: &'static str,
+
+        #[error(label = "first defined here", severity = errful::Severity::Info)]
+        first: Span<u8>,
+
+        #[error(label = "redefined here", severity = errful::Severity::Error)]
+        second: Span<u8>,
+    }
+
+    let value = E {
+        input: "a = 1\na = 2",
+        first: Span::try_from(0..1usize).unwrap(),
+        second: Span::try_from(6..7usize).unwrap(),
+    };
+
+    let labels = value.errful().labels().unwrap();
+    assert_eq!(labels[0].severity().map(|s| s.name()), Some("Info"));
+    assert_eq!(labels[1].severity().map(|s| s.name()), Some("Error"));
+
+    let pretty = value.display_pretty().with_color(true).to_string();
+    assert_snapshot!(snippets::markup::ansi_to_markup(&pretty), @r#"
+    <red>×</red> <red><bold><underline>Error</underline></bold>:</red> duplicate key
+
+    <bold>Details:</bold>
+     <red>×</red> <red>┐</red> duplicate key
+    <red>   │ </red>  ┌
+    <red>   │ </red>1 │ <blue>a</blue> = 1
+    <red>   │ </red>  │ <blue>╿</blue>
+    <red>   │ </red>  │ <blue>└╴first defined here</blue>
+    <red>   │ </red>2 │ <red>a</red> = 2
+    <red>   │ </red>  │ <red>╿</red>
+    <red>   │ </red>  │ <red>└╴redefined here</red>
+    <red>   │ </red>  └
+       <red>┷</red>
+    "#);
+}
+
+#[test]
+fn source_types() {
+    use std::{error::Error, sync::Arc};
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "outer")]
+    struct Boxed {
+        source: Box<dyn Error + Send + Sync>,
+    }
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "outer")]
+    struct Shared {
+        #[error(source)]
+        inner: Arc<ParseIntError>,
+    }
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "outer")]
+    struct Optional {
+        source: Option<ParseIntError>,
+    }
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "outer")]
+    struct OptionalBoxed {
+        source: Option<Box<dyn Error + Send + Sync>>,
+    }
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "outer")]
+    struct Generic<E: Error + 'static> {
+        source: E,
+    }
+
+    let inner = || "x".parse::<i32>().unwrap_err();
+    let source = |e: &dyn Error| e.source().map(|s| s.to_string());
+    let expected = Some(inner().to_string());
+
+    assert_eq!(source(&Boxed { source: Box::new(inner()) }), expected);
+    assert_eq!(source(&Shared { inner: Arc::new(inner()) }), expected);
+    assert_eq!(source(&Optional { source: Some(inner()) }), expected);
+    assert_eq!(source(&Optional { source: None }), None);
+    assert_eq!(
+        source(&OptionalBoxed { source: Some(Box::new(inner())) }),
+        expected
+    );
+    assert_eq!(source(&OptionalBoxed { source: None }), None);
+    assert_eq!(source(&Generic { source: inner() }), expected);
+}
+
+#[test]
+fn aggregate() {
+    use std::process::ExitCode;
+
+    use errful::aggregate::{Aggregate, ExitPolicy};
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "invalid file", exit_code = 3)]
+    enum FileError {
+        #[error(severity = errful::Severity::Warning)]
+        Suspicious,
+        #[error(severity = errful::Severity::Error)]
+        Invalid,
+    }
+
+    let aggregate = Aggregate::from_iter([FileError::Suspicious, FileError::Invalid]);
+
+    assert!(aggregate.has_failures());
+    assert_eq!(aggregate.exit_code(), ExitCode::from(3));
+    assert_eq!(
+        std::error::request_value::<ExitCode>(&aggregate),
+        Some(ExitCode::from(3))
+    );
+
+    assert_snapshot!(aggregate.display_pretty_nocolor(), @r#"
+    × Error: 2 errors occurred
+
+    Details:
+     × ┐ 2 errors occurred
+       │  ⚠ ┐ invalid file
+       │    ┷
+       │  × ┐ invalid file
+       │    ┷
+       ┷
+    "#);
+
+    let aggregate = aggregate.with_exit_policy(ExitPolicy::Fixed(ExitCode::from(9)));
+    assert_eq!(aggregate.exit_code(), ExitCode::from(9));
+
+    let warnings = Aggregate::from_iter([FileError::Suspicious])
+        .with_exit_policy(ExitPolicy::Fixed(ExitCode::from(9)));
+    assert!(!warnings.has_failures());
+    assert_eq!(warnings.exit_code(), ExitCode::SUCCESS);
+
+    assert!(Aggregate::<FileError>::new().into_result().is_ok());
+}
+
+#[test]
+fn multiline_label() {
+    #[derive(Debug, errful::Error)]
+    #[error(display = "unclosed block")]
+    struct E {
+        #[error(source_code)]
+        input: &'static str,
+
+        #[error(label = "this block is never closed")]
+        block: Span<u8>,
+    }
+
+    let input = "fn main() {\n    body();\n";
+    let value = E {
+        input,
+        block: Span::try_from(10..input.len() - 1).unwrap(),
+    };
+
+    assert_snapshot!(value.display_pretty_nocolor(), @r#"
+    × Error: unclosed block
+
+    Details:
+     × ┐ unclosed block
+       │   ┌
+       │ 1 ┢╸fn main() {
+       │ 2 ┃     body();
+       │   ┡━╸this block is never closed
+       │   └
+       ┷
+    "#);
+}
+
+#[test]
+fn multiline_label_severity() {
+    #[derive(Debug, errful::Error)]
+    #[error(display = "unclosed block")]
+    struct E {
+        #[error(source_code)]
+        input: &'static str,
+
+        #[error(label = "this block is never closed", severity = errful::Severity::Error)]
+        block: Span<u8>,
+
+        #[error(label = "opened here", severity = errful::Severity::Info)]
+        brace: Span<u8>,
+    }
+
+    let input = "fn main() {\n    body();\n";
+    let value = E {
+        input,
+        block: Span::try_from(0..input.len() - 1).unwrap(),
+        brace: Span::try_from(10..11usize).unwrap(),
+    };
+
+    let pretty = value.display_pretty().with_color(true).to_string();
+    assert_snapshot!(snippets::markup::ansi_to_markup(&pretty), @r#"
+    <red>×</red> <red><bold><underline>Error</underline></bold>:</red> unclosed block
+
+    <bold>Details:</bold>
+     <red>×</red> <red>┐</red> unclosed block
+    <red>   │ </red>  ┌
+    <red>   │ </red>1 ┢╸fn main() <blue>{</blue>
+    <red>   │ </red>  ┃           <blue>╿</blue>
+    <red>   │ </red>  ┃ <blue>          └╴opened here</blue>
+    <red>   │ </red>2 ┃     body();
+    <red>   │ </red>  ┡━╸<red>this block is never closed</red>
+    <red>   │ </red>  └
+       <red>┷</red>
+    "#);
+}
+
+#[test]
+fn cause_chain() {
+    #[derive(Debug, errful::Error)]
+    #[error(display = "could not load config", code = "config::load")]
+    struct Load {
+        #[error(source)]
+        source: std::io::Error,
+    }
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "file is locked", code = "fs::locked", severity = errful::Severity::Warning)]
+    struct Locked {
+        #[error(source)]
+        source: Inner,
+    }
+
+    let io_error = || std::io::Error::other(Locked { source: Inner {} });
+    let value = Load { source: io_error() };
+
+    assert_snapshot!(value.display_pretty_nocolor(), @r#"
+    × Error [config::load]
+    could not load config
+
+    Details:
+     × ┐ could not load config
+     1 ├▷ [fs::locked] file is locked
+     2 ├──▷ inner
+       ┷
+    "#);
+
+    let value = Load { source: io_error() };
+    assert_snapshot!(value.display_pretty_nocolor().with_max_depth(1), @r#"
+    × Error [config::load]
+    could not load config
+
+    Details:
+     × ┐ could not load config
+     1 ├▷ [fs::locked] file is locked
+       │ … and 1 more cause
+       ┷
+    "#);
+
+    let value = Load { source: io_error() };
+    let pretty = value.display_pretty().with_color(true).to_string();
+    assert_snapshot!(snippets::markup::ansi_to_markup(&pretty), @r#"
+    <red>×</red> <red><bold><underline>Error</underline></bold></red> [config::load]
+    could not load config
+
+    <bold>Details:</bold>
+     <red>×</red> <red>┐</red> could not load config
+    <yellow><dimmed> 1</dimmed></yellow> <yellow>├▷</yellow> [fs::locked] file is locked
+    <red><dimmed> 2</dimmed></red> <red>├──▷</red> inner
+       <red>┷</red>
+    "#);
+
+    // causes hidden beyond the maximum depth are counted as they would be shown,
+    // looking through each io::Error to the error it wraps
+    #[derive(Debug, errful::Error)]
+    #[error(display = "could not reload config", code = "config::reload")]
+    struct Reload {
+        #[error(source)]
+        source: std::io::Error,
+    }
+
+    let reload = || Reload {
+        source: std::io::Error::other(Load { source: io_error() }),
+    };
+    assert_snapshot!(reload().display_pretty_nocolor(), @r#"
+    × Error [config::reload]
+    could not reload config
+
+    Details:
+     × ┐ could not reload config
+     1 ├▷ [config::load] could not load config
+     2 ├──▷ [fs::locked] file is locked
+     3 ├────▷ inner
+       ┷
+    "#);
+    assert_snapshot!(reload().display_pretty_nocolor().with_max_depth(1), @r#"
+    × Error [config::reload]
+    could not reload config
+
+    Details:
+     × ┐ could not reload config
+     1 ├▷ [config::load] could not load config
+       │ … and 2 more causes
+       ┷
+    "#);
+}
+
+#[test]
+fn extract() {
+    use std::{error::Error, process::ExitCode};
+
+    #[derive(Debug, errful::Error)]
+    #[error(
+        display = "bad digit",
+        exit_code = 7,
+        url = "https://example.com/digits"
+    )]
+    struct Digit {
+        #[error(source_code)]
+        input: &'static str,
+
+        #[error(label = "here")]
+        span: Span<u8>,
+    }
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "could not parse")]
+    struct Parse {
+        #[error(source)]
+        source: Digit,
+    }
+
+    let err: Box<dyn Error + Send + Sync> = Box::new(Parse {
+        source: Digit {
+            input: "12x4",
+            span: Span::try_from(2..3usize).unwrap(),
+        },
+    });
+
+    assert_eq!(errful::extract::<ExitCode>(&*err), Some(ExitCode::from(7)));
+    assert_eq!(
+        errful::extract_url(&*err).map(String::from),
+        Some("https://example.com/digits".to_string())
+    );
+
+    let (source_code, labels) = errful::extract_labels(&*err).unwrap();
+    assert_eq!(source_code, Some("12x4"));
+    assert_eq!(labels.len(), 1);
+    assert_eq!(labels[0].span(), Span::try_from(2..3usize).unwrap());
+
+    let inner = Inner {};
+    assert_eq!(errful::extract::<ExitCode>(&inner), None);
+    assert!(errful::extract_labels(&inner).is_none());
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn problem_json() {
+    use errful::http::HttpStatus;
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "invalid field", code = "E400", status = 422)]
+    struct Invalid {
+        #[error(source_code)]
+        body: &'static str,
+
+        #[error(label = "not a number")]
+        span: Span<u8>,
+
+        #[error(source)]
+        inner: Inner,
+    }
+
+    #[derive(Debug, errful::Error)]
+    #[error(
+        display = "could not create user",
+        url = "https://example.com/problems/user"
+    )]
+    struct Create {
+        #[error(source)]
+        source: Invalid,
+    }
+
+    let err = Create {
+        source: Invalid {
+            body: r#"{"age":"x"}"#,
+            span: Span::new(7.into(), 3.into()),
+            inner: Inner {},
+        },
+    };
+
+    let problem = err.display_problem_json();
+    assert_eq!(problem.status(), HttpStatus(422));
+    assert_snapshot!(problem, @r#"
+    {"type":"https://example.com/problems/user","title":"could not create user","status":422,"detail":"invalid field: inner","code":"E400","labels":[{"message":"not a number","offset":7,"length":3,"source_id":null}]}
+    "#);
+
+    let inner = Inner {};
+    assert_eq!(
+        inner.display_problem_json().status(),
+        HttpStatus::INTERNAL_SERVER_ERROR
+    );
+    assert_snapshot!(inner.display_problem_json(), @r#"
+    {"type":"about:blank","title":"inner","status":500}
+    "#);
+}
+
+#[test]
+fn from() {
+    use std::{error::Error, num::ParseIntError, panic::Location};
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "could not parse")]
+    struct Parse {
+        #[error(from)]
+        inner: ParseIntError,
+
+        #[error(location)]
+        location: &'static Location<'static>,
+    }
+
+    #[derive(Debug, errful::Error)]
+    #[error(display = "could not load")]
+    enum Load {
+        Parse(#[error(from)] Box<Parse>),
+        Missing {
+            #[error(from)]
+            source: Option<Inner>,
+        },
+    }
+
+    fn parse(s: &str) -> Result<i32, Parse> {
+        Ok(s.parse::<i32>()?)
+    }
+
+    let expected = Location::caller();
+    let err = parse("x").unwrap_err();
+    assert_eq!(err.location.line(), expected.line() - 3);
+    assert_eq!(
+        err.source().map(|e| e.to_string()),
+        Some("invalid digit found in string".to_string())
+    );
+
+    let load = Load::from(err);
+    assert!(matches!(&load, Load::Parse(p) if p.inner == "x".parse::<i32>().unwrap_err()));
+    assert!(matches!(
+        Load::from(Box::new(parse("y").unwrap_err())),
+        Load::Parse(_)
+    ));
+
+    let missing = Load::from(Inner {});
+    assert_eq!(
+        missing.source().map(|e| e.to_string()),
+        Some("inner".to_string())
+    );
+}
+
+#[test]
+fn label_with() {
+    #[derive(Debug, errful::Error)]
+    #[error(display = "duplicate key `{key}`")]
+    struct Duplicate {
+        #[error(source_code)]
+        input: &'static str,
+
+        #[error(label = "this key is repeated", label_with = "key_span")]
+        key: &'static str,
+    }
+
+    impl Duplicate {
+        // only searched for when the error is rendered
+        fn key_span(&self) -> Option<std::ops::Range<usize>> {
+            let start = self.input.rfind(self.key)?;
+            Some(start..start + self.key.len())
+        }
+    }
+
+    let value = Duplicate { input: "a = 1\nb = 2\na = 3", key: "a" };
+    assert_snapshot!(value.display_pretty_nocolor(), @r#"
+    × Error: duplicate key `a`
+
+    Details:
+     × ┐ duplicate key `a`
+       │   ┌
+       │ 1 │ a = 1
+       │ 2 │ b = 2
+       │ 3 │ a = 3
+       │   │ ╿
+       │   │ └╴this key is repeated
+       │   └
+       ┷
+    "#);
+
+    let missing = Duplicate { input: "b = 2", key: "a" };
+    assert_eq!(missing.errful().labels().map(|l| l.len()), Some(0));
+}
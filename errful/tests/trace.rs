@@ -0,0 +1,68 @@
+#![cfg_attr(feature = "tracing", feature(error_generic_member_access))]
+#![cfg(feature = "tracing")]
+
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use complex_indifference::Span;
+use insta::assert_snapshot;
+
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn trace_fields() {
+    #[derive(Debug, errful::Error)]
+    #[error(
+        display = "bad digit",
+        code = "E001",
+        severity = errful::Severity::Warning,
+        url = "https://example.com"
+    )]
+    struct E {
+        source: std::num::ParseIntError,
+
+        #[error(source_code)]
+        input: &'static str,
+
+        #[error(label = "here")]
+        location: Span<u8>,
+    }
+
+    let value = E {
+        source: "x".parse::<i32>().unwrap_err(),
+        input: "12x4",
+        location: Span::try_from(2..3usize).unwrap(),
+    };
+
+    let captured = Captured::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer({
+            let captured = captured.clone();
+            move || captured.clone()
+        })
+        .with_ansi(false)
+        .without_time()
+        .with_target(false)
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, || errful::trace(&value));
+
+    let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+    assert_snapshot!(output.trim(), @r#"
+    WARN bad digit error.code="E001" error.severity="Warning" error.url="https://example.com/" error.labels="2..3: here" error.label_count=1 error.sources="invalid digit found in string"
+    "#);
+}
@@ -0,0 +1,7 @@
+#![feature(error_generic_member_access)]
+
+#[derive(Debug, errful::Error)]
+#[error(display = "bad input", severity = std::cmp::Ordering::Less)]
+struct E;
+
+fn main() {}
@@ -0,0 +1,10 @@
+#![feature(error_generic_member_access)]
+
+#[derive(Debug, errful::Error)]
+#[error(help = "check the file at {path}")]
+enum E {
+    NotFound { path: String },
+    Denied,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+#![feature(error_generic_member_access)]
+
+#[derive(Debug, errful::Error)]
+#[error(display = "bad input")]
+struct E {
+    #[error(from)]
+    inner: std::num::ParseIntError,
+
+    input: String,
+}
+
+fn main() {}
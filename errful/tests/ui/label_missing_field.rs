@@ -0,0 +1,13 @@
+#![feature(error_generic_member_access)]
+
+#[derive(Debug, errful::Error)]
+#[error(display = "bad input")]
+struct E {
+    #[error(source_code)]
+    input: String,
+
+    #[error(label = inner)]
+    location: (usize, usize),
+}
+
+fn main() {}
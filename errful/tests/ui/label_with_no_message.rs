@@ -0,0 +1,13 @@
+#![feature(error_generic_member_access)]
+
+#[derive(Debug, errful::Error)]
+#[error(display = "duplicate key")]
+struct E {
+    #[error(source_code)]
+    input: String,
+
+    #[error(label_with = "key_span")]
+    key: String,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+#![feature(error_generic_member_access)]
+
+#[derive(Debug, errful::Error)]
+#[error(display = "bad input")]
+struct E {
+    #[error(source_code)]
+    input: String,
+
+    #[error(source_code)]
+    other_input: String,
+}
+
+fn main() {}
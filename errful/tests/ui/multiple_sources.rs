@@ -0,0 +1,12 @@
+#![feature(error_generic_member_access)]
+
+#[derive(Debug, errful::Error)]
+#[error(display = "bad input")]
+struct E {
+    source: std::num::ParseIntError,
+
+    #[error(source)]
+    other: std::num::ParseIntError,
+}
+
+fn main() {}
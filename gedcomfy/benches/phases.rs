@@ -0,0 +1,203 @@
+//! Benchmarks for each phase of reading a file: decoding (including ANSEL),
+//! line iteration (and, within that, finding the line breaks), record building,
+//! and schema conversion. Building the records into an arena instead is also
+//! measured, for comparison.
+//!
+//! The files are generated, in sizes of 1MB, 10MB, and 100MB, and each size is
+//! a separate group with its throughput given in bytes. Each phase is timed on
+//! its own using [`Reader::measure`]. To run only some of the benchmarks, give
+//! part of their names, e.g.:
+//!
+//! ```sh
+//! cargo bench -p gedcomfy --bench phases -- 10MB/ansel
+//! ```
+//!
+//! Criterion compares each run with the previous one, so that regressions stand
+//! out. Once the benchmarks have run, the throughput of each phase is also listed.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use complex_indifference::{Count, Rate};
+use criterion::{Criterion, Throughput};
+use gedcomfy::reader::{
+    Reader,
+    metrics::{Byte, Metrics},
+};
+
+const MB: usize = 1 << 20;
+
+const SIZES: [(&str, usize); 3] = [("1MB", MB), ("10MB", 10 * MB), ("100MB", 100 * MB)];
+
+const GIVEN_NAMES: [&str; 6] = ["John", "Mary", "Ada", "Thomas", "Edith", "Walter"];
+
+const SURNAMES: [&str; 4] = ["Smith", "Jones", "Brown", "Taylor"];
+
+const PLACES: [&str; 3] = [
+    "Leeds, Yorkshire, England",
+    "Manchester, Lancashire, England",
+    "York, Yorkshire, England",
+];
+
+#[derive(Clone, Copy)]
+enum Charset {
+    Utf8,
+    Ansel,
+}
+
+impl Charset {
+    fn name(self) -> &'static str {
+        match self {
+            Charset::Utf8 => "utf8",
+            Charset::Ansel => "ansel",
+        }
+    }
+
+    /// “José”, which has a combining accent in ANSEL.
+    fn accented_name(self) -> &'static [u8] {
+        match self {
+            Charset::Utf8 => "José".as_bytes(),
+            Charset::Ansel => b"Jos\xE2e",
+        }
+    }
+}
+
+/// Generates a file of at least `size` bytes, made of families
+/// of two parents and two children.
+fn generate(charset: Charset, size: usize) -> Vec<u8> {
+    let char = match charset {
+        Charset::Utf8 => "UTF-8",
+        Charset::Ansel => "ANSEL",
+    };
+
+    let mut output = Vec::with_capacity(size + 1024);
+    output.extend_from_slice(
+        format!(
+            "0 HEAD\n1 SOUR bench\n1 SUBM @SUBM@\n1 GEDC\n2 VERS 5.5.1\n\
+             2 FORM LINEAGE-LINKED\n1 CHAR {char}\n0 @SUBM@ SUBM\n1 NAME Benchmark\n"
+        )
+        .as_bytes(),
+    );
+
+    let mut family = 0;
+    while output.len() < size {
+        let first = family * 4;
+        for (i, role) in ["FAMS", "FAMS", "FAMC", "FAMC"].iter().enumerate() {
+            let id = first + i;
+            let given = GIVEN_NAMES[id % GIVEN_NAMES.len()];
+            let surname = SURNAMES[family % SURNAMES.len()];
+            let place = PLACES[id % PLACES.len()];
+            let year = 1800 + (id % 150);
+            let sex = if i % 2 == 0 { "M" } else { "F" };
+            let record = format!(
+                "0 @I{id}@ INDI\n1 NAME {given} /{surname}/\n1 SEX {sex}\n\
+                 1 BIRT\n2 DATE 12 MAR {year}\n2 PLAC {place}\n1 {role} @F{family}@\n1 NOTE "
+            );
+
+            output.extend_from_slice(record.as_bytes());
+            output.extend_from_slice(b"Godparent: ");
+            output.extend_from_slice(charset.accented_name());
+            output.extend_from_slice(b" ");
+            output.extend_from_slice(surname.as_bytes());
+            output.extend_from_slice(b"\n2 CONT Transcribed from the parish register.\n");
+        }
+
+        let record = format!(
+            "0 @F{family}@ FAM\n1 HUSB @I{}@\n1 WIFE @I{}@\n1 CHIL @I{}@\n1 CHIL @I{}@\n\
+             1 MARR\n2 DATE {}\n",
+            first,
+            first + 1,
+            first + 2,
+            first + 3,
+            1820 + (family % 150),
+        );
+
+        output.extend_from_slice(record.as_bytes());
+        family += 1;
+    }
+
+    output.extend_from_slice(b"0 TRLR\n");
+    output
+}
+
+/// Finds the time taken by a phase.
+type Elapsed = fn(&Metrics) -> Duration;
+
+/// The phases which are benchmarked.
+const PHASES: [(&str, Elapsed); 6] = [
+    ("decode", |metrics| metrics.decode.elapsed),
+    ("line splitting", |metrics| metrics.line_split.elapsed),
+    ("line iteration", |metrics| metrics.line_parse.elapsed),
+    ("record building", |metrics| metrics.record_build.elapsed),
+    ("arena building", |metrics| metrics.arena_build.elapsed),
+    ("schema conversion", |metrics| {
+        metrics.schema_conversion.elapsed
+    }),
+];
+
+/// The total number of bytes read and time taken by each benchmark,
+/// over all of its iterations.
+type Totals = BTreeMap<String, (Count<Byte>, Duration)>;
+
+fn phases(criterion: &mut Criterion, totals: &mut Totals) {
+    let reader = Reader::default();
+    for (size_name, size) in SIZES {
+        let mut group = criterion.benchmark_group(size_name);
+        if size > MB {
+            group.sample_size(10);
+        }
+
+        for charset in [Charset::Utf8, Charset::Ansel] {
+            let data = generate(charset, size);
+            let bytes = Count::<Byte>::new(data.len());
+            group.throughput(Throughput::Bytes(data.len() as u64));
+
+            // make sure that the file can be read at all
+            if let Err(err) = reader.measure(&data) {
+                panic!("{size_name}: {:?}", miette::Report::new(err.into_owned()));
+            }
+
+            for (phase, elapsed) in PHASES {
+                // the other phases do not depend on how the file was encoded
+                if matches!(charset, Charset::Ansel) && phase != "decode" {
+                    continue;
+                }
+
+                let name = format!("{}/{phase}", charset.name());
+                let total = totals.entry(format!("{size_name}/{name}")).or_default();
+                group.bench_function(name, |b| {
+                    b.iter_custom(|iterations| {
+                        let mut time = Duration::ZERO;
+                        for _ in 0..iterations {
+                            // UNWRAP: the file was read successfully above
+                            time += elapsed(&reader.measure(&data).unwrap());
+                        }
+
+                        total.0 += bytes * iterations as usize;
+                        total.1 += time;
+                        time
+                    });
+                });
+            }
+        }
+
+        group.finish();
+    }
+}
+
+fn main() {
+    let mut criterion = Criterion::default().configure_from_args();
+    let mut totals = Totals::new();
+    phases(&mut criterion, &mut totals);
+    criterion.final_summary();
+
+    println!("{:<32} {:>24}", "Benchmark", "Throughput (bytes)");
+    for (name, (bytes, elapsed)) in totals {
+        // benchmarks which were filtered out were never run
+        if elapsed.is_zero() {
+            continue;
+        }
+
+        let rate: Rate<Byte> = bytes / elapsed;
+        println!("{name:<32} {:>24}", rate.to_string());
+    }
+}
@@ -0,0 +1,68 @@
+//! Finding the age of each individual at each of their events, from the
+//! dates of the events and the date of their birth.
+//!
+//! Dates are parsed with [`DateRange::parse`], so an age is a range when
+//! either date is imprecise; for example, someone born in 1900 who died
+//! in 1950 was either 49 or 50 when they died.
+
+use miette::SourceSpan;
+
+use crate::{
+    reader::{Sourced, records::RawRecord},
+    schemas::dates::{AgeRange, DateRange},
+};
+
+/// The age of an individual at one of their events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventAge<'i> {
+    /// The xref of the individual.
+    pub xref: &'i str,
+    /// The tag of the event, such as `DEAT`.
+    pub event: &'i str,
+    pub age: AgeRange,
+    /// The span of the event’s date.
+    pub span: SourceSpan,
+}
+
+/// Finds the ages of individuals at each of their dated events (other
+/// than their birth), in the order they appear in the file.
+///
+/// Individuals without a birth date which can be parsed are left out.
+pub fn event_ages<'i>(records: &[Sourced<RawRecord<'i>>]) -> Vec<EventAge<'i>> {
+    let mut result = Vec::new();
+    for record in records {
+        let (Some(xref), "INDI") = (&record.line.xref, record.line.tag.as_str()) else {
+            continue;
+        };
+
+        let Some((birth, _)) = record.subrecords("BIRT").find_map(event_date) else {
+            continue;
+        };
+
+        for event in record.records.iter().map(|event| &event.sourced_value) {
+            let tag = event.line.tag.sourced_value.as_str();
+            if tag == "BIRT" {
+                continue;
+            }
+
+            if let Some((date, span)) = event_date(event) {
+                result.push(EventAge {
+                    xref: xref.sourced_value,
+                    event: tag,
+                    age: AgeRange::at(&birth, &date),
+                    span,
+                });
+            }
+        }
+    }
+
+    result
+}
+
+/// The first date of the event which can be parsed, and its span.
+pub(crate) fn event_date(event: &RawRecord) -> Option<(DateRange, SourceSpan)> {
+    event.subrecords("DATE").find_map(|date| {
+        let range = DateRange::parse(date.string_value()?)?;
+        Some((range, date.line.value.span))
+    })
+}
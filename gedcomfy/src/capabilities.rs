@@ -0,0 +1,122 @@
+//! What this build of the library supports, so that tools built on it can
+//! report it (for example, in bug reports).
+
+use crate::{reader::encodings::Encoding, versions::KnownVersion};
+
+/// The optional features of the crate, and whether each was enabled
+/// when it was compiled.
+const FEATURES: &[(&str, bool)] = &[
+    ("corpus", cfg!(feature = "corpus")),
+    ("gedzip", cfg!(feature = "gedzip")),
+    ("json", cfg!(feature = "json")),
+    ("kdl", cfg!(feature = "kdl")),
+    ("legacy-encodings", cfg!(feature = "legacy-encodings")),
+    ("miette-highlighting", cfg!(feature = "miette-highlighting")),
+    ("toml", cfg!(feature = "toml")),
+    ("turtle", cfg!(feature = "turtle")),
+    ("yaml", cfg!(feature = "yaml")),
+];
+
+/// A format which parsed files can be exported to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, derive_more::Display)]
+pub enum ExportMode {
+    /// [`Reader::parse_json`](crate::Reader::parse_json)
+    #[display("JSON")]
+    Json,
+    /// [`Reader::parse_kdl`](crate::Reader::parse_kdl)
+    #[display("KDL")]
+    Kdl,
+    /// [`Reader::parse_toml`](crate::Reader::parse_toml)
+    #[display("TOML")]
+    Toml,
+    /// [`Reader::parse_yaml`](crate::Reader::parse_yaml)
+    #[display("YAML")]
+    Yaml,
+    /// [`Reader::parse_ttl`](crate::Reader::parse_ttl)
+    #[display("Turtle")]
+    Turtle,
+    /// [`FamilyGraph::to_graphml`](crate::graph::FamilyGraph::to_graphml)
+    #[display("GraphML")]
+    Graphml,
+    /// [`FamilyGraph::to_gexf`](crate::graph::FamilyGraph::to_gexf)
+    #[display("GEXF")]
+    Gexf,
+}
+
+/// The export modes, and whether each was enabled when the crate was compiled.
+const EXPORT_MODES: &[(ExportMode, bool)] = &[
+    (ExportMode::Json, cfg!(feature = "json")),
+    (ExportMode::Kdl, cfg!(feature = "kdl")),
+    (ExportMode::Toml, cfg!(feature = "toml")),
+    (ExportMode::Yaml, cfg!(feature = "yaml")),
+    (ExportMode::Turtle, cfg!(feature = "turtle")),
+    (ExportMode::Graphml, true),
+    (ExportMode::Gexf, true),
+];
+
+/// What this build of the crate supports; see [`capabilities`].
+#[derive(Clone, Debug)]
+pub struct Capabilities {
+    /// The version of the `gedcomfy` crate.
+    pub version: &'static str,
+    /// The names of the enabled features, in alphabetical order.
+    pub features: Vec<&'static str>,
+    /// The versions of GEDCOM which can be read.
+    pub versions: &'static [KnownVersion],
+    /// The encodings which files can be decoded from.
+    pub encodings: &'static [Encoding],
+    /// The formats which parsed files can be exported to.
+    pub export_modes: Vec<ExportMode>,
+}
+
+/// Returns what this build of the crate supports, which depends upon
+/// the features it was compiled with.
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        version: env!("CARGO_PKG_VERSION"),
+        features: Vec::from_iter(
+            FEATURES
+                .iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(name, _)| *name),
+        ),
+        versions: KnownVersion::ALL,
+        encodings: Encoding::ALL,
+        export_modes: Vec::from_iter(
+            EXPORT_MODES
+                .iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(mode, _)| *mode),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn enabled_features() {
+        let capabilities = capabilities();
+        let features = &capabilities.features;
+        for (feature, enabled) in [
+            ("corpus", cfg!(feature = "corpus")),
+            ("json", cfg!(feature = "json")),
+            ("kdl", cfg!(feature = "kdl")),
+            ("yaml", cfg!(feature = "yaml")),
+        ] {
+            assert_eq!(features.contains(&feature), enabled, "{feature}");
+        }
+
+        assert!(features.is_sorted());
+        assert!(capabilities.versions.contains(&KnownVersion::V5_5_1));
+        assert!(capabilities.encodings.contains(&Encoding::Ansel));
+        for (mode, enabled) in [
+            (ExportMode::Json, cfg!(feature = "json")),
+            (ExportMode::Kdl, cfg!(feature = "kdl")),
+            (ExportMode::Gexf, true),
+        ] {
+            assert_eq!(capabilities.export_modes.contains(&mode), enabled, "{mode}");
+        }
+    }
+}
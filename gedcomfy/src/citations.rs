@@ -0,0 +1,96 @@
+//! Summarizing how well the events and attributes of individuals and families
+//! are supported by their source citations, using the certainty assessment
+//! (`QUAY`) of each citation.
+//!
+//! For example, the events which are supported only by unreliable evidence are
+//! those for which [`EventSupport::supported_only_by`] is true for
+//! [`CertaintyAssessment::Unreliable`].
+
+use miette::SourceSpan;
+
+use crate::{
+    normalize::EVENT_TAGS,
+    reader::{Sourced, records::RawRecord},
+    schemas::quality::CertaintyAssessment,
+};
+
+/// The citations of one event or attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventSupport<'i> {
+    /// The xref of the individual or family.
+    pub xref: &'i str,
+    /// The tag of the event, such as `BIRT`.
+    pub event: &'i str,
+    /// The certainty assessment of each citation of the event, in order;
+    /// `None` for citations without a (valid) `QUAY`.
+    pub qualities: Vec<Option<CertaintyAssessment>>,
+    /// The span of the event’s tag.
+    pub span: SourceSpan,
+}
+
+impl EventSupport<'_> {
+    /// The most reliable assessment of any of the citations.
+    pub fn best_quality(&self) -> Option<CertaintyAssessment> {
+        self.qualities.iter().flatten().max().copied()
+    }
+
+    /// Whether the event is cited, and every citation is assessed
+    /// as no more reliable than `max`.
+    pub fn supported_only_by(&self, max: CertaintyAssessment) -> bool {
+        !self.qualities.is_empty()
+            && self
+                .qualities
+                .iter()
+                .all(|quality| quality.is_some_and(|q| q <= max))
+    }
+}
+
+/// Finds the citations of each event and attribute of the individuals and
+/// families, in the order they appear in the file. Events without any
+/// citations are included, with no qualities.
+pub fn event_support<'i>(records: &[Sourced<RawRecord<'i>>]) -> Vec<EventSupport<'i>> {
+    let mut result = Vec::new();
+    for record in records {
+        let (Some(xref), "INDI" | "FAM") = (&record.line.xref, record.line.tag.as_str()) else {
+            continue;
+        };
+
+        for event in &record.records {
+            let tag = event.line.tag.sourced_value.as_str();
+            if EVENT_TAGS.contains(&tag) {
+                result.push(EventSupport {
+                    xref: xref.sourced_value,
+                    event: tag,
+                    qualities: citation_qualities(event).collect(),
+                    span: event.line.tag.span,
+                });
+            }
+        }
+    }
+
+    result
+}
+
+/// Finds the events which are supported only by citations assessed as no more
+/// reliable than `max`; see [`EventSupport::supported_only_by`].
+pub fn supported_only_by<'i>(
+    records: &[Sourced<RawRecord<'i>>],
+    max: CertaintyAssessment,
+) -> Vec<EventSupport<'i>> {
+    Vec::from_iter(
+        event_support(records)
+            .into_iter()
+            .filter(|support| support.supported_only_by(max)),
+    )
+}
+
+/// The certainty assessment of each citation (`SOUR`) of the record.
+pub(crate) fn citation_qualities<'r>(
+    record: &'r RawRecord,
+) -> impl Iterator<Item = Option<CertaintyAssessment>> + use<'r> {
+    record.subrecords("SOUR").map(|citation| {
+        citation
+            .subrecords("QUAY")
+            .find_map(|quay| CertaintyAssessment::parse(quay.string_value()?))
+    })
+}
@@ -0,0 +1,616 @@
+//! Documentation for each of the diagnostic codes which can be produced
+//! when reading a GEDCOM file, so that tools can explain them in more
+//! detail than the diagnostic itself.
+
+/// The documentation for a diagnostic code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeDocumentation {
+    pub code: &'static str,
+    /// A one-line summary of the problem.
+    pub summary: &'static str,
+    /// What causes the problem, and how it can be fixed.
+    pub explanation: &'static str,
+    /// An example of GEDCOM which produces the diagnostic, if there is a useful one.
+    pub example: Option<&'static str>,
+    /// The part of the GEDCOM specification which is relevant.
+    pub specification: Option<&'static str>,
+}
+
+const GRAMMAR: Option<&str> = Some("GEDCOM 5.5.1, Chapter 1: Data Representation Grammar");
+const LINEAGE_LINKED: Option<&str> = Some("GEDCOM 5.5.1, Chapter 2: Lineage-Linked Grammar");
+const CHARACTER_SETS: Option<&str> = Some("GEDCOM 5.5.1, Chapter 3: Using ANSEL and UNICODE");
+
+/// The documentation for every diagnostic code, sorted by code.
+pub const CODES: &[CodeDocumentation] = &[
+    CodeDocumentation {
+        code: "gedcom::encoding::external_encoding_mismatch",
+        summary: "The encoding given in the header does not match the byte-order mark",
+        explanation: "The file starts with a byte-order mark (BOM) for one encoding, but the \
+            CHAR record in the header names a different encoding. The BOM is more reliable, \
+            so the header should be corrected to match it.",
+        example: Some("<UTF-8 BOM>0 HEAD\n1 CHAR ANSEL"),
+        specification: CHARACTER_SETS,
+    },
+    CodeDocumentation {
+        code: "gedcom::encoding::file_encoding_mismatch",
+        summary: "The encoding given in the header does not match the encoding of the file",
+        explanation: "The encoding detected from the contents of the file is different from \
+            the one named by the CHAR record in the header. The header should be corrected, \
+            or the encoding can be chosen with the `--force-encoding` option.",
+        example: Some("0 HEAD\n1 CHAR UNICODE"),
+        specification: CHARACTER_SETS,
+    },
+    CodeDocumentation {
+        code: "gedcom::encoding::invalid_bom",
+        summary: "The byte-order mark is for an unsupported encoding",
+        explanation: "The file starts with a byte-order mark (BOM) for an encoding such as \
+            UTF-32, which is not permitted by any version of GEDCOM. The file should be \
+            converted to UTF-8.",
+        example: None,
+        specification: CHARACTER_SETS,
+    },
+    CodeDocumentation {
+        code: "gedcom::encoding::invalid_data",
+        summary: "The file contains data which is not valid in its encoding",
+        explanation: "Some bytes in the file cannot be decoded using the encoding of the file. \
+            This usually means that the encoding in the header is wrong, or that the file \
+            was edited with a tool that used a different encoding.",
+        example: Some("0 HEAD\n1 CHAR UTF-8\n0 @I1@ INDI\n1 NAME Ren<0xE9> /Dupont/"),
+        specification: CHARACTER_SETS,
+    },
+    CodeDocumentation {
+        code: "gedcom::encoding::invalid_encoding",
+        summary: "The header names an unknown encoding",
+        explanation: "The value of the CHAR record in the header is not one of the encodings \
+            defined by GEDCOM (such as ANSEL, ASCII, UNICODE, or UTF-8), or a commonly-used \
+            alternative.",
+        example: Some("0 HEAD\n1 CHAR EBCDIC"),
+        specification: CHARACTER_SETS,
+    },
+    CodeDocumentation {
+        code: "gedcom::encoding::missing_encoding",
+        summary: "The header does not give the encoding",
+        explanation: "GEDCOM 5 files must name their encoding in a CHAR record in the header. \
+            The encoding can be chosen with the `--force-encoding` option.",
+        example: Some("0 HEAD\n1 GEDC\n2 VERS 5.5.1"),
+        specification: CHARACTER_SETS,
+    },
+    CodeDocumentation {
+        code: "gedcom::encoding::repeated_bom",
+        summary: "The byte-order mark is repeated",
+        explanation: "The file starts with more than one byte-order mark (BOM), usually because \
+            it was saved several times by a tool which always adds one. The repeated marks \
+            are ignored when the `--skip-repeated-bom` option is given.",
+        example: Some("<UTF-8 BOM><UTF-8 BOM>0 HEAD"),
+        specification: CHARACTER_SETS,
+    },
+    CodeDocumentation {
+        code: "gedcom::encoding::trailing_nuls",
+        summary: "The file ends with NUL characters",
+        explanation: "The file is padded with NUL characters after the trailer, which some \
+            tools do to reach a fixed size. They are ignored when the `--trim-trailing-nuls` \
+            option is given.",
+        example: Some("0 TRLR\n<NUL><NUL><NUL>"),
+        specification: None,
+    },
+    CodeDocumentation {
+        code: "gedcom::encoding::version_encoding_mismatch",
+        summary: "The encoding is not permitted by the version of GEDCOM",
+        explanation: "Each version of GEDCOM only permits some encodings; for example, \
+            GEDCOM 7.0 files must be UTF-8. The file should be converted to an encoding \
+            which the version permits.",
+        example: Some("0 HEAD\n1 GEDC\n2 VERS 7.0\n1 CHAR ANSEL"),
+        specification: CHARACTER_SETS,
+    },
+    CodeDocumentation {
+        code: "gedcom::encoding_reason::assumed",
+        summary: "The encoding was assumed",
+        explanation: "No encoding was given by the file, so the default encoding for its \
+            version of GEDCOM was assumed.",
+        example: None,
+        specification: CHARACTER_SETS,
+    },
+    CodeDocumentation {
+        code: "gedcom::encoding_reason::bom",
+        summary: "The encoding was detected from the byte-order mark",
+        explanation: "The encoding of the file was chosen because of the byte-order mark (BOM) \
+            at the start of the file.",
+        example: None,
+        specification: CHARACTER_SETS,
+    },
+    CodeDocumentation {
+        code: "gedcom::encoding_reason::forced",
+        summary: "The encoding was chosen explicitly",
+        explanation: "The encoding of the file was given by the parsing options \
+            (such as `--force-encoding`), rather than being detected.",
+        example: None,
+        specification: None,
+    },
+    CodeDocumentation {
+        code: "gedcom::encoding_reason::header",
+        summary: "The encoding was given by the header",
+        explanation: "The encoding of the file was chosen because it was named by the CHAR \
+            record in the header.",
+        example: Some("0 HEAD\n1 CHAR UTF-8"),
+        specification: CHARACTER_SETS,
+    },
+    CodeDocumentation {
+        code: "gedcom::encoding_reason::sniffed",
+        summary: "The encoding was detected from the first bytes of the file",
+        explanation: "The encoding of the file was chosen because of the way in which the \
+            first characters (`0 HEAD`) were encoded; for example, UTF-16 without a \
+            byte-order mark.",
+        example: None,
+        specification: CHARACTER_SETS,
+    },
+    CodeDocumentation {
+        code: "gedcom::kdl::invalid_tag",
+        summary: "A KDL node cannot be converted to GEDCOM, because its name is not a tag",
+        explanation: "When converting KDL back to GEDCOM, the name of each node is used as \
+            the tag of the record, so it must be a valid GEDCOM tag: an uppercase letter or \
+            underscore, followed by letters or numbers.",
+        example: Some("INDI xref=\"I1\" {\n    name \"John /Smith/\"\n}"),
+        specification: GRAMMAR,
+    },
+    CodeDocumentation {
+        code: "gedcom::kdl::invalid_value",
+        summary: "A KDL value cannot be converted to GEDCOM, because it is not a string",
+        explanation: "When converting KDL back to GEDCOM, the values of records and their \
+            xrefs must be strings. The `see` property (for pointers) can also be null, which \
+            is converted to `@VOID@`.",
+        example: Some("INDI xref=\"I1\" {\n    NAME 42\n}"),
+        specification: None,
+    },
+    CodeDocumentation {
+        code: "gedcom::kdl::syntax",
+        summary: "A KDL document cannot be converted to GEDCOM, because it is not valid KDL",
+        explanation: "The document could not be parsed as KDL, so it could not be converted \
+            back to GEDCOM.",
+        example: Some("INDI xref=\"I1\" {"),
+        specification: None,
+    },
+    CodeDocumentation {
+        code: "gedcom::kdl::unexpected_entry",
+        summary: "A KDL node has an entry which cannot be converted to GEDCOM",
+        explanation: "When converting KDL back to GEDCOM, each node can have an `xref` \
+            property, and either a single string argument (the value of the record) or a \
+            `see` property (a pointer to another record). Any other entries cannot be \
+            converted.",
+        example: Some("INDI xref=\"I1\" {\n    NAME \"John\" \"Smith\"\n}"),
+        specification: None,
+    },
+    CodeDocumentation {
+        code: "gedcom::limit::archive_too_large",
+        summary: "The GEDCOM file in a GEDZip archive is larger than the maximum size",
+        explanation: "Once extracted, the GEDCOM file in the archive is larger than the file \
+            could be to decode within the maximum size set by the parsing options (such as \
+            `--max-decoded-size`). The archive is not extracted any further, so that a \
+            corrupted archive cannot use unreasonable amounts of memory.",
+        example: None,
+        specification: None,
+    },
+    CodeDocumentation {
+        code: "gedcom::limit::input_too_large",
+        summary: "The file is larger than the maximum size",
+        explanation: "Once decoded, the file is larger than the maximum size set by the \
+            parsing options (such as `--max-decoded-size`). The limit protects against \
+            corrupted files using unreasonable amounts of memory; it can be raised if \
+            the file is genuinely this large.",
+        example: None,
+        specification: None,
+    },
+    CodeDocumentation {
+        code: "gedcom::limit::level_too_deep",
+        summary: "A line has a level deeper than the maximum",
+        explanation: "The level of a line is deeper than the maximum set by the parsing \
+            options (such as `--max-level`). Real files rarely go deeper than a few levels, \
+            so this usually means that the file is corrupted.",
+        example: Some("0 @I1@ INDI\n1 BIRT\n2 DATE 1900\n3 _A\n4 _B\n5 _C"),
+        specification: GRAMMAR,
+    },
+    CodeDocumentation {
+        code: "gedcom::limit::line_too_long",
+        summary: "A line is longer than the maximum length",
+        explanation: "A line is longer than the maximum set by the parsing options (such as \
+            `--max-line-length`). GEDCOM 5.5.1 limits lines to 255 characters, and longer \
+            values should be split using CONC records.",
+        example: None,
+        specification: GRAMMAR,
+    },
+    CodeDocumentation {
+        code: "gedcom::limit::too_many_records",
+        summary: "The file contains more records than the maximum",
+        explanation: "The number of records (including subrecords) in the file is more than \
+            the maximum set by the parsing options (such as `--max-records`).",
+        example: None,
+        specification: None,
+    },
+    CodeDocumentation {
+        code: "gedcom::parse_error::incomplete_pointer",
+        summary: "A pointer does not end with `@`",
+        explanation: "A value which starts with `@` is a pointer to another record, and must \
+            also end with `@`. If the value is text which starts with `@`, the `@` should be \
+            doubled (`@@`).",
+        example: Some("1 FAMC @F1"),
+        specification: GRAMMAR,
+    },
+    CodeDocumentation {
+        code: "gedcom::parse_error::invalid_level",
+        summary: "A line does not start with a level number",
+        explanation: "Every line must start with a level number, which gives its depth in the \
+            record. This often happens when a value contains a line break which should have \
+            been written using a CONT record; see the `--repair-line-breaks` option.",
+        example: Some("1 NOTE First line\nsecond line"),
+        specification: GRAMMAR,
+    },
+    CodeDocumentation {
+        code: "gedcom::parse_error::invalid_tag",
+        summary: "A tag contains an invalid character",
+        explanation: "Tags must start with an uppercase letter or an underscore \
+            (for user-defined tags), followed by letters or numbers.",
+        example: Some("1 birt"),
+        specification: GRAMMAR,
+    },
+    CodeDocumentation {
+        code: "gedcom::parse_error::line_break_in_value",
+        summary: "A value contains a line break",
+        explanation: "A line which could not be parsed was joined onto the value of the line \
+            before it. Line breaks in values should be written using CONT records.",
+        example: Some("1 NOTE First line\nsecond line"),
+        specification: GRAMMAR,
+    },
+    CodeDocumentation {
+        code: "gedcom::parse_error::no_space",
+        summary: "A line has no space after the level",
+        explanation: "Every line must consist of a level and a tag (and optionally an xref \
+            and a value), separated by spaces.",
+        example: Some("1BIRT"),
+        specification: GRAMMAR,
+    },
+    CodeDocumentation {
+        code: "gedcom::parse_error::no_tag",
+        summary: "A line has no tag",
+        explanation: "Every line must have a tag after its level (and xref, if it has one).",
+        example: Some("0 @I1@"),
+        specification: GRAMMAR,
+    },
+    CodeDocumentation {
+        code: "gedcom::parse_error::reserved_xref",
+        summary: "A record uses a reserved xref",
+        explanation: "The xref `@VOID@` is reserved for pointers which do not point to \
+            any record, so it cannot be used as the xref of a record.",
+        example: Some("0 @VOID@ INDI"),
+        specification: Some("GEDCOM 7.0, Section 1.3.3: Pointers"),
+    },
+    CodeDocumentation {
+        code: "gedcom::possible_encodings",
+        summary: "The file could be read using another encoding",
+        explanation: "The file could not be decoded using its encoding, but could have been \
+            decoded using one of the listed encodings instead. The encoding can be chosen \
+            with the `--force-encoding` option.",
+        example: None,
+        specification: CHARACTER_SETS,
+    },
+    CodeDocumentation {
+        code: "gedcom::record_error::invalid_child_level",
+        summary: "A line has a level which is too deep",
+        explanation: "The level of each line can be at most one more than the level of the \
+            line before it.",
+        example: Some("0 @I1@ INDI\n2 DATE 1 JAN 1900"),
+        specification: GRAMMAR,
+    },
+    CodeDocumentation {
+        code: "gedcom::record_error::value_missing",
+        summary: "A record has neither a value nor subrecords",
+        explanation: "A record which has no subrecords should have a value, since \
+            otherwise it contains no information.",
+        example: Some("1 NOTE"),
+        specification: GRAMMAR,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::ancestry_cycle",
+        summary: "An individual is their own ancestor",
+        explanation: "Following the parents of the individual (through the families in \
+            which they are a child) leads back to the individual. One of the links between \
+            the families is probably wrong; for example, a child may have been added to \
+            their own family.",
+        example: Some("0 @I1@ INDI\n0 @F1@ FAM\n1 HUSB @I1@\n1 CHIL @I1@"),
+        specification: None,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::burial_before_death",
+        summary: "An individual was buried before they died",
+        explanation: "The date of the individual's burial (BURI) is before the date of their \
+            death (DEAT). One of the dates may have been recorded incorrectly.",
+        example: Some("0 @I1@ INDI\n1 DEAT\n2 DATE 1870\n1 BURI\n2 DATE 1869"),
+        specification: None,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::duplicate_spouse",
+        summary: "A family has the same individual as more than one spouse",
+        explanation: "The same individual is listed as both the husband and the wife of \
+            a family (or as a spouse more than once). This is usually a mistake made when \
+            linking the family.",
+        example: Some("0 @F1@ FAM\n1 HUSB @I1@\n1 WIFE @I1@"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::duplicate_subrecord",
+        summary: "A subrecord which may only appear once was repeated, and ignored",
+        explanation: "The record has more than one of a subrecord which may appear at most \
+            once, such as the DATE of an event. Only one of them was kept, because the \
+            `--duplicate-subrecords` option was given; without it, this is an error.",
+        example: Some("1 BIRT\n2 DATE 1850\n2 DATE 1851"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::excess_subrecords",
+        summary: "A record has too many subrecords with the same tag",
+        explanation: "The specification limits how many times some subrecords can appear; \
+            for example, an individual can have only one SEX record.",
+        example: Some("0 @I1@ INDI\n1 SEX M\n1 SEX F"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::married_too_young",
+        summary: "A spouse was younger than the minimum age when married",
+        explanation: "The date of a family's marriage (MARR) is before one of its spouses would \
+            have reached the minimum age of marriage that was configured. This is only \
+            checked when a minimum age is given.",
+        example: Some(
+            "0 @I1@ INDI\n1 BIRT\n2 DATE 1800\n0 @F1@ FAM\n1 WIFE @I1@\n1 MARR\n2 DATE 1810",
+        ),
+        specification: None,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::missing_associate",
+        summary: "An association points to a record which does not exist",
+        explanation: "The ASSO record points to an xref which is not used by any record \
+            in the file.",
+        example: Some("0 @I1@ INDI\n1 ASSO @I9@\n2 RELA Godfather"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::missing_submitter",
+        summary: "A pointer to a submitter refers to a record which does not exist",
+        explanation: "The SUBM record points to an xref which is not used by any \
+            submitter record in the file.",
+        example: Some("0 HEAD\n1 SUBM @U9@"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::missing_subrecord",
+        summary: "A record is missing a required subrecord",
+        explanation: "The specification requires this record to contain a subrecord with \
+            the given tag; for example, the header must contain a GEDC record.",
+        example: Some("0 HEAD\n1 CHAR UTF-8"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::mother_too_old",
+        summary: "A mother was older than 60 at the birth of her child",
+        explanation: "The wife of a family would have been older than 60 when one of the \
+            children of the family was born. One of the dates may have been recorded \
+            incorrectly, or the child may have been linked to the wrong family.",
+        example: Some(
+            "0 @I1@ INDI\n1 BIRT\n2 DATE 1800\n0 @I2@ INDI\n1 BIRT\n2 DATE 1870\n0 @F1@ FAM\n1 WIFE @I1@\n1 CHIL @I2@",
+        ),
+        specification: None,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::mother_too_young",
+        summary: "A mother was younger than 13 at the birth of her child",
+        explanation: "The wife of a family would have been younger than 13 when one of the \
+            children of the family was born. One of the dates may have been recorded \
+            incorrectly, or the child may have been linked to the wrong family.",
+        example: Some(
+            "0 @I1@ INDI\n1 BIRT\n2 DATE 1800\n0 @I2@ INDI\n1 BIRT\n2 DATE 1810\n0 @F1@ FAM\n1 WIFE @I1@\n1 CHIL @I2@",
+        ),
+        specification: None,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::multiple_birth_families",
+        summary: "An individual is a child of more than one birth family",
+        explanation: "The individual is linked as a child (FAMC) to more than one family, \
+            and the links do not say how the individual is related to the families. If the \
+            individual was adopted or fostered, the PEDI record of the link should say so.",
+        example: Some("0 @I1@ INDI\n1 FAMC @F1@\n1 FAMC @F2@"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::place_anachronism",
+        summary: "An event happened in a place before or after it existed",
+        explanation: "The place of the event is in a jurisdiction (such as a country) which \
+            did not exist at the date of the event. The place may have been recorded using \
+            its modern name, rather than the name at the time of the event.",
+        example: Some("1 BIRT\n2 DATE 1850\n2 PLAC Berlin, East Germany"),
+        specification: Some("GEDCOM 5.5.1, Appendix A: PLACe"),
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::poorly_supported_event",
+        summary: "An event is only cited by unreliable sources",
+        explanation: "None of the citations of the event have a certainty assessment (QUAY) \
+            as high as the minimum which was requested. Better evidence for the event could \
+            be found, or the assessments of its citations could be added or corrected.",
+        example: Some("1 BIRT\n2 SOUR @S1@\n3 QUAY 0"),
+        specification: Some("GEDCOM 5.5.1, Appendix A: QUAY"),
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::too_many_spouses",
+        summary: "A family has more than two spouses",
+        explanation: "A family has at most one husband and one wife. Some programs record \
+            polygamous marriages as a single family with more spouses; if this is intended, \
+            a user-defined _POLY record in the family suppresses this warning.",
+        example: Some("0 @F1@ FAM\n1 HUSB @I1@\n1 WIFE @I2@\n1 WIFE @I3@"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::unexpected_subrecord",
+        summary: "A record contains a subrecord which is not permitted there",
+        explanation: "The specification does not permit a subrecord with this tag inside \
+            its parent record. User-defined tags should start with an underscore.",
+        example: Some("0 @I1@ INDI\n1 WIFE @I2@"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::unknown_record",
+        summary: "A top-level record has an unknown tag",
+        explanation: "Top-level records must be one of those defined by the specification \
+            (such as INDI, FAM, or SOUR), or a user-defined record whose tag starts with \
+            an underscore.",
+        example: Some("0 @P1@ PERSON"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::unknown_temple_code",
+        summary: "A temple code is not known",
+        explanation: "The value of a TEMP record should be one of the temple codes listed \
+            by the specification.",
+        example: Some("1 BAPL\n2 TEMP NOWHERE"),
+        specification: Some("GEDCOM 5.5.1, Appendix B: Temple codes"),
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::unreferenced_submitter",
+        summary: "A submitter is not referred to by any record",
+        explanation: "A submitter record was found, but neither the header nor any other \
+            record points to it.",
+        example: Some("0 @U1@ SUBM\n1 NAME Someone"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema::unsupported_version",
+        summary: "Records of this version of GEDCOM cannot be parsed yet",
+        explanation: "Files of this version can be decoded and validated, and their raw \
+            records read, but they cannot yet be parsed into the typed records of their \
+            version.",
+        example: Some("0 HEAD\n1 GEDC\n2 VERS 7.0"),
+        specification: None,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema_error::missing_head_record",
+        summary: "The file does not start with a header",
+        explanation: "Every GEDCOM file must start with a HEAD record, which describes the \
+            version and encoding of the file.",
+        example: Some("0 @I1@ INDI"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema_error::missing_trailer_record",
+        summary: "The file does not end with a trailer",
+        explanation: "Every GEDCOM file must end with a TRLR record. A missing trailer \
+            often means that the file was truncated.",
+        example: None,
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::schema_error::records_after_trailer",
+        summary: "There are records after the trailer",
+        explanation: "The TRLR record must be the last record in the file. Any records after \
+            it are ignored by most tools.",
+        example: Some("0 TRLR\n0 @I1@ INDI"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::search::invalid_pattern",
+        summary: "A search pattern is not a valid regular expression",
+        explanation: "The pattern could not be parsed as a regular expression. Special \
+            characters (such as `(` or `*`) must be escaped with a backslash to match \
+            them literally.",
+        example: None,
+        specification: None,
+    },
+    CodeDocumentation {
+        code: "gedcom::version::missing",
+        summary: "The version of the file could not be found",
+        explanation: "The header should contain a GEDC record with a VERS subrecord giving \
+            the version of GEDCOM. The version can be chosen with the `--force-version` \
+            option.",
+        example: Some("0 HEAD\n1 CHAR UTF-8"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::version::too_old",
+        summary: "The file uses a version of GEDCOM from before 5.x",
+        explanation: "GEDCOM 2.x, 3.0, and 4.x files cannot be read. These are detected from \
+            a GEDC.VERS record giving a version before 5.x, or from a header with a SOUR \
+            record but no GEDC record. Such files should be converted to GEDCOM 5.5.1 with \
+            another genealogy program first.",
+        example: Some("0 HEAD\n1 SOUR PAF\n2 VERS 2.1"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::version::unsupported",
+        summary: "The version of the file is not supported",
+        explanation: "Only GEDCOM 5.5, 5.5.1, and 7.0 files can be read. Files using other \
+            versions can be read as one of these using the `--force-version` option.",
+        example: Some("0 HEAD\n1 GEDC\n2 VERS 6.0"),
+        specification: LINEAGE_LINKED,
+    },
+    CodeDocumentation {
+        code: "gedcom::visitor::annotation",
+        summary: "A note made about a record by a record visitor",
+        explanation: "A record visitor supplied by the program reading the file (for example, \
+            one which normalizes producer-specific tags) noted something about the record. \
+            The message explains what it was.",
+        example: None,
+        specification: None,
+    },
+];
+
+/// The documentation for the diagnostic code, if it is known.
+pub fn explain(code: &str) -> Option<&'static CodeDocumentation> {
+    CODES
+        .binary_search_by(|doc| doc.code.cmp(code))
+        .ok()
+        .map(|ix| &CODES[ix])
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn codes_are_sorted() {
+        assert!(CODES.is_sorted_by_key(|doc| doc.code));
+    }
+
+    /// Finds all of the `code(...)` attributes in the source files.
+    fn find_codes(dir: &Path, found: &mut Vec<String>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                find_codes(&path, found);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                let source = std::fs::read_to_string(&path).unwrap();
+                for (ix, _) in source.match_indices("code(") {
+                    let rest = source[ix + "code(".len()..].trim_start_matches('"');
+                    if rest.starts_with("gedcom::") {
+                        let end = rest.find(['"', ')']).unwrap();
+                        found.push(rest[..end].to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn all_codes_are_documented() {
+        let mut found = Vec::new();
+        find_codes(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+            &mut found,
+        );
+        assert!(!found.is_empty());
+
+        let undocumented: Vec<_> = found
+            .iter()
+            .filter(|code| explain(code).is_none())
+            .collect();
+        assert!(
+            undocumented.is_empty(),
+            "undocumented codes: {undocumented:?}"
+        );
+    }
+}
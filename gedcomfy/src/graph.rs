@@ -0,0 +1,358 @@
+//! Exporting the relationships between individuals as a graph, for analysis
+//! in tools such as Gephi (using GEXF) or Cytoscape (using GraphML).
+//!
+//! Each individual is a node, with their name, sex, and years of birth and death
+//! as attributes. The edges are typed: `spouse` edges (which are undirected) join
+//! the partners in a family, `parent-child` edges go from each partner in a family
+//! to each of its children, and `association` edges go from an individual to
+//! their associates (`ASSO`), labelled with the relation (`RELA`).
+
+use std::{collections::HashSet, fmt::Write};
+
+use crate::{
+    reader::{Sourced, records::RawRecord},
+    schemas::years_of,
+};
+
+/// The individuals in a file, and the relationships between them.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FamilyGraph<'i> {
+    /// The individuals, in the order they appear in the file.
+    pub nodes: Vec<Person<'i>>,
+    /// The relationships, in the order they appear in the file.
+    pub edges: Vec<Relationship<'i>>,
+}
+
+/// An individual in a [`FamilyGraph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Person<'i> {
+    pub xref: &'i str,
+    /// The first name of the individual, without the slashes around the surname.
+    pub name: Option<String>,
+    pub sex: Option<&'i str>,
+    pub birth_year: Option<i32>,
+    pub death_year: Option<i32>,
+}
+
+/// A relationship between two individuals in a [`FamilyGraph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relationship<'i> {
+    pub kind: RelationshipKind,
+    pub source: &'i str,
+    pub target: &'i str,
+    /// How the target is related to the source, for associations.
+    pub relation: Option<&'i str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum RelationshipKind {
+    #[display("spouse")]
+    Spouse,
+    #[display("parent-child")]
+    ParentChild,
+    #[display("association")]
+    Association,
+}
+
+impl RelationshipKind {
+    /// Whether the relationship differs between its two directions
+    /// (a parent of a child, rather than a spouse of a spouse).
+    pub fn is_directed(self) -> bool {
+        !matches!(self, RelationshipKind::Spouse)
+    }
+}
+
+impl<'i> FamilyGraph<'i> {
+    /// Builds the graph from the records of a file.
+    ///
+    /// Relationships with an individual who does not exist
+    /// (including `@VOID@` pointers) are left out.
+    pub fn new(records: &[Sourced<RawRecord<'i>>]) -> Self {
+        let mut graph = Self::default();
+        for record in records {
+            if record.line.tag.as_str() == "INDI" {
+                if let Some(xref) = &record.line.xref {
+                    graph.nodes.push(Person::new(xref.sourced_value, record));
+                }
+            }
+        }
+
+        for record in records {
+            match record.line.tag.as_str() {
+                "INDI" => graph.add_associations(record),
+                "FAM" => graph.add_family(record),
+                _ => {}
+            }
+        }
+
+        let xrefs: HashSet<&str> = graph.nodes.iter().map(|node| node.xref).collect();
+        graph
+            .edges
+            .retain(|edge| xrefs.contains(edge.source) && xrefs.contains(edge.target));
+
+        graph
+    }
+
+    fn add_associations(&mut self, record: &RawRecord<'i>) {
+        let Some(xref) = &record.line.xref else {
+            return;
+        };
+
+        for association in record.subrecords("ASSO") {
+            if let Some(target) = association.pointer() {
+                let relation = association
+                    .subrecords("RELA")
+                    .find_map(RawRecord::string_value);
+                self.add_edge(
+                    RelationshipKind::Association,
+                    xref.sourced_value,
+                    target,
+                    relation,
+                );
+            }
+        }
+    }
+
+    fn add_family(&mut self, record: &RawRecord<'i>) {
+        let partners: Vec<&str> = ["HUSB", "WIFE"]
+            .into_iter()
+            .filter_map(|tag| record.subrecords(tag).find_map(RawRecord::pointer))
+            .collect();
+
+        if let [husband, wife] = partners[..] {
+            self.add_edge(RelationshipKind::Spouse, husband, wife, None);
+        }
+
+        for child in record.subrecords("CHIL").filter_map(RawRecord::pointer) {
+            for &partner in &partners {
+                self.add_edge(RelationshipKind::ParentChild, partner, child, None);
+            }
+        }
+    }
+
+    fn add_edge(
+        &mut self,
+        kind: RelationshipKind,
+        source: &'i str,
+        target: &'i str,
+        relation: Option<&'i str>,
+    ) {
+        self.edges
+            .push(Relationship { kind, source, target, relation });
+    }
+
+    /// Writes the graph in the [GraphML](http://graphml.graphdrawing.org/) format.
+    pub fn to_graphml(&self) -> String {
+        let mut output = String::new();
+        _ = writeln!(output, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        _ = writeln!(
+            output,
+            r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+        );
+        for (id, domain, kind) in [
+            ("name", "node", "string"),
+            ("sex", "node", "string"),
+            ("birth_year", "node", "int"),
+            ("death_year", "node", "int"),
+            ("type", "edge", "string"),
+            ("relation", "edge", "string"),
+        ] {
+            _ = writeln!(
+                output,
+                r#"  <key id="{id}" for="{domain}" attr.name="{id}" attr.type="{kind}"/>"#
+            );
+        }
+
+        _ = writeln!(output, r#"  <graph id="G" edgedefault="directed">"#);
+        for node in &self.nodes {
+            _ = writeln!(output, r#"    <node id="{}">"#, escape(node.xref));
+            let data = [
+                ("name", node.name.clone()),
+                ("sex", node.sex.map(String::from)),
+                ("birth_year", node.birth_year.map(|year| year.to_string())),
+                ("death_year", node.death_year.map(|year| year.to_string())),
+            ];
+
+            for (key, value) in data {
+                if let Some(value) = value {
+                    _ = writeln!(
+                        output,
+                        r#"      <data key="{key}">{}</data>"#,
+                        escape(&value)
+                    );
+                }
+            }
+
+            _ = writeln!(output, "    </node>");
+        }
+
+        for (id, edge) in self.edges.iter().enumerate() {
+            _ = writeln!(
+                output,
+                r#"    <edge id="e{id}" source="{}" target="{}" directed="{}">"#,
+                escape(edge.source),
+                escape(edge.target),
+                edge.kind.is_directed(),
+            );
+            _ = writeln!(output, r#"      <data key="type">{}</data>"#, edge.kind);
+            if let Some(relation) = edge.relation {
+                _ = writeln!(
+                    output,
+                    r#"      <data key="relation">{}</data>"#,
+                    escape(relation)
+                );
+            }
+
+            _ = writeln!(output, "    </edge>");
+        }
+
+        _ = writeln!(output, "  </graph>");
+        _ = writeln!(output, "</graphml>");
+        output
+    }
+
+    /// Writes the graph in the [GEXF](https://gexf.net/) 1.3 format.
+    pub fn to_gexf(&self) -> String {
+        let mut output = String::new();
+        _ = writeln!(output, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        _ = writeln!(
+            output,
+            r#"<gexf xmlns="http://gexf.net/1.3" version="1.3">"#
+        );
+        _ = writeln!(
+            output,
+            r#"  <graph mode="static" defaultedgetype="directed">"#
+        );
+        _ = writeln!(output, r#"    <attributes class="node">"#);
+        for (id, kind) in [
+            ("sex", "string"),
+            ("birth_year", "integer"),
+            ("death_year", "integer"),
+        ] {
+            _ = writeln!(
+                output,
+                r#"      <attribute id="{id}" title="{id}" type="{kind}"/>"#
+            );
+        }
+
+        _ = writeln!(output, "    </attributes>");
+        _ = writeln!(output, r#"    <attributes class="edge">"#);
+        _ = writeln!(
+            output,
+            r#"      <attribute id="relation" title="relation" type="string"/>"#
+        );
+        _ = writeln!(output, "    </attributes>");
+
+        _ = writeln!(output, "    <nodes>");
+        for node in &self.nodes {
+            let label = node.name.as_deref().unwrap_or(node.xref);
+            _ = write!(
+                output,
+                r#"      <node id="{}" label="{}""#,
+                escape(node.xref),
+                escape(label)
+            );
+
+            let values = [
+                ("sex", node.sex.map(String::from)),
+                ("birth_year", node.birth_year.map(|year| year.to_string())),
+                ("death_year", node.death_year.map(|year| year.to_string())),
+            ];
+
+            if values.iter().all(|(_, value)| value.is_none()) {
+                _ = writeln!(output, "/>");
+                continue;
+            }
+
+            _ = writeln!(output, ">");
+            _ = writeln!(output, "        <attvalues>");
+            for (key, value) in values {
+                if let Some(value) = value {
+                    _ = writeln!(
+                        output,
+                        r#"          <attvalue for="{key}" value="{}"/>"#,
+                        escape(&value)
+                    );
+                }
+            }
+
+            _ = writeln!(output, "        </attvalues>");
+            _ = writeln!(output, "      </node>");
+        }
+
+        _ = writeln!(output, "    </nodes>");
+        _ = writeln!(output, "    <edges>");
+        for (id, edge) in self.edges.iter().enumerate() {
+            _ = write!(
+                output,
+                r#"      <edge id="{id}" source="{}" target="{}" type="{}" kind="{}""#,
+                escape(edge.source),
+                escape(edge.target),
+                if edge.kind.is_directed() {
+                    "directed"
+                } else {
+                    "undirected"
+                },
+                edge.kind,
+            );
+
+            match edge.relation {
+                None => _ = writeln!(output, "/>"),
+                Some(relation) => {
+                    _ = writeln!(output, ">");
+                    _ = writeln!(output, "        <attvalues>");
+                    _ = writeln!(
+                        output,
+                        r#"          <attvalue for="relation" value="{}"/>"#,
+                        escape(relation)
+                    );
+                    _ = writeln!(output, "        </attvalues>");
+                    _ = writeln!(output, "      </edge>");
+                }
+            }
+        }
+
+        _ = writeln!(output, "    </edges>");
+        _ = writeln!(output, "  </graph>");
+        _ = writeln!(output, "</gexf>");
+        output
+    }
+}
+
+impl<'i> Person<'i> {
+    /// The details of the individual from their `INDI` record.
+    pub(crate) fn new(xref: &'i str, record: &RawRecord<'i>) -> Self {
+        let event_year = |tag| {
+            record
+                .subrecords(tag)
+                .flat_map(|event| event.subrecords("DATE"))
+                .find_map(RawRecord::string_value)
+                .and_then(|date| years_of(date).next())
+        };
+
+        Person {
+            xref,
+            name: record
+                .subrecords("NAME")
+                .find_map(RawRecord::string_value)
+                .map(|name| {
+                    name.replace('/', " ")
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .filter(|name| !name.is_empty()),
+            sex: record.subrecords("SEX").find_map(RawRecord::string_value),
+            birth_year: event_year("BIRT"),
+            death_year: event_year("DEAT"),
+        }
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
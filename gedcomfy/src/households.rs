@@ -0,0 +1,244 @@
+//! Reconstructing the households of a census year, from the places
+//! where individuals and families were living and the links between them.
+//!
+//! Each individual who was alive in the year is placed in a household: that of
+//! the family they had formed by then (as a husband or wife), or otherwise the
+//! family they were born into (as a child). The place of a household is found from
+//! the residence (`RESI`) and census (`CENS`) events of its members or of the
+//! family itself, in that year; an individual whose own events put them somewhere
+//! else (such as a child who had left home) is listed at that place instead.
+//!
+//! Households for which no place is known are left out.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    graph::Person,
+    reader::{Sourced, records::RawRecord},
+    schemas::years_of,
+};
+
+/// The households in each place, for a single year.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Households<'i> {
+    pub year: i32,
+    /// The households in each place, ordered by place, and then
+    /// by the order their first member appears in the file.
+    pub places: BTreeMap<&'i str, Vec<Household<'i>>>,
+}
+
+/// The members of a household, in one place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Household<'i> {
+    /// The xref of the family, if the members were part of one.
+    pub family: Option<&'i str>,
+    /// The husband and wife first, and then the children, in the
+    /// order they appear in the file.
+    pub members: Vec<Member<'i>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member<'i> {
+    pub person: Person<'i>,
+    pub role: HouseholdRole,
+    /// The age the individual would have been by the end of the year, if known.
+    pub age: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, derive_more::Display)]
+pub enum HouseholdRole {
+    Husband,
+    Wife,
+    Child,
+    /// Someone who was not part of a family in that year.
+    Individual,
+}
+
+struct Family<'i> {
+    xref: &'i str,
+    husband: Option<&'i str>,
+    wife: Option<&'i str>,
+    children: Vec<&'i str>,
+    /// The year the family was formed, from the marriage or the first child.
+    start_year: Option<i32>,
+    place: Option<&'i str>,
+}
+
+impl<'i> Households<'i> {
+    /// Finds the households for the year, from the records of a file.
+    pub fn new(records: &[Sourced<RawRecord<'i>>], year: i32) -> Self {
+        let mut people = Vec::new();
+        let mut places = HashMap::new();
+        for record in records {
+            if let (Some(xref), "INDI") = (&record.line.xref, record.line.tag.as_str()) {
+                let person = Person::new(xref.sourced_value, record);
+                if is_alive(&person, year) {
+                    if let Some(place) = residence(record, year) {
+                        places.insert(xref.sourced_value, place);
+                    }
+
+                    people.push(person);
+                }
+            }
+        }
+
+        let births: HashMap<&str, Option<i32>> = people
+            .iter()
+            .map(|person| (person.xref, person.birth_year))
+            .collect();
+
+        let families: Vec<Family> = records
+            .iter()
+            .filter(|record| record.line.tag.as_str() == "FAM")
+            .filter_map(|record| {
+                let xref = record.line.xref.as_ref()?.sourced_value;
+                let partner = |tag| record.subrecords(tag).find_map(RawRecord::pointer);
+                let children: Vec<&str> = record
+                    .subrecords("CHIL")
+                    .filter_map(RawRecord::pointer)
+                    .collect();
+
+                let marriage = record
+                    .subrecords("MARR")
+                    .flat_map(|marriage| marriage.subrecords("DATE"))
+                    .find_map(RawRecord::string_value)
+                    .and_then(|date| years_of(date).next());
+
+                let first_birth = children
+                    .iter()
+                    .filter_map(|child| births.get(child).copied().flatten())
+                    .min();
+
+                Some(Family {
+                    xref,
+                    husband: partner("HUSB"),
+                    wife: partner("WIFE"),
+                    children,
+                    start_year: marriage.or(first_birth),
+                    place: residence(record, year),
+                })
+            })
+            .collect();
+
+        // the household of each individual, and their role in it
+        let mut households: HashMap<&str, (usize, HouseholdRole)> = HashMap::new();
+        for (index, family) in families.iter().enumerate() {
+            // families with no known start are assumed to have formed by then
+            if family.start_year.is_some_and(|start| start > year) {
+                continue;
+            }
+
+            for (partner, role) in [
+                (family.husband, HouseholdRole::Husband),
+                (family.wife, HouseholdRole::Wife),
+            ] {
+                let Some(partner) = partner else {
+                    continue;
+                };
+
+                // a later family replaces an earlier one
+                match households.get(partner) {
+                    Some(&(other, HouseholdRole::Husband | HouseholdRole::Wife))
+                        if families[other].start_year > family.start_year => {}
+                    _ => _ = households.insert(partner, (index, role)),
+                }
+            }
+        }
+
+        for (index, family) in families.iter().enumerate() {
+            for &child in &family.children {
+                households
+                    .entry(child)
+                    .or_insert((index, HouseholdRole::Child));
+            }
+        }
+
+        // the place of a family's household is that of the family,
+        // or else of its first member with a known place
+        let family_places: Vec<Option<&str>> = families
+            .iter()
+            .enumerate()
+            .map(|(index, family)| {
+                family.place.or_else(|| {
+                    let members = family
+                        .husband
+                        .iter()
+                        .chain(&family.wife)
+                        .chain(&family.children);
+                    members
+                        .filter(|member| {
+                            households
+                                .get(*member)
+                                .is_some_and(|&(other, _)| other == index)
+                        })
+                        .find_map(|member| places.get(member).copied())
+                })
+            })
+            .collect();
+
+        let mut result = Households { year, places: BTreeMap::new() };
+        for person in people {
+            let (family, role) = match households.get(person.xref) {
+                Some(&(index, role)) => (Some(index), role),
+                None => (None, HouseholdRole::Individual),
+            };
+
+            let own_place = places.get(person.xref).copied();
+            let Some(place) = own_place.or(family.and_then(|index| family_places[index])) else {
+                continue;
+            };
+
+            let age = person.birth_year.map(|birth| year - birth);
+            let member = Member { person, role, age };
+            let family = family.map(|index| families[index].xref);
+            let households = result.places.entry(place).or_default();
+            match households
+                .iter_mut()
+                .find(|household| family.is_some() && household.family == family)
+            {
+                Some(household) => household.members.push(member),
+                None => households.push(Household { family, members: vec![member] }),
+            }
+        }
+
+        for households in result.places.values_mut() {
+            for household in households {
+                household.members.sort_by_key(|member| member.role);
+            }
+        }
+
+        result
+    }
+}
+
+/// Whether the individual could have been alive in the year,
+/// as far as their birth and death are known.
+fn is_alive(person: &Person, year: i32) -> bool {
+    person.birth_year.is_none_or(|birth| birth <= year)
+        && person.death_year.is_none_or(|death| death >= year)
+}
+
+/// The place of the first residence or census event of the record in the year.
+fn residence<'i>(record: &RawRecord<'i>, year: i32) -> Option<&'i str> {
+    record
+        .records
+        .iter()
+        .map(|event| &event.sourced_value)
+        .filter(|event| matches!(event.line.tag.as_str(), "RESI" | "CENS"))
+        .filter(|event| {
+            event
+                .subrecords("DATE")
+                .find_map(RawRecord::string_value)
+                .is_some_and(|date| covers(date, year))
+        })
+        .find_map(|event| event.subrecords("PLAC").find_map(RawRecord::string_value))
+}
+
+/// Whether the date (or the range of years within it) includes the year.
+fn covers(date: &str, year: i32) -> bool {
+    let years: Vec<i32> = years_of(date).collect();
+    match (years.iter().min(), years.iter().max()) {
+        (Some(&first), Some(&last)) => (first..=last).contains(&year),
+        _ => false,
+    }
+}
@@ -8,12 +8,24 @@ use reader::{
 };
 use vec1::Vec1;
 
+pub mod ages;
+pub mod capabilities;
+pub mod citations;
+pub mod codes;
 pub mod encodings;
+pub mod graph;
 pub mod highlighting;
+pub mod households;
+pub mod normalize;
 pub mod reader;
+pub mod reports;
 pub mod schemas;
+pub mod search;
+pub mod site;
 pub mod versions;
+pub mod writer;
 
+pub use capabilities::capabilities;
 pub use reader::Reader;
 
 #[derive(thiserror::Error, derive_more::Display, Debug, miette::Diagnostic)]
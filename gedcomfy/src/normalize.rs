@@ -0,0 +1,218 @@
+//! Normalizing GEDCOM records, so that files which are equivalent
+//! produce the same output, and the differences between files are stable.
+
+use std::{cmp::Ordering, collections::HashMap};
+
+use itertools::{EitherOrBoth, Itertools};
+
+use crate::reader::{Sourced, lines::LineValue, records::RawRecord};
+
+/// The prefix used for canonical xrefs of each kind of top-level record.
+fn xref_prefix(tag: &str) -> &'static str {
+    match tag {
+        "INDI" => "I",
+        "FAM" => "F",
+        "SOUR" => "S",
+        "REPO" => "R",
+        "NOTE" => "N",
+        "OBJE" => "O",
+        "SUBM" => "U",
+        "SUBN" => "SUBN",
+        _ => "X",
+    }
+}
+
+/// A mapping from the xrefs used in a file to canonical xrefs,
+/// which are numbered sequentially for each kind of record (`@I1@`, `@F1@`, …).
+#[derive(Debug, Default)]
+pub struct XRefRenumbering {
+    xrefs: HashMap<String, String>,
+}
+
+impl XRefRenumbering {
+    /// Numbers the top-level records in the order that they appear.
+    pub fn new(records: &[Sourced<RawRecord>]) -> Self {
+        let mut counts = HashMap::<&str, usize>::new();
+        let mut xrefs = HashMap::new();
+        for record in records {
+            if let Some(xref) = &record.line.xref {
+                let prefix = xref_prefix(record.line.tag.as_str());
+                let count = counts.entry(prefix).or_default();
+                *count += 1;
+                xrefs
+                    .entry(xref.to_string())
+                    .or_insert_with(|| format!("{prefix}{count}"));
+            }
+        }
+
+        Self { xrefs }
+    }
+
+    /// The canonical xref for the given xref, if there is a record with that xref.
+    pub fn get(&self, xref: &str) -> Option<&str> {
+        self.xrefs.get(xref).map(String::as_str)
+    }
+
+    /// Replaces the xrefs of the records, and all pointers to them, with their canonical xrefs.
+    ///
+    /// Pointers to records which do not exist are left unchanged. The spans of the
+    /// records still refer to their original location in the source.
+    pub fn apply<'a>(
+        &'a self,
+        mut records: Vec<Sourced<RawRecord<'a>>>,
+    ) -> Vec<Sourced<RawRecord<'a>>> {
+        for record in &mut records {
+            self.apply_to(record);
+        }
+
+        records
+    }
+
+    fn apply_to<'a>(&'a self, record: &mut Sourced<RawRecord<'a>>) {
+        let record = &mut record.sourced_value;
+        if let Some(xref) = &mut record.line.sourced_value.xref {
+            if let Some(new) = self.get(xref.sourced_value) {
+                xref.sourced_value = new;
+            }
+        }
+
+        if let LineValue::Ptr(Some(pointer)) = &mut record.line.sourced_value.value.sourced_value {
+            if let Some(new) = self.get(pointer) {
+                *pointer = new;
+            }
+        }
+
+        for child in &mut record.records {
+            self.apply_to(child);
+        }
+    }
+}
+
+/// The order of top-level records in canonical output.
+const RECORD_ORDER: &[&str] = &[
+    "HEAD", "SUBM", "SUBN", "INDI", "FAM", "SOUR", "REPO", "NOTE", "OBJE",
+];
+
+/// The order of the subrecords of the header, following the specification.
+const HEADER_ORDER: &[&str] = &[
+    "SOUR", "DEST", "DATE", "SUBM", "SUBN", "FILE", "COPR", "GEDC", "CHAR", "LANG", "PLAC", "NOTE",
+];
+
+/// Event and attribute tags, which are all ordered as if they were `EVEN`, so that
+/// they keep their original order amongst themselves (since it is usually chronological).
+pub(crate) const EVENT_TAGS: &[&str] = &[
+    "BIRT", "CHR", "DEAT", "BURI", "CREM", "ADOP", "BAPM", "BARM", "BASM", "BLES", "CHRA", "CONF",
+    "FCOM", "ORDN", "NATU", "EMIG", "IMMI", "CENS", "PROB", "WILL", "GRAD", "RETI", "EVEN", "ANUL",
+    "DIV", "DIVF", "ENGA", "MARB", "MARC", "MARR", "MARL", "MARS", "CAST", "DSCR", "EDUC", "IDNO",
+    "NATI", "NCHI", "NMR", "OCCU", "PROP", "RESI", "SSN", "FACT",
+];
+
+/// The order of all other subrecords, following the order in which they
+/// are given in the record definitions of the specification. `CONT` is ordered
+/// as if it were `CONC`, as the two together make up the text of the value.
+const SUBRECORD_ORDER: &[&str] = &[
+    "CONC", "TYPE", "RESN", "NAME", "NPFX", "GIVN", "NICK", "SPFX", "SURN", "NSFX", "ROMN", "FONE",
+    "SEX", "EVEN", "DATE", "TIME", "PLAC", "ADDR", "ADR1", "ADR2", "ADR3", "CITY", "STAE", "POST",
+    "CTRY", "PHON", "EMAIL", "FAX", "WWW", "AGE", "AGNC", "RELI", "CAUS", "HUSB", "WIFE", "CHIL",
+    "FAMC", "FAMS", "PEDI", "STAT", "BAPL", "CONL", "ENDL", "SLGC", "SLGS", "TEMP", "SUBM", "ASSO",
+    "RELA", "ALIA", "ANCI", "DESI", "AUTH", "TITL", "ABBR", "PUBL", "PAGE", "ROLE", "DATA", "TEXT",
+    "QUAY", "REPO", "CALN", "MEDI", "RFN", "AFN", "REFN", "RIN", "CHAN", "NOTE", "SOUR", "OBJE",
+];
+
+/// The position of the tag in the order; tags which are not
+/// listed (such as user-defined tags) come after all those which are.
+fn rank(order: &[&str], tag: &str) -> usize {
+    order.iter().position(|t| *t == tag).unwrap_or(order.len())
+}
+
+fn subrecord_rank(parent_tag: &str, tag: &str) -> usize {
+    if parent_tag == "HEAD" {
+        rank(HEADER_ORDER, tag)
+    } else if EVENT_TAGS.contains(&tag) {
+        rank(SUBRECORD_ORDER, "EVEN")
+    } else if tag == "CONT" {
+        rank(SUBRECORD_ORDER, "CONC")
+    } else {
+        rank(SUBRECORD_ORDER, tag)
+    }
+}
+
+/// Compares xrefs so that any numbers within them are ordered by
+/// their value; e.g. `@I2@` comes before `@I10@`.
+fn compare_xrefs(left: &str, right: &str) -> Ordering {
+    fn chunks(xref: &str) -> impl Iterator<Item = &str> {
+        xref.as_bytes()
+            .chunk_by(|a, b| a.is_ascii_digit() == b.is_ascii_digit())
+            // UNWRAP: chunks are split between ASCII bytes, so are valid UTF-8
+            .map(|chunk| std::str::from_utf8(chunk).unwrap())
+    }
+
+    for chunk in chunks(left).zip_longest(chunks(right)) {
+        let ordering = match chunk {
+            EitherOrBoth::Both(l, r) => match (l.parse::<u64>(), r.parse::<u64>()) {
+                (Ok(l_num), Ok(r_num)) => l_num.cmp(&r_num).then_with(|| l.cmp(r)),
+                _ => l.cmp(r),
+            },
+            EitherOrBoth::Left(_) => Ordering::Greater,
+            EitherOrBoth::Right(_) => Ordering::Less,
+        };
+
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Sorts records into a canonical order, so that files containing the same
+/// records produce the same output.
+///
+/// Top-level records are ordered by kind (the header, then submitters, individuals,
+/// families, sources, and any other records, and finally the trailer), and then by xref,
+/// with any records without xrefs first (in their original order).
+/// Subrecords are ordered by tag, following the order given in the specification;
+/// subrecords with the same tag (and events, which may be in date order) keep their
+/// original order, since it can be significant.
+///
+/// To order records by their position in the file, use [`XRefRenumbering`] first.
+pub fn sort_records(records: &mut [Sourced<RawRecord>]) {
+    // the key is the rank of the tag and then the xref (if any), with
+    // records without xrefs first, so that this is a total order
+    fn key<'r>(record: &'r Sourced<RawRecord>) -> (usize, Option<&'r str>) {
+        let rank = match record.line.tag.as_str() {
+            "TRLR" => RECORD_ORDER.len() + 1,
+            tag => rank(RECORD_ORDER, tag),
+        };
+
+        let xref = record.line.xref.as_ref().map(|xref| xref.sourced_value);
+        (rank, xref)
+    }
+
+    records.sort_by(|left, right| {
+        let (left_rank, left_xref) = key(left);
+        let (right_rank, right_xref) = key(right);
+        left_rank
+            .cmp(&right_rank)
+            .then_with(|| match (left_xref, right_xref) {
+                (Some(l), Some(r)) => compare_xrefs(l, r),
+                (l, r) => l.is_some().cmp(&r.is_some()),
+            })
+    });
+
+    for record in records {
+        sort_subrecords(record);
+    }
+}
+
+fn sort_subrecords(record: &mut Sourced<RawRecord>) {
+    let record = &mut record.sourced_value;
+    let parent_tag = record.line.tag.as_str();
+    record
+        .records
+        .sort_by_key(|child| subrecord_rank(parent_tag, child.line.tag.as_str()));
+
+    for child in &mut record.records {
+        sort_subrecords(child);
+    }
+}
@@ -4,34 +4,60 @@ use std::{
     sync::Arc,
 };
 
+use ancestry::AncestryChecker;
+use arena::{ArenaBuilder, RecordArena};
 use ascii::{AsciiChar, AsciiStr};
-use decoding::{DecodingError, DetectedEncoding, detect_external_encoding};
+use chronology::ChronologyChecker;
+use decoding::{DecodingError, DecodingWarning, DetectedEncoding, detect_external_encoding};
 use encodings::EncodingReason;
 use input::{Input, RawInput};
+use itertools::Either;
+use limits::LimitError;
 use lines::LineValue;
 use miette::{SourceOffset, SourceSpan};
-use options::ParseOptions;
+use offsets::{OffsetMap, RawOffsetDiagnostic};
+use options::{DuplicateSubrecords, ParseOptions};
 use records::{RawRecord, RecordBuilder};
 use tracing::instrument;
 use versions::VersionError;
+use visitors::{Annotation, Visit};
+use xrefs::XRefResolver;
 use yoke::{Yoke, Yokeable};
 
 use crate::{
     FileStructureError,
-    schemas::SchemaError,
-    versions::{FileVersion, KnownVersion, parse_version_head_gedc_vers},
+    schemas::{SchemaError, v551::TopLevelRecord},
+    versions::{FileVersion, KnownVersion, LegacyVersion, parse_version_head_gedc_vers},
 };
 
+mod ancestry;
+pub mod arena;
+pub mod batch;
+mod chronology;
 pub mod decoding;
 pub mod encodings;
+#[cfg(feature = "kdl")]
+pub mod from_kdl;
+pub mod incremental;
 pub mod input;
+pub mod limits;
 pub mod lines;
+mod lints;
+pub mod metrics;
 mod modes;
+pub mod offsets;
 pub mod options;
+pub mod packed_tags;
+pub mod producers;
 pub mod records;
 pub(crate) mod versions;
+pub mod visitors;
+mod xrefs;
 
-pub use modes::{parse::ParseResult, validation::ValidationResult};
+pub use modes::{
+    parse::ParseResult,
+    validation::{ValidationResult, Validity},
+};
 
 /// Represents the minimal amount of decoding needed to
 /// parse information from GEDCOM files.
@@ -54,14 +80,8 @@ pub trait GEDCOMSource: ascii::AsAsciiStr + PartialEq<AsciiStr> {
 
 impl GEDCOMSource for str {
     fn lines(&self) -> impl Iterator<Item = &Self> {
-        // GEDCOM lines are terminated by "any combination of a carriage return and a line feed"
-        (*self).split(['\r', '\n']).map(|mut s| {
-            while s.starts_with('\n') || s.starts_with('\r') {
-                s = &s[1..];
-            }
-
-            s
-        })
+        // line breaks are ASCII, so every range is on a character boundary
+        lines::line_ranges(self.as_bytes()).map(|range| &self[range])
     }
 
     fn span_of(&self, source: &Self) -> SourceSpan {
@@ -99,14 +119,7 @@ impl GEDCOMSource for str {
 
 impl GEDCOMSource for [u8] {
     fn lines(&self) -> impl Iterator<Item = &Self> {
-        // GEDCOM lines are terminated by "any combination of a carriage return and a line feed"
-        (*self).split(|&x| x == b'\r' || x == b'\n').map(|mut s| {
-            while s.starts_with(b"\n") || s.starts_with(b"\r") {
-                s = &s[1..];
-            }
-
-            s
-        })
+        lines::line_ranges(self).map(|range| &self[range])
     }
 
     fn span_of(&self, source: &Self) -> SourceSpan {
@@ -134,7 +147,7 @@ impl GEDCOMSource for [u8] {
     }
 
     fn split_once(&self, char: AsciiChar) -> Option<(&Self, &Self)> {
-        let ix = self.iter().position(|&x| x == char.as_byte())?;
+        let ix = memchr::memchr(char.as_byte(), self)?;
         let (before, after) = self.split_at(ix);
         Some((before, &after[1..]))
     }
@@ -198,7 +211,7 @@ impl<T> std::ops::Deref for Sourced<T> {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Reader {
     opts: ParseOptions,
 }
@@ -227,6 +240,7 @@ fn attach_name<'a>(
     }
 }
 
+#[derive(Clone)]
 pub enum AnySourceCode<'a> {
     Borrowed(Cow<'a, [u8]>),
     Shared(Arc<dyn miette::SourceCode>),
@@ -258,6 +272,16 @@ impl miette::SourceCode for AnySourceCode<'_> {
     }
 }
 
+impl AnySourceCode<'_> {
+    /// Copies borrowed source code, so that it no longer refers to the input.
+    pub fn into_owned(self) -> AnySourceCode<'static> {
+        match self {
+            AnySourceCode::Borrowed(data) => AnySourceCode::Borrowed(Cow::Owned(data.into_owned())),
+            AnySourceCode::Shared(data) => AnySourceCode::Shared(data),
+        }
+    }
+}
+
 pub trait NonFatalHandler {
     fn report<E>(&mut self, error: E) -> Result<(), E>
     where
@@ -282,6 +306,18 @@ pub enum ReaderError {
     #[diagnostic(transparent)]
     Decoding(#[from] DecodingError),
 
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Limit(#[from] LimitError),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Warning(#[from] DecodingWarning),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Annotation(#[from] Annotation),
+
     #[error(transparent)]
     #[diagnostic(transparent)]
     Schema(#[from] SchemaError),
@@ -292,6 +328,28 @@ pub enum ReaderError {
 pub struct WithSourceCode<'i, E> {
     pub source: E,
     pub source_code: AnySourceCode<'i>,
+    // the source, with its spans mapped to offsets in the source code,
+    // if the source code is not the decoded text
+    raw_offsets: Option<Box<RawOffsetDiagnostic>>,
+}
+
+impl<E> WithSourceCode<'_, E> {
+    /// Copies borrowed source code, so that the error can outlive the input.
+    pub fn into_owned(self) -> WithSourceCode<'static, E> {
+        WithSourceCode {
+            source: self.source,
+            source_code: self.source_code.into_owned(),
+            raw_offsets: self.raw_offsets,
+        }
+    }
+}
+
+impl<E: miette::Diagnostic> WithSourceCode<'_, E> {
+    fn map_offsets(self, offsets: Option<&OffsetMap>) -> Self {
+        let raw_offsets =
+            offsets.map(|offsets| Box::new(RawOffsetDiagnostic::new(&self.source, offsets)));
+        Self { raw_offsets, ..self }
+    }
 }
 
 impl<E: std::error::Error + 'static> std::error::Error for WithSourceCode<'_, E> {
@@ -310,26 +368,51 @@ impl<E: miette::Diagnostic + 'static> miette::Diagnostic for WithSourceCode<'_,
     }
 
     fn diagnostic_source(&self) -> Option<&dyn miette::Diagnostic> {
-        Some(&self.source)
+        match &self.raw_offsets {
+            Some(mapped) => Some(mapped.as_ref()),
+            None => Some(&self.source),
+        }
     }
 }
 
 trait AttachSourceCode<'a> {
     type Output;
     fn attach_source_code(self, source_code: impl Into<AnySourceCode<'a>>) -> Self::Output;
+
+    /// Attaches the source code of the input, mapping spans to offsets
+    /// in the source code if it is not the decoded text.
+    fn attach_input(self, input: &(impl Input<'a> + ?Sized)) -> Self::Output;
 }
 
 impl<'a> AttachSourceCode<'a> for ReaderError {
     type Output = WithSourceCode<'a, ReaderError>;
     fn attach_source_code(self, source_code: impl Into<AnySourceCode<'a>>) -> Self::Output {
-        Self::Output { source: self, source_code: source_code.into() }
+        Self::Output {
+            source: self,
+            source_code: source_code.into(),
+            raw_offsets: None,
+        }
+    }
+
+    fn attach_input(self, input: &(impl Input<'a> + ?Sized)) -> Self::Output {
+        self.attach_source_code(input.source_code())
+            .map_offsets(input.offsets())
     }
 }
 
 impl<'a> AttachSourceCode<'a> for DecodingError {
     type Output = WithSourceCode<'a, DecodingError>;
     fn attach_source_code(self, source_code: impl Into<AnySourceCode<'a>>) -> Self::Output {
-        Self::Output { source: self, source_code: source_code.into() }
+        Self::Output {
+            source: self,
+            source_code: source_code.into(),
+            raw_offsets: None,
+        }
+    }
+
+    fn attach_input(self, input: &(impl Input<'a> + ?Sized)) -> Self::Output {
+        self.attach_source_code(input.source_code())
+            .map_offsets(input.offsets())
     }
 }
 
@@ -338,12 +421,20 @@ impl<'a, T, E: AttachSourceCode<'a>> AttachSourceCode<'a> for Result<T, E> {
     fn attach_source_code(self, source_code: impl Into<AnySourceCode<'a>>) -> Self::Output {
         self.map_err(|e| e.attach_source_code(source_code))
     }
+
+    fn attach_input(self, input: &(impl Input<'a> + ?Sized)) -> Self::Output {
+        self.map_err(|e| e.attach_input(input))
+    }
 }
 
 #[derive(Yokeable)]
 struct DecodedInput<'i> {
     version: KnownVersion,
     output: Cow<'i, str>,
+    offsets: Option<OffsetMap>,
+    // the original data, to show in diagnostics when `offsets` is set
+    raw_source: Option<Cow<'i, [u8]>>,
+    warnings: Vec<DecodingWarning>,
 }
 
 impl Reader {
@@ -398,13 +489,13 @@ impl Reader {
     pub fn decode(
         &self,
         data: impl RawInput<'static> + 'static,
-    ) -> Result<impl Input<'static>, WithSourceCode<'static, DecodingError>> {
+    ) -> Result<impl Input<'static>, WithSourceCode<'static, ReaderError>> {
         let data = Arc::new(data);
         let result: Yoke<DecodedInput<'static>, _> =
             Yoke::try_attach_to_cart(data.clone(), |data| self.decode_inner(data.as_ref()))
                 .attach_source_code(data.source_code())?;
 
-        struct Yoked<D>(Yoke<DecodedInput<'static>, Arc<D>>);
+        struct Yoked<D>(Arc<Yoke<DecodedInput<'static>, Arc<D>>>);
 
         impl<D> AsRef<str> for Yoked<D> {
             fn as_ref(&self) -> &str {
@@ -412,18 +503,50 @@ impl Reader {
             }
         }
 
-        impl<D: RawInput<'static>> Input<'static> for Yoked<D> {
+        impl<D: RawInput<'static> + 'static> Input<'static> for Yoked<D> {
             fn source_code(&self) -> AnySourceCode<'static> {
-                self.0.backing_cart().source_code()
+                AnySourceCode::Shared(Arc::new(DecodedSource(self.0.clone())))
             }
+
             fn version(&self) -> Option<KnownVersion> {
                 Some(self.0.get().version)
             }
+
+            fn offsets(&self) -> Option<&OffsetMap> {
+                self.0.get().offsets.as_ref()
+            }
+
+            fn warnings(&self) -> &[DecodingWarning] {
+                &self.0.get().warnings
+            }
+        }
+
+        /// The decoded text (or the original data, if offsets are being mapped),
+        /// named in the same way as the original data.
+        struct DecodedSource<D>(Arc<Yoke<DecodedInput<'static>, Arc<D>>>);
+
+        impl<D: RawInput<'static>> miette::SourceCode for DecodedSource<D> {
+            fn read_span<'a>(
+                &'a self,
+                span: &SourceSpan,
+                context_lines_before: usize,
+                context_lines_after: usize,
+            ) -> Result<Box<dyn miette::SpanContents<'a> + 'a>, miette::MietteError> {
+                let decoded = self.0.get();
+                let data = match &decoded.raw_source {
+                    Some(raw_source) => raw_source.as_ref(),
+                    None => decoded.output.as_bytes(),
+                };
+
+                let content = data.read_span(span, context_lines_before, context_lines_after)?;
+
+                Ok(attach_name(content, self.0.backing_cart().name()))
+            }
         }
 
         // TODO: drop original input if we owned it via Cow::Owned
 
-        Ok(Yoked(result))
+        Ok(Yoked(Arc::new(result)))
     }
 
     pub fn decode_borrowed<'s>(
@@ -432,49 +555,72 @@ impl Reader {
     ) -> Result<impl Input<'s>, WithSourceCode<'s, ReaderError>> {
         let decoded = self
             .decode_inner(data)
-            .map_err(ReaderError::from)
             .attach_source_code(data.source_code())?;
 
-        enum D<'s> {
-            Owned(Arc<String>, Option<KnownVersion>),
-            Borrowed(&'s str, Option<KnownVersion>),
+        enum Text<'s> {
+            Owned(Arc<String>),
+            Borrowed(&'s str),
+        }
+
+        struct D<'s> {
+            text: Text<'s>,
+            raw_source: Option<AnySourceCode<'s>>,
+            version: Option<KnownVersion>,
+            offsets: Option<OffsetMap>,
+            warnings: Vec<DecodingWarning>,
         }
 
         impl AsRef<str> for D<'_> {
             fn as_ref(&self) -> &str {
-                match self {
-                    D::Owned(arc, _) => arc.as_str(),
-                    D::Borrowed(s, _) => s,
+                match &self.text {
+                    Text::Owned(arc) => arc.as_str(),
+                    Text::Borrowed(s) => s,
                 }
             }
         }
 
         impl<'s> Input<'s> for D<'s> {
             fn source_code(&self) -> AnySourceCode<'s> {
-                match self {
-                    D::Owned(arc, _) => AnySourceCode::Shared(arc.clone()),
-                    D::Borrowed(s, _) => AnySourceCode::Borrowed(Cow::Borrowed(s.as_bytes())),
+                if let Some(raw_source) = &self.raw_source {
+                    return raw_source.clone();
+                }
+
+                match &self.text {
+                    Text::Owned(arc) => AnySourceCode::Shared(arc.clone()),
+                    Text::Borrowed(s) => AnySourceCode::Borrowed(Cow::Borrowed(s.as_bytes())),
                 }
             }
 
             fn version(&self) -> Option<KnownVersion> {
-                match self {
-                    D::Owned(_, v) | D::Borrowed(_, v) => *v,
-                }
+                self.version
             }
-        }
 
-        match decoded.output {
-            Cow::Owned(owned) => {
-                let version = Some(decoded.version);
-                let arc = Arc::new(owned);
-                Ok(D::Owned(arc, version))
+            fn offsets(&self) -> Option<&OffsetMap> {
+                self.offsets.as_ref()
             }
-            Cow::Borrowed(borrowed) => {
-                let version = Some(decoded.version);
-                Ok(D::Borrowed(borrowed, version))
+
+            fn warnings(&self) -> &[DecodingWarning] {
+                &self.warnings
             }
         }
+
+        let text = match decoded.output {
+            Cow::Owned(owned) => Text::Owned(Arc::new(owned)),
+            Cow::Borrowed(borrowed) => Text::Borrowed(borrowed),
+        };
+
+        let raw_source = decoded.raw_source.map(|raw_source| match raw_source {
+            Cow::Owned(owned) => AnySourceCode::Shared(Arc::new(owned)),
+            borrowed => AnySourceCode::Borrowed(borrowed),
+        });
+
+        Ok(D {
+            text,
+            raw_source,
+            version: Some(decoded.version),
+            offsets: decoded.offsets,
+            warnings: decoded.warnings,
+        })
     }
 
     /// Shorthand for:
@@ -488,7 +634,7 @@ impl Reader {
         Ok(self.decode(input::File::load(path.into())?)?)
     }
 
-    fn decode_inner<'i>(&self, data: &'i [u8]) -> Result<DecodedInput<'i>, DecodingError> {
+    fn decode_inner<'i>(&self, data: &'i [u8]) -> Result<DecodedInput<'i>, ReaderError> {
         // TODO: these need to go somwhere
         #[derive(Default)]
         struct WarningsCollector(Vec<ReaderError>);
@@ -504,10 +650,23 @@ impl Reader {
 
         let mut warnings = WarningsCollector::default();
 
-        let (version, output) = if let Some(encoding) = self.opts.force_encoding {
+        let mut decoding_warnings = Vec::new();
+        let original = data;
+        let data_len = data.len();
+        let data = if self.opts.skip_repeated_bom {
+            let (data, warning) = decoding::skip_repeated_bom(data);
+            decoding_warnings.extend(warning);
+            data
+        } else {
+            data
+        };
+
+        let (version, encoding, bom_length, mut output) = if let Some(encoding) =
+            self.opts.force_encoding
+        {
             // encoding is being forced by settings
             let detected_encoding = DetectedEncoding::new(encoding, EncodingReason::Forced {});
-            let decoded = detected_encoding.decode(data)?;
+            let decoded = self.decode_limited(&detected_encoding, data)?;
 
             let version = if let Some(forced_version) = self.opts.force_version {
                 forced_version
@@ -517,14 +676,15 @@ impl Reader {
                 *version
             };
 
-            (version, decoded)
+            (version, encoding, detected_encoding.bom_length(), decoded)
         } else if let Some(external_encoding) = detect_external_encoding(data)? {
             // we discovered the encoding externally
             tracing::debug!(encoding = ?external_encoding.encoding(), "detected encoding");
             let ext_enc = external_encoding.encoding();
+            let bom_length = external_encoding.bom_length();
 
             // now we can decode the file to actually look inside it
-            let decoded = external_encoding.decode(data)?;
+            let decoded = self.decode_limited(&external_encoding, data)?;
 
             let version = if let Some(forced_version) = self.opts.force_version {
                 forced_version
@@ -544,7 +704,7 @@ impl Reader {
                 version.value
             };
 
-            (version, decoded)
+            (version, ext_enc, bom_length, decoded)
         } else {
             tracing::debug!("parsing GEDCOM file to determine encoding");
             // we need to determine the encoding from the file itself
@@ -558,13 +718,50 @@ impl Reader {
                 "GEDCOM version & encoding determined");
 
             // now we can actually decode the input
-            let decoded = file_encoding.decode(data)?;
+            let decoded = self.decode_limited(&file_encoding, data)?;
+
+            (
+                version.value,
+                file_encoding.encoding(),
+                file_encoding.bom_length(),
+                decoded,
+            )
+        };
 
-            (version.value, decoded)
+        if self.opts.trim_trailing_nuls {
+            decoding_warnings.extend(decoding::trim_trailing_nuls(&mut output));
+        }
+
+        let (offsets, raw_source) = if self.opts.raw_offsets {
+            let start = data_len - data.len() + bom_length;
+            let offsets = OffsetMap::new(encoding, start, &output);
+            (Some(offsets), Some(offsets::displayable(original)))
+        } else {
+            (None, None)
         };
 
         tracing::debug!("input decoded successfully");
-        Ok(DecodedInput { version, output })
+        Ok(DecodedInput {
+            version,
+            output,
+            offsets,
+            raw_source,
+            warnings: decoding_warnings,
+        })
+    }
+
+    /// Decodes the data, after checking that the result could not be larger
+    /// than the limits allow.
+    fn decode_limited<'i>(
+        &self,
+        encoding: &DetectedEncoding,
+        data: &'i [u8],
+    ) -> Result<Cow<'i, str>, ReaderError> {
+        let size = data.len() - encoding.bom_length();
+        self.opts
+            .limits
+            .check_decoded_size(encoding.encoding(), size)?;
+        Ok(encoding.decode(data)?)
     }
 
     fn version_from_input(
@@ -591,6 +788,40 @@ impl Reader {
         self.build_result::<modes::raw::Mode>(input)
     }
 
+    /// Reads the records into a [`RecordArena`], which is cheaper to build than
+    /// the records from [`Reader::raw_records`]. Unlike that function, this does
+    /// not check the version of the file; like it, non-fatal problems are ignored.
+    pub fn record_arena<'i, 's>(
+        &self,
+        input: &'i impl Input<'s>,
+    ) -> Result<RecordArena<'i>, WithSourceCode<'s, ReaderError>> {
+        let build = || -> Result<_, ReaderError> {
+            let mut mode = modes::raw::Mode::default();
+            let mut arena =
+                ArenaBuilder::new().recover_invalid_levels(self.opts.recover_invalid_levels);
+            for (count, line) in lines::iterate_lines(input.as_ref()).enumerate() {
+                let (level, line) = line.map_err(DecodingError::from)?;
+                self.opts.limits.check_line(count + 1, &level, &line)?;
+                arena.handle_line((level, line), &mut mode)?;
+            }
+
+            Ok(arena.complete(&mut mode)?)
+        };
+
+        build().attach_input(input)
+    }
+
+    /// The product which produced the file, from the value of `HEAD.SOUR`
+    /// (e.g. `GRAMPS`), if it is present.
+    pub fn producer<'s>(&self, input: &impl Input<'s>) -> Option<String> {
+        let mut ignored = modes::raw::Mode::default();
+        let header = Self::extract_gedcom_header(input.as_ref(), &mut ignored).ok()?;
+        match header.subrecord_optional("SOUR")?.line.value.sourced_value {
+            LineValue::Str(product) => Some(product.to_string()),
+            LineValue::Ptr(_) | LineValue::None => None,
+        }
+    }
+
     pub fn validate<'i, 's>(
         &self,
         input: &'i impl Input<'s>,
@@ -598,6 +829,18 @@ impl Reader {
         self.build_result::<modes::validation::Mode>(input)
     }
 
+    #[cfg(feature = "json")]
+    /// Parses a GEDCOM file into JSON format.
+    ///
+    /// Each record is an object with a `tag`, and optionally an `xref`,
+    /// a `value` (or a `pointer`, which is `null` for `@VOID@`), and subrecords as `records`.
+    pub fn parse_json<'i, 's>(
+        &self,
+        input: &'i (impl Input<'s> + ?Sized),
+    ) -> Result<serde_json::Value, WithSourceCode<'s, ReaderError>> {
+        self.build_result::<modes::json::Mode>(input)
+    }
+
     #[cfg(feature = "kdl")]
     /// Parses a GEDCOM file into KDL format.
     pub fn parse_kdl<'i, 's>(
@@ -607,6 +850,30 @@ impl Reader {
         self.build_result::<modes::kdl::Mode>(input)
     }
 
+    #[cfg(feature = "toml")]
+    /// Parses a GEDCOM file into TOML format.
+    ///
+    /// The records have the same structure as in [`parse_json`](Self::parse_json),
+    /// within the `records` array of the document. As TOML has no `null`, a
+    /// `@VOID@` pointer is written as `"VOID"`.
+    pub fn parse_toml<'i, 's>(
+        &self,
+        input: &'i (impl Input<'s> + ?Sized),
+    ) -> Result<toml::Table, WithSourceCode<'s, ReaderError>> {
+        self.build_result::<modes::toml::Mode>(input)
+    }
+
+    #[cfg(feature = "yaml")]
+    /// Parses a GEDCOM file into YAML format.
+    ///
+    /// The records have the same structure as in [`parse_json`](Self::parse_json).
+    pub fn parse_yaml<'i, 's>(
+        &self,
+        input: &'i (impl Input<'s> + ?Sized),
+    ) -> Result<serde_yaml::Value, WithSourceCode<'s, ReaderError>> {
+        self.build_result::<modes::yaml::Mode>(input)
+    }
+
     #[cfg(feature = "turtle")]
     /// Parses a GEDCOM file into Turtle format.
     pub fn parse_ttl<'i, 's>(
@@ -623,22 +890,28 @@ impl Reader {
     ) -> Result<<M::ResultBuilder as ResultBuilder<'i>>::Result, WithSourceCode<'s, ReaderError>>
     {
         let mut mode = M::default();
+        for warning in input.warnings() {
+            mode.report(warning.clone())
+                .map_err(ReaderError::from)
+                .attach_input(input)?;
+        }
+
         let version = match input.version() {
             Some(v) => v,
             None => Self::version_from_input(input.as_ref(), &mut mode)
                 .map_err(ReaderError::from)
-                .attach_source_code(input.source_code())?,
+                .attach_input(input)?,
         };
 
         tracing::trace!(%version, "version found");
 
         let build = || -> Result<_, ReaderError> {
             let mut builder = mode.into_result_builder(version)?;
-            Self::read_all_records(input.as_ref(), &mut builder)?;
+            self.read_all_records(input.as_ref(), &mut builder)?;
             builder.complete()
         };
 
-        build().attach_source_code(input.source_code())
+        build().attach_input(input)
     }
 
     fn extract_gedcom_header<'s, S>(
@@ -713,20 +986,27 @@ impl Reader {
                     }
                 };
 
-                return data
+                let version = data
                     .try_map(|d| parse_version_head_gedc_vers(d))
-                    .map_err(|source| VersionError::Invalid { source, span: data.span });
+                    .map_err(|source| VersionError::Invalid { source, span: data.span })?;
+
+                if let Some(detected) = version.sourced_value.legacy() {
+                    return Err(VersionError::TooOld { detected, span: version.span });
+                }
+
+                return Ok(version);
             }
         }
 
         if let Some(sour) = head.subrecord_optional("SOUR") {
-            // GEDCOM 2.x or 3.0
-            if let Some(_vers) = sour.subrecord_optional("VERS") {
-                // this is 3.0 – TODO check line data value
-                todo!("3.x handling");
-            } else {
-                todo!("2.x handling");
-            }
+            // GEDCOM 2.x or 3.0, which have no GEDC record;
+            // 3.0 added the version of the source program
+            let (detected, span) = match sour.subrecord_optional("VERS") {
+                Some(vers) => (LegacyVersion::V3, vers.span),
+                None => (LegacyVersion::V2, sour.span),
+            };
+
+            return Err(VersionError::TooOld { detected, span });
         }
 
         Err(VersionError::NotFound { head: head.span })
@@ -752,20 +1032,130 @@ impl Reader {
 
     /// Attempts to read all records found in the input.
     fn read_all_records<'i>(
+        &self,
         input: &'i str,
         result: &mut impl ResultBuilder<'i>,
     ) -> Result<(), ReaderError> {
-        let mut record = RecordBuilder::new();
+        let mut xrefs = XRefResolver::default();
+        let mut ancestry = AncestryChecker::default();
+        let mut chronology = ChronologyChecker::new(self.opts.min_marriage_age);
+        self.read_records_within(input, input, result, |mut record, result| {
+            self.deduplicate_record(&mut record.sourced_value, result)?;
+            xrefs.add_record(&record);
+            ancestry.add_record(&record);
+            chronology.add_record(&record);
+            result.handle_record(record)
+        })?;
+
+        xrefs.complete(result)?;
+        ancestry.complete(result)?;
+        chronology.complete(result)
+    }
+
+    /// Reads the records found in the part of the input given by `within`
+    /// (which must start at the beginning of a line), passing each one to `handle`
+    /// after checking it.
+    fn read_records_within<'i, H: NonFatalHandler>(
+        &self,
+        input: &'i str,
+        within: &'i str,
+        handler: &mut H,
+        mut handle: impl FnMut(Sourced<RawRecord<'i>>, &mut H) -> Result<(), ReaderError>,
+    ) -> Result<(), ReaderError> {
+        let mut record =
+            RecordBuilder::new().recover_invalid_levels(self.opts.recover_invalid_levels);
+        let lines = if self.opts.repair_line_breaks {
+            Either::Left(lines::iterate_lines_repairing(input, within))
+        } else {
+            Either::Right(
+                lines::iterate_lines_within(input, within)
+                    .map(|line| line.map(|(level, line)| (level, line, Vec::new()))),
+            )
+        };
 
-        for line in lines::iterate_lines(input) {
-            let line = line.map_err(DecodingError::from)?;
-            if let Some(record) = record.handle_line(line, result)? {
-                result.handle_record(record)?;
+        for (count, line) in lines.enumerate() {
+            let (level, line, warnings) = line.map_err(DecodingError::from)?;
+            self.opts.limits.check_line(count + 1, &level, &line)?;
+            for warning in warnings {
+                handler.report(DecodingError::from(warning))?;
+            }
+
+            if let Some(record) = record.handle_line((level, line), handler)? {
+                if let Some(record) = self.visit_record(record, handler)? {
+                    self.lint_record(&record, handler)?;
+                    handle(record, handler)?;
+                }
             }
         }
 
-        if let Some(record) = record.complete(result)? {
-            result.handle_record(record)?;
+        if let Some(record) = record.complete(handler)? {
+            if let Some(record) = self.visit_record(record, handler)? {
+                self.lint_record(&record, handler)?;
+                handle(record, handler)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes repeated subrecords which may only appear once, if the options allow it.
+    fn deduplicate_record(
+        &self,
+        record: &mut RawRecord,
+        handler: &mut impl NonFatalHandler,
+    ) -> Result<(), ReaderError> {
+        if self.opts.duplicate_subrecords == DuplicateSubrecords::Error {
+            return Ok(());
+        }
+
+        let mut found = Vec::new();
+        TopLevelRecord::deduplicate(record, self.opts.duplicate_subrecords, &mut found);
+        for duplicate in found {
+            handler.report(duplicate)?;
+        }
+
+        Ok(())
+    }
+
+    /// Passes the record through each visitor in turn, returning it
+    /// unless one of them drops it.
+    fn visit_record<'i>(
+        &self,
+        mut record: Sourced<RawRecord<'i>>,
+        handler: &mut impl NonFatalHandler,
+    ) -> Result<Option<Sourced<RawRecord<'i>>>, ReaderError> {
+        let mut annotations = Vec::new();
+        let mut visit = Visit::Keep;
+        for visitor in &self.opts.visitors {
+            visit = visitor.visit(&mut record, &mut annotations);
+            if visit == Visit::Drop {
+                break;
+            }
+        }
+
+        for annotation in annotations {
+            handler.report(annotation)?;
+        }
+
+        Ok(match visit {
+            Visit::Keep => Some(record),
+            Visit::Drop => None,
+        })
+    }
+
+    /// Runs the advisory checks on the record.
+    fn lint_record(
+        &self,
+        record: &Sourced<RawRecord>,
+        handler: &mut impl NonFatalHandler,
+    ) -> Result<(), ReaderError> {
+        lints::check_temple_codes(record, handler)?;
+        lints::check_family_links(record, handler)?;
+        if let Some(gazetteer) = &self.opts.gazetteer {
+            lints::check_event_places(record, gazetteer.as_ref(), handler)?;
+        }
+        if let Some(min_quality) = self.opts.min_citation_quality {
+            lints::check_citation_quality(record, min_quality, handler)?;
         }
 
         Ok(())
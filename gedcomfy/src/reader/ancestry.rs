@@ -0,0 +1,95 @@
+//! Checking that no individual is their own ancestor.
+//!
+//! Like [`XRefResolver`](super::xrefs::XRefResolver), this is done once
+//! the whole file has been seen, since families may refer forwards.
+
+use std::collections::HashMap;
+
+use miette::SourceSpan;
+
+use super::{NonFatalHandler, ReaderError, Sourced, records::RawRecord};
+use crate::schemas::SchemaError;
+
+#[derive(Default)]
+pub(crate) struct AncestryChecker {
+    /// The individuals who are children in any family, in the order they are found.
+    children: Vec<String>,
+    /// The parents of each child, and where the child is listed in the parents’ family.
+    parents: HashMap<String, Vec<(String, SourceSpan)>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    InProgress,
+    Done,
+}
+
+impl AncestryChecker {
+    pub(crate) fn add_record(&mut self, record: &Sourced<RawRecord>) {
+        if record.line.tag.as_str() != "FAM" {
+            return;
+        }
+
+        let partners: Vec<&str> = record
+            .records
+            .iter()
+            .filter(|child| matches!(child.line.tag.as_str(), "HUSB" | "WIFE"))
+            .filter_map(|partner| partner.pointer())
+            .collect();
+
+        for child in record.subrecords("CHIL") {
+            let Some(xref) = child.pointer() else {
+                continue;
+            };
+
+            let parents = self.parents.entry(xref.to_string()).or_insert_with(|| {
+                self.children.push(xref.to_string());
+                Vec::new()
+            });
+
+            for &partner in &partners {
+                parents.push((partner.to_string(), child.line.value.span));
+            }
+        }
+    }
+
+    /// Reports each cycle in the ancestry of the individuals, once.
+    pub(crate) fn complete(self, handler: &mut impl NonFatalHandler) -> Result<(), ReaderError> {
+        let mut visits: HashMap<&str, Visit> = HashMap::new();
+        for start in &self.children {
+            if visits.contains_key(start.as_str()) {
+                continue;
+            }
+
+            // depth-first search up through the parents, where the stack holds
+            // each individual and how many of their parents have been visited
+            let mut stack: Vec<(&str, usize)> = vec![(start, 0)];
+            visits.insert(start, Visit::InProgress);
+            while let Some((xref, next)) = stack.last_mut() {
+                let parents = self.parents.get(*xref).map_or(&[][..], Vec::as_slice);
+                let Some((parent, span)) = parents.get(*next) else {
+                    visits.insert(xref, Visit::Done);
+                    stack.pop();
+                    continue;
+                };
+
+                *next += 1;
+                match visits.get(parent.as_str()) {
+                    None => {
+                        visits.insert(parent, Visit::InProgress);
+                        stack.push((parent, 0));
+                    }
+                    Some(Visit::InProgress) => {
+                        handler.report(SchemaError::AncestryCycle {
+                            xref: parent.clone(),
+                            span: *span,
+                        })?;
+                    }
+                    Some(Visit::Done) => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+//! Storing the records of a file in a single arena.
+//!
+//! Each [`RawRecord`] keeps its subrecords in a `Vec` of their own, so building
+//! them makes a small allocation for almost every line of the file. A [`RecordArena`]
+//! instead keeps every record in one `Vec`, in the order of the lines of the file:
+//! since the subrecords of a record (and theirs, and so on) directly follow it,
+//! they can be found from a [`Span`] of indices into the arena.
+//!
+//! [`RawRecord`]: super::records::RawRecord
+
+use complex_indifference::{Count, Index, Span};
+use miette::SourceSpan;
+
+use super::{
+    NonFatalHandler, Sourced,
+    lines::RawLine,
+    records::{RecordStructureError, is_missing_value},
+};
+
+/// All of the records of a file; see the [module documentation](self).
+#[derive(Debug, Default)]
+pub struct RecordArena<'i> {
+    records: Vec<ArenaRecord<'i>>,
+}
+
+/// A record (or subrecord) stored in a [`RecordArena`].
+#[derive(Debug)]
+pub struct ArenaRecord<'i> {
+    pub line: Sourced<RawLine<'i, str>>,
+    /// Covers the line of the record, and those of all its subrecords.
+    pub span: SourceSpan,
+    /// The subrecords of the record, their subrecords, and so on.
+    descendants: Span<ArenaRecord<'i>>,
+}
+
+/// A record in a [`RecordArena`], through which its subrecords can be reached.
+#[derive(Debug, Clone, Copy)]
+pub struct RecordRef<'a, 'i> {
+    /// The record, followed by all of its descendants.
+    records: &'a [ArenaRecord<'i>],
+}
+
+/// An iterator over records at one level of a [`RecordArena`].
+#[derive(Debug, Clone)]
+pub struct Records<'a, 'i> {
+    /// Records at the level, each followed by its descendants.
+    remaining: &'a [ArenaRecord<'i>],
+}
+
+impl<'i> RecordArena<'i> {
+    /// The top-level records, in order.
+    pub fn records(&self) -> Records<'_, 'i> {
+        Records { remaining: &self.records }
+    }
+
+    /// The number of records in the arena, including subrecords.
+    pub fn len(&self) -> Count<ArenaRecord<'i>> {
+        Count::new(self.records.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+impl<'a, 'i> RecordRef<'a, 'i> {
+    pub fn record(&self) -> &'a ArenaRecord<'i> {
+        &self.records[0]
+    }
+
+    /// The direct subrecords of the record, in order.
+    pub fn subrecords(&self) -> Records<'a, 'i> {
+        Records { remaining: &self.records[1..] }
+    }
+}
+
+impl<'i> std::ops::Deref for RecordRef<'_, 'i> {
+    type Target = ArenaRecord<'i>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.records[0]
+    }
+}
+
+impl<'a, 'i> Iterator for Records<'a, 'i> {
+    type Item = RecordRef<'a, 'i>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.remaining.first()?;
+        let (records, remaining) = self
+            .remaining
+            .split_at(1 + first.descendants.len().as_usize());
+        self.remaining = remaining;
+        Some(RecordRef { records })
+    }
+}
+
+/// Builds a [`RecordArena`] from lines, in the same way as the
+/// [`RecordBuilder`](super::records::RecordBuilder) builds records.
+pub(crate) struct ArenaBuilder<'i> {
+    records: Vec<ArenaRecord<'i>>,
+    /// The indices of the records which may still get subrecords.
+    stack: Vec<Index<ArenaRecord<'i>>>,
+    recover_invalid_levels: bool,
+}
+
+impl<'i> ArenaBuilder<'i> {
+    pub(crate) fn new() -> Self {
+        Self {
+            records: Vec::new(),
+            stack: Vec::new(),
+            recover_invalid_levels: false,
+        }
+    }
+
+    /// Instead of failing when a line’s level is too deep, report the problem
+    /// to the handler and attach the line to the deepest open record.
+    pub(crate) fn recover_invalid_levels(self, recover_invalid_levels: bool) -> Self {
+        Self { recover_invalid_levels, ..self }
+    }
+
+    fn pop_to_level(
+        &mut self,
+        level: usize,
+        warnings: &mut impl NonFatalHandler,
+    ) -> Result<(), RecordStructureError> {
+        let end = Index::new(self.records.len());
+        while self.stack.len() > level {
+            let index = self.stack.pop().unwrap(); // UNWRAP: guaranteed, len > 0
+
+            // the last line of the record is that of its last descendant
+            let last_span = self.records[end.as_usize() - 1].line.span;
+            let record = &mut self.records[index.as_usize()];
+            record.descendants = Span::try_from_indices(record.descendants.start(), end).unwrap(); // UNWRAP: guaranteed, records are only ever added
+
+            if is_missing_value(&record.line, !record.descendants.is_empty()) {
+                warnings
+                    .report(RecordStructureError::MissingRecordValue { span: record.line.span })?;
+            }
+
+            let offset = record.line.span.offset();
+            let len = last_span.offset() + last_span.len() - offset;
+            record.span = SourceSpan::from((offset, len));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn handle_line(
+        &mut self,
+        (level, line): (Sourced<usize>, Sourced<RawLine<'i, str>>),
+        warnings: &mut impl NonFatalHandler,
+    ) -> Result<(), RecordStructureError> {
+        self.pop_to_level(level.sourced_value, warnings)?;
+
+        let expected_level = self.stack.len();
+        if level.sourced_value != expected_level {
+            let error = RecordStructureError::InvalidChildLevel {
+                level: level.sourced_value,
+                expected_level,
+                span: level.span,
+            };
+
+            if !self.recover_invalid_levels {
+                return Err(error);
+            }
+
+            warnings.report(error)?;
+        }
+
+        let index = Index::new(self.records.len());
+        self.stack.push(index);
+        self.records.push(ArenaRecord {
+            span: line.span,
+            line,
+            descendants: Span::new(index + Count::ONE, Count::ZERO),
+        });
+
+        Ok(())
+    }
+
+    pub(crate) fn complete(
+        mut self,
+        warnings: &mut impl NonFatalHandler,
+    ) -> Result<RecordArena<'i>, RecordStructureError> {
+        self.pop_to_level(0, warnings)?;
+        Ok(RecordArena { records: self.records })
+    }
+}
@@ -0,0 +1,207 @@
+//! Reading all the GEDCOM files in a directory at once.
+
+// a `ScanError` carries the whole error for a file, but there is only one per file
+#![allow(clippy::result_large_err)]
+
+use std::{
+    collections::HashSet,
+    panic::AssertUnwindSafe,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError, mpsc},
+    thread,
+};
+
+use super::{
+    Reader, ReaderError, ValidationResult, WithSourceCode,
+    input::{self, FileLoadError, Input, RawInput},
+};
+use crate::versions::KnownVersion;
+
+/// The result of validating one file found by [`Reader::scan_dir`].
+#[derive(Debug)]
+pub struct FileSummary {
+    pub path: PathBuf,
+    pub version: Option<KnownVersion>,
+    /// The product which produced the file, from `HEAD.SOUR`.
+    pub producer: Option<String>,
+    pub validation: ValidationResult,
+}
+
+#[derive(thiserror::Error, derive_more::Display, Debug, miette::Diagnostic)]
+pub enum ScanError {
+    #[display("An error occurred while reading the directory: {}", path.display())]
+    Directory {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+    #[display("The file could not be loaded: {}", path.display())]
+    Load {
+        #[diagnostic_source]
+        source: FileLoadError,
+        path: PathBuf,
+    },
+    #[display("The file could not be read: {}", path.display())]
+    Read {
+        #[diagnostic_source]
+        source: WithSourceCode<'static, ReaderError>,
+        /// The product which produced the file, if it could be found in the header.
+        producer: Option<String>,
+        path: PathBuf,
+    },
+    #[display("Reading the file caused a panic: {}", path.display())]
+    #[diagnostic(help("{message}"))]
+    Panicked { message: String, path: PathBuf },
+}
+
+impl ScanError {
+    /// The file or directory which could not be read.
+    pub fn path(&self) -> &Path {
+        match self {
+            ScanError::Directory { path, .. }
+            | ScanError::Load { path, .. }
+            | ScanError::Read { path, .. }
+            | ScanError::Panicked { path, .. } => path,
+        }
+    }
+}
+
+/// Whether the file looks like something that [`Reader::scan_dir`] should read.
+fn is_gedcom_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| {
+        ext.eq_ignore_ascii_case("ged")
+            || (cfg!(feature = "gedzip") && ext.eq_ignore_ascii_case("gdz"))
+    })
+}
+
+/// Finds all the GEDCOM files under the directory, in order of their paths.
+///
+/// Symbolic links to directories are followed, but each directory
+/// is only searched once (so that a link to a parent cannot loop).
+fn find_files(
+    dir: &Path,
+    visited: &mut HashSet<PathBuf>,
+    found: &mut Vec<Result<PathBuf, ScanError>>,
+) {
+    match dir.canonicalize() {
+        Ok(canonical) => {
+            if !visited.insert(canonical) {
+                return;
+            }
+        }
+        Err(source) => {
+            found.push(Err(ScanError::Directory { source, path: dir.to_owned() }));
+            return;
+        }
+    }
+
+    let entries =
+        match std::fs::read_dir(dir).and_then(|entries| entries.collect::<Result<Vec<_>, _>>()) {
+            Ok(entries) => entries,
+            Err(source) => {
+                found.push(Err(ScanError::Directory { source, path: dir.to_owned() }));
+                return;
+            }
+        };
+
+    let mut paths: Vec<PathBuf> = entries.into_iter().map(|entry| entry.path()).collect();
+    paths.sort();
+
+    for path in paths {
+        if path.is_dir() {
+            find_files(&path, visited, found);
+        } else if is_gedcom_file(&path) {
+            found.push(Ok(path));
+        }
+    }
+}
+
+impl Reader {
+    /// Validates every GEDCOM file (`.ged`, or `.gdz` with the `gedzip` feature)
+    /// in the directory and its subdirectories.
+    ///
+    /// Files are read in parallel, so the results are produced in the
+    /// order that the files finish being read, rather than in order of their paths.
+    pub fn scan_dir(
+        &self,
+        path: &Path,
+    ) -> impl Iterator<Item = Result<FileSummary, ScanError>> + use<> {
+        let mut found = Vec::new();
+        find_files(path, &mut HashSet::new(), &mut found);
+
+        let (sender, receiver) = mpsc::channel();
+        let queue = Arc::new(Mutex::new(found.into_iter()));
+        let workers = thread::available_parallelism().map_or(1, |n| n.get());
+        for _ in 0..workers {
+            let (reader, queue, sender) = (self.clone(), queue.clone(), sender.clone());
+            thread::spawn(move || {
+                loop {
+                    // a panic is caught before it can poison the lock,
+                    // but there is no harm in carrying on if it did
+                    let next = queue.lock().unwrap_or_else(PoisonError::into_inner).next();
+                    let Some(next) = next else {
+                        break;
+                    };
+
+                    let result = next.and_then(|path| reader.summarize_caught(path));
+                    if sender.send(result).is_err() {
+                        break; // the iterator was dropped
+                    }
+                }
+            });
+        }
+
+        receiver.into_iter()
+    }
+
+    /// Summarizes the file, reporting any panic as an error
+    /// so that the other files are still read.
+    fn summarize_caught(&self, path: PathBuf) -> Result<FileSummary, ScanError> {
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| self.summarize(path.clone())));
+        result.unwrap_or_else(|payload| {
+            let message = match payload.downcast::<String>() {
+                Ok(message) => *message,
+                Err(payload) => match payload.downcast::<&'static str>() {
+                    Ok(message) => message.to_string(),
+                    Err(_) => "the panic had no message".to_string(),
+                },
+            };
+
+            Err(ScanError::Panicked { message, path })
+        })
+    }
+
+    fn summarize(&self, path: PathBuf) -> Result<FileSummary, ScanError> {
+        #[cfg(feature = "gedzip")]
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("gdz"))
+        {
+            let max_size = self.opts.limits.max_encoded_size();
+            let file = input::GedZip::load_with_limit(path.clone(), max_size);
+            return self.summarize_input(path, file);
+        }
+
+        let file = input::File::load(path.clone());
+        self.summarize_input(path, file)
+    }
+
+    fn summarize_input(
+        &self,
+        path: PathBuf,
+        file: Result<impl RawInput<'static> + 'static, FileLoadError>,
+    ) -> Result<FileSummary, ScanError> {
+        let decoded = file
+            .and_then(|file| Ok(self.decode(file)?))
+            .map_err(|source| ScanError::Load { source, path: path.clone() })?;
+
+        match self.validate(&decoded) {
+            Ok(validation) => Ok(FileSummary {
+                version: decoded.version(),
+                producer: self.producer(&decoded),
+                validation,
+                path,
+            }),
+            Err(source) => Err(ScanError::Read { source, producer: self.producer(&decoded), path }),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! Checking that the dates of events are plausible, given the ages
+//! the individuals involved would have been at the time.
+//!
+//! Like [`AncestryChecker`](super::ancestry::AncestryChecker), this is done
+//! once the whole file has been seen, since families may refer forwards.
+
+use std::collections::HashMap;
+
+use miette::SourceSpan;
+
+use super::{NonFatalHandler, ReaderError, Sourced, records::RawRecord};
+use crate::{
+    ages,
+    schemas::{
+        SchemaError,
+        dates::{AgeRange, DateRange},
+    },
+};
+
+/// Mothers younger than this at the birth of a child are reported.
+const MIN_MOTHER_AGE: i32 = 13;
+
+/// Mothers older than this at the birth of a child are reported.
+const MAX_MOTHER_AGE: i32 = 60;
+
+type Dated = (DateRange, SourceSpan);
+
+#[derive(Default)]
+struct Individual {
+    birth: Option<Dated>,
+    death: Option<Dated>,
+    burial: Option<Dated>,
+}
+
+struct Family {
+    wife: Option<String>,
+    partners: Vec<String>,
+    children: Vec<String>,
+    marriage: Option<Dated>,
+}
+
+#[derive(Default)]
+pub(crate) struct ChronologyChecker {
+    min_marriage_age: Option<u32>,
+    /// The individuals, in the order they are found.
+    individuals: Vec<(String, Individual)>,
+    families: Vec<Family>,
+}
+
+impl ChronologyChecker {
+    pub(crate) fn new(min_marriage_age: Option<u32>) -> Self {
+        Self { min_marriage_age, ..Self::default() }
+    }
+
+    pub(crate) fn add_record(&mut self, record: &Sourced<RawRecord>) {
+        let Some(xref) = &record.line.xref else {
+            return;
+        };
+
+        match record.line.tag.as_str() {
+            "INDI" => {
+                let individual = Individual {
+                    birth: event_date(record, "BIRT"),
+                    death: event_date(record, "DEAT"),
+                    burial: event_date(record, "BURI"),
+                };
+
+                self.individuals.push((xref.to_string(), individual));
+            }
+            "FAM" => {
+                let pointers = |tag| {
+                    record
+                        .subrecords(tag)
+                        .filter_map(RawRecord::pointer)
+                        .map(String::from)
+                };
+
+                self.families.push(Family {
+                    wife: pointers("WIFE").next(),
+                    partners: pointers("HUSB").chain(pointers("WIFE")).collect(),
+                    children: pointers("CHIL").collect(),
+                    marriage: event_date(record, "MARR"),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Reports any improbable dates, in the order the individuals and families were found.
+    pub(crate) fn complete(self, handler: &mut impl NonFatalHandler) -> Result<(), ReaderError> {
+        for (_, individual) in &self.individuals {
+            if let (Some((death, death_span)), Some((burial, burial_span))) =
+                (&individual.death, &individual.burial)
+            {
+                if burial.is_before(death) {
+                    handler.report(SchemaError::BurialBeforeDeath {
+                        burial_span: *burial_span,
+                        death_span: *death_span,
+                    })?;
+                }
+            }
+        }
+
+        let individuals: HashMap<&str, &Individual> = self
+            .individuals
+            .iter()
+            .map(|(xref, individual)| (xref.as_str(), individual))
+            .collect();
+
+        let birth = |xref: &str| individuals.get(xref).and_then(|i| i.birth.as_ref());
+
+        for family in &self.families {
+            if let Some((mother_birth, mother_span)) = family.wife.as_deref().and_then(birth) {
+                for (child_birth, child_span) in family.children.iter().filter_map(|c| birth(c)) {
+                    let age = AgeRange::at(mother_birth, child_birth);
+                    if let Some(age) = age.max.filter(|age| *age < MIN_MOTHER_AGE) {
+                        handler.report(SchemaError::MotherTooYoung {
+                            age,
+                            mother_birth_span: *mother_span,
+                            child_birth_span: *child_span,
+                        })?;
+                    } else if let Some(age) = age.min.filter(|age| *age > MAX_MOTHER_AGE) {
+                        handler.report(SchemaError::MotherTooOld {
+                            age,
+                            mother_birth_span: *mother_span,
+                            child_birth_span: *child_span,
+                        })?;
+                    }
+                }
+            }
+
+            if let (Some(min_age), Some((marriage, marriage_span))) =
+                (self.min_marriage_age, &family.marriage)
+            {
+                for (partner_birth, birth_span) in family.partners.iter().filter_map(|p| birth(p)) {
+                    let age = AgeRange::at(partner_birth, marriage).max;
+                    if let Some(age) = age.filter(|age| *age < min_age as i32) {
+                        handler.report(SchemaError::MarriedTooYoung {
+                            age,
+                            min_age,
+                            birth_span: *birth_span,
+                            marriage_span: *marriage_span,
+                        })?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The date of the first event with the tag which has a date that can be parsed.
+fn event_date(record: &RawRecord, tag: &'static str) -> Option<Dated> {
+    record.subrecords(tag).find_map(ages::event_date)
+}
@@ -49,6 +49,29 @@ pub enum DecodingError {
     SyntaxError(#[from] LineSyntaxError),
 }
 
+/// Problems with the data which were tolerated while decoding the file,
+/// because the corresponding [`ParseOptions`](super::options::ParseOptions) were set.
+#[derive(thiserror::Error, derive_more::Display, Debug, Clone, miette::Diagnostic)]
+pub enum DecodingWarning {
+    #[display("Repeated byte order mark")]
+    #[diagnostic(severity(Warning), code(gedcom::encoding::repeated_bom))]
+    RepeatedBOM {
+        #[label("the byte order mark was repeated before this")]
+        span: SourceSpan,
+    },
+
+    #[display("Trailing NUL characters")]
+    #[diagnostic(
+        severity(Warning),
+        code(gedcom::encoding::trailing_nuls),
+        help("the file might have been exported with padding up to a fixed size")
+    )]
+    TrailingNuls {
+        #[label("the NUL characters after this line were ignored")]
+        span: SourceSpan,
+    },
+}
+
 #[derive(derive_more::Display, Debug, miette::Diagnostic)]
 #[display("Invalid data for encoding {encoding}")]
 #[diagnostic(code(gedcom::encoding::invalid_data))]
@@ -64,6 +87,12 @@ pub struct InvalidDataForEncodingError {
     reason: Vec1<Box<dyn miette::Diagnostic + Send + Sync + 'static>>,
 }
 
+impl From<InvalidDataForEncodingError> for super::ReaderError {
+    fn from(value: InvalidDataForEncodingError) -> Self {
+        DecodingError::from(value).into()
+    }
+}
+
 // TODO: https://github.com/JelteF/derive_more/issues/426
 //  will be released in 2.1.0
 impl std::error::Error for InvalidDataForEncodingError {
@@ -144,7 +173,7 @@ pub fn detect_external_encoding(input: &[u8]) -> Result<Option<DetectedEncoding>
 
             let span_until = if line.len() < 100 { line.len() } else { 0 };
 
-            if lines::parse_line(input, line).is_ok() {
+            if !line.is_empty() && lines::parse_line(input, line).is_ok() {
                 return Err(EncodingError::MultiVolume {
                     start: SourceSpan::from((0, span_until)),
                 });
@@ -157,6 +186,49 @@ pub fn detect_external_encoding(input: &[u8]) -> Result<Option<DetectedEncoding>
     Ok(Some(result))
 }
 
+const BOMS: [&[u8]; 3] = [b"\xEF\xBB\xBF", b"\xFF\xFE", b"\xFE\xFF"];
+
+/// Removes any repeated byte order marks from the start of the data,
+/// leaving a single byte order mark.
+///
+/// As the byte order marks are not part of the decoded text, the span of the
+/// warning is the (empty) start of the decoded text.
+pub(crate) fn skip_repeated_bom(data: &[u8]) -> (&[u8], Option<DecodingWarning>) {
+    for bom in BOMS {
+        let mut rest = data;
+        while rest.starts_with(bom) && rest[bom.len()..].starts_with(bom) {
+            rest = &rest[bom.len()..];
+        }
+
+        if rest.len() < data.len() {
+            let span = SourceSpan::from((0, 0));
+            return (rest, Some(DecodingWarning::RepeatedBOM { span }));
+        }
+    }
+
+    (data, None)
+}
+
+/// Removes any NUL characters from the end of the decoded text.
+///
+/// As the NULs are no longer part of the decoded text, the span of the
+/// warning is the (empty) end of the last line of the remaining text.
+pub(crate) fn trim_trailing_nuls(decoded: &mut Cow<str>) -> Option<DecodingWarning> {
+    let trimmed_len = decoded.trim_end_matches('\0').len();
+    if trimmed_len == decoded.len() {
+        return None;
+    }
+
+    match decoded {
+        Cow::Borrowed(s) => *s = &s[..trimmed_len],
+        Cow::Owned(s) => s.truncate(trimmed_len),
+    }
+
+    let line_end = decoded.trim_end_matches(['\r', '\n']).len();
+    let span = SourceSpan::from((line_end, 0));
+    Some(DecodingWarning::TrailingNuls { span })
+}
+
 /// Represents the result of performing encoding detection.
 ///
 /// Returns the detected [`SupportedEncoding`] and the reason for the detection;
@@ -179,6 +251,14 @@ impl DetectedEncoding {
     pub fn reason(&self) -> EncodingReason {
         self.reason
     }
+
+    /// The length of the byte order mark which is skipped when decoding, if any.
+    pub(crate) fn bom_length(&self) -> usize {
+        match self.reason {
+            EncodingReason::BOMDetected { bom_length } => bom_length,
+            _ => 0,
+        }
+    }
 }
 
 impl DetectedEncoding {
@@ -189,10 +269,7 @@ impl DetectedEncoding {
         tracing::debug!(encoding = %self.encoding, "decoding file data");
 
         // trim off BOM, if any
-        let offset_adjustment = match self.reason {
-            EncodingReason::BOMDetected { bom_length } => bom_length,
-            _ => 0,
-        };
+        let offset_adjustment = self.bom_length();
 
         let data = &data[offset_adjustment..];
 
@@ -38,6 +38,32 @@ pub enum Encoding {
     Windows1252,
 }
 
+impl Encoding {
+    /// Every supported encoding.
+    pub const ALL: &'static [Encoding] = &[
+        Encoding::Ascii,
+        Encoding::Ansel,
+        Encoding::Utf8,
+        Encoding::Utf16BE,
+        Encoding::Utf16LE,
+        Encoding::Windows1252,
+    ];
+
+    /// The largest number of bytes that data of the given length
+    /// could take up once it has been decoded to UTF-8.
+    pub(crate) fn max_decoded_len(self, len: usize) -> usize {
+        match self {
+            Encoding::Ascii | Encoding::Utf8 => len,
+            // every byte becomes a character in the Basic Multilingual Plane,
+            // which takes up at most 3 bytes in UTF-8
+            Encoding::Ansel | Encoding::Windows1252 => len.saturating_mul(3),
+            // every 2 bytes become a character in the Basic Multilingual Plane,
+            // or every 4 bytes a character outside of it (which takes up 4 bytes)
+            Encoding::Utf16BE | Encoding::Utf16LE => (len / 2).saturating_mul(3),
+        }
+    }
+}
+
 #[derive(thiserror::Error, derive_more::Display, Debug, miette::Diagnostic, Copy, Clone)]
 pub enum EncodingReason {
     #[display("this encoding was detected from the byte-order mark (BOM) at the start of the file")]
@@ -142,6 +168,35 @@ pub enum EncodingError {
         assumed_version: KnownVersion,
     },
 
+    #[error(
+        "GEDCOM version {version}{} does not permit the encoding {encoding}",
+        if version_span.is_none() { " (this version was selected explicitly in the options)" } else { "" }
+    )]
+    #[diagnostic(code(gedcom::encoding::version_encoding_mismatch))]
+    VersionEncodingNotPermitted {
+        version: KnownVersion,
+
+        #[label("file version was specified here")]
+        version_span: Option<SourceSpan>,
+
+        encoding: Encoding,
+
+        #[label("encoding was specified here")]
+        encoding_span: SourceSpan,
+    },
+
+    #[error("The file’s GEDCOM header does not specify an encoding")]
+    #[diagnostic(
+        code(gedcom::encoding::missing_encoding),
+        help("GEDCOM {version} files must contain a CHAR record in the header")
+    )]
+    MissingEncoding {
+        version: KnownVersion,
+
+        #[label("this header has no CHAR record")]
+        span: SourceSpan,
+    },
+
     #[error(
         "The file’s GEDCOM header specifies the encoding to be {file_encoding}, but the file encoding was determined to be {external_encoding}"
     )]
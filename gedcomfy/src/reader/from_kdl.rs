@@ -0,0 +1,214 @@
+//! Converting KDL documents back into GEDCOM.
+//!
+//! This is the reverse of [`Reader::parse_kdl`](super::Reader::parse_kdl), so that a
+//! file can be edited as KDL and then converted back. The KDL document must have the
+//! same shape as the ones produced by `parse_kdl`:
+//!
+//! ```kdl
+//! INDI xref="I1" {
+//!     NAME "John /Smith/"
+//!     FAMS see="F1"
+//! }
+//! ```
+//!
+//! Each node is a record, whose name is the tag. Its value is either a single
+//! string argument or (for pointers) a `see` property, which is `null` for `@VOID@`.
+//! The record’s xref is given by the `xref` property, and its subrecords by the
+//! node’s children.
+//!
+//! The result is GEDCOM text, which can be read in the usual way:
+//!
+//! ```
+//! # use gedcomfy::reader::{Reader, from_kdl};
+//! let gedcom = from_kdl::to_gedcom(r#"
+//!     HEAD { GEDC { VERS "5.5.1"; }; CHAR "UTF-8"; }
+//!     INDI xref="I1" { NAME "John /Smith/"; }
+//!     TRLR
+//! "#)?;
+//!
+//! let input = gedcom.as_str();
+//! let records = Reader::default().raw_records(&input).map_err(|e| e.into_owned())?;
+//! assert_eq!(records.len(), 3);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Note that the text is always UTF-8, so the `HEAD.CHAR` record should say so.
+//! The line terminators and escaping can be chosen with [`to_gedcom_with_options`],
+//! for programs which need them; see the [`writer`](crate::writer) module.
+
+use kdl::{KdlDocument, KdlEntry, KdlNode, KdlValue};
+use miette::SourceSpan;
+
+use crate::writer::{GedcomWriter, LineText, WriteOptions};
+
+#[derive(thiserror::Error, derive_more::Display, Debug, miette::Diagnostic)]
+pub enum KdlImportError {
+    #[display("The KDL document could not be parsed: {message}")]
+    #[diagnostic(code(gedcom::kdl::syntax))]
+    Syntax {
+        message: String,
+        #[label("{}", label.unwrap_or("here"))]
+        span: SourceSpan,
+        label: Option<&'static str>,
+        #[help]
+        help: Option<&'static str>,
+    },
+
+    #[display("Invalid GEDCOM tag '{tag}'")]
+    #[diagnostic(
+        code(gedcom::kdl::invalid_tag),
+        help(
+            "tag names must begin with either an uppercase letter or underscore, followed by letters or numbers"
+        )
+    )]
+    InvalidTag {
+        tag: String,
+        #[label("this node name is not a valid tag")]
+        span: SourceSpan,
+    },
+
+    #[display("Unexpected entry in KDL node")]
+    #[diagnostic(
+        code(gedcom::kdl::unexpected_entry),
+        help(
+            "a node can have an `xref` property, and either a single string argument or a `see` property"
+        )
+    )]
+    UnexpectedEntry {
+        #[label("this entry cannot be converted to GEDCOM")]
+        span: SourceSpan,
+    },
+
+    #[display("Invalid value for KDL entry")]
+    #[diagnostic(
+        code(gedcom::kdl::invalid_value),
+        help("values and xrefs must be strings; a `see` property can also be null, for `@VOID@`")
+    )]
+    InvalidValue {
+        #[label("this value cannot be converted to GEDCOM")]
+        span: SourceSpan,
+    },
+}
+
+// KDL uses a different version of miette, so its spans must be converted
+macro_rules! convert_span {
+    ($span:expr) => {{
+        let span = $span;
+        SourceSpan::from((span.offset(), span.len()))
+    }};
+}
+
+/// Converts KDL text in the shape produced by [`Reader::parse_kdl`](super::Reader::parse_kdl)
+/// into GEDCOM text; see the [module documentation](self).
+pub fn to_gedcom(kdl: &str) -> Result<String, KdlImportError> {
+    to_gedcom_with_options(kdl, WriteOptions::default())
+}
+
+/// Like [`to_gedcom`], but writing the GEDCOM text with the given options.
+pub fn to_gedcom_with_options(kdl: &str, options: WriteOptions) -> Result<String, KdlImportError> {
+    let doc: KdlDocument = kdl
+        .parse()
+        .map_err(|err: kdl::KdlError| KdlImportError::Syntax {
+            message: err.kind.to_string(),
+            span: convert_span!(err.span),
+            label: err.label,
+            help: err.help,
+        })?;
+
+    document_to_gedcom_with_options(&doc, options)
+}
+
+/// Converts a KDL document in the shape produced by
+/// [`Reader::parse_kdl`](super::Reader::parse_kdl) into GEDCOM text.
+pub fn document_to_gedcom(doc: &KdlDocument) -> Result<String, KdlImportError> {
+    document_to_gedcom_with_options(doc, WriteOptions::default())
+}
+
+/// Like [`document_to_gedcom`], but writing the GEDCOM text with the given options.
+pub fn document_to_gedcom_with_options(
+    doc: &KdlDocument,
+    options: WriteOptions,
+) -> Result<String, KdlImportError> {
+    let mut writer = GedcomWriter::new(options);
+    for node in doc.nodes() {
+        write_node(&mut writer, 0, node)?;
+    }
+
+    Ok(writer.finish())
+}
+
+fn write_node(
+    writer: &mut GedcomWriter,
+    level: usize,
+    node: &KdlNode,
+) -> Result<(), KdlImportError> {
+    let tag = node.name().value();
+    if !is_valid_tag(tag) {
+        return Err(KdlImportError::InvalidTag {
+            tag: tag.to_string(),
+            span: convert_span!(node.name().span()),
+        });
+    }
+
+    let mut xref = None;
+    let mut value = None;
+    for entry in node.entries() {
+        let slot = match entry.name().map(|name| name.value()) {
+            Some("xref") => &mut xref,
+            Some("see") | None => &mut value,
+            Some(_) => return Err(unexpected(entry)),
+        };
+
+        if slot.is_some() {
+            return Err(unexpected(entry));
+        }
+
+        *slot = Some(entry);
+    }
+
+    let xref = xref.map(string_value).transpose()?;
+
+    match value {
+        None => writer.write_line(level, xref, tag, None),
+        // a pointer, from the `see` property
+        Some(entry) if entry.name().is_some() => {
+            let pointer = match entry.value() {
+                KdlValue::Null => None,
+                _ => Some(string_value(entry)?),
+            };
+
+            writer.write_line(level, xref, tag, Some(LineText::Pointer(pointer)));
+        }
+        // line breaks and long lines become CONT and CONC records
+        Some(entry) => writer.write_text(level, xref, tag, string_value(entry)?),
+    }
+
+    if let Some(children) = node.children() {
+        for child in children.nodes() {
+            write_node(writer, level + 1, child)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn string_value(entry: &KdlEntry) -> Result<&str, KdlImportError> {
+    entry
+        .value()
+        .as_string()
+        .ok_or_else(|| KdlImportError::InvalidValue { span: convert_span!(entry.span()) })
+}
+
+fn unexpected(entry: &KdlEntry) -> KdlImportError {
+    KdlImportError::UnexpectedEntry { span: convert_span!(entry.span()) }
+}
+
+fn is_valid_tag(tag: &str) -> bool {
+    let mut chars = tag.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_uppercase() || first == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric())
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,297 @@
+//! Reparsing only the records which are affected by an edit to the source,
+//! so that the records of a large file can be kept up to date as it is edited.
+
+use std::ops::Range;
+
+use ascii::AsAsciiStr;
+use miette::SourceSpan;
+
+use super::{
+    NonFatalHandler, Reader, ReaderError, Sourced,
+    lines::{LineValue, RawLine},
+    records::RawRecord,
+};
+
+/// An edit to the source code: the bytes in `range` of the old
+/// source code were replaced by `new_len` bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub range: Range<usize>,
+    pub new_len: usize,
+}
+
+impl Edit {
+    pub fn new(range: Range<usize>, new_len: usize) -> Self {
+        Self { range, new_len }
+    }
+
+    /// How far the source code after the edit has moved,
+    /// if that can be represented.
+    fn shift(&self) -> Option<isize> {
+        let new_len = isize::try_from(self.new_len).ok()?;
+        let old_len = isize::try_from(self.range.len()).ok()?;
+        new_len.checked_sub(old_len)
+    }
+}
+
+/// The result of [`Reader::reparse`].
+#[derive(Debug)]
+pub struct Reparsed<'i> {
+    /// All of the top-level records in the new source code.
+    pub records: Vec<Sourced<RawRecord<'i>>>,
+    /// The indices (in `records`) of the records which were reparsed;
+    /// all the others were carried over from the previous records.
+    pub reparsed: Range<usize>,
+    /// The part of the new source code which was reparsed.
+    pub region: Range<usize>,
+    /// Any non-fatal problems found in the reparsed records.
+    pub warnings: Vec<ReaderError>,
+}
+
+#[derive(Default)]
+struct WarningsCollector(Vec<ReaderError>);
+
+impl NonFatalHandler for WarningsCollector {
+    fn report<E>(&mut self, error: E) -> Result<(), E>
+    where
+        E: Into<ReaderError> + miette::Diagnostic,
+    {
+        self.0.push(error.into());
+        Ok(())
+    }
+}
+
+impl Reader {
+    /// Reads all of the records in the source code, so that they
+    /// can be kept up to date by [`Reader::reparse`] as it is edited.
+    ///
+    /// As with [`Reader::reparse`], checks which involve the whole file are not performed.
+    pub fn parse_incremental<'i>(&self, source: &'i str) -> Result<Reparsed<'i>, ReaderError> {
+        let mut warnings = WarningsCollector::default();
+        let mut records = Vec::new();
+        self.read_records_within(source, source, &mut warnings, |record, _| {
+            records.push(record);
+            Ok(())
+        })?;
+
+        Ok(Reparsed {
+            reparsed: 0..records.len(),
+            region: 0..source.len(),
+            records,
+            warnings: warnings.0,
+        })
+    }
+
+    /// Updates the records read from `old_source` (for example, by [`Reader::raw_records`])
+    /// after it has been edited to produce `new_source`.
+    ///
+    /// Only the top-level records touched by the edit (and the record before them,
+    /// in case the edit changed the level of a line) are reparsed. The other records are
+    /// moved across to the new source code, with their spans adjusted to match.
+    ///
+    /// Checks which involve the whole file (such as checking that all pointers refer
+    /// to records) are not performed; use [`Reader::validate`] for those.
+    ///
+    /// If the edit does not fit the old and new source code, the whole
+    /// of the new source code is read, as by [`Reader::parse_incremental`].
+    pub fn reparse<'i>(
+        &self,
+        old_source: &str,
+        previous: &[Sourced<RawRecord<'_>>],
+        edit: &Edit,
+        new_source: &'i str,
+    ) -> Result<Reparsed<'i>, ReaderError> {
+        // the text outside of the edit must be unchanged
+        let Some(shift) = edit.shift().filter(|&shift| {
+            edit.range.start <= edit.range.end
+                && edit.range.end <= old_source.len()
+                && old_source.len().checked_add_signed(shift) == Some(new_source.len())
+                && old_source.as_bytes()[..edit.range.start]
+                    == new_source.as_bytes()[..edit.range.start]
+                && edit
+                    .range
+                    .start
+                    .checked_add(edit.new_len)
+                    .and_then(|new_end| new_source.as_bytes().get(new_end..))
+                    == Some(&old_source.as_bytes()[edit.range.end..])
+        }) else {
+            return self.parse_incremental(new_source);
+        };
+
+        let end_of = |record: &Sourced<RawRecord>| record.span.offset() + record.span.len();
+        let first = previous
+            .partition_point(|record| end_of(record) < edit.range.start)
+            .saturating_sub(1);
+        let after = previous
+            .partition_point(|record| record.span.offset() <= edit.range.end)
+            .max(first);
+
+        // the reparsed region runs up to the start of the next untouched record,
+        // so that it includes anything between the records
+        let region_start = match first {
+            0 => 0,
+            _ => previous[first].span.offset(),
+        };
+        let region_end = match previous.get(after) {
+            Some(record) => record.span.offset(),
+            None => old_source.len(),
+        };
+        let Some(new_region) = region_end
+            .checked_add_signed(shift)
+            .map(|region_end| region_start..region_end)
+            .filter(|region| new_source.get(region.clone()).is_some())
+        else {
+            // the previous records do not fit the old source code
+            return self.parse_incremental(new_source);
+        };
+
+        let mut warnings = WarningsCollector::default();
+        let mut reparsed = Vec::new();
+        self.read_records_within(
+            new_source,
+            &new_source[new_region.clone()],
+            &mut warnings,
+            |record, _| {
+                reparsed.push(record);
+                Ok(())
+            },
+        )?;
+
+        let before = Rebase { old_source, new_source, shift: 0 };
+        let mut records = Vec::with_capacity(previous.len());
+        for record in &previous[..first] {
+            records.extend(self.carry_over(&before, record, new_source)?);
+        }
+
+        let reparsed_range = records.len()..records.len() + reparsed.len();
+        records.extend(reparsed);
+
+        let after_edit = Rebase { old_source, new_source, shift };
+        for record in &previous[after..] {
+            records.extend(self.carry_over(&after_edit, record, new_source)?);
+        }
+
+        Ok(Reparsed {
+            records,
+            reparsed: reparsed_range,
+            region: new_region,
+            warnings: warnings.0,
+        })
+    }
+
+    /// Moves a record which was not touched by the edit to the new source code.
+    ///
+    /// If the record contains text which is not from the old source code (because
+    /// a [`RecordVisitor`](super::visitors::RecordVisitor) rewrote it), the record
+    /// is read again from the new source code instead, so that it is visited again
+    /// (and it might be dropped by the visitor this time).
+    fn carry_over<'i>(
+        &self,
+        rebase: &Rebase<'_, 'i>,
+        record: &Sourced<RawRecord<'_>>,
+        new_source: &'i str,
+    ) -> Result<Option<Sourced<RawRecord<'i>>>, ReaderError> {
+        if let Some(record) = rebase.record(record) {
+            return Ok(Some(record));
+        }
+
+        let span = rebase.span(record.span);
+        let text = new_source
+            .get(span.offset()..span.offset() + span.len())
+            .unwrap_or_default();
+
+        // the record’s text has not changed, so neither have its warnings,
+        // which were reported when it was first read
+        let mut warnings = WarningsCollector::default();
+        let mut reread = None;
+        self.read_records_within(new_source, text, &mut warnings, |record, _| {
+            reread.get_or_insert(record);
+            Ok(())
+        })?;
+
+        Ok(reread)
+    }
+}
+
+/// Moves records from the old source code to the same text in the new source code.
+struct Rebase<'o, 'n> {
+    old_source: &'o str,
+    new_source: &'n str,
+    shift: isize,
+}
+
+impl<'n> Rebase<'_, 'n> {
+    fn span(&self, span: SourceSpan) -> SourceSpan {
+        let offset = span.offset().saturating_add_signed(self.shift);
+        SourceSpan::from((offset, span.len()))
+    }
+
+    /// Finds the same text in the new source code, given the span it was read from
+    /// and its offset within that span; or `None` if the text is not found there
+    /// in both the old and new source code (for example, because it was rewritten).
+    fn str(&self, span: SourceSpan, offset: usize, value: &str) -> Option<&'n str> {
+        let start = span.offset() + offset;
+        let end = start + value.len();
+        if end > span.offset() + span.len() || self.old_source.get(start..end)? != value {
+            return None;
+        }
+
+        let start = start.checked_add_signed(self.shift)?;
+        let moved = self.new_source.get(start..start + value.len())?;
+        (moved == value).then_some(moved)
+    }
+
+    fn sourced<T, U>(
+        &self,
+        sourced: &Sourced<T>,
+        f: impl FnOnce(&T) -> Option<U>,
+    ) -> Option<Sourced<U>> {
+        Some(Sourced {
+            span: self.span(sourced.span),
+            sourced_value: f(&sourced.sourced_value)?,
+        })
+    }
+
+    fn record(&self, record: &Sourced<RawRecord<'_>>) -> Option<Sourced<RawRecord<'n>>> {
+        self.sourced(record, |record| {
+            Some(RawRecord {
+                line: self.sourced(&record.line, |line| {
+                    Some(RawLine {
+                        tag: self.sourced(&line.tag, |tag| {
+                            self.str(line.tag.span, 0, tag.as_str())?
+                                .as_ascii_str()
+                                .ok()
+                        })?,
+                        packed_tag: line.packed_tag,
+                        xref: match &line.xref {
+                            Some(xref) => Some(self.sourced(xref, |x| self.str(xref.span, 0, x))?),
+                            None => None,
+                        },
+                        value: self.sourced(&line.value, |value| {
+                            let span = line.value.span;
+                            Some(match value {
+                                LineValue::Ptr(None) => LineValue::Ptr(None),
+                                // the pointer is within its `@`s
+                                LineValue::Ptr(Some(ptr)) => {
+                                    LineValue::Ptr(Some(self.str(span, 1, ptr)?))
+                                }
+                                // the value may have had an escaping `@` removed from its start
+                                LineValue::Str(value) => LineValue::Str(self.str(
+                                    span,
+                                    span.len().saturating_sub(value.len()),
+                                    value,
+                                )?),
+                                LineValue::None => LineValue::None,
+                            })
+                        })?,
+                    })
+                })?,
+                records: record
+                    .records
+                    .iter()
+                    .map(|child| self.record(child))
+                    .collect::<Option<_>>()?,
+            })
+        })
+    }
+}
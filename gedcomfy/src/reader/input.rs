@@ -1,11 +1,21 @@
-use std::{borrow::Cow, ops::Deref, path::PathBuf, sync::Arc};
+use std::{
+    borrow::Cow,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use miette::NamedSource;
 
-use super::{AnySourceCode, WithSourceCode, attach_name, decoding::DecodingError};
+use super::{AnySourceCode, ReaderError, WithSourceCode, attach_name, offsets::OffsetMap};
 
 pub trait RawInput<'s>: AsRef<[u8]> + Send + Sync {
     fn source_code(&self) -> AnySourceCode<'s>;
+
+    /// The path the input was loaded from, if any.
+    fn name(&self) -> Option<&Path> {
+        None
+    }
 }
 
 impl<'s> RawInput<'s> for &'s [u8] {
@@ -17,6 +27,17 @@ impl<'s> RawInput<'s> for &'s [u8] {
 pub trait Input<'s>: AsRef<str> {
     fn source_code(&self) -> AnySourceCode<'s>;
     fn version(&self) -> Option<crate::versions::KnownVersion>;
+
+    /// If the [source code](Input::source_code) is the original data rather than
+    /// the decoded text, maps offsets in the decoded text to offsets in the source code.
+    fn offsets(&self) -> Option<&OffsetMap> {
+        None
+    }
+
+    /// Problems which were tolerated while decoding the input.
+    fn warnings(&self) -> &[super::decoding::DecodingWarning] {
+        &[]
+    }
 }
 
 impl<'s> Input<'s> for &'s str {
@@ -65,6 +86,10 @@ impl miette::SourceCode for File {
 }
 
 impl RawInput<'static> for File {
+    fn name(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
     fn source_code(&self) -> AnySourceCode<'static> {
         struct Wrap(Arc<memmap2::Mmap>);
 
@@ -87,6 +112,84 @@ impl RawInput<'static> for File {
     }
 }
 
+/// A GEDZip archive (`.gdz`), which contains a GEDCOM file named `gedcom.ged`
+/// alongside any media files that it refers to.
+///
+/// Only the GEDCOM file is loaded.
+#[cfg(feature = "gedzip")]
+pub struct GedZip {
+    path: PathBuf,
+    data: Arc<Vec<u8>>,
+}
+
+#[cfg(feature = "gedzip")]
+impl GedZip {
+    /// The name of the GEDCOM file within the archive.
+    pub const GEDCOM_FILE_NAME: &str = "gedcom.ged";
+
+    /// The most memory which is allocated up front for the GEDCOM file,
+    /// since the size recorded in the archive cannot be trusted.
+    const MAX_PREALLOCATION: u64 = 1 << 20;
+
+    pub fn load(path: PathBuf) -> Result<GedZip, FileLoadError> {
+        Self::load_with_limit(path, None)
+    }
+
+    /// Loads the archive, failing if the GEDCOM file is larger than `max_size` bytes
+    /// once extracted, so that a corrupted (or malicious) archive cannot use
+    /// unreasonable amounts of memory.
+    pub fn load_with_limit(
+        path: PathBuf,
+        max_size: Option<usize>,
+    ) -> Result<GedZip, FileLoadError> {
+        let file = match std::fs::File::open(&path) {
+            Ok(file) => file,
+            Err(source) => return Err(FileLoadError::IO { source, path }),
+        };
+
+        let extract = || -> zip::result::ZipResult<Vec<u8>> {
+            let mut archive = zip::ZipArchive::new(std::io::BufReader::new(file))?;
+            let entry = archive.by_name(Self::GEDCOM_FILE_NAME)?;
+            let mut data = Vec::with_capacity(entry.size().min(Self::MAX_PREALLOCATION) as usize);
+            // read one byte more than the limit, to find out if it is exceeded
+            let limit = max_size.map_or(u64::MAX, |max_size| (max_size as u64).saturating_add(1));
+            std::io::Read::read_to_end(&mut std::io::Read::take(entry, limit), &mut data)?;
+            Ok(data)
+        };
+
+        match extract() {
+            Ok(data) => match max_size {
+                Some(max_size) if data.len() > max_size => {
+                    Err(FileLoadError::ArchiveTooLarge { max_size, path })
+                }
+                _ => Ok(GedZip { path, data: Arc::new(data) }),
+            },
+            Err(source) => Err(FileLoadError::Archive { source, path }),
+        }
+    }
+}
+
+#[cfg(feature = "gedzip")]
+impl AsRef<[u8]> for GedZip {
+    fn as_ref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+#[cfg(feature = "gedzip")]
+impl RawInput<'static> for GedZip {
+    fn name(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn source_code(&self) -> AnySourceCode<'static> {
+        AnySourceCode::Shared(Arc::new(NamedSource::new(
+            self.path.to_string_lossy(),
+            self.data.clone(),
+        )))
+    }
+}
+
 #[derive(thiserror::Error, derive_more::Display, Debug, miette::Diagnostic)]
 pub enum FileLoadError {
     #[display( "An error occurred while loading the file: {}", path.display())]
@@ -94,10 +197,23 @@ pub enum FileLoadError {
         source: std::io::Error,
         path: PathBuf,
     },
+    #[cfg(feature = "gedzip")]
+    #[display("The GEDCOM file could not be extracted from the archive: {}", path.display())]
+    Archive {
+        source: zip::result::ZipError,
+        path: PathBuf,
+    },
+    #[cfg(feature = "gedzip")]
+    #[display(
+        "The GEDCOM file in the archive is larger than the maximum of {max_size} bytes: {}",
+        path.display()
+    )]
+    #[diagnostic(code(gedcom::limit::archive_too_large))]
+    ArchiveTooLarge { max_size: usize, path: PathBuf },
     #[error(transparent)]
     #[diagnostic(transparent)]
     Decoding {
         #[from]
-        source: WithSourceCode<'static, DecodingError>,
+        source: WithSourceCode<'static, ReaderError>,
     },
 }
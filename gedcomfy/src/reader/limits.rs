@@ -0,0 +1,116 @@
+//! Limits on the size of the input, so that corrupted (or malicious) files
+//! cannot use unreasonable amounts of memory or time.
+//!
+//! The limits are set by [`ParseOptions`](super::options::ParseOptions);
+//! by default there are none.
+
+use miette::SourceSpan;
+
+use super::{Sourced, encodings::Encoding, lines::RawLine};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Limits {
+    pub(crate) max_level: Option<usize>,
+    pub(crate) max_line_length: Option<usize>,
+    pub(crate) max_records: Option<usize>,
+    pub(crate) max_decoded_size: Option<usize>,
+}
+
+#[derive(thiserror::Error, derive_more::Display, Debug, miette::Diagnostic)]
+pub enum LimitError {
+    #[display("Level {level} is deeper than the maximum of {max_level}")]
+    #[diagnostic(code(gedcom::limit::level_too_deep))]
+    LevelTooDeep {
+        level: usize,
+        max_level: usize,
+        #[label("this level is too deep")]
+        span: SourceSpan,
+    },
+
+    #[display("Line is longer than the maximum of {max_line_length} bytes")]
+    #[diagnostic(code(gedcom::limit::line_too_long))]
+    LineTooLong {
+        max_line_length: usize,
+        #[label("this line is {} bytes long", span.len())]
+        span: SourceSpan,
+    },
+
+    #[display("The file contains more than the maximum of {max_records} records")]
+    #[diagnostic(code(gedcom::limit::too_many_records))]
+    TooManyRecords {
+        max_records: usize,
+        #[label("this record is over the limit")]
+        span: SourceSpan,
+    },
+
+    #[display("The decoded file could be larger than the maximum of {max_decoded_size} bytes")]
+    #[diagnostic(code(gedcom::limit::input_too_large))]
+    #[diagnostic(help(
+        "the file is {size} bytes in {encoding}, which could decode to as many as {decoded_size} bytes"
+    ))]
+    InputTooLarge {
+        size: usize,
+        encoding: Encoding,
+        decoded_size: usize,
+        max_decoded_size: usize,
+    },
+}
+
+impl Limits {
+    /// The largest file which could decode to within the limit on the decoded size,
+    /// since no supported encoding decodes to fewer than half as many bytes.
+    pub(crate) fn max_encoded_size(&self) -> Option<usize> {
+        self.max_decoded_size
+            .map(|max_decoded_size| max_decoded_size.saturating_mul(2))
+    }
+
+    /// Checks the size of the data before it is decoded, so that a file which
+    /// is too large is rejected without using the memory needed to decode it.
+    /// Since the decoded size is not known yet, this uses the largest size
+    /// that data of this size could have once decoded.
+    pub(crate) fn check_decoded_size(
+        &self,
+        encoding: Encoding,
+        size: usize,
+    ) -> Result<(), LimitError> {
+        let decoded_size = encoding.max_decoded_len(size);
+        match self.max_decoded_size {
+            Some(max_decoded_size) if decoded_size > max_decoded_size => {
+                Err(LimitError::InputTooLarge { size, encoding, decoded_size, max_decoded_size })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks a line, which is the `count`th line read (counting from 1).
+    pub(crate) fn check_line(
+        &self,
+        count: usize,
+        level: &Sourced<usize>,
+        line: &Sourced<RawLine<'_, str>>,
+    ) -> Result<(), LimitError> {
+        if let Some(max_level) = self.max_level {
+            if level.sourced_value > max_level {
+                return Err(LimitError::LevelTooDeep {
+                    level: level.sourced_value,
+                    max_level,
+                    span: level.span,
+                });
+            }
+        }
+
+        if let Some(max_line_length) = self.max_line_length {
+            if line.span.len() > max_line_length {
+                return Err(LimitError::LineTooLong { max_line_length, span: line.span });
+            }
+        }
+
+        if let Some(max_records) = self.max_records {
+            if count > max_records {
+                return Err(LimitError::TooManyRecords { max_records, span: line.span });
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
+use std::ops::Range;
+
 use ascii::{AsAsciiStr, AsciiChar, AsciiStr};
 use miette::SourceSpan;
 
-use super::{GEDCOMSource, Sourced};
+use super::{GEDCOMSource, Sourced, packed_tags::PackedTag};
 
 /// Represents a single line in a GEDCOM file.
 ///
@@ -12,10 +14,20 @@ use super::{GEDCOMSource, Sourced};
 #[derive(Debug)]
 pub struct RawLine<'a, S: GEDCOMSource + ?Sized> {
     pub tag: Sourced<&'a AsciiStr>,
+    /// The tag, packed for quick comparisons.
+    pub packed_tag: PackedTag,
     pub xref: Option<Sourced<&'a S>>,
     pub value: Sourced<LineValue<'a, S>>,
 }
 
+impl<'a, S: GEDCOMSource + ?Sized> RawLine<'a, S> {
+    /// Replaces the tag of the line (keeping its span), and its packed form.
+    pub fn set_tag(&mut self, tag: &'a AsciiStr) {
+        self.tag.sourced_value = tag;
+        self.packed_tag = PackedTag::of(tag);
+    }
+}
+
 #[derive(PartialEq, Eq, Debug)]
 pub enum LineValue<'a, S: GEDCOMSource + ?Sized> {
     Ptr(Option<&'a S>),
@@ -82,6 +94,46 @@ pub enum LineSyntaxError {
         #[label("this pointer value should end with '@'")]
         span: SourceSpan,
     },
+
+    #[error("Line break inside a value")]
+    #[diagnostic(
+        severity(Warning),
+        code(gedcom::parse_error::line_break_in_value),
+        help(
+            "the line has been joined onto the value; line breaks in values should be written using CONT records"
+        )
+    )]
+    LineBreakInValue {
+        #[label("this value")]
+        value_span: SourceSpan,
+        #[label("is continued on this line")]
+        continuation_span: SourceSpan,
+    },
+}
+
+/// Splits the input into lines, returning the range of each one. This is the same
+/// as splitting at every CR and LF (so there are empty lines between the two
+/// characters of a CRLF), but finds them with [`memchr`], which searches many
+/// bytes at a time rather than checking each byte in turn.
+///
+/// GEDCOM lines are terminated by “any combination of a carriage
+/// return and a line feed”, so empty lines should be skipped.
+pub(crate) fn line_ranges(source: &[u8]) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut breaks = memchr::memchr2_iter(b'\r', b'\n', source);
+    let mut start = Some(0);
+    std::iter::from_fn(move || {
+        let line_start = start?;
+        match breaks.next() {
+            Some(end) => {
+                start = Some(end + 1);
+                Some(line_start..end)
+            }
+            None => {
+                start = None;
+                Some(line_start..source.len())
+            }
+        }
+    })
 }
 
 /// This is a straightforward parser for GEDCOM lines. It performs
@@ -97,6 +149,15 @@ pub enum LineSyntaxError {
 pub(crate) fn iterate_lines<S: GEDCOMSource + ?Sized>(
     source_code: &S,
 ) -> impl Iterator<Item = Result<(Sourced<usize>, Sourced<RawLine<'_, S>>), LineSyntaxError>> {
+    iterate_lines_within(source_code, source_code)
+}
+
+/// Like [`iterate_lines`], but only parses the lines in `within`, which must
+/// be a part of the source code (spans are still relative to the whole source code).
+pub(crate) fn iterate_lines_within<'a, S: GEDCOMSource + ?Sized>(
+    source_code: &'a S,
+    within: &'a S,
+) -> impl Iterator<Item = Result<(Sourced<usize>, Sourced<RawLine<'a, S>>), LineSyntaxError>> {
     // Line syntax is as follows:
     /*
     Line    = Level D [Xref D] Tag [D LineVal] EOL
@@ -120,7 +181,7 @@ pub(crate) fn iterate_lines<S: GEDCOMSource + ?Sized>(
     lineStr = (nonAt / atsign atsign) *nonEOL ; leading @ doubled
     */
 
-    source_code.lines().filter_map(move |line| {
+    within.lines().filter_map(move |line| {
         debug_assert!(!line.ends_with(AsciiChar::LineFeed));
         debug_assert!(!line.ends_with(AsciiChar::CarriageReturn));
         debug_assert!(!line.starts_with(AsciiChar::LineFeed));
@@ -134,6 +195,58 @@ pub(crate) fn iterate_lines<S: GEDCOMSource + ?Sized>(
     })
 }
 
+/// A line (with its level), along with any warnings about how it was repaired.
+pub(crate) type RepairedLine<'a> = (
+    Sourced<usize>,
+    Sourced<RawLine<'a, str>>,
+    Vec<LineSyntaxError>,
+);
+
+/// Like [`iterate_lines_within`], but repairs values which contain raw line breaks
+/// (rather than using `CONT` records), as emitted by some exporters.
+///
+/// Any lines which cannot be parsed and which follow a line with a string value
+/// are assumed to be part of that value, and the value is extended to include them
+/// (so it contains the original line breaks). Each line which is joined in this way
+/// is returned as a [`LineSyntaxError::LineBreakInValue`] warning alongside the line.
+pub(crate) fn iterate_lines_repairing<'a>(
+    source_code: &'a str,
+    within: &'a str,
+) -> impl Iterator<Item = Result<RepairedLine<'a>, LineSyntaxError>> {
+    let mut lines = within.lines().filter(|line| !line.is_empty()).peekable();
+    std::iter::from_fn(move || {
+        let (level, mut line) = match parse_line(source_code, lines.next()?) {
+            Ok(parsed) => parsed,
+            Err(err) => return Some(Err(err)),
+        };
+
+        let mut warnings = Vec::new();
+        if let LineValue::Str(_) = line.value.sourced_value {
+            let value_start = line.value.span.offset();
+            let mut value_end = value_start + line.value.span.len();
+            while let Some(next) = lines.next_if(|next| parse_line(source_code, next).is_err()) {
+                let continuation_span = source_code.span_of(next);
+                warnings.push(LineSyntaxError::LineBreakInValue {
+                    value_span: (value_start..value_end).into(),
+                    continuation_span,
+                });
+
+                value_end = continuation_span.offset() + continuation_span.len();
+            }
+
+            if !warnings.is_empty() {
+                line.sourced_value.value = Sourced {
+                    sourced_value: LineValue::Str(&source_code[value_start..value_end]),
+                    span: (value_start..value_end).into(),
+                };
+                line.span = (line.span.offset()..value_end).into();
+            }
+        }
+
+        Some(Ok((level, line, warnings)))
+    })
+}
+
 pub(crate) fn parse_line<'a, S: GEDCOMSource + ?Sized>(
     source_code: &'a S,
     line: &'a S,
@@ -223,6 +336,7 @@ pub(crate) fn parse_line<'a, S: GEDCOMSource + ?Sized>(
         return Err(LineSyntaxError::InvalidTagCharacter { span });
     }
 
+    let packed_tag = PackedTag::of(tag);
     let tag = Sourced {
         sourced_value: tag,
         span: source_code.span_of(tag_part),
@@ -268,7 +382,7 @@ pub(crate) fn parse_line<'a, S: GEDCOMSource + ?Sized>(
         level,
         Sourced {
             span: source_code.span_of(line),
-            sourced_value: RawLine { tag, xref, value: line_value },
+            sourced_value: RawLine { tag, packed_tag, xref, value: line_value },
         },
     ))
 }
@@ -372,4 +486,21 @@ mod test {
         );
         Ok(())
     }
+
+    #[test]
+    fn line_ranges_split_at_every_break() {
+        for source in [
+            "",
+            "0 HEAD",
+            "0 HEAD\n",
+            "0 HEAD\r\n1 CHAR UTF-8\r\n",
+            "\n\r0 HEAD\r\r1 SOUR x\n\n\n0 TRLR",
+        ] {
+            let lines: Vec<&str> = line_ranges(source.as_bytes())
+                .map(|range| &source[range])
+                .collect();
+            let expected: Vec<&str> = source.split(['\r', '\n']).collect();
+            assert_eq!(lines, expected, "{source:?}");
+        }
+    }
 }
@@ -0,0 +1,168 @@
+//! Advisory checks on the content of records, which do not
+//! affect whether a file is valid.
+
+use super::{NonFatalHandler, ReaderError, Sourced, lines::LineValue, records::RawRecord};
+use crate::{
+    citations::citation_qualities,
+    normalize::EVENT_TAGS,
+    schemas::{SchemaError, places::Gazetteer, quality::CertaintyAssessment, temples, years_of},
+};
+
+/// Reports any `TEMP` values in the record (or its subrecords)
+/// which are not known temple codes.
+pub(crate) fn check_temple_codes(
+    record: &Sourced<RawRecord>,
+    handler: &mut impl NonFatalHandler,
+) -> Result<(), ReaderError> {
+    for child in &record.records {
+        if child.line.tag.as_str() == "TEMP" {
+            if let LineValue::Str(code) = child.line.value.sourced_value {
+                if temples::temple_name(code).is_none() {
+                    handler.report(SchemaError::UnknownTempleCode {
+                        code: code.to_string(),
+                        span: child.line.value.span,
+                    })?;
+                }
+            }
+        }
+
+        check_temple_codes(child, handler)?;
+    }
+
+    Ok(())
+}
+
+/// Reports any events (records with both `DATE` and `PLAC`) which took place
+/// in a jurisdiction which did not exist at the time, according to the gazetteer.
+pub(crate) fn check_event_places(
+    record: &Sourced<RawRecord>,
+    gazetteer: &dyn Gazetteer,
+    handler: &mut impl NonFatalHandler,
+) -> Result<(), ReaderError> {
+    let subrecord = |tag: &str| {
+        record
+            .records
+            .iter()
+            .find(|child| child.line.tag.as_str() == tag)
+            .and_then(|child| match child.line.value.sourced_value {
+                LineValue::Str(value) => Some((value, child.line.value.span)),
+                _ => None,
+            })
+    };
+
+    if let (Some((date, date_span)), Some((place, place_span))) =
+        (subrecord("DATE"), subrecord("PLAC"))
+    {
+        let years: Vec<i32> = years_of(date).collect();
+        if !years.is_empty() {
+            for jurisdiction in place.split(',').map(str::trim) {
+                let existed = |year: &i32| gazetteer.existed_in(jurisdiction, *year);
+                if years.iter().all(|year| existed(year) == Some(false)) {
+                    handler.report(SchemaError::PlaceAnachronism {
+                        jurisdiction: jurisdiction.to_string(),
+                        place_span,
+                        date_span,
+                    })?;
+                }
+            }
+        }
+    }
+
+    for child in &record.records {
+        check_event_places(child, gazetteer, handler)?;
+    }
+
+    Ok(())
+}
+
+/// Reports events and attributes of individuals and families which are cited,
+/// but not by any citation assessed as at least `min_quality`. Events without
+/// citations are not reported.
+pub(crate) fn check_citation_quality(
+    record: &Sourced<RawRecord>,
+    min_quality: CertaintyAssessment,
+    handler: &mut impl NonFatalHandler,
+) -> Result<(), ReaderError> {
+    if !matches!(record.line.tag.as_str(), "INDI" | "FAM") {
+        return Ok(());
+    }
+
+    for event in &record.records {
+        let tag = event.line.tag.as_str();
+        if !EVENT_TAGS.contains(&tag) {
+            continue;
+        }
+
+        let mut qualities = citation_qualities(event).peekable();
+        if qualities.peek().is_some() && !qualities.any(|q| q.is_some_and(|q| q >= min_quality)) {
+            handler.report(SchemaError::PoorlySupportedEvent {
+                tag: tag.to_string(),
+                min_quality,
+                span: event.line.tag.span,
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reports family links which cannot all be correct: an individual who is
+/// a child of more than one birth family (`FAMC` without a `PEDI` explaining it),
+/// or a family with the same spouse twice or more than two spouses.
+pub(crate) fn check_family_links(
+    record: &Sourced<RawRecord>,
+    handler: &mut impl NonFatalHandler,
+) -> Result<(), ReaderError> {
+    match record.line.tag.as_str() {
+        "INDI" => {
+            let mut birth_families = record.subrecords("FAMC").filter(|link| {
+                link.subrecords("PEDI")
+                    .find_map(RawRecord::string_value)
+                    .is_none_or(|pedigree| pedigree.eq_ignore_ascii_case("birth"))
+            });
+
+            if let Some(first) = birth_families.next() {
+                for link in birth_families {
+                    handler.report(SchemaError::MultipleBirthFamilies {
+                        span: link.line.span,
+                        first_span: first.line.span,
+                    })?;
+                }
+            }
+        }
+        "FAM" => {
+            let spouses: Vec<&Sourced<RawRecord>> = record
+                .records
+                .iter()
+                .filter(|child| matches!(child.line.tag.as_str(), "HUSB" | "WIFE"))
+                .collect();
+
+            for (index, spouse) in spouses.iter().enumerate() {
+                let Some(xref) = spouse.pointer() else {
+                    continue;
+                };
+
+                if let Some(first) = spouses[..index]
+                    .iter()
+                    .find(|other| other.pointer() == Some(xref))
+                {
+                    handler.report(SchemaError::DuplicateSpouse {
+                        xref: xref.to_string(),
+                        span: spouse.line.value.span,
+                        first_span: first.line.value.span,
+                    })?;
+                }
+            }
+
+            if spouses.len() > 2 && record.subrecords("_POLY").next().is_none() {
+                handler.report(SchemaError::TooManySpouses {
+                    count: spouses.len(),
+                    span: spouses[2].line.span,
+                })?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
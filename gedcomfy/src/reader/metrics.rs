@@ -0,0 +1,194 @@
+//! Measuring how long each phase of reading a file takes.
+//!
+//! Normally the phases are interleaved (lines are parsed as records are built),
+//! so [`Reader::measure`] runs each phase to completion before starting the next one.
+//! This makes it slower than the ordinary reading functions, but means that
+//! each phase can be timed on its own.
+
+use std::{
+    num::NonZeroU32,
+    time::{Duration, Instant},
+};
+
+use complex_indifference::{Count, Rate};
+
+use super::{
+    AttachSourceCode, GEDCOMSource, Reader, ReaderError, WithSourceCode, arena::ArenaBuilder,
+    decoding::DecodingError, input::Input, lines, modes, records::RecordBuilder,
+};
+use crate::schemas::AnyFileVersion;
+
+/// Unit for counting bytes of input.
+pub type Byte = u8;
+
+/// Unit for counting lines.
+pub enum Line {}
+
+/// Unit for counting (top-level) records.
+pub enum Record {}
+
+/// The time taken to process some number of items.
+pub struct Phase<T> {
+    pub elapsed: Duration,
+    pub count: Count<T>,
+}
+
+impl<T> Phase<T> {
+    fn time<R, E>(
+        f: impl FnOnce() -> Result<R, E>,
+        count: impl FnOnce(&R) -> usize,
+    ) -> Result<(R, Self), E> {
+        let start = Instant::now();
+        let result = f()?;
+        let elapsed = start.elapsed();
+        let count = Count::new(count(&result));
+        Ok((result, Phase { elapsed, count }))
+    }
+
+    pub fn rate(&self) -> Rate<T> {
+        self.count / self.elapsed
+    }
+
+    // the counts are the same for every iteration, so only the times are summed
+    fn add(self, other: Self) -> Self {
+        Phase { elapsed: self.elapsed + other.elapsed, ..other }
+    }
+
+    fn mean(self, iterations: u32) -> Self {
+        Phase { elapsed: self.elapsed / iterations, ..self }
+    }
+}
+
+/// Timings for each phase of reading a file.
+pub struct Metrics {
+    /// Detecting the encoding and version, and decoding the input.
+    pub decode: Phase<Byte>,
+    /// Finding the line breaks in the decoded input, which is
+    /// also part of `line_parse`.
+    pub line_split: Phase<Line>,
+    /// Parsing the decoded input into lines.
+    pub line_parse: Phase<Line>,
+    /// Assembling lines into records.
+    pub record_build: Phase<Record>,
+    /// Assembling the same lines into a [`RecordArena`](super::arena::RecordArena)
+    /// instead, for comparison; this is not part of reading the file.
+    pub arena_build: Phase<Record>,
+    /// Converting records into the schema for the file’s version.
+    pub schema_conversion: Phase<Record>,
+}
+
+impl Metrics {
+    /// The throughput of a phase, in bytes of input per second.
+    pub fn throughput<T>(&self, phase: &Phase<T>) -> Rate<Byte> {
+        self.decode.count / phase.elapsed
+    }
+}
+
+impl Reader {
+    /// Reads the input in the same way as [`Reader::parse`], but
+    /// measures each phase separately; see the [module documentation](self).
+    pub fn measure<'s>(&self, data: &'s [u8]) -> Result<Metrics, WithSourceCode<'s, ReaderError>> {
+        let (input, decode) = Phase::time(|| self.decode_borrowed(data), |_| data.len())?;
+
+        let mut mode = modes::parse::Mode::default();
+        let measure = || -> Result<_, ReaderError> {
+            let version = match input.version() {
+                Some(v) => v,
+                None => Self::version_from_input(input.as_ref(), &mut mode)?,
+            };
+
+            let (_, line_split) = Phase::time(
+                || {
+                    let lines = GEDCOMSource::lines(input.as_ref()).filter(|line| !line.is_empty());
+                    Ok::<_, ReaderError>(lines.count())
+                },
+                |count| *count,
+            )?;
+
+            let (lines, line_parse) = Phase::time(
+                || lines::iterate_lines(input.as_ref()).collect::<Result<Vec<_>, _>>(),
+                Vec::len,
+            )
+            .map_err(|e| ReaderError::from(DecodingError::from(e)))?;
+
+            // the arena needs its own copy of the lines, which is not timed
+            let arena_lines = lines::iterate_lines(input.as_ref())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| ReaderError::from(DecodingError::from(e)))?;
+
+            let (_, arena_build) = Phase::time(
+                || -> Result<_, ReaderError> {
+                    let mut builder = ArenaBuilder::new();
+                    for line in arena_lines {
+                        builder.handle_line(line, &mut mode)?;
+                    }
+
+                    Ok(builder.complete(&mut mode)?)
+                },
+                |arena| arena.records().count(),
+            )?;
+
+            let (records, record_build) = Phase::time(
+                || -> Result<_, ReaderError> {
+                    let mut builder = RecordBuilder::new();
+                    let mut records = Vec::new();
+                    for line in lines {
+                        records.extend(builder.handle_line(line, &mut mode)?);
+                    }
+
+                    records.extend(builder.complete(&mut mode)?);
+                    Ok(records)
+                },
+                Vec::len,
+            )?;
+
+            let record_count = records.len();
+            let (_, schema_conversion) = Phase::time(
+                || AnyFileVersion::try_from((version, records)),
+                |_| record_count,
+            )?;
+
+            Ok(Metrics {
+                decode,
+                line_split,
+                line_parse,
+                record_build,
+                arena_build,
+                schema_conversion,
+            })
+        };
+
+        measure().attach_input(&input)
+    }
+
+    /// Reads the input `iterations` times with [`Reader::measure`],
+    /// returning the mean time taken by each phase.
+    pub fn measure_mean<'s>(
+        &self,
+        data: &'s [u8],
+        iterations: NonZeroU32,
+    ) -> Result<Metrics, WithSourceCode<'s, ReaderError>> {
+        let mut totals = self.measure(data)?;
+        for _ in 1..iterations.get() {
+            let metrics = self.measure(data)?;
+            totals = Metrics {
+                decode: totals.decode.add(metrics.decode),
+                line_split: totals.line_split.add(metrics.line_split),
+                line_parse: totals.line_parse.add(metrics.line_parse),
+                record_build: totals.record_build.add(metrics.record_build),
+                arena_build: totals.arena_build.add(metrics.arena_build),
+                schema_conversion: totals.schema_conversion.add(metrics.schema_conversion),
+            };
+        }
+
+        let iterations = iterations.get();
+        Ok(Metrics {
+            decode: totals.decode.mean(iterations),
+            line_split: totals.line_split.mean(iterations),
+            line_parse: totals.line_parse.mean(iterations),
+            record_build: totals.record_build.mean(iterations),
+            arena_build: totals.arena_build.mean(iterations),
+            schema_conversion: totals.schema_conversion.mean(iterations),
+        })
+    }
+}
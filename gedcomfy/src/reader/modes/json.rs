@@ -0,0 +1,92 @@
+use serde_json::{Map, Value};
+
+use crate::reader::{
+    NonFatalHandler, ReadMode, ResultBuilder, Sourced, lines::LineValue, records::RawRecord,
+};
+
+#[derive(Default)]
+pub(in crate::reader) struct Mode {}
+
+impl NonFatalHandler for Mode {
+    fn report<E>(&mut self, _error: E) -> Result<(), E>
+    where
+        E: Into<crate::reader::ReaderError> + miette::Diagnostic,
+    {
+        Ok(())
+    }
+}
+
+impl<'i> ReadMode<'i> for Mode {
+    type ResultBuilder = Builder;
+
+    fn into_result_builder(
+        self,
+        _version: crate::versions::KnownVersion,
+    ) -> Result<Self::ResultBuilder, crate::reader::ReaderError> {
+        Ok(Builder { mode: self, records: Vec::new() })
+    }
+}
+
+pub(in crate::reader) struct Builder {
+    mode: Mode,
+    records: Vec<Value>,
+}
+
+impl NonFatalHandler for Builder {
+    fn report<E>(&mut self, error: E) -> Result<(), E>
+    where
+        E: Into<crate::reader::ReaderError> + miette::Diagnostic,
+    {
+        self.mode.report(error)
+    }
+}
+
+impl<'i> ResultBuilder<'i> for Builder {
+    type Result = Value;
+
+    fn handle_record(
+        &mut self,
+        record: Sourced<RawRecord>,
+    ) -> Result<(), crate::reader::ReaderError> {
+        self.records.push(record_to_json(record.sourced_value));
+        Ok(())
+    }
+
+    fn complete(self) -> Result<Self::Result, crate::reader::ReaderError> {
+        Ok(Value::Array(self.records))
+    }
+}
+
+fn record_to_json(record: RawRecord) -> Value {
+    let mut object = Map::new();
+    object.insert("tag".into(), record.line.tag.to_string().into());
+
+    if let Some(xref) = &record.line.xref {
+        object.insert("xref".into(), xref.sourced_value.to_string().into());
+    }
+
+    match record.line.value.sourced_value {
+        LineValue::Ptr(None) => {
+            object.insert("pointer".into(), Value::Null);
+        }
+        LineValue::Ptr(Some(value)) => {
+            object.insert("pointer".into(), value.to_string().into());
+        }
+        LineValue::Str(data) => {
+            object.insert("value".into(), data.to_string().into());
+        }
+        LineValue::None => {}
+    }
+
+    if !record.records.is_empty() {
+        let records = record
+            .records
+            .into_iter()
+            .map(|subrecord| record_to_json(subrecord.sourced_value))
+            .collect();
+
+        object.insert("records".into(), Value::Array(records));
+    }
+
+    Value::Object(object)
+}
@@ -1,7 +1,13 @@
+#[cfg(feature = "json")]
+pub(super) mod json;
 #[cfg(feature = "kdl")]
 pub(super) mod kdl;
 pub(super) mod parse;
 pub(super) mod raw;
+#[cfg(feature = "toml")]
+pub(super) mod toml;
 #[cfg(feature = "turtle")]
 pub(super) mod ttl;
 pub(super) mod validation;
+#[cfg(feature = "yaml")]
+pub(super) mod yaml;
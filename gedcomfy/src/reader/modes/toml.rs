@@ -0,0 +1,96 @@
+use toml::{Table, Value};
+
+use crate::reader::{
+    NonFatalHandler, ReadMode, ResultBuilder, Sourced, lines::LineValue, records::RawRecord,
+};
+
+#[derive(Default)]
+pub(in crate::reader) struct Mode {}
+
+impl NonFatalHandler for Mode {
+    fn report<E>(&mut self, _error: E) -> Result<(), E>
+    where
+        E: Into<crate::reader::ReaderError> + miette::Diagnostic,
+    {
+        Ok(())
+    }
+}
+
+impl<'i> ReadMode<'i> for Mode {
+    type ResultBuilder = Builder;
+
+    fn into_result_builder(
+        self,
+        _version: crate::versions::KnownVersion,
+    ) -> Result<Self::ResultBuilder, crate::reader::ReaderError> {
+        Ok(Builder { mode: self, records: Vec::new() })
+    }
+}
+
+pub(in crate::reader) struct Builder {
+    mode: Mode,
+    records: Vec<Value>,
+}
+
+impl NonFatalHandler for Builder {
+    fn report<E>(&mut self, error: E) -> Result<(), E>
+    where
+        E: Into<crate::reader::ReaderError> + miette::Diagnostic,
+    {
+        self.mode.report(error)
+    }
+}
+
+impl<'i> ResultBuilder<'i> for Builder {
+    type Result = Table;
+
+    fn handle_record(
+        &mut self,
+        record: Sourced<RawRecord>,
+    ) -> Result<(), crate::reader::ReaderError> {
+        self.records.push(record_to_toml(record.sourced_value));
+        Ok(())
+    }
+
+    fn complete(self) -> Result<Self::Result, crate::reader::ReaderError> {
+        // a TOML document must be a table, so the records are put in one
+        let mut table = Table::new();
+        table.insert("records".into(), Value::Array(self.records));
+        Ok(table)
+    }
+}
+
+fn record_to_toml(record: RawRecord) -> Value {
+    let mut table = Table::new();
+    table.insert("tag".into(), record.line.tag.to_string().into());
+
+    if let Some(xref) = &record.line.xref {
+        table.insert("xref".into(), xref.sourced_value.to_string().into());
+    }
+
+    match record.line.value.sourced_value {
+        // TOML has no null, so this is written as it appears in the file
+        LineValue::Ptr(None) => {
+            table.insert("pointer".into(), "VOID".into());
+        }
+        LineValue::Ptr(Some(value)) => {
+            table.insert("pointer".into(), value.to_string().into());
+        }
+        LineValue::Str(data) => {
+            table.insert("value".into(), data.to_string().into());
+        }
+        LineValue::None => {}
+    }
+
+    if !record.records.is_empty() {
+        let records = record
+            .records
+            .into_iter()
+            .map(|subrecord| record_to_toml(subrecord.sourced_value))
+            .collect();
+
+        table.insert("records".into(), Value::Array(records));
+    }
+
+    Value::Table(table)
+}
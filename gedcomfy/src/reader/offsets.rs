@@ -0,0 +1,273 @@
+//! Mapping offsets in the decoded text back to offsets in the original data.
+//!
+//! Spans found while reading a file refer to the decoded (UTF-8) text. When the
+//! file was in another encoding (or had a byte order mark), these do not match
+//! the offsets of the same data in the original file, which is confusing when
+//! looking at the file in a hex editor. When
+//! [`ParseOptions::raw_offsets`](super::options::ParseOptions::raw_offsets)
+//! is set, diagnostics are reported against the original data instead,
+//! with their spans mapped back using an [`OffsetMap`].
+
+use std::borrow::Cow;
+
+use miette::{Diagnostic, LabeledSpan, Severity, SourceSpan};
+
+use super::encodings::Encoding;
+
+/// Maps offsets in decoded text to offsets in the data it was decoded from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffsetMap {
+    // never empty, and the first run always starts at decoded offset 0
+    runs: Vec<Run>,
+}
+
+/// A run of characters which all have the same width in the decoded text,
+/// and the same width in the original data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Run {
+    decoded: usize,
+    raw: usize,
+    decoded_width: usize,
+    raw_width: usize,
+}
+
+impl OffsetMap {
+    /// Builds the map for text which was decoded from data in the given encoding,
+    /// where the decoded text starts at offset `start` in the data (after any
+    /// byte order marks).
+    pub(crate) fn new(encoding: Encoding, start: usize, decoded: &str) -> Self {
+        let mut runs = vec![Run {
+            decoded: 0,
+            raw: start,
+            decoded_width: 1,
+            raw_width: 1,
+        }];
+
+        let raw_width: fn(char) -> usize = match encoding {
+            // every character is the same width in the decoded text
+            Encoding::Ascii | Encoding::Utf8 => return Self { runs },
+            // every character is a single byte; note that ANSEL combining characters
+            // come before the character they modify, but after it in the decoded text,
+            // so offsets within such a pair are only approximate
+            Encoding::Windows1252 | Encoding::Ansel => |_| 1,
+            Encoding::Utf16BE | Encoding::Utf16LE => |c| c.len_utf16() * 2,
+        };
+
+        let mut raw = start;
+        for (decoded, c) in decoded.char_indices() {
+            let raw_len = raw_width(c);
+            let (decoded_width, raw_width) = match (c.len_utf8(), raw_len) {
+                (d, r) if d == r => (1, 1),
+                widths => widths,
+            };
+
+            let last = runs.last().expect("runs is never empty");
+            if (last.decoded_width, last.raw_width) != (decoded_width, raw_width) {
+                runs.push(Run { decoded, raw, decoded_width, raw_width });
+            }
+
+            raw += raw_len;
+        }
+
+        Self { runs }
+    }
+
+    /// The offset in the original data of the given offset in the decoded text.
+    ///
+    /// Offsets which are within a character are mapped to the start of the character.
+    pub fn raw_offset(&self, offset: usize) -> usize {
+        let index = self.runs.partition_point(|run| run.decoded <= offset) - 1;
+        let run = &self.runs[index];
+        run.raw + (offset - run.decoded) / run.decoded_width * run.raw_width
+    }
+
+    /// The span in the original data of the given span in the decoded text.
+    pub fn raw_span(&self, span: SourceSpan) -> SourceSpan {
+        let start = self.raw_offset(span.offset());
+        let end = self.raw_offset(span.offset() + span.len());
+        SourceSpan::from((start, end - start))
+    }
+}
+
+/// Replaces any bytes which are not valid UTF-8 with `?`, so that the original
+/// data can be shown in diagnostics without changing the offsets within it.
+pub(crate) fn displayable(data: &[u8]) -> Cow<'_, [u8]> {
+    if std::str::from_utf8(data).is_ok() {
+        return Cow::Borrowed(data);
+    }
+
+    let mut result = Vec::with_capacity(data.len());
+    for chunk in data.utf8_chunks() {
+        result.extend_from_slice(chunk.valid().as_bytes());
+        result.resize(result.len() + chunk.invalid().len(), b'?');
+    }
+
+    Cow::Owned(result)
+}
+
+/// A copy of a diagnostic (and its causes) with its spans mapped by an [`OffsetMap`].
+#[derive(Debug)]
+pub(crate) struct RawOffsetDiagnostic {
+    message: String,
+    code: Option<String>,
+    severity: Option<Severity>,
+    help: Option<String>,
+    url: Option<String>,
+    labels: Vec<LabeledSpan>,
+    related: Vec<RawOffsetDiagnostic>,
+    cause: Option<Box<RawOffsetDiagnostic>>,
+    // whether the cause is a diagnostic, rather than a plain error
+    diagnostic_cause: bool,
+}
+
+impl RawOffsetDiagnostic {
+    pub(crate) fn new(diagnostic: &dyn Diagnostic, offsets: &OffsetMap) -> Self {
+        let (cause, diagnostic_cause) = match diagnostic.diagnostic_source() {
+            Some(source) => (Some(Self::new(source, offsets)), true),
+            None => (diagnostic.source().map(Self::from_error), false),
+        };
+
+        Self {
+            message: diagnostic.to_string(),
+            code: diagnostic.code().map(|code| code.to_string()),
+            severity: diagnostic.severity(),
+            help: diagnostic.help().map(|help| help.to_string()),
+            url: diagnostic.url().map(|url| url.to_string()),
+            labels: diagnostic
+                .labels()
+                .into_iter()
+                .flatten()
+                .map(|label| {
+                    let text = label.label().map(String::from);
+                    let span = offsets.raw_span(*label.inner());
+                    if label.primary() {
+                        LabeledSpan::new_primary_with_span(text, span)
+                    } else {
+                        LabeledSpan::new_with_span(text, span)
+                    }
+                })
+                .collect(),
+            related: diagnostic
+                .related()
+                .into_iter()
+                .flatten()
+                .map(|related| Self::new(related, offsets))
+                .collect(),
+            cause: cause.map(Box::new),
+            diagnostic_cause,
+        }
+    }
+
+    fn from_error(error: &(dyn std::error::Error + 'static)) -> Self {
+        Self {
+            message: error.to_string(),
+            code: None,
+            severity: None,
+            help: None,
+            url: None,
+            labels: Vec::new(),
+            related: Vec::new(),
+            cause: error
+                .source()
+                .map(|source| Box::new(Self::from_error(source))),
+            diagnostic_cause: false,
+        }
+    }
+}
+
+impl std::fmt::Display for RawOffsetDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for RawOffsetDiagnostic {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.cause.as_deref().map(|cause| cause as _)
+    }
+}
+
+impl Diagnostic for RawOffsetDiagnostic {
+    fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        self.code.as_ref().map(|code| Box::new(code) as _)
+    }
+
+    fn severity(&self) -> Option<Severity> {
+        self.severity
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        self.help.as_ref().map(|help| Box::new(help) as _)
+    }
+
+    fn url<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        self.url.as_ref().map(|url| Box::new(url) as _)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        if self.labels.is_empty() {
+            None
+        } else {
+            Some(Box::new(self.labels.iter().cloned()))
+        }
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        if self.related.is_empty() {
+            None
+        } else {
+            Some(Box::new(self.related.iter().map(|related| related as _)))
+        }
+    }
+
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        match &self.cause {
+            Some(cause) if self.diagnostic_cause => Some(cause.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn windows_1252() {
+        // "0 Né\n": é is 1 byte in the data but 2 in the text
+        let offsets = OffsetMap::new(Encoding::Windows1252, 0, "0 Né\n1");
+        let mapped: Vec<usize> = [0, 2, 3, 5, 6, 7]
+            .into_iter()
+            .map(|offset| offsets.raw_offset(offset))
+            .collect();
+
+        assert_eq!(mapped, [0, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn utf16_with_bom() {
+        let offsets = OffsetMap::new(Encoding::Utf16LE, 2, "0 é😀\n");
+        let mapped: Vec<usize> = [0, 1, 2, 4, 8, 9]
+            .into_iter()
+            .map(|offset| offsets.raw_offset(offset))
+            .collect();
+
+        assert_eq!(mapped, [2, 4, 6, 8, 12, 14]);
+        assert_eq!(
+            offsets.raw_span(SourceSpan::from((2, 6))),
+            SourceSpan::from((6, 6))
+        );
+    }
+
+    #[test]
+    fn displayable_keeps_offsets() {
+        let data = b"0 N\xE9\xE9\n\xC3\xA9";
+        assert_eq!(displayable(data).as_ref(), b"0 N??\n\xC3\xA9");
+    }
+
+    #[test]
+    fn utf8_is_identity() {
+        let offsets = OffsetMap::new(Encoding::Utf8, 3, "0 é😀\n");
+        assert_eq!(offsets.raw_offset(7), 10);
+    }
+}
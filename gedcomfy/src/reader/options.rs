@@ -1,11 +1,40 @@
-use super::encodings::Encoding;
-use crate::versions::KnownVersion;
+use std::sync::Arc;
+
+use super::{encodings::Encoding, limits::Limits, visitors::RecordVisitor};
+use crate::{
+    schemas::{places::Gazetteer, quality::CertaintyAssessment},
+    versions::KnownVersion,
+};
+
+/// What to do when a subrecord which may only appear once
+/// (such as the `DATE` of an event) appears more than once.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateSubrecords {
+    /// Fail to convert the record.
+    #[default]
+    Error,
+    /// Keep the first of the subrecords, reporting the others as warnings.
+    KeepFirst,
+    /// Keep the last of the subrecords, reporting the others as warnings.
+    KeepLast,
+}
 
 #[non_exhaustive]
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct ParseOptions {
     pub(super) force_encoding: Option<Encoding>,
     pub(super) force_version: Option<KnownVersion>,
+    pub(super) repair_line_breaks: bool,
+    pub(super) recover_invalid_levels: bool,
+    pub(super) skip_repeated_bom: bool,
+    pub(super) trim_trailing_nuls: bool,
+    pub(super) raw_offsets: bool,
+    pub(super) gazetteer: Option<Arc<dyn Gazetteer>>,
+    pub(super) min_marriage_age: Option<u32>,
+    pub(super) min_citation_quality: Option<CertaintyAssessment>,
+    pub(super) duplicate_subrecords: DuplicateSubrecords,
+    pub(super) limits: Limits,
+    pub(super) visitors: Vec<Arc<dyn RecordVisitor>>,
 }
 
 impl ParseOptions {
@@ -18,4 +47,104 @@ impl ParseOptions {
     pub fn force_version(self, force_version: impl Into<Option<KnownVersion>>) -> Self {
         Self { force_version: force_version.into(), ..self }
     }
+
+    /// Repair values which contain line breaks (instead of using `CONT` records),
+    /// by joining lines which cannot be parsed onto the value of the previous line.
+    /// Each repair is reported as a warning.
+    pub fn repair_line_breaks(self, repair_line_breaks: bool) -> Self {
+        Self { repair_line_breaks, ..self }
+    }
+
+    /// Continue reading when a line’s level is too deep (e.g. a level 3 line directly
+    /// inside a level 1 record), attaching it to the deepest record open at that point.
+    /// Each such line is still reported as an error, so this is mostly useful when
+    /// validating, to find all of the problems in the file at once.
+    pub fn recover_invalid_levels(self, recover_invalid_levels: bool) -> Self {
+        Self { recover_invalid_levels, ..self }
+    }
+
+    /// Ignore byte order marks which are repeated at the start of the file,
+    /// reporting them as a warning.
+    pub fn skip_repeated_bom(self, skip_repeated_bom: bool) -> Self {
+        Self { skip_repeated_bom, ..self }
+    }
+
+    /// Ignore NUL characters at the end of the file (as produced by
+    /// fixed-size exports), reporting them as a warning.
+    pub fn trim_trailing_nuls(self, trim_trailing_nuls: bool) -> Self {
+        Self { trim_trailing_nuls, ..self }
+    }
+
+    /// Report problems against the original data of the file, with their spans
+    /// given as byte offsets into it, rather than against the decoded (UTF-8) text.
+    /// These differ when the file is not in UTF-8, or starts with a byte order mark.
+    pub fn raw_offsets(self, raw_offsets: bool) -> Self {
+        Self { raw_offsets, ..self }
+    }
+
+    /// Check that the places of events existed at the time of the events,
+    /// according to the gazetteer. Any that did not are reported as advice.
+    pub fn gazetteer(self, gazetteer: impl Into<Option<Arc<dyn Gazetteer>>>) -> Self {
+        Self { gazetteer: gazetteer.into(), ..self }
+    }
+
+    /// Report individuals who married when they were younger than this age,
+    /// as advice. By default, the ages of spouses are not checked.
+    pub fn min_marriage_age(self, min_marriage_age: impl Into<Option<u32>>) -> Self {
+        Self { min_marriage_age: min_marriage_age.into(), ..self }
+    }
+
+    /// Pass each record through the visitor before it is checked or converted.
+    /// Visitors are called in the order that they were added.
+    pub fn add_visitor(mut self, visitor: Arc<dyn RecordVisitor>) -> Self {
+        self.visitors.push(visitor);
+        self
+    }
+
+    /// Keep one of the subrecords when a subrecord which may only appear once is
+    /// repeated, rather than failing. The others are removed from the records
+    /// (including those from [`Reader::raw_records`](super::Reader::raw_records)).
+    pub fn duplicate_subrecords(self, duplicate_subrecords: DuplicateSubrecords) -> Self {
+        Self { duplicate_subrecords, ..self }
+    }
+
+    /// Report events and attributes which are cited, but only by citations
+    /// assessed (by their `QUAY`) as less reliable than this, as advice.
+    pub fn min_citation_quality(
+        self,
+        min_citation_quality: impl Into<Option<CertaintyAssessment>>,
+    ) -> Self {
+        Self {
+            min_citation_quality: min_citation_quality.into(),
+            ..self
+        }
+    }
+
+    /// Fail if any line has a level deeper than this.
+    pub fn max_level(mut self, max_level: impl Into<Option<usize>>) -> Self {
+        self.limits.max_level = max_level.into();
+        self
+    }
+
+    /// Fail if any line is longer than this many bytes, including any lines
+    /// joined onto it by [`ParseOptions::repair_line_breaks`].
+    pub fn max_line_length(mut self, max_line_length: impl Into<Option<usize>>) -> Self {
+        self.limits.max_line_length = max_line_length.into();
+        self
+    }
+
+    /// Fail if the file contains more than this many records, including subrecords.
+    pub fn max_records(mut self, max_records: impl Into<Option<usize>>) -> Self {
+        self.limits.max_records = max_records.into();
+        self
+    }
+
+    /// Fail if the file is larger than this many bytes, once decoded to UTF-8.
+    ///
+    /// This is checked before the file is decoded, so for encodings other than
+    /// UTF-8 (or ASCII) it applies to the largest size that the file could have.
+    pub fn max_decoded_size(mut self, max_decoded_size: impl Into<Option<usize>>) -> Self {
+        self.limits.max_decoded_size = max_decoded_size.into();
+        self
+    }
 }
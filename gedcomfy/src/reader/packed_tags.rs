@@ -0,0 +1,71 @@
+//! Tags packed into integers, so that they can be compared cheaply.
+//!
+//! Every standard GEDCOM tag is at most five (ASCII) characters long, so it fits
+//! into a `u64`. Each line’s tag is packed once, when the line is parsed, and then
+//! dispatching on the tag (as schema conversion does for every record) can compare
+//! integers rather than strings.
+
+use ascii::AsciiStr;
+
+/// A tag of up to eight characters, packed into an integer.
+///
+/// Longer tags (which can only be user-defined tags, such as `_MARRIAGE`)
+/// are all packed to the same value, which is not equal to that of
+/// any shorter tag; they must be compared as strings instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PackedTag(u64);
+
+impl PackedTag {
+    /// The value of tags which are too long to pack.
+    const UNPACKED: Self = Self(0);
+
+    pub const CONC: Self = Self::new("CONC");
+    pub const CONT: Self = Self::new("CONT");
+    pub const TRLR: Self = Self::new("TRLR");
+
+    /// Packs a tag, which must be between one and eight characters long;
+    /// this is intended for tags which are known at compile time.
+    pub const fn new(tag: &str) -> Self {
+        let bytes = tag.as_bytes();
+        assert!(
+            !bytes.is_empty() && bytes.len() <= 8,
+            "tag cannot be packed"
+        );
+
+        let mut packed = 0;
+        let mut ix = 0;
+        while ix < bytes.len() {
+            packed = (packed << 8) | bytes[ix] as u64;
+            ix += 1;
+        }
+
+        Self(packed)
+    }
+
+    /// Packs a tag, if it is short enough.
+    pub fn of(tag: &AsciiStr) -> Self {
+        match tag.len() {
+            1..=8 => Self::new(tag.as_str()),
+            _ => Self::UNPACKED,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ascii::AsAsciiStr;
+
+    use super::*;
+
+    #[test]
+    fn packs_short_tags() {
+        let of = |tag: &str| PackedTag::of(tag.as_ascii_str().unwrap());
+        assert_eq!(of("INDI"), PackedTag::new("INDI"));
+        assert_eq!(of("FAM"), PackedTag::new("FAM"));
+        assert_ne!(of("FAM"), of("FAMS"));
+        assert_ne!(of("FAM"), of("AFAM"));
+        assert_eq!(of("EMAIL"), PackedTag::new("EMAIL"));
+        assert_eq!(of("_MARRIAGE"), of("_MARRIAGES"));
+        assert_ne!(of("_MARRIAGE"), of("_MARRIAG"));
+    }
+}
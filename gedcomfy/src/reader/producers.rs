@@ -0,0 +1,156 @@
+//! Statistics about the problems found in files, grouped by the
+//! product which produced them, to find out which problems are
+//! common in the files exported by each product.
+
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use miette::Diagnostic;
+
+use super::{
+    ValidationResult,
+    batch::{FileSummary, ScanError},
+    input::FileLoadError,
+};
+
+/// How often a kind of problem was found.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ProblemCount {
+    /// How many files the problem was found in.
+    pub files: usize,
+    /// How many times the problem was found, in total.
+    pub occurrences: usize,
+}
+
+/// The problems found in the files produced by one product.
+#[derive(Debug, Default)]
+pub struct ProducerStats {
+    pub file_count: usize,
+    /// How many of the files could not be read at all;
+    /// the reason for each is counted as one of its problems.
+    pub unreadable_count: usize,
+    problems: BTreeMap<String, ProblemCount>,
+}
+
+impl ProducerStats {
+    /// The kinds of problem (identified by their diagnostic codes) found in
+    /// files from this producer, with those found in the most files first.
+    pub fn problems(&self) -> Vec<(&str, ProblemCount)> {
+        let mut result: Vec<(&str, ProblemCount)> = self
+            .problems
+            .iter()
+            .map(|(code, count)| (code.as_str(), *count))
+            .collect();
+
+        // problems are already in order, so a stable sort keeps ties alphabetical
+        result.sort_by_key(|(_, count)| Reverse((count.files, count.occurrences)));
+        result
+    }
+}
+
+/// The problems found in a set of files, grouped by the product which produced
+/// each file (the value of `HEAD.SOUR`).
+///
+/// This can be built from the results of [`Reader::scan_dir`](super::Reader::scan_dir)
+/// using [`ProducerReport::add_summary`].
+#[derive(Debug, Default)]
+pub struct ProducerReport {
+    producers: BTreeMap<Option<String>, ProducerStats>,
+}
+
+impl ProducerReport {
+    /// Adds the problems found when validating a file from the producer
+    /// (or `None` if the producer is not known).
+    pub fn add(&mut self, producer: Option<&str>, validation: &ValidationResult) {
+        self.add_problems(
+            producer,
+            validation.errors.iter().map(|error| problem_code(error)),
+        );
+    }
+
+    /// Adds a file from the producer (or `None` if the producer is not known)
+    /// which could not be read, because of the given error.
+    pub fn add_unreadable(&mut self, producer: Option<&str>, error: &dyn Diagnostic) {
+        self.add_unreadable_problem(producer, problem_code(error));
+    }
+
+    fn add_unreadable_problem(&mut self, producer: Option<&str>, problem: String) {
+        self.add_problems(producer, [problem]);
+        self.stats(producer).unreadable_count += 1;
+    }
+
+    fn stats(&mut self, producer: Option<&str>) -> &mut ProducerStats {
+        self.producers
+            .entry(producer.map(str::to_string))
+            .or_default()
+    }
+
+    fn add_problems(&mut self, producer: Option<&str>, codes: impl IntoIterator<Item = String>) {
+        let stats = self.stats(producer);
+        stats.file_count += 1;
+
+        let mut found = BTreeMap::<String, usize>::new();
+        for code in codes {
+            *found.entry(code).or_default() += 1;
+        }
+
+        for (code, occurrences) in found {
+            let count = stats.problems.entry(code).or_default();
+            count.files += 1;
+            count.occurrences += occurrences;
+        }
+    }
+
+    /// Adds the problems found in a file by [`Reader::scan_dir`](super::Reader::scan_dir).
+    pub fn add_summary(&mut self, summary: &FileSummary) {
+        self.add(summary.producer.as_deref(), &summary.validation);
+    }
+
+    /// Adds a file which [`Reader::scan_dir`](super::Reader::scan_dir) could not read.
+    ///
+    /// The file is counted for its producer if the header could be read,
+    /// and otherwise for an unknown producer. Errors which do not concern
+    /// a single file (such as a directory which could not be read) are ignored.
+    pub fn add_scan_error(&mut self, error: &ScanError) {
+        match error {
+            ScanError::Directory { .. } => {}
+            ScanError::Load { source: FileLoadError::Decoding { source }, .. } => {
+                self.add_unreadable(None, &source.source)
+            }
+            // the messages of these errors include their paths,
+            // so they cannot be used to identify the problem
+            ScanError::Load { .. } => {
+                self.add_unreadable_problem(None, "the file could not be loaded".to_string())
+            }
+            ScanError::Read { source, producer, .. } => {
+                self.add_unreadable(producer.as_deref(), &source.source)
+            }
+            ScanError::Panicked { .. } => {
+                self.add_unreadable_problem(None, "reading the file caused a panic".to_string())
+            }
+        }
+    }
+
+    /// The producers (in order of name, with unknown producers first)
+    /// and the problems found in their files.
+    pub fn producers(&self) -> impl Iterator<Item = (Option<&str>, &ProducerStats)> {
+        self.producers
+            .iter()
+            .map(|(producer, stats)| (producer.as_deref(), stats))
+    }
+}
+
+/// Identifies the kind of problem by its diagnostic code, or by its message
+/// if it has no code; an error without either of its own is identified
+/// by the innermost error that it wraps.
+fn problem_code(mut error: &dyn Diagnostic) -> String {
+    loop {
+        if let Some(code) = error.code() {
+            return code.to_string();
+        }
+
+        match error.diagnostic_source() {
+            Some(source) => error = source,
+            None => return error.to_string(),
+        }
+    }
+}
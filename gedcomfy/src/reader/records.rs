@@ -1,7 +1,10 @@
 use miette::SourceSpan;
 
 use super::{
-    GEDCOMSource, NonFatalHandler, ReaderError, Sourced, decoding::DecodingError, lines::RawLine,
+    GEDCOMSource, NonFatalHandler, ReaderError, Sourced,
+    decoding::DecodingError,
+    lines::{LineValue, RawLine},
+    packed_tags::PackedTag,
 };
 
 /// Represents an assembled GEDCOM record, or sub-record,
@@ -18,6 +21,35 @@ impl<'i, S: GEDCOMSource + ?Sized> RawRecord<'i, S> {
     }
 }
 
+impl<'i> RawRecord<'i> {
+    /// The subrecords with the given tag, in order.
+    pub(crate) fn subrecords<'r>(
+        &'r self,
+        tag: &'static str,
+    ) -> impl Iterator<Item = &'r RawRecord<'i>> + use<'r, 'i> {
+        self.records
+            .iter()
+            .map(|subrecord| &subrecord.sourced_value)
+            .filter(move |subrecord| subrecord.line.tag.as_str() == tag)
+    }
+
+    /// The value of the record, if it is a string.
+    pub(crate) fn string_value(&self) -> Option<&'i str> {
+        match self.line.value.sourced_value {
+            LineValue::Str(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The xref the record points to, if it is a pointer (other than `@VOID@`).
+    pub(crate) fn pointer(&self) -> Option<&'i str> {
+        match self.line.value.sourced_value {
+            LineValue::Ptr(value) => value,
+            _ => None,
+        }
+    }
+}
+
 #[derive(thiserror::Error, derive_more::Display, Debug, miette::Diagnostic)]
 pub enum RecordStructureError {
     #[display("Invalid child level {level}, expected {expected_level} or less")]
@@ -43,11 +75,24 @@ impl From<RecordStructureError> for ReaderError {
     }
 }
 
+/// Whether a record is missing a value which it should have.
+// this sort of feels like the wrong place to enforce this
+pub(super) fn is_missing_value<S: GEDCOMSource + ?Sized>(
+    line: &RawLine<S>,
+    has_subrecords: bool,
+) -> bool {
+    !has_subrecords
+        && line.value.is_none()
+        && line.packed_tag != PackedTag::CONT
+        && line.packed_tag != PackedTag::TRLR
+}
+
 pub(crate) struct RecordBuilder<'i, S = str>
 where
     S: GEDCOMSource + ?Sized,
 {
     stack: Vec<RawRecord<'i, S>>,
+    recover_invalid_levels: bool,
 }
 
 impl<'i, S> RecordBuilder<'i, S>
@@ -55,7 +100,13 @@ where
     S: GEDCOMSource + ?Sized,
 {
     pub(crate) fn new() -> Self {
-        Self { stack: Vec::new() }
+        Self { stack: Vec::new(), recover_invalid_levels: false }
+    }
+
+    /// Instead of failing when a line’s level is too deep, report the problem
+    /// to the handler and attach the line to the deepest open record.
+    pub(crate) fn recover_invalid_levels(self, recover_invalid_levels: bool) -> Self {
+        Self { recover_invalid_levels, ..self }
     }
 
     fn pop_to_level<NF: NonFatalHandler>(
@@ -66,12 +117,7 @@ where
         while self.stack.len() > level {
             let child = self.stack.pop().unwrap(); // UNWRAP: guaranteed, len > 0
 
-            // this sort of feels like the wrong place to enforce this
-            if child.records.is_empty()
-                && child.line.value.is_none()
-                && child.line.tag.as_str() != "CONT"
-                && child.line.tag.as_str() != "TRLR"
-            {
+            if is_missing_value(&child.line, !child.records.is_empty()) {
                 warnings
                     .report(RecordStructureError::MissingRecordValue { span: child.line.span })?;
             }
@@ -112,11 +158,17 @@ where
 
         let expected_level = self.stack.len();
         if level.sourced_value != expected_level {
-            return Err(RecordStructureError::InvalidChildLevel {
+            let error = RecordStructureError::InvalidChildLevel {
                 level: level.sourced_value,
                 expected_level,
                 span: level.span,
-            });
+            };
+
+            if !self.recover_invalid_levels {
+                return Err(error);
+            }
+
+            warnings.report(error)?;
         }
 
         self.stack.push(RawRecord::new(line));
@@ -1,6 +1,6 @@
 use miette::SourceSpan;
 
-use crate::versions::{InvalidGEDCOMVersionError, UnsupportedGEDCOMVersionError};
+use crate::versions::{InvalidGEDCOMVersionError, LegacyVersion, UnsupportedGEDCOMVersionError};
 
 #[derive(thiserror::Error, Debug, miette::Diagnostic)]
 pub enum VersionError {
@@ -23,6 +23,19 @@ pub enum VersionError {
         help: UnsupportedGEDCOMVersionError,
     },
 
+    #[error("GEDCOM {detected} files are too old to be read")]
+    #[diagnostic(
+        code(gedcom::version::too_old),
+        help(
+            "convert the file to GEDCOM 5.5.1 first; most genealogy programs (such as PAF or Gramps) can import older GEDCOM files and export them as GEDCOM 5.5.1"
+        )
+    )]
+    TooOld {
+        detected: LegacyVersion,
+        #[label("this identifies the file as GEDCOM {detected}")]
+        span: SourceSpan,
+    },
+
     #[error("GEDCOM file appeared to be syntactically valid, but no version could be found")]
     #[diagnostic(
         code(gedcom::version::missing),
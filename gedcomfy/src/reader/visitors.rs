@@ -0,0 +1,49 @@
+//! Hooks which are called for every record as it is read, so that records
+//! can be rewritten (for example, to normalize producer-specific tags)
+//! before they are checked and converted.
+
+use miette::SourceSpan;
+
+use super::{Sourced, records::RawRecord};
+
+/// What should happen to a record once it has been visited.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Visit {
+    /// Keep the record (including any changes made to it).
+    Keep,
+    /// Drop the record, as if it were not in the file.
+    Drop,
+}
+
+/// Visits each top-level record (with its subrecords) as it is read, before
+/// it is linted or converted to the schema; set with
+/// [`ParseOptions::add_visitor`](super::options::ParseOptions::add_visitor).
+///
+/// The header is visited like any other record, but only after the version
+/// and encoding of the file have been determined from it. Visitors are not
+/// called by [`Reader::record_arena`](super::Reader::record_arena).
+pub trait RecordVisitor: Send + Sync {
+    /// Visits a record, which may be changed in place. Any annotations
+    /// added are reported as advice.
+    fn visit(
+        &self,
+        record: &mut Sourced<RawRecord<'_>>,
+        annotations: &mut Vec<Annotation>,
+    ) -> Visit;
+}
+
+/// A note about a record, made by a [`RecordVisitor`].
+#[derive(thiserror::Error, derive_more::Display, Debug, Clone, miette::Diagnostic)]
+#[display("{message}")]
+#[diagnostic(severity(Advice), code(gedcom::visitor::annotation))]
+pub struct Annotation {
+    pub message: String,
+    #[label]
+    pub span: SourceSpan,
+}
+
+impl Annotation {
+    pub fn new(message: impl Into<String>, span: SourceSpan) -> Self {
+        Self { message: message.into(), span }
+    }
+}
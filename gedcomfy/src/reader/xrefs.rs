@@ -0,0 +1,85 @@
+//! Checking that pointers between records refer to records which exist.
+//!
+//! This is done as records are read, and the problems are reported once
+//! the whole file has been seen (since pointers may refer forwards).
+
+use std::collections::HashSet;
+
+use miette::SourceSpan;
+
+use super::{NonFatalHandler, ReaderError, Sourced, lines::LineValue, records::RawRecord};
+use crate::schemas::SchemaError;
+
+#[derive(Default)]
+pub(crate) struct XRefResolver {
+    /// The xrefs of all records.
+    records: HashSet<String>,
+    /// Pointers to associated individuals (`ASSO`).
+    associate_pointers: Vec<(String, SourceSpan)>,
+    /// The xrefs of the `SUBM` records, and where they are.
+    submitters: Vec<(String, SourceSpan)>,
+    /// Pointers to submitters (from the header, submission, and other records).
+    submitter_pointers: Vec<(String, SourceSpan)>,
+}
+
+impl XRefResolver {
+    pub(crate) fn add_record(&mut self, record: &Sourced<RawRecord>) {
+        if let Some(xref) = &record.line.xref {
+            self.records.insert(xref.to_string());
+            if record.line.tag.as_str() == "SUBM" {
+                self.submitters.push((xref.to_string(), xref.span));
+            }
+        }
+
+        self.add_pointers(record);
+    }
+
+    fn add_pointers(&mut self, record: &Sourced<RawRecord>) {
+        for child in &record.records {
+            if let LineValue::Ptr(Some(pointer)) = child.line.value.sourced_value {
+                let pointers = match child.line.tag.as_str() {
+                    "SUBM" => Some(&mut self.submitter_pointers),
+                    "ASSO" => Some(&mut self.associate_pointers),
+                    _ => None,
+                };
+
+                if let Some(pointers) = pointers {
+                    pointers.push((pointer.to_string(), child.line.value.span));
+                }
+            }
+
+            self.add_pointers(child);
+        }
+    }
+
+    /// Reports any pointers to missing records, and any
+    /// submitters which are not referred to.
+    pub(crate) fn complete(self, handler: &mut impl NonFatalHandler) -> Result<(), ReaderError> {
+        let is_submitter = |xref: &str| self.submitters.iter().any(|(s, _)| s == xref);
+        for (xref, span) in &self.submitter_pointers {
+            if !is_submitter(xref) {
+                handler
+                    .report(SchemaError::MissingSubmitter { xref: xref.clone(), span: *span })?;
+            }
+        }
+
+        for (xref, span) in &self.associate_pointers {
+            if !self.records.contains(xref) {
+                handler
+                    .report(SchemaError::MissingAssociate { xref: xref.clone(), span: *span })?;
+            }
+        }
+
+        let is_referenced = |xref: &str| self.submitter_pointers.iter().any(|(p, _)| p == xref);
+        for (xref, span) in &self.submitters {
+            if !is_referenced(xref) {
+                handler.report(SchemaError::UnreferencedSubmitter {
+                    xref: xref.clone(),
+                    span: *span,
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,372 @@
+//! Descendant reports, in the style of the NEHGS Register and the NGS Quarterly:
+//! a narrative of the descendants of an individual, grouped into generations.
+//!
+//! Each descendant is numbered in the order that they are listed. Their paragraph
+//! gives the dates and places of their birth, death, and burial, and of each of
+//! their marriages, followed by the children of the marriage (numbered with roman
+//! numerals). A `+` marks the children who had children of their own; their
+//! paragraphs follow, in the next generation. The citations of each fact are
+//! given as footnotes, which are listed at the end of the report.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Write,
+};
+
+use crate::{
+    graph::Person,
+    reader::{Sourced, records::RawRecord},
+};
+
+/// How a report is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    /// Plain text.
+    #[default]
+    Text,
+    /// Markdown, with the citations as footnotes.
+    Markdown,
+}
+
+/// Writes the report of the descendants of the individual with the given xref
+/// (without `@`s), or returns `None` if there is no such individual.
+pub fn descendants(
+    records: &[Sourced<RawRecord>],
+    root: &str,
+    format: ReportFormat,
+) -> Option<String> {
+    let mut report = Report::new(records, format);
+    report.index.get(root)?;
+    report.write_descendants(root);
+    Some(report.finish())
+}
+
+struct Report<'r, 'i> {
+    format: ReportFormat,
+    /// The records with xrefs.
+    index: HashMap<&'i str, &'r RawRecord<'i>>,
+    /// The families in which each individual is a partner, in file order.
+    families: HashMap<&'i str, Vec<&'r RawRecord<'i>>>,
+    /// The text of each footnote, in order.
+    footnotes: Vec<String>,
+    output: String,
+}
+
+impl<'r, 'i> Report<'r, 'i> {
+    fn new(records: &'r [Sourced<RawRecord<'i>>], format: ReportFormat) -> Self {
+        let mut index = HashMap::new();
+        let mut families: HashMap<&str, Vec<_>> = HashMap::new();
+        for record in records {
+            let Some(xref) = &record.line.xref else {
+                continue;
+            };
+
+            index.insert(xref.sourced_value, &record.sourced_value);
+            if record.line.tag.as_str() == "FAM" {
+                for tag in ["HUSB", "WIFE"] {
+                    if let Some(partner) = record.subrecords(tag).find_map(RawRecord::pointer) {
+                        families
+                            .entry(partner)
+                            .or_default()
+                            .push(&record.sourced_value);
+                    }
+                }
+            }
+        }
+
+        Self {
+            format,
+            index,
+            families,
+            footnotes: Vec::new(),
+            output: String::new(),
+        }
+    }
+
+    fn individual(&self, xref: &str) -> Option<&'r RawRecord<'i>> {
+        self.index
+            .get(xref)
+            .copied()
+            .filter(|record| record.line.tag.as_str() == "INDI")
+    }
+
+    fn name(&self, xref: &'i str) -> String {
+        self.individual(xref)
+            .and_then(|record| Person::new(xref, record).name)
+            .unwrap_or_else(|| "(unnamed)".to_string())
+    }
+
+    /// Whether the individual has any children, in any of their families.
+    fn has_children(&self, xref: &str) -> bool {
+        self.families.get(xref).is_some_and(|families| {
+            families
+                .iter()
+                .any(|family| family.subrecords("CHIL").next().is_some())
+        })
+    }
+
+    fn write_descendants(&mut self, root: &'i str) {
+        let title = format!("Descendants of {}", self.name(root));
+        match self.format {
+            ReportFormat::Text => _ = writeln!(self.output, "{title}"),
+            ReportFormat::Markdown => _ = writeln!(self.output, "# {title}"),
+        }
+
+        // each descendant is numbered when they are first listed; those who had
+        // children are queued to have their own paragraph
+        let mut numbers = HashMap::from([(root, 1)]);
+        let mut queue = VecDeque::from([(root, 1, 1)]);
+        let mut written = HashSet::new();
+        let mut current_generation = 0;
+        while let Some((xref, number, generation)) = queue.pop_front() {
+            if !written.insert(xref) {
+                continue;
+            }
+
+            if generation != current_generation {
+                current_generation = generation;
+                match self.format {
+                    ReportFormat::Text => _ = write!(self.output, "\nGeneration {generation}\n"),
+                    ReportFormat::Markdown => {
+                        _ = write!(self.output, "\n## Generation {generation}\n")
+                    }
+                }
+            }
+
+            self.write_individual(xref, number);
+
+            let families = self.families.get(xref).cloned().unwrap_or_default();
+            for family in families {
+                let children: Vec<&str> = family
+                    .subrecords("CHIL")
+                    .filter_map(RawRecord::pointer)
+                    .collect();
+                self.write_marriage(xref, family, !children.is_empty());
+
+                for (ix, child) in children.into_iter().enumerate() {
+                    let next_number = numbers.len() + 1;
+                    let number = *numbers.entry(child).or_insert(next_number);
+                    let continued = self.has_children(child);
+                    if continued && !written.contains(child) {
+                        queue.push_back((child, number, generation + 1));
+                    }
+
+                    self.write_child(child, number, ix + 1, continued);
+                }
+            }
+        }
+    }
+
+    fn write_individual(&mut self, xref: &'i str, number: usize) {
+        let name = self.name(xref);
+        let facts = self
+            .individual(xref)
+            .map(|record| self.life_facts(record))
+            .unwrap_or_default();
+        let heading = match self.format {
+            ReportFormat::Text => format!("{number}. {name}"),
+            ReportFormat::Markdown => format!("**{number}. {name}**"),
+        };
+
+        match facts.as_slice() {
+            [] => _ = write!(self.output, "\n{heading}.\n"),
+            facts => _ = write!(self.output, "\n{heading}, {}.\n", join(facts)),
+        }
+    }
+
+    fn write_marriage(&mut self, xref: &'i str, family: &'r RawRecord<'i>, has_children: bool) {
+        let name = self.name(xref);
+        let spouse = ["HUSB", "WIFE"]
+            .into_iter()
+            .filter_map(|tag| family.subrecords(tag).find_map(RawRecord::pointer))
+            .find(|partner| *partner != xref);
+
+        let indent = match self.format {
+            ReportFormat::Text => "   ",
+            ReportFormat::Markdown => "",
+        };
+
+        if let Some(spouse) = spouse {
+            let mut sentence = format!("{name} married {}", self.name(spouse));
+            let spouse_facts = self
+                .individual(spouse)
+                .map(|record| self.life_facts(record))
+                .unwrap_or_default();
+            if !spouse_facts.is_empty() {
+                _ = write!(sentence, " ({})", join(&spouse_facts));
+            }
+
+            // the phrase starts with a space if there is a date or place
+            if let Some(marriage) = self.event(family, "MARR", "") {
+                if marriage.starts_with(' ') {
+                    sentence.push(',');
+                }
+
+                sentence.push_str(&marriage);
+            }
+
+            self.paragraph(indent, &sentence);
+        }
+
+        if has_children {
+            let heading = match spouse {
+                Some(spouse) => format!("Children of {name} and {}:", self.name(spouse)),
+                None => format!("Children of {name}:"),
+            };
+
+            match self.format {
+                ReportFormat::Text => _ = writeln!(self.output, "{indent}{heading}"),
+                ReportFormat::Markdown => _ = write!(self.output, "\n{heading}\n\n"),
+            }
+        }
+    }
+
+    fn paragraph(&mut self, indent: &str, sentence: &str) {
+        match self.format {
+            ReportFormat::Text => _ = writeln!(self.output, "{indent}{sentence}."),
+            ReportFormat::Markdown => _ = write!(self.output, "\n{sentence}.\n"),
+        }
+    }
+
+    fn write_child(&mut self, xref: &'i str, number: usize, ordinal: usize, continued: bool) {
+        let name = self.name(xref);
+        let marker = if continued { "+" } else { " " };
+        let ordinal = roman(ordinal);
+        let facts = self
+            .individual(xref)
+            .map(|record| self.life_facts(record))
+            .unwrap_or_default();
+        let mut entry = match self.format {
+            ReportFormat::Text => format!("   {marker} {number:<3} {ordinal:>4}. {name}"),
+            // escaped, so that they do not start a list within the list
+            ReportFormat::Markdown if continued => format!("- \\+ {number}\\. {ordinal}. {name}"),
+            ReportFormat::Markdown => format!("- {number}\\. {ordinal}. {name}"),
+        };
+
+        // the paragraphs of continued children give their details
+        if !continued && !facts.is_empty() {
+            _ = write!(entry, ", {}", join(&facts));
+        }
+
+        _ = writeln!(self.output, "{}", entry.trim_end());
+    }
+
+    /// The birth, death, and burial of the individual, as phrases.
+    fn life_facts(&mut self, record: &RawRecord) -> Vec<String> {
+        [("BIRT", "born"), ("DEAT", "died"), ("BURI", "buried")]
+            .into_iter()
+            .filter_map(|(tag, verb)| self.event(record, tag, verb))
+            .collect()
+    }
+
+    /// Describes the first event with the tag, as the verb followed by its date,
+    /// place, and citations; or `None` if there is no such event.
+    fn event(&mut self, record: &RawRecord, tag: &'static str, verb: &str) -> Option<String> {
+        let event = record.subrecords(tag).next()?;
+        let mut phrase = verb.to_string();
+        if let Some(date) = event.subrecords("DATE").find_map(RawRecord::string_value) {
+            _ = write!(phrase, " {date}");
+        }
+
+        if let Some(place) = event.subrecords("PLAC").find_map(RawRecord::string_value) {
+            _ = write!(phrase, " in {place}");
+        }
+
+        for citation in event.subrecords("SOUR") {
+            let number = self.footnote(citation);
+            match self.format {
+                ReportFormat::Text => _ = write!(phrase, "[{number}]"),
+                ReportFormat::Markdown => _ = write!(phrase, "[^{number}]"),
+            }
+        }
+
+        (!phrase.is_empty()).then_some(phrase)
+    }
+
+    /// The number of the footnote for the citation, adding it if it is new.
+    fn footnote(&mut self, citation: &RawRecord) -> usize {
+        let mut text = match citation.pointer() {
+            Some(source) => self
+                .index
+                .get(source)
+                .and_then(|source| source.subrecords("TITL").find_map(RawRecord::string_value))
+                .map_or_else(|| format!("@{source}@"), str::to_string),
+            None => citation.string_value().unwrap_or_default().to_string(),
+        };
+
+        if let Some(page) = citation
+            .subrecords("PAGE")
+            .find_map(RawRecord::string_value)
+        {
+            _ = write!(text, ", {page}");
+        }
+
+        match self.footnotes.iter().position(|footnote| *footnote == text) {
+            Some(ix) => ix + 1,
+            None => {
+                self.footnotes.push(text);
+                self.footnotes.len()
+            }
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if !self.footnotes.is_empty() {
+            if self.format == ReportFormat::Text {
+                _ = write!(self.output, "\nSources\n\n");
+            } else {
+                _ = writeln!(self.output);
+            }
+
+            for (ix, footnote) in self.footnotes.iter().enumerate() {
+                match self.format {
+                    ReportFormat::Text => _ = writeln!(self.output, "[{}] {footnote}", ix + 1),
+                    ReportFormat::Markdown => {
+                        _ = writeln!(self.output, "[^{}]: {footnote}", ix + 1)
+                    }
+                }
+            }
+        }
+
+        self.output
+    }
+}
+
+/// Joins phrases into a list: `a`, `a and b`, or `a, b, and c`.
+fn join(phrases: &[String]) -> String {
+    match phrases {
+        [] => String::new(),
+        [only] => only.clone(),
+        [first, second] => format!("{first} and {second}"),
+        [init @ .., last] => format!("{}, and {last}", init.join(", ")),
+    }
+}
+
+/// The number in lower-case roman numerals.
+fn roman(mut number: usize) -> String {
+    const NUMERALS: &[(usize, &str)] = &[
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+
+    let mut result = String::new();
+    for &(value, numeral) in NUMERALS {
+        while number >= value {
+            result.push_str(numeral);
+            number -= value;
+        }
+    }
+
+    result
+}
@@ -0,0 +1,86 @@
+//! Support for recording when records were changed (`CHAN` structures).
+//!
+//! The current time is obtained from a [`Clock`], so that tests (or tools that
+//! want reproducible output) can supply a fixed time with [`FixedClock`].
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A source of the current time.
+pub trait Clock {
+    fn now(&self) -> SystemTime;
+}
+
+/// The system clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A clock which is stopped at a given time.
+#[derive(Clone, Copy, Debug)]
+pub struct FixedClock(pub SystemTime);
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
+
+pub(crate) const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// A point in time, formatted as a GEDCOM `DATE` and `TIME` (in UTC).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Timestamp {
+    /// e.g. `16 OCT 2026`
+    pub date: String,
+    /// e.g. `09:05:00`
+    pub time: String,
+}
+
+impl Timestamp {
+    /// The current time according to the clock.
+    ///
+    /// Times before the Unix epoch are treated as the epoch.
+    pub fn now(clock: &impl Clock) -> Self {
+        let seconds = clock
+            .now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let (days, seconds_of_day) = (seconds / 86_400, seconds % 86_400);
+        let (year, month, day) = civil_from_days(days);
+        Timestamp {
+            date: format!("{day} {} {year}", MONTHS[month as usize - 1]),
+            time: format!(
+                "{:02}:{:02}:{:02}",
+                seconds_of_day / 3600,
+                seconds_of_day / 60 % 60,
+                seconds_of_day % 60
+            ),
+        }
+    }
+}
+
+/// Converts days since the Unix epoch to a (year, month, day) date in the
+/// proleptic Gregorian calendar, following Howard Hinnant’s `civil_from_days`.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // months are counted from March, so that the leap day is at the end of the year
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
@@ -1,15 +1,26 @@
 use super::{SchemaError, XRef};
 use crate::{
-    reader::{Sourced, lines::LineValue, records::RawRecord},
+    reader::{Sourced, lines::LineValue, packed_tags::PackedTag, records::RawRecord},
     schemas::DataError,
 };
 
+/// Values which are converted from a single line cannot have subrecords.
+fn expect_no_subrecords(source: &Sourced<RawRecord>) -> Result<(), SchemaError> {
+    match source.records.first() {
+        None => Ok(()),
+        Some(record) => Err(SchemaError::UnexpectedTag {
+            tag: record.line.tag.to_string(),
+            span: record.line.tag.span,
+            parent_span: source.span,
+        }),
+    }
+}
+
 impl<'a> TryFrom<Sourced<RawRecord<'a>>> for Option<String> {
     type Error = SchemaError;
 
     fn try_from(source: Sourced<RawRecord<'a>>) -> Result<Self, Self::Error> {
-        assert!(source.records.is_empty()); // todo: proper error
-
+        expect_no_subrecords(&source)?;
         match source.line.value.sourced_value {
             LineValue::Ptr(_) => Err(SchemaError::DataError {
                 tag: source.line.tag.to_string(),
@@ -37,36 +48,41 @@ impl TryFrom<Sourced<RawRecord<'_>>> for String {
     type Error = SchemaError;
 
     fn try_from(source: Sourced<RawRecord<'_>>) -> Result<Self, Self::Error> {
+        let unexpected_pointer = |record: &RawRecord| SchemaError::DataError {
+            tag: record.line.tag.to_string(),
+            source: DataError::UnexpectedPointer,
+        };
+
         let mut result = match source.line.value.sourced_value {
-            LineValue::Ptr(_) => todo!("proper error"),
+            LineValue::Ptr(_) => return Err(unexpected_pointer(&source)),
             // it’s ok to have no value here because it could be a string like "\nsomething": newline followed by CONT/C
             LineValue::None => String::new(),
             LineValue::Str(s) => s.to_string(),
         };
 
         for rec in &source.sourced_value.records {
-            match rec.line.tag.as_str() {
-                "CONT" => {
+            match rec.line.packed_tag {
+                PackedTag::CONT => {
                     result.push('\n');
                     match rec.line.value.sourced_value {
                         LineValue::Str(s) => {
                             result.push_str(s);
                         }
                         LineValue::None => (),
-                        LineValue::Ptr(_) => todo!(),
+                        LineValue::Ptr(_) => return Err(unexpected_pointer(rec)),
                     }
                 }
-                "CONC" => match rec.line.value.sourced_value {
+                PackedTag::CONC => match rec.line.value.sourced_value {
                     LineValue::Str(s) => {
                         result.push_str(s);
                     }
                     LineValue::None => (),
-                    LineValue::Ptr(_) => todo!(),
+                    LineValue::Ptr(_) => return Err(unexpected_pointer(rec)),
                 },
-                tag => {
+                _ => {
                     return Err(SchemaError::UnexpectedTag {
                         parent_span: source.span,
-                        tag: tag.to_string(),
+                        tag: rec.line.tag.to_string(),
                         span: rec.line.tag.span,
                     });
                 }
@@ -103,7 +119,7 @@ impl<'a> TryFrom<Sourced<RawRecord<'a, str>>> for XRef {
     type Error = SchemaError;
 
     fn try_from(rec: Sourced<RawRecord<'a, str>>) -> Result<Self, Self::Error> {
-        debug_assert!(rec.records.is_empty()); // TODO: error
+        expect_no_subrecords(&rec)?;
         let tag = rec.line.tag.as_str();
         XRef::try_from(rec.sourced_value.line.sourced_value.value)
             .map_err(|source| SchemaError::DataError { tag: tag.to_string(), source })
@@ -117,7 +133,7 @@ impl<'a> TryFrom<Sourced<LineValue<'a, str>>> for Option<XRef> {
         match source.sourced_value {
             LineValue::None => Ok(None),
             LineValue::Ptr(xref) => Ok(Some(XRef { xref: xref.map(|x| x.to_string()) })),
-            LineValue::Str(_) => todo!("proper error for string"),
+            LineValue::Str(_) => Err(DataError::ExpectedPointer),
         }
     }
 }
@@ -128,8 +144,8 @@ impl<'a> TryFrom<Sourced<LineValue<'a, str>>> for XRef {
     fn try_from(source: Sourced<LineValue<'a, str>>) -> Result<Self, Self::Error> {
         match source.sourced_value {
             LineValue::Ptr(xref) => Ok(XRef { xref: xref.map(|x| x.to_string()) }),
-            LineValue::Str(_) => todo!(),
-            LineValue::None => todo!(),
+            LineValue::Str(_) => Err(DataError::ExpectedPointer),
+            LineValue::None => Err(DataError::MissingData),
         }
     }
 }
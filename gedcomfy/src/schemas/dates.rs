@@ -0,0 +1,221 @@
+//! Parsing date values (`DATE`), so that they can be compared,
+//! and the ages of individuals at events can be found.
+//!
+//! A date value is parsed into the range of days it could refer to; for example,
+//! `MAR 1900` is any day from 1 to 31 March 1900, and `BEF 1900` is any day before
+//! 1 January 1900. Approximate dates (`ABT`, `CAL`, `EST`) are treated as the date
+//! they approximate, and dates in calendars other than the Gregorian and Julian
+//! calendars (or which are only a phrase) are not parsed.
+
+use super::changes::MONTHS;
+
+/// A day, which is part of a [`DateRange`].
+///
+/// Days are not checked to exist in their month; the last
+/// day of a month is always given as the 31st.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Day {
+    /// The year, which is negative for years B.C.
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+/// The range of days which a date value could refer to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    /// The earliest day, or `None` if the range is unbounded (e.g. `BEF 1900`).
+    pub earliest: Option<Day>,
+    /// The latest day, or `None` if the range is unbounded (e.g. `AFT 1900`).
+    pub latest: Option<Day>,
+}
+
+/// The range of ages that an individual could have been at an event, in whole years.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgeRange {
+    /// The youngest age, or `None` if it is not known.
+    pub min: Option<i32>,
+    /// The oldest age, or `None` if it is not known.
+    pub max: Option<i32>,
+}
+
+impl DateRange {
+    /// Parses a date value, returning `None` if it cannot be understood.
+    pub fn parse(value: &str) -> Option<Self> {
+        let upper = value.to_ascii_uppercase();
+        let mut words: Vec<&str> = upper.split_ascii_whitespace().collect();
+
+        if let Some(calendar) = words.first().filter(|word| word.starts_with("@#")) {
+            if !matches!(*calendar, "@#DGREGORIAN@" | "@#DJULIAN@") {
+                return None;
+            }
+
+            words.remove(0);
+        }
+
+        let range = match words.as_slice() {
+            ["ABT" | "CAL" | "EST", date @ ..] => Self::of(date)?,
+            ["INT", rest @ ..] => {
+                // an interpreted date is followed by the phrase it was interpreted from
+                let end = rest
+                    .iter()
+                    .position(|word| word.starts_with('('))
+                    .unwrap_or(rest.len());
+                Self::of(&rest[..end])?
+            }
+            ["BEF", date @ ..] => Self { earliest: None, latest: Self::of(date)?.earliest },
+            ["AFT", date @ ..] => Self { earliest: Self::of(date)?.latest, latest: None },
+            ["BET", rest @ ..] => {
+                let and = rest.iter().position(|word| *word == "AND")?;
+                Self::between(&rest[..and], &rest[and + 1..])?
+            }
+            ["FROM", rest @ ..] => match rest.iter().position(|word| *word == "TO") {
+                Some(to) => Self::between(&rest[..to], &rest[to + 1..])?,
+                None => Self { earliest: Self::of(rest)?.earliest, latest: None },
+            },
+            ["TO", date @ ..] => Self { earliest: None, latest: Self::of(date)?.latest },
+            date => Self::of(date)?,
+        };
+
+        Some(range)
+    }
+
+    fn between(from: &[&str], to: &[&str]) -> Option<Self> {
+        Some(Self {
+            earliest: Self::of(from)?.earliest,
+            latest: Self::of(to)?.latest,
+        })
+    }
+
+    /// Parses a single date: `[[day] month] year [B.C.]`.
+    fn of(words: &[&str]) -> Option<Self> {
+        let (words, bc) = match words {
+            [rest @ .., "B.C." | "BC" | "(B.C.)"] => (rest, true),
+            _ => (words, false),
+        };
+
+        let (day, month, year) = match words {
+            [year] => (None, None, *year),
+            [month, year] => (None, Some(*month), *year),
+            [day, month, year] => (Some(*day), Some(*month), *year),
+            _ => return None,
+        };
+
+        // for dual years (e.g. 1750/51), the first year is used
+        let year = year.split_once('/').map_or(year, |(year, _)| year);
+        if year.is_empty() || !year.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        let year: i32 = year.parse().ok()?;
+        let year = if bc { -year } else { year };
+
+        let month = match month {
+            Some(month) => Some(MONTHS.iter().position(|m| *m == month)? as u8 + 1),
+            None => None,
+        };
+
+        let day = match day {
+            Some(day) => Some(day.parse().ok().filter(|day| (1..=31).contains(day))?),
+            None => None,
+        };
+
+        Some(Self {
+            earliest: Some(Day {
+                year,
+                month: month.unwrap_or(1),
+                day: day.unwrap_or(1),
+            }),
+            latest: Some(Day {
+                year,
+                month: month.unwrap_or(12),
+                day: day.unwrap_or(31),
+            }),
+        })
+    }
+
+    /// Whether every day in this range is before every day in the other.
+    pub fn is_before(&self, other: &DateRange) -> bool {
+        match (self.latest, other.earliest) {
+            (Some(latest), Some(earliest)) => latest < earliest,
+            _ => false,
+        }
+    }
+}
+
+impl AgeRange {
+    /// The ages an individual born in the range `birth` could have been at `event`.
+    pub fn at(birth: &DateRange, event: &DateRange) -> Self {
+        let age = |birth: Option<Day>, event: Option<Day>| Some(years_between(birth?, event?));
+        Self {
+            min: age(birth.latest, event.earliest),
+            max: age(birth.earliest, event.latest),
+        }
+    }
+}
+
+/// The number of whole years from one day to another.
+fn years_between(from: Day, to: Day) -> i32 {
+    let years = to.year - from.year;
+    if (to.month, to.day) < (from.month, from.day) {
+        years - 1
+    } else {
+        years
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn day(year: i32, month: u8, day: u8) -> Option<Day> {
+        Some(Day { year, month, day })
+    }
+
+    #[test]
+    fn parses_dates() {
+        let range = |value| DateRange::parse(value).map(|range| (range.earliest, range.latest));
+        assert_eq!(
+            range("12 MAR 1900"),
+            Some((day(1900, 3, 12), day(1900, 3, 12)))
+        );
+        assert_eq!(range("mar 1900"), Some((day(1900, 3, 1), day(1900, 3, 31))));
+        assert_eq!(
+            range("ABT 1900"),
+            Some((day(1900, 1, 1), day(1900, 12, 31)))
+        );
+        assert_eq!(range("BEF 1900"), Some((None, day(1900, 1, 1))));
+        assert_eq!(range("AFT 2 JAN 1900"), Some((day(1900, 1, 2), None)));
+        assert_eq!(
+            range("BET 1900 AND FEB 1910"),
+            Some((day(1900, 1, 1), day(1910, 2, 31)))
+        );
+        assert_eq!(range("FROM 1900"), Some((day(1900, 1, 1), None)));
+        assert_eq!(
+            range("12 MAR 1750/51"),
+            Some((day(1750, 3, 12), day(1750, 3, 12)))
+        );
+        assert_eq!(range("44 B.C."), Some((day(-44, 1, 1), day(-44, 12, 31))));
+        assert_eq!(
+            range("INT 1900 (about the turn of the century)"),
+            range("1900")
+        );
+        assert_eq!(range("@#DJULIAN@ 1700"), range("1700"));
+        assert_eq!(range("@#DHEBREW@ 5660"), None);
+        assert_eq!(range("(unknown)"), None);
+        assert_eq!(range("32 MAR 1900"), None);
+    }
+
+    #[test]
+    fn ages() {
+        let parse = |value| DateRange::parse(value).unwrap();
+        let age = AgeRange::at(&parse("12 MAR 1900"), &parse("11 MAR 1920"));
+        assert_eq!(age, AgeRange { min: Some(19), max: Some(19) });
+
+        let age = AgeRange::at(&parse("1900"), &parse("1920"));
+        assert_eq!(age, AgeRange { min: Some(19), max: Some(20) });
+
+        let age = AgeRange::at(&parse("1900"), &parse("AFT 1920"));
+        assert_eq!(age, AgeRange { min: Some(20), max: None });
+    }
+}
@@ -1,7 +1,8 @@
 use miette::SourceSpan;
 use vec1::Vec1;
 
-use super::SchemaError;
+use super::{SchemaError, XRef};
+use crate::reader::{options::DuplicateSubrecords, records::RawRecord};
 
 // embedded structures can only have 0:1 or 1:1 cardinality
 macro_rules! structure_cardinality {
@@ -20,11 +21,10 @@ macro_rules! from_struct_cardinality {
             None => None,
         }
     };
+    // if none of the structure’s subrecords are present, completing an empty
+    // structure reports whichever of them are required
     ($parent_span:expr, $value:expr, 1, 1) => {
-        match $value {
-            Some(x) => x.complete($parent_span)?,
-            None => todo!("required but not found"),
-        }
+        $value.unwrap_or_default().complete($parent_span)?
     };
 }
 
@@ -77,6 +77,65 @@ pub(super) fn c_vec2vec1<T>(
     Vec1::try_from_vec(v).map_err(|_| SchemaError::MissingRecord { parent_span, tag })
 }
 
+/// Removes repeated subrecords, for subrecords which may appear at most once,
+/// so that the record can be converted; this is only done when requested by
+/// [`ParseOptions::duplicate_subrecords`](crate::reader::options::ParseOptions::duplicate_subrecords).
+pub(crate) trait Deduplicate {
+    fn deduplicate(
+        _record: &mut RawRecord,
+        _keep: DuplicateSubrecords,
+        _found: &mut Vec<SchemaError>,
+    ) {
+    }
+}
+
+impl Deduplicate for String {}
+impl Deduplicate for XRef {}
+
+/// Keeps only one of the subrecords with the given tag, reporting each one removed.
+pub(super) fn remove_duplicates(
+    record: &mut RawRecord,
+    tag: &'static str,
+    keep: DuplicateSubrecords,
+    found: &mut Vec<SchemaError>,
+) {
+    let positions = Vec::from_iter(
+        (record.records.iter().enumerate())
+            .filter(|(_, subrecord)| subrecord.line.tag.as_str() == tag)
+            .map(|(ix, _)| ix),
+    );
+
+    let kept = match (keep, positions.as_slice()) {
+        (_, [] | [_]) | (DuplicateSubrecords::Error, _) => return,
+        (DuplicateSubrecords::KeepFirst, [first, ..]) => *first,
+        (DuplicateSubrecords::KeepLast, [.., last]) => *last,
+    };
+
+    let kept_span = record.records[kept].line.tag.span;
+    for &ix in &positions {
+        if ix != kept {
+            found.push(SchemaError::DuplicateSubrecord {
+                tag,
+                span: record.records[ix].line.tag.span,
+                kept_span,
+            });
+        }
+    }
+
+    for &ix in positions.iter().rev() {
+        if ix != kept {
+            record.records.remove(ix);
+        }
+    }
+}
+
+macro_rules! deduplicate_if_singular {
+    ($record:expr, $tag:literal, $keep:expr, $found:expr, 1) => {
+        $crate::schemas::macros::remove_duplicates($record, $tag, $keep, $found)
+    };
+    ($record:expr, $tag:literal, $keep:expr, $found:expr, $max:tt) => {};
+}
+
 macro_rules! from_cardinality {
     ($parent_span:expr, $tag:literal, $x:expr, 0, 1) => {{ crate::schemas::macros::c_vec2opt($tag, $x)? }};
     ($parent_span:expr, $tag:literal, $x:expr, 1, 1) => {{ crate::schemas::macros::c_vec2one($parent_span, $tag, $x)? }};
@@ -93,6 +152,20 @@ macro_rules! from_cardinality {
 }
 
 macro_rules! define_enum {
+    // Enum whose variants all have the same field, which can be accessed through the enum:
+    (enum $name:ident { $($struct_ty:ident),+ $(,)? } with $field:ident: $field_ty:ty) => {
+        $crate::schemas::macros::define_enum!(enum $name { $($struct_ty),+ });
+
+        impl $name {
+            pub fn $field(&self) -> &$field_ty {
+                match self {
+                    $(
+                        $name::$struct_ty(x) => &x.$field,
+                    )+
+                }
+            }
+        }
+    };
     (enum $name:ident { $($struct_ty:ident),+ $(,)? }) => {
         #[derive(Debug, Eq, PartialEq, Clone)]
         pub enum $name {
@@ -110,6 +183,15 @@ macro_rules! define_enum {
                 )+ false
             }
 
+            /// The tag of the variant’s record.
+            pub fn tag(&self) -> &'static str {
+                match self {
+                    $(
+                        $name::$struct_ty(_) => $struct_ty::TAG,
+                    )+
+                }
+            }
+
             fn build_from(record: Sourced<RawRecord>) -> Result<$name, SchemaError> {
                 debug_assert!($name::matches_tag(record.line.tag.as_str()));
                 match record.line.tag.as_str() {
@@ -121,12 +203,38 @@ macro_rules! define_enum {
             }
         }
 
+        impl $crate::schemas::macros::Deduplicate for $name {
+            fn deduplicate(
+                record: &mut RawRecord,
+                keep: $crate::reader::options::DuplicateSubrecords,
+                found: &mut Vec<SchemaError>,
+            ) {
+                let tag = record.line.tag.sourced_value.as_str();
+                $(
+                    if $struct_ty::matches_tag(tag) {
+                        <$struct_ty as $crate::schemas::macros::Deduplicate>::deduplicate(record, keep, found);
+                    } else
+                )+
+                {}
+            }
+        }
+
         $(
             impl From<$struct_ty> for $name {
                 fn from(e: $struct_ty) -> Self {
                     Self::$struct_ty(e)
                 }
             }
+
+            impl $crate::schemas::Variant<$name> for $struct_ty {
+                #[allow(unreachable_patterns)]
+                fn from_enum(e: &$name) -> Option<&Self> {
+                    match e {
+                        $name::$struct_ty(x) => Some(x),
+                        _ => None,
+                    }
+                }
+            }
         )+
     };
 }
@@ -160,12 +268,13 @@ macro_rules! define_structure {
             impl [< $name Builder >] {
                 fn build_from(&mut self, record: Sourced<RawRecord>) -> Result<(), SchemaError> {
                     debug_assert!($name::matches_tag(record.line.tag.as_str()));
-                    match record.line.tag.as_str() {
-                        $($tag => {
+                    let tag = record.line.tag.as_str();
+                    match record.line.packed_tag {
+                        $(packed if packed == const { $crate::reader::packed_tags::PackedTag::new($tag) } => {
                             let $field: $ty = <$ty>::try_from(record)?;
                             self.$field.push($field);
                         })*
-                        tag => {
+                        _ => {
                             $(
                                 if $struct_ty::matches_tag(tag) {
                                     self.$struct_field.get_or_insert_with(Default::default).build_from(record)?;
@@ -204,6 +313,43 @@ macro_rules! define_structure {
                     }
                 }
             }
+
+            /// Removes the duplicates of the structure’s subrecords from the record
+            /// which it is embedded in.
+            fn deduplicate_within(
+                record: &mut RawRecord,
+                keep: $crate::reader::options::DuplicateSubrecords,
+                found: &mut Vec<SchemaError>,
+            ) {
+                $(
+                    $crate::schemas::macros::deduplicate_if_singular!(record, $tag, keep, found, $max);
+                )*
+                $(
+                    <$struct_ty>::deduplicate_within(record, keep, found);
+                )*
+            }
+
+            /// Removes duplicates from within one of the structure’s subrecords.
+            #[allow(unused)]
+            fn deduplicate_subrecord(
+                subrecord: &mut RawRecord,
+                keep: $crate::reader::options::DuplicateSubrecords,
+                found: &mut Vec<SchemaError>,
+            ) {
+                match subrecord.line.tag.sourced_value.as_str() {
+                    $(
+                        $tag => <$ty as $crate::schemas::macros::Deduplicate>::deduplicate(subrecord, keep, found),
+                    )*
+                    tag => {
+                        $(
+                            if <$struct_ty>::matches_tag(tag) {
+                                <$struct_ty>::deduplicate_subrecord(subrecord, keep, found);
+                            } else
+                        )*
+                        {}
+                    }
+                }
+            }
         }
     };
 }
@@ -240,12 +386,52 @@ macro_rules! define_record {
         }
 
         impl $name {
+            pub const TAG: &'static str = $self_tag;
+
             #[inline]
             pub fn matches_tag(tag: &str) -> bool {
                 tag == $self_tag
             }
         }
 
+        impl $crate::schemas::macros::Deduplicate for $name {
+            fn deduplicate(
+                record: &mut RawRecord,
+                keep: $crate::reader::options::DuplicateSubrecords,
+                found: &mut Vec<SchemaError>,
+            ) {
+                $(
+                    $crate::schemas::macros::deduplicate_if_singular!(record, $tag, keep, found, $max);
+                )*
+                $(
+                    $struct_ty::deduplicate_within(record, keep, found);
+                )*
+
+                for subrecord in &mut record.records {
+                    let subrecord = &mut subrecord.sourced_value;
+                    match subrecord.line.tag.sourced_value.as_str() {
+                        $(
+                            $tag => <$ty as $crate::schemas::macros::Deduplicate>::deduplicate(subrecord, keep, found),
+                        )*
+                        #[allow(unused)]
+                        tag => {
+                            $(
+                                if $struct_ty::matches_tag(tag) {
+                                    $struct_ty::deduplicate_subrecord(subrecord, keep, found);
+                                } else
+                            )*
+                            $(
+                                if $enum_ty::matches_tag(tag) {
+                                    <$enum_ty as $crate::schemas::macros::Deduplicate>::deduplicate(subrecord, keep, found);
+                                } else
+                            )*
+                            {}
+                        }
+                    }
+                }
+            }
+        }
+
         impl<'a> TryFrom<Sourced<RawRecord<'a>>> for $name {
             type Error = SchemaError;
 
@@ -276,19 +462,20 @@ macro_rules! define_record {
                 let parent_span = source.span;
 
                 for record in source.sourced_value.records {
-                    match record.line.tag.as_str() {
+                    let tag = record.line.tag.as_str();
+                    match record.line.packed_tag {
                         $(
-                            $tag => {
+                            packed if packed == const { $crate::reader::packed_tags::PackedTag::new($tag) } => {
                                 let $field: $ty = <$ty>::try_from(record)?;
                                 result.$field.push($field);
                             }
                         )*
-                        "CONC" | "CONT" => {
+                        $crate::reader::packed_tags::PackedTag::CONC | $crate::reader::packed_tags::PackedTag::CONT => {
                             // will be handled by line_value
                             // TODO: is CONC valid in other versions?
                             unused_records.push(record);
                         }
-                        tag => {
+                        _ => {
                             $(
                                 if $struct_ty::matches_tag(tag) {
                                     $struct_field.get_or_insert_with(Default::default).build_from(record)?;
@@ -315,8 +502,12 @@ macro_rules! define_record {
                 source.sourced_value.records = unused_records;
 
                 $crate::schemas::macros::if_not_provided!(($($value_name)?) {
-                    if !source.sourced_value.records.is_empty() {
-                        todo!("CONT not permitted here - no value expected")
+                    if let Some(record) = source.sourced_value.records.first() {
+                        return Err(SchemaError::UnexpectedTag {
+                            parent_span,
+                            tag: record.line.tag.to_string(),
+                            span: record.line.tag.span,
+                        });
                     }
                 });
 
@@ -340,6 +531,7 @@ macro_rules! define_record {
 }
 
 pub(crate) use collection_for_cardinality;
+pub(crate) use deduplicate_if_singular;
 pub(crate) use define_enum;
 pub(crate) use define_record;
 pub(crate) use define_structure;
@@ -1,15 +1,41 @@
 use miette::SourceSpan;
+use quality::CertaintyAssessment;
 
 use crate::{
     reader::{Sourced, records::RawRecord},
     versions::KnownVersion,
 };
 
+pub mod changes;
 mod conversions;
+pub mod dates;
 mod macros;
+pub mod places;
+pub mod quality;
+pub mod tags;
+pub mod temples;
 pub mod v551;
 pub mod v7;
 
+/// Types which are one of the variants of the enum `E`;
+/// e.g. [`v551::Birth`] is a variant of [`v551::IndividualEvent`].
+pub trait Variant<E> {
+    fn from_enum(e: &E) -> Option<&Self>;
+}
+
+/// The years mentioned in a date value; e.g. `BET 1900 AND 1910` contains
+/// 1900 and 1910, and `12 MAR 1750/51` contains 1750.
+pub(crate) fn years_of(date: &str) -> impl Iterator<Item = i32> + use<'_> {
+    date.split_ascii_whitespace().filter_map(|word| {
+        let year = word.split_once('/').map_or(word, |(year, _)| year);
+        if (3..=4).contains(&year.len()) && year.bytes().all(|b| b.is_ascii_digit()) {
+            year.parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
 #[derive(Debug)]
 pub enum AnyFileVersion {
     V551(v551::File),
@@ -26,8 +52,9 @@ impl TryFrom<(KnownVersion, Vec<Sourced<RawRecord<'_>>>)> for AnyFileVersion {
             KnownVersion::V5_5 | KnownVersion::V5_5_1 => {
                 AnyFileVersion::V551(v551::File::from_records(records)?)
             }
-            KnownVersion::V5_5_5 => todo!(),
-            KnownVersion::V7_0 => todo!(),
+            version @ (KnownVersion::V5_5_5 | KnownVersion::V7_0) => {
+                return Err(SchemaError::UnsupportedVersion { version });
+            }
         })
     }
 }
@@ -64,6 +91,205 @@ pub enum SchemaError {
         parent_span: SourceSpan,
     },
 
+    #[display("Pointer to missing submitter @{xref}@")]
+    #[diagnostic(code("gedcom::schema::missing_submitter"))]
+    MissingSubmitter {
+        xref: String,
+
+        #[label("there is no SUBM record with this xref")]
+        span: SourceSpan,
+    },
+
+    #[display("Pointer to missing associate @{xref}@")]
+    #[diagnostic(code("gedcom::schema::missing_associate"))]
+    MissingAssociate {
+        xref: String,
+
+        #[label("there is no record with this xref")]
+        span: SourceSpan,
+    },
+
+    #[display("Submitter @{xref}@ is not referred to by any record")]
+    #[diagnostic(severity(Warning), code("gedcom::schema::unreferenced_submitter"))]
+    UnreferencedSubmitter {
+        xref: String,
+
+        #[label("this submitter is never used")]
+        span: SourceSpan,
+    },
+
+    #[display("Unknown temple code {code}")]
+    #[diagnostic(
+        severity(Advice),
+        code("gedcom::schema::unknown_temple_code"),
+        help("temple codes are listed in the GEDCOM specification")
+    )]
+    UnknownTempleCode {
+        code: String,
+
+        #[label("this is not a known temple code")]
+        span: SourceSpan,
+    },
+
+    #[display("{jurisdiction} did not exist at the date of the event")]
+    #[diagnostic(severity(Advice), code("gedcom::schema::place_anachronism"))]
+    PlaceAnachronism {
+        jurisdiction: String,
+
+        #[label("this place is in {jurisdiction}")]
+        place_span: SourceSpan,
+
+        #[label("but the event happened at this date")]
+        date_span: SourceSpan,
+    },
+
+    #[display("{tag} has no citations assessed as at least {min_quality}")]
+    #[diagnostic(
+        severity(Advice),
+        code("gedcom::schema::poorly_supported_event"),
+        help("the QUAY of each citation of this event is lower, or missing")
+    )]
+    PoorlySupportedEvent {
+        tag: String,
+        min_quality: CertaintyAssessment,
+
+        #[label("this event is only cited by less reliable sources")]
+        span: SourceSpan,
+    },
+
+    #[display("@{xref}@ is their own ancestor")]
+    #[diagnostic(
+        severity(Warning),
+        code("gedcom::schema::ancestry_cycle"),
+        help(
+            "following the parents of @{xref}@ leads back to them, so one of the links between these families is probably wrong"
+        )
+    )]
+    AncestryCycle {
+        xref: String,
+
+        #[label("this child link completes the cycle")]
+        span: SourceSpan,
+    },
+
+    #[display("Individual is a child of more than one birth family")]
+    #[diagnostic(
+        severity(Warning),
+        code("gedcom::schema::multiple_birth_families"),
+        help(
+            "if the individual was adopted or fostered, add a PEDI record to the FAMC link to say so"
+        )
+    )]
+    MultipleBirthFamilies {
+        #[label("this family link")]
+        span: SourceSpan,
+
+        #[label("conflicts with this one")]
+        first_span: SourceSpan,
+    },
+
+    #[display("Family has @{xref}@ as more than one spouse")]
+    #[diagnostic(severity(Warning), code("gedcom::schema::duplicate_spouse"))]
+    DuplicateSpouse {
+        xref: String,
+
+        #[label("this spouse")]
+        span: SourceSpan,
+
+        #[label("is the same individual as this one")]
+        first_span: SourceSpan,
+    },
+
+    #[display("Family has {count} spouses")]
+    #[diagnostic(
+        severity(Warning),
+        code("gedcom::schema::too_many_spouses"),
+        help(
+            "a family has at most one husband and one wife; if this is intended, add a _POLY record to the family"
+        )
+    )]
+    TooManySpouses {
+        count: usize,
+
+        #[label("this spouse is one too many")]
+        span: SourceSpan,
+    },
+
+    #[display("Burial before death")]
+    #[diagnostic(severity(Warning), code("gedcom::schema::burial_before_death"))]
+    BurialBeforeDeath {
+        #[label("the individual was buried at this date")]
+        burial_span: SourceSpan,
+
+        #[label("but died at this date")]
+        death_span: SourceSpan,
+    },
+
+    #[display("Mother was at most {age} years old at the birth of her child")]
+    #[diagnostic(
+        severity(Warning),
+        code("gedcom::schema::mother_too_young"),
+        help("check the dates of both births, and that the child is in the right family")
+    )]
+    MotherTooYoung {
+        age: i32,
+
+        #[label("the mother was born at this date")]
+        mother_birth_span: SourceSpan,
+
+        #[label("and the child at this date")]
+        child_birth_span: SourceSpan,
+    },
+
+    #[display("Mother was at least {age} years old at the birth of her child")]
+    #[diagnostic(
+        severity(Warning),
+        code("gedcom::schema::mother_too_old"),
+        help("check the dates of both births, and that the child is in the right family")
+    )]
+    MotherTooOld {
+        age: i32,
+
+        #[label("the mother was born at this date")]
+        mother_birth_span: SourceSpan,
+
+        #[label("and the child at this date")]
+        child_birth_span: SourceSpan,
+    },
+
+    #[display("Spouse was at most {age} years old when married, which is younger than {min_age}")]
+    #[diagnostic(severity(Advice), code("gedcom::schema::married_too_young"))]
+    MarriedTooYoung {
+        age: i32,
+        min_age: u32,
+
+        #[label("the spouse was born at this date")]
+        birth_span: SourceSpan,
+
+        #[label("and married at this date")]
+        marriage_span: SourceSpan,
+    },
+
+    #[display("Duplicate subrecord {tag}")]
+    #[diagnostic(
+        severity(Warning),
+        code("gedcom::schema::duplicate_subrecord"),
+        help("this subrecord may only appear once, so only one of them was kept")
+    )]
+    DuplicateSubrecord {
+        tag: &'static str,
+
+        #[label("this subrecord was ignored")]
+        span: SourceSpan,
+
+        #[label("in favour of this one")]
+        kept_span: SourceSpan,
+    },
+
+    #[display("Parsing GEDCOM {version} records is not yet supported")]
+    #[diagnostic(code("gedcom::schema::unsupported_version"))]
+    UnsupportedVersion { version: KnownVersion },
+
     #[display("Error reading data for record {tag}")]
     DataError { tag: String, source: DataError },
 
@@ -86,6 +312,9 @@ pub enum DataError {
     #[display("Unexpected pointer")]
     UnexpectedPointer,
 
+    #[display("Expected a pointer")]
+    ExpectedPointer,
+
     #[display("Missing required data")]
     MissingData,
 }
@@ -94,3 +323,10 @@ pub enum DataError {
 pub struct XRef {
     xref: Option<String>,
 }
+
+impl XRef {
+    /// The xref which is pointed to, or `None` for `@VOID@`.
+    pub fn xref(&self) -> Option<&str> {
+        self.xref.as_deref()
+    }
+}
@@ -0,0 +1,65 @@
+//! Historical information about places, used to check that the places
+//! of events existed at the time that the events happened.
+
+/// A source of information about when jurisdictions (countries, states, and so on)
+/// existed, so that events can be checked against their places.
+///
+/// This can be implemented to supply a historical place database;
+/// [`HistoricalJurisdictions`] is a simple implementation.
+pub trait Gazetteer: Send + Sync {
+    /// Whether the jurisdiction with the given name existed in the year,
+    /// or `None` if this is not known.
+    fn existed_in(&self, jurisdiction: &str, year: i32) -> Option<bool>;
+}
+
+/// A gazetteer built from a list of jurisdictions and the years in which they existed.
+#[derive(Clone, Debug, Default)]
+pub struct HistoricalJurisdictions {
+    jurisdictions: Vec<(String, Option<i32>, Option<i32>)>,
+}
+
+impl HistoricalJurisdictions {
+    /// A small set of well-known jurisdictions which no longer exist,
+    /// or which were created relatively recently.
+    pub fn well_known() -> Self {
+        [
+            ("Austria-Hungary", Some(1867), Some(1918)),
+            ("Czechoslovakia", Some(1918), Some(1992)),
+            ("East Germany", Some(1949), Some(1990)),
+            ("Israel", Some(1948), None),
+            ("Pakistan", Some(1947), None),
+            ("Prussia", Some(1701), Some(1947)),
+            ("Soviet Union", Some(1922), Some(1991)),
+            ("USSR", Some(1922), Some(1991)),
+            ("West Germany", Some(1949), Some(1990)),
+            ("Yugoslavia", Some(1918), Some(2003)),
+        ]
+        .into_iter()
+        .fold(Self::default(), |gazetteer, (name, from, until)| {
+            gazetteer.with_jurisdiction(name, from, until)
+        })
+    }
+
+    /// Adds a jurisdiction which existed from the first year until the last year
+    /// (inclusive); either may be `None` if it is unbounded.
+    pub fn with_jurisdiction(
+        mut self,
+        name: impl Into<String>,
+        from: Option<i32>,
+        until: Option<i32>,
+    ) -> Self {
+        self.jurisdictions.push((name.into(), from, until));
+        self
+    }
+}
+
+impl Gazetteer for HistoricalJurisdictions {
+    fn existed_in(&self, jurisdiction: &str, year: i32) -> Option<bool> {
+        let (_, from, until) = self
+            .jurisdictions
+            .iter()
+            .find(|(name, _, _)| name.eq_ignore_ascii_case(jurisdiction))?;
+
+        Some(from.is_none_or(|from| from <= year) && until.is_none_or(|until| year <= until))
+    }
+}
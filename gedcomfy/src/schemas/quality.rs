@@ -0,0 +1,73 @@
+//! The certainty assessment (`QUAY`) of a source citation, which
+//! rates how reliable the cited evidence is.
+
+use super::{DataError, SchemaError, macros::Deduplicate};
+use crate::reader::{Sourced, records::RawRecord};
+
+/// How reliable the evidence of a source citation is, from the value of
+/// its `QUAY` record. The variants are ordered from least to most reliable.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, derive_more::Display)]
+pub enum CertaintyAssessment {
+    /// `0`: unreliable evidence or estimated data.
+    #[display("0")]
+    Unreliable,
+    /// `1`: questionable reliability of evidence (interviews, census,
+    /// oral genealogies, or potential for bias, such as an autobiography).
+    #[display("1")]
+    Questionable,
+    /// `2`: secondary evidence, data officially recorded sometime after
+    /// the event.
+    #[display("2")]
+    Secondary,
+    /// `3`: direct and primary evidence, or dominance of the evidence.
+    #[display("3")]
+    Primary,
+}
+
+impl CertaintyAssessment {
+    /// Parses the value of a `QUAY` record.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "0" => Some(Self::Unreliable),
+            "1" => Some(Self::Questionable),
+            "2" => Some(Self::Secondary),
+            "3" => Some(Self::Primary),
+            _ => None,
+        }
+    }
+}
+
+impl Deduplicate for CertaintyAssessment {}
+
+impl TryFrom<Sourced<RawRecord<'_>>> for CertaintyAssessment {
+    type Error = SchemaError;
+
+    fn try_from(source: Sourced<RawRecord<'_>>) -> Result<Self, Self::Error> {
+        source
+            .string_value()
+            .and_then(Self::parse)
+            .ok_or_else(|| SchemaError::DataError {
+                tag: source.line.tag.to_string(),
+                source: DataError::InvalidData {},
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            CertaintyAssessment::parse("0"),
+            Some(CertaintyAssessment::Unreliable)
+        );
+        assert_eq!(
+            CertaintyAssessment::parse(" 3"),
+            Some(CertaintyAssessment::Primary)
+        );
+        assert_eq!(CertaintyAssessment::parse("4"), None);
+        assert!(CertaintyAssessment::Secondary > CertaintyAssessment::Questionable);
+    }
+}
@@ -0,0 +1,548 @@
+//! Short descriptions of the standard GEDCOM tags, for documentation
+//! (for example, when hovering over a tag in an editor).
+//!
+//! The descriptions are summarized from the GEDCOM 5.5.1 specification.
+
+/// Each standard tag and a description of what it means, sorted by tag.
+pub const TAG_DESCRIPTIONS: &[(&str, &str)] = &[
+    (
+        "ABBR",
+        "Abbreviation: a short name of a title, description, or name.",
+    ),
+    (
+        "ADDR",
+        "Address: the contemporary place, usually required for postal purposes.",
+    ),
+    (
+        "ADOP",
+        "Adoption: creation of a child-parent relationship that does not exist biologically.",
+    ),
+    ("ADR1", "Address line 1: the first line of an address."),
+    ("ADR2", "Address line 2: the second line of an address."),
+    ("ADR3", "Address line 3: the third line of an address."),
+    (
+        "AFN",
+        "Ancestral File number: a unique permanent record number of an individual in Ancestral File.",
+    ),
+    (
+        "AGE",
+        "Age: the age of the individual at the time an event occurred.",
+    ),
+    (
+        "AGNC",
+        "Agency: the institution or individual having authority or responsibility.",
+    ),
+    (
+        "ALIA",
+        "Alias: an indicator to link different record descriptions of a person who may be the same person.",
+    ),
+    (
+        "ANCE",
+        "Ancestors: pertaining to forbearers of an individual.",
+    ),
+    (
+        "ANCI",
+        "Ancestor interest: indicates an interest in additional research for ancestors of this individual.",
+    ),
+    (
+        "ANUL",
+        "Annulment: declaring a marriage void from the beginning.",
+    ),
+    (
+        "ASSO",
+        "Associates: an indicator to link friends, neighbors, relatives, or associates of an individual.",
+    ),
+    (
+        "AUTH",
+        "Author: the name of the individual who created or compiled information.",
+    ),
+    (
+        "BAPL",
+        "Baptism (LDS): the event of baptism performed at age eight or later by priesthood authority of the LDS Church.",
+    ),
+    (
+        "BAPM",
+        "Baptism: the event of baptism (not LDS), performed in infancy or later.",
+    ),
+    (
+        "BARM",
+        "Bar Mitzvah: the ceremonial event held when a Jewish boy reaches age 13.",
+    ),
+    (
+        "BASM",
+        "Bas Mitzvah: the ceremonial event held when a Jewish girl reaches age 13.",
+    ),
+    ("BIRT", "Birth: the event of entering into life."),
+    (
+        "BLES",
+        "Blessing: a religious event of bestowing divine care or intercession.",
+    ),
+    (
+        "BURI",
+        "Burial: the event of the proper disposing of the mortal remains of a deceased person.",
+    ),
+    (
+        "CALN",
+        "Call number: the number used by a repository to identify the specific items in its collections.",
+    ),
+    (
+        "CAST",
+        "Caste: the name of an individual's rank or status in society.",
+    ),
+    (
+        "CAUS",
+        "Cause: a description of the cause of the associated event or fact, such as the cause of death.",
+    ),
+    (
+        "CENS",
+        "Census: the event of the periodic count of the population for a designated locality.",
+    ),
+    (
+        "CHAN",
+        "Change: indicates a change, correction, or modification, along with when it was made.",
+    ),
+    (
+        "CHAR",
+        "Character: an indicator of the character set used in writing this automated information.",
+    ),
+    (
+        "CHIL",
+        "Child: the natural, adopted, or sealed (LDS) child of a father and a mother.",
+    ),
+    (
+        "CHR",
+        "Christening: the religious event (not LDS) of baptizing and/or naming a child.",
+    ),
+    (
+        "CHRA",
+        "Adult christening: the religious event (not LDS) of baptizing and/or naming an adult person.",
+    ),
+    (
+        "CITY",
+        "City: a lower level jurisdictional unit, normally an incorporated municipal unit.",
+    ),
+    (
+        "CONC",
+        "Concatenation: additional data belonging to the superior value, joined without a line break.",
+    ),
+    (
+        "CONF",
+        "Confirmation: the religious event (not LDS) of conferring the gift of the Holy Ghost.",
+    ),
+    (
+        "CONL",
+        "Confirmation (LDS): the religious event by which a person receives membership in the LDS Church.",
+    ),
+    (
+        "CONT",
+        "Continued: additional data belonging to the superior value, following a line break.",
+    ),
+    (
+        "COPR",
+        "Copyright: a statement that accompanies data to protect it from unlawful duplication.",
+    ),
+    (
+        "CORP",
+        "Corporate: a name of an institution, agency, corporation, or company.",
+    ),
+    (
+        "CREM",
+        "Cremation: disposal of the remains of a person's body by fire.",
+    ),
+    ("CTRY", "Country: the name or code of the country."),
+    ("DATA", "Data: pertaining to stored automated information."),
+    ("DATE", "Date: the time of an event in a calendar format."),
+    ("DEAT", "Death: the event when mortal life terminates."),
+    (
+        "DESC",
+        "Descendants: pertaining to offspring of an individual.",
+    ),
+    (
+        "DESI",
+        "Descendant interest: indicates an interest in research to identify additional descendants of this individual.",
+    ),
+    ("DEST", "Destination: a system receiving data."),
+    (
+        "DIV",
+        "Divorce: the event of dissolving a marriage through civil action.",
+    ),
+    (
+        "DIVF",
+        "Divorce filed: an event of filing for a divorce by a spouse.",
+    ),
+    (
+        "DSCR",
+        "Physical description: the physical characteristics of a person, place, or thing.",
+    ),
+    (
+        "EDUC",
+        "Education: indicator of a level of education attained.",
+    ),
+    (
+        "EMAIL",
+        "Email: an electronic address that can be used for contact.",
+    ),
+    (
+        "EMIG",
+        "Emigration: an event of leaving one's homeland with the intent of residing elsewhere.",
+    ),
+    (
+        "ENDL",
+        "Endowment (LDS): a religious event where an endowment ordinance was performed in an LDS temple.",
+    ),
+    (
+        "ENGA",
+        "Engagement: an event of recording or announcing an agreement between two people to become married.",
+    ),
+    (
+        "EVEN",
+        "Event: a noteworthy happening related to an individual, a group, or an organization.",
+    ),
+    (
+        "FACT",
+        "Fact: a noteworthy attribute or fact concerning an individual, a group, or an organization.",
+    ),
+    (
+        "FAM",
+        "Family: a legal, common law, or other customary relationship of man and woman and their children.",
+    ),
+    (
+        "FAMC",
+        "Family child: identifies the family in which an individual appears as a child.",
+    ),
+    (
+        "FAMF",
+        "Family file: pertaining to, or the name of, a family file.",
+    ),
+    (
+        "FAMS",
+        "Family spouse: identifies the family in which an individual appears as a spouse.",
+    ),
+    ("FAX", "Facsimile: a fax telephone number."),
+    (
+        "FCOM",
+        "First communion: a religious rite, the first act of sharing in the Lord's supper.",
+    ),
+    (
+        "FILE",
+        "File: an information storage place that is ordered and arranged for preservation and reference.",
+    ),
+    (
+        "FONE",
+        "Phonetic: a phonetic variation of a superior text string.",
+    ),
+    (
+        "FORM",
+        "Format: an assigned name given to a consistent format in which information can be conveyed.",
+    ),
+    (
+        "GEDC",
+        "GEDCOM: information about the use of GEDCOM in a transmission.",
+    ),
+    (
+        "GIVN",
+        "Given name: a given or earned name used for official identification of a person.",
+    ),
+    (
+        "GRAD",
+        "Graduation: an event of awarding educational diplomas or degrees to individuals.",
+    ),
+    (
+        "HEAD",
+        "Header: identifies information pertaining to an entire GEDCOM transmission.",
+    ),
+    (
+        "HUSB",
+        "Husband: an individual in the family role of a married man or father.",
+    ),
+    (
+        "IDNO",
+        "Identification number: a number assigned to identify a person within some significant external system.",
+    ),
+    (
+        "IMMI",
+        "Immigration: an event of entering into a new locality with the intent of residing there.",
+    ),
+    ("INDI", "Individual: a person."),
+    (
+        "LANG",
+        "Language: the name of the language used in a communication or transmission of information.",
+    ),
+    (
+        "LATI",
+        "Latitude: a value indicating a coordinate position on a line, plane, or space.",
+    ),
+    (
+        "LEGA",
+        "Legatee: a role of an individual acting as a person receiving a bequest or legal devise.",
+    ),
+    (
+        "LONG",
+        "Longitude: a value indicating a coordinate position on a line, plane, or space.",
+    ),
+    (
+        "MAP",
+        "Map: pertains to a representation of measurements usually presented in a graphical form.",
+    ),
+    (
+        "MARB",
+        "Marriage bann: an event of an official public notice given that two people intend to marry.",
+    ),
+    (
+        "MARC",
+        "Marriage contract: an event of recording a formal agreement of marriage.",
+    ),
+    (
+        "MARL",
+        "Marriage licence: an event of obtaining a legal license to marry.",
+    ),
+    (
+        "MARR",
+        "Marriage: a legal, common-law, or customary event of creating a family unit.",
+    ),
+    (
+        "MARS",
+        "Marriage settlement: an event of creating an agreement between two people contemplating marriage.",
+    ),
+    (
+        "MEDI",
+        "Media: identifies information about the media or having to do with the medium in which information is stored.",
+    ),
+    (
+        "NAME",
+        "Name: a word or combination of words used to help identify an individual, title, or other item.",
+    ),
+    (
+        "NATI",
+        "Nationality: the national heritage of an individual.",
+    ),
+    (
+        "NATU",
+        "Naturalization: the event of obtaining citizenship.",
+    ),
+    (
+        "NCHI",
+        "Children count: the number of children that this person is known to be the parent of.",
+    ),
+    (
+        "NICK",
+        "Nickname: a descriptive or familiar name used instead of, or in addition to, one's proper name.",
+    ),
+    (
+        "NMR",
+        "Marriage count: the number of times this person has participated in a family as a spouse or parent.",
+    ),
+    (
+        "NOTE",
+        "Note: additional information provided by the submitter for understanding the enclosing data.",
+    ),
+    (
+        "NPFX",
+        "Name prefix: text which appears on a name line before the given and surname parts of a name.",
+    ),
+    (
+        "NSFX",
+        "Name suffix: text which appears on a name line after or behind the given and surname parts of a name.",
+    ),
+    (
+        "OBJE",
+        "Object: pertaining to a grouping of attributes used in describing something, such as a multimedia file.",
+    ),
+    (
+        "OCCU",
+        "Occupation: the type of work or profession of an individual.",
+    ),
+    (
+        "ORDI",
+        "Ordinance: pertaining to a religious ordinance in general.",
+    ),
+    (
+        "ORDN",
+        "Ordination: a religious event of receiving authority to act in religious matters.",
+    ),
+    (
+        "PAGE",
+        "Page: a number or description to identify where information can be found in a referenced work.",
+    ),
+    (
+        "PEDI",
+        "Pedigree: information pertaining to an individual to parent lineage chart.",
+    ),
+    (
+        "PHON",
+        "Phone: a unique number assigned to access a specific telephone.",
+    ),
+    (
+        "PLAC",
+        "Place: a jurisdictional name to identify the place or location of an event.",
+    ),
+    (
+        "POST",
+        "Postal code: a code used by a postal service to identify an area to facilitate mail handling.",
+    ),
+    (
+        "PROB",
+        "Probate: an event of judicial determination of the validity of a will.",
+    ),
+    (
+        "PROP",
+        "Property: pertaining to possessions such as real estate or other property of interest.",
+    ),
+    (
+        "PUBL",
+        "Publication: refers to when and/or where a work was published or created.",
+    ),
+    (
+        "QUAY",
+        "Quality of data: an assessment of the certainty of the evidence to support the conclusion drawn from evidence.",
+    ),
+    (
+        "REFN",
+        "Reference: a description or number used to identify an item for filing, storage, or other reference purposes.",
+    ),
+    (
+        "RELA",
+        "Relationship: a relationship value between the indicated contexts.",
+    ),
+    (
+        "RELI",
+        "Religion: a religious denomination to which a person is affiliated or for which a record applies.",
+    ),
+    (
+        "REPO",
+        "Repository: an institution or person that has the specified item as part of their collection.",
+    ),
+    (
+        "RESI",
+        "Residence: the act of dwelling at an address for a period of time.",
+    ),
+    (
+        "RESN",
+        "Restriction: a processing indicator signifying access to information has been denied or otherwise restricted.",
+    ),
+    (
+        "RETI",
+        "Retirement: an event of exiting an occupational relationship with an employer after a qualifying time period.",
+    ),
+    (
+        "RFN",
+        "Record file number: a permanent number assigned to a record that uniquely identifies it within a known file.",
+    ),
+    (
+        "RIN",
+        "Record ID number: a number assigned to a record by an originating automated system.",
+    ),
+    (
+        "ROLE",
+        "Role: a name given to a role played by an individual in connection with an event.",
+    ),
+    (
+        "ROMN",
+        "Romanized: a romanized variation of a superior text string.",
+    ),
+    (
+        "SEX",
+        "Sex: indicates the sex of an individual, male or female.",
+    ),
+    (
+        "SLGC",
+        "Sealing child (LDS): a religious event pertaining to the sealing of a child to his or her parents in an LDS temple.",
+    ),
+    (
+        "SLGS",
+        "Sealing spouse (LDS): a religious event pertaining to the sealing of a husband and wife in an LDS temple.",
+    ),
+    (
+        "SOUR",
+        "Source: the initial or original material from which information was obtained.",
+    ),
+    (
+        "SPFX",
+        "Surname prefix: a name piece used as a non-indexing pre-part of a surname.",
+    ),
+    (
+        "SSN",
+        "Social security number: a number assigned by the United States Social Security Administration.",
+    ),
+    (
+        "STAE",
+        "State: a geographical division of a larger jurisdictional area, such as a state within the United States.",
+    ),
+    (
+        "STAT",
+        "Status: an assessment of the state or condition of something.",
+    ),
+    (
+        "SUBM",
+        "Submitter: an individual or organization who contributes genealogical data to a file or transfers it to someone else.",
+    ),
+    (
+        "SUBN",
+        "Submission: pertains to a collection of data issued for processing.",
+    ),
+    (
+        "SURN",
+        "Surname: a family name passed on or used by members of a family.",
+    ),
+    (
+        "TEMP",
+        "Temple: the name or code that represents the name of a temple of the LDS Church.",
+    ),
+    (
+        "TEXT",
+        "Text: the exact wording found in an original source document.",
+    ),
+    ("TIME", "Time: a time value in a 24-hour clock format."),
+    (
+        "TITL",
+        "Title: a description of a specific writing or other work, or a formal designation of nobility or rank.",
+    ),
+    (
+        "TRLR",
+        "Trailer: at level 0, specifies the end of a GEDCOM transmission.",
+    ),
+    (
+        "TYPE",
+        "Type: a further qualification to the meaning of the associated superior tag.",
+    ),
+    (
+        "VERS",
+        "Version: indicates which version of a product, item, or publication is being used or referenced.",
+    ),
+    (
+        "WIFE",
+        "Wife: an individual in the role as a mother and/or married woman.",
+    ),
+    (
+        "WILL",
+        "Will: a legal document treated as an event, by which a person disposes of his or her estate.",
+    ),
+    ("WWW", "Web page: a World Wide Web home page."),
+];
+
+/// A description of the tag, if it is a standard tag.
+pub fn tag_description(tag: &str) -> Option<&'static str> {
+    TAG_DESCRIPTIONS
+        .binary_search_by(|(t, _)| (*t).cmp(tag))
+        .ok()
+        .map(|ix| TAG_DESCRIPTIONS[ix].1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tags_are_sorted() {
+        assert!(TAG_DESCRIPTIONS.is_sorted_by_key(|(tag, _)| *tag));
+    }
+
+    #[test]
+    fn finds_tags() {
+        assert_eq!(
+            tag_description("BIRT"),
+            Some("Birth: the event of entering into life.")
+        );
+        assert_eq!(tag_description("_CUSTOM"), None);
+    }
+}
@@ -0,0 +1,159 @@
+//! Codes for the temples of the Church of Jesus Christ of Latter-day Saints,
+//! which are used as the values of `TEMP` records in LDS ordinances and submissions.
+//!
+//! The codes are those listed by the GEDCOM 5.5 and 5.5.1 specifications,
+//! along with the historical codes which are commonly found in older files.
+
+/// Each temple code and the name of the temple that it refers to, sorted by code.
+pub const TEMPLE_CODES: &[(&str, &str)] = &[
+    ("ABA", "Aba, Nigeria"),
+    ("ACCRA", "Accra, Ghana"),
+    ("ADELA", "Adelaide, Australia"),
+    ("ALBER", "Cardston, Alberta, Canada"),
+    ("ALBUQ", "Albuquerque, New Mexico"),
+    ("ANCHO", "Anchorage, Alaska"),
+    ("APIA", "Apia, Samoa"),
+    ("ARIZO", "Mesa, Arizona"),
+    ("ASUNC", "Asuncion, Paraguay"),
+    ("ATLAN", "Atlanta, Georgia"),
+    ("BAIRE", "Buenos Aires, Argentina"),
+    ("BILLI", "Billings, Montana"),
+    ("BIRMI", "Birmingham, Alabama"),
+    ("BISMA", "Bismarck, North Dakota"),
+    ("BOGOT", "Bogota, Colombia"),
+    ("BOISE", "Boise, Idaho"),
+    ("BOSTO", "Boston, Massachusetts"),
+    ("BOUNT", "Bountiful, Utah"),
+    ("BRISB", "Brisbane, Australia"),
+    ("BROUG", "Baton Rouge, Louisiana"),
+    ("CAMPI", "Campinas, Brazil"),
+    ("CARAC", "Caracas, Venezuela"),
+    ("CHICA", "Chicago, Illinois"),
+    ("CIUJU", "Ciudad Juarez, Mexico"),
+    ("COCHA", "Cochabamba, Bolivia"),
+    ("COLJU", "Colonia Juarez, Mexico"),
+    ("COLSC", "Columbia, South Carolina"),
+    ("COLUM", "Columbus, Ohio"),
+    ("COPEN", "Copenhagen, Denmark"),
+    ("CRIVE", "Columbia River, Washington"),
+    ("DALLA", "Dallas, Texas"),
+    ("DENVE", "Denver, Colorado"),
+    ("DETRO", "Detroit, Michigan"),
+    ("EDMON", "Edmonton, Alberta, Canada"),
+    ("EHOUS", "Endowment House, Salt Lake City"),
+    ("FRANK", "Frankfurt, Germany"),
+    ("FREIB", "Freiberg, Germany"),
+    ("FRESN", "Fresno, California"),
+    ("FUKUO", "Fukuoka, Japan"),
+    ("GUADA", "Guadalajara, Mexico"),
+    ("GUATE", "Guatemala City, Guatemala"),
+    ("GUAYA", "Guayaquil, Ecuador"),
+    ("HAGUE", "The Hague, Netherlands"),
+    ("HALIF", "Halifax, Nova Scotia, Canada"),
+    ("HARTF", "Hartford, Connecticut"),
+    ("HAWAI", "Laie, Hawaii"),
+    ("HELSI", "Helsinki, Finland"),
+    ("HERMO", "Hermosillo, Mexico"),
+    ("HKONG", "Hong Kong"),
+    ("HOUST", "Houston, Texas"),
+    ("IFALL", "Idaho Falls, Idaho"),
+    ("JOHAN", "Johannesburg, South Africa"),
+    ("JRIVE", "Jordan River, Utah"),
+    ("KIEV", "Kiev, Ukraine"),
+    ("KONA", "Kona, Hawaii"),
+    ("LANGE", "Los Angeles, California"),
+    ("LAPAZ", "La Paz, Bolivia"),
+    ("LIMA", "Lima, Peru"),
+    ("LOGAN", "Logan, Utah"),
+    ("LONDO", "London, England"),
+    ("LOUIS", "Louisville, Kentucky"),
+    ("LUBBO", "Lubbock, Texas"),
+    ("MADRI", "Madrid, Spain"),
+    ("MANIL", "Manila, Philippines"),
+    ("MANTI", "Manti, Utah"),
+    ("MEDFO", "Medford, Oregon"),
+    ("MELBO", "Melbourne, Australia"),
+    ("MEMPH", "Memphis, Tennessee"),
+    ("MERID", "Merida, Mexico"),
+    ("MEXIC", "Mexico City, Mexico"),
+    ("MNTVD", "Montevideo, Uruguay"),
+    ("MONTE", "Monterrey, Mexico"),
+    ("MONTI", "Monticello, Utah"),
+    ("MONTR", "Montreal, Quebec, Canada"),
+    ("MTIMP", "Mount Timpanogos, Utah"),
+    ("NASHV", "Nashville, Tennessee"),
+    ("NAUV2", "Nauvoo, Illinois (rebuilt)"),
+    ("NAUVO", "Nauvoo, Illinois (original)"),
+    ("NBEAC", "Newport Beach, California"),
+    ("NUKUA", "Nuku'Alofa, Tonga"),
+    ("NYORK", "New York, New York"),
+    ("NZEAL", "Hamilton, New Zealand"),
+    ("OAKLA", "Oakland, California"),
+    ("OAXAC", "Oaxaca, Mexico"),
+    ("OGDEN", "Ogden, Utah"),
+    ("OKLAH", "Oklahoma City, Oklahoma"),
+    ("ORLAN", "Orlando, Florida"),
+    ("PALEG", "Porto Alegre, Brazil"),
+    ("PALMY", "Palmyra, New York"),
+    ("PAPEE", "Papeete, Tahiti"),
+    ("PERTH", "Perth, Australia"),
+    ("POFFI", "President's Office"),
+    ("PORTL", "Portland, Oregon"),
+    ("PREST", "Preston, England"),
+    ("PROVO", "Provo, Utah"),
+    ("RALEI", "Raleigh, North Carolina"),
+    ("RECIF", "Recife, Brazil"),
+    ("REDLA", "Redlands, California"),
+    ("REGIN", "Regina, Saskatchewan, Canada"),
+    ("RENO", "Reno, Nevada"),
+    ("SACRA", "Sacramento, California"),
+    ("SANTI", "Santiago, Chile"),
+    ("SANTO", "Santo Domingo, Dominican Republic"),
+    ("SAOPA", "Sao Paulo, Brazil"),
+    ("SDIEG", "San Diego, California"),
+    ("SEATT", "Seattle, Washington"),
+    ("SEOUL", "Seoul, Korea"),
+    ("SLAKE", "Salt Lake City, Utah"),
+    ("SLOUI", "St. Louis, Missouri"),
+    ("SPMIN", "St. Paul, Minnesota"),
+    ("SPOKA", "Spokane, Washington"),
+    ("STGEO", "St. George, Utah"),
+    ("STOCK", "Stockholm, Sweden"),
+    ("SUVA", "Suva, Fiji"),
+    ("SWISS", "Bern, Switzerland"),
+    ("SYDNE", "Sydney, Australia"),
+    ("TAIPE", "Taipei, Taiwan"),
+    ("TAMPI", "Tampico, Mexico"),
+    ("TOKYO", "Tokyo, Japan"),
+    ("TORNO", "Toronto, Ontario, Canada"),
+    ("TUXTL", "Tuxtla Gutierrez, Mexico"),
+    ("VERAC", "Veracruz, Mexico"),
+    ("VERNA", "Vernal, Utah"),
+    ("VILLA", "Villahermosa, Mexico"),
+    ("WASHI", "Washington, D.C."),
+    ("WINTE", "Winter Quarters, Nebraska"),
+];
+
+/// The name of the temple with the given code, if it is a known code.
+pub fn temple_name(code: &str) -> Option<&'static str> {
+    TEMPLE_CODES
+        .binary_search_by(|(c, _)| (*c).cmp(code))
+        .ok()
+        .map(|ix| TEMPLE_CODES[ix].1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn codes_are_sorted() {
+        assert!(TEMPLE_CODES.is_sorted_by_key(|(code, _)| *code));
+    }
+
+    #[test]
+    fn finds_codes() {
+        assert_eq!(temple_name("SLAKE"), Some("Salt Lake City, Utah"));
+        assert_eq!(temple_name("SLAK"), None);
+    }
+}
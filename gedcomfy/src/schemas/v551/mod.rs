@@ -1,10 +1,15 @@
 use miette::SourceSpan;
 
 use super::{
-    SchemaError, XRef,
-    macros::{define_enum, define_record, define_structure},
+    SchemaError, Variant, XRef,
+    changes::{Clock, Timestamp},
+    macros::{Deduplicate, define_enum, define_record, define_structure},
+    quality::CertaintyAssessment,
 };
-use crate::reader::{Sourced, records::RawRecord};
+use crate::reader::{Sourced, options::DuplicateSubrecords, records::RawRecord};
+
+pub mod names;
+pub mod witnesses;
 
 #[derive(Debug)]
 pub struct File {
@@ -16,7 +21,10 @@ impl File {
     pub(crate) fn from_records(records: Vec<Sourced<RawRecord>>) -> Result<Self, SchemaError> {
         let mut iter = records.into_iter();
         let Some(header) = iter.next() else {
-            todo!();
+            return Err(SchemaError::MissingRecord {
+                tag: "HEAD",
+                parent_span: SourceSpan::from((0, 0)),
+            });
         };
 
         let header = Header::try_from(header)?;
@@ -87,6 +95,26 @@ impl TryFrom<Sourced<RawRecord<'_>>> for TopLevelRecord {
     }
 }
 
+impl TopLevelRecord {
+    /// Removes repeated subrecords from the record (which may be the header),
+    /// wherever they may only appear once; see [`Deduplicate`].
+    pub(crate) fn deduplicate(
+        record: &mut RawRecord,
+        keep: DuplicateSubrecords,
+        found: &mut Vec<SchemaError>,
+    ) {
+        match record.line.tag.as_str() {
+            "HEAD" => Header::deduplicate(record, keep, found),
+            "INDI" => Individual::deduplicate(record, keep, found),
+            "SUBM" => Submitter::deduplicate(record, keep, found),
+            "SUBN" => Submission::deduplicate(record, keep, found),
+            "FAM" => Family::deduplicate(record, keep, found),
+            "SOUR" => Source::deduplicate(record, keep, found),
+            _ => {}
+        }
+    }
+}
+
 define_record!(
     "PLAC" Place (place: String) {
         "FORM" format: String {0:1},
@@ -166,6 +194,7 @@ define_record!(
         "FAMC" child_family_link: ChildFamilyLink {0:N},
         "FAMS" spouse_family_link: SpouseFamilyLink {0:N},
         "SUBM" submitter: XRef {0:1},
+        "ASSO" associations: Association {0:N},
         "ALIA" alias: XRef {0:N},
         "ANCI" ancestor_interest: XRef {0:N},
         "DESI" descendant_interest: XRef {0:N},
@@ -213,6 +242,14 @@ define_record!(
     }
 );
 
+define_record!(
+    "ASSO" Association (individual: XRef) {
+        "RELA" relation: String {1:1},
+        "SOUR" source_citations: SourceCitation {0:N},
+        "NOTE" notes: String {0:N},
+    }
+);
+
 define_record!(
     "FAMS" SpouseFamilyLink (family: XRef) {
         "NOTE" notes: String {0:N},
@@ -244,7 +281,7 @@ define_enum!(
         Graduation,
         Retirement,
         EventIndividual,
-    }
+    } with detail: Option<IndividualEventDetail>
 );
 
 define_enum!(
@@ -261,7 +298,7 @@ define_enum!(
         MarriageSettlement,
         ResidenceFamily,
         EventFamily,
-    }
+    } with detail: Option<FamilyEventDetail>
 );
 
 define_enum!(
@@ -552,7 +589,7 @@ define_record!(
         "EVEN" event: SourceEvent {0:1},
         "DATA" data: CitationData {0:1},
         "NOTE" note: String {0:N},
-        "QUAY" certainty_assessment: String {0:1},
+        "QUAY" certainty_assessment: CertaintyAssessment {0:1},
     }
 );
 
@@ -565,7 +602,7 @@ define_record!(
 
 define_record!(
     "EVEN" SourceEvent (event_type_cited_from: String) {
-        "ROLE" role_in_event: String {0:1},
+        "ROLE" role_in_event: Role {0:1},
     }
 );
 
@@ -595,6 +632,117 @@ impl Name {
     }
 }
 
+impl Individual {
+    /// All the events of the given type, in the order they appear in the file;
+    /// e.g. `individual.events_of::<Burial>()`.
+    pub fn events_of<'a, T: Variant<IndividualEvent> + 'a>(
+        &'a self,
+    ) -> impl Iterator<Item = &'a T> {
+        self.events.iter().filter_map(T::from_enum)
+    }
+
+    /// All the attributes of the given type, in the order they appear in the file.
+    pub fn attributes_of<'a, T: Variant<IndividualAttribute> + 'a>(
+        &'a self,
+    ) -> impl Iterator<Item = &'a T> {
+        self.attributes.iter().filter_map(T::from_enum)
+    }
+
+    /// The individual’s birth.
+    ///
+    /// If there is more than one `BIRT` event (e.g. when sources disagree),
+    /// this is the first, which is the preferred one; see [`Individual::births`].
+    pub fn birth(&self) -> Option<&Birth> {
+        self.births().next()
+    }
+
+    pub fn births(&self) -> impl Iterator<Item = &Birth> {
+        self.events_of()
+    }
+
+    /// The individual’s death; as with [`Individual::birth`], the first is preferred.
+    pub fn death(&self) -> Option<&Death> {
+        self.deaths().next()
+    }
+
+    pub fn deaths(&self) -> impl Iterator<Item = &Death> {
+        self.events_of()
+    }
+
+    /// The individual’s christening; as with [`Individual::birth`], the first is preferred.
+    pub fn christening(&self) -> Option<&Christening> {
+        self.events_of().next()
+    }
+
+    /// The individual’s burial; as with [`Individual::birth`], the first is preferred.
+    pub fn burial(&self) -> Option<&Burial> {
+        self.events_of().next()
+    }
+}
+
+impl Family {
+    /// All the events of the given type, in the order they appear in the file;
+    /// e.g. `family.events_of::<Engagement>()`.
+    pub fn events_of<'a, T: Variant<FamilyEvent> + 'a>(&'a self) -> impl Iterator<Item = &'a T> {
+        self.events.iter().filter_map(T::from_enum)
+    }
+
+    /// All the marriages of the couple; there may be more than one
+    /// if, for example, they remarried after a divorce.
+    pub fn marriages(&self) -> impl Iterator<Item = &Marriage> {
+        self.events_of()
+    }
+
+    pub fn divorces(&self) -> impl Iterator<Item = &Divorce> {
+        self.events_of()
+    }
+}
+
+impl ChangeDate {
+    /// A change date recording the current time.
+    pub fn now(clock: &impl Clock) -> Self {
+        let Timestamp { date, time } = Timestamp::now(clock);
+        Self {
+            date: DateTime { date, time: Some(time) },
+            note: Vec::new(),
+        }
+    }
+
+    /// Sets the date and time to the current time, keeping any notes.
+    pub fn update(&mut self, clock: &impl Clock) {
+        self.date = Self::now(clock).date;
+    }
+}
+
+impl TopLevelRecord {
+    pub fn change_date(&self) -> Option<&ChangeDate> {
+        match self {
+            TopLevelRecord::Individual(r) => r.change_date.as_ref(),
+            TopLevelRecord::Submitter(r) => r.change_date.as_ref(),
+            TopLevelRecord::Submission(r) => r.change_date.as_ref(),
+            TopLevelRecord::Family(r) => r.change_date.as_ref(),
+            TopLevelRecord::Source(r) => r.change_date.as_ref(),
+        }
+    }
+
+    /// Marks the record as having been modified, by updating the
+    /// date and time in its `CHAN` structure (or adding one, if it has none).
+    pub fn touch(&mut self, clock: &impl Clock) {
+        let change_date = match self {
+            TopLevelRecord::Individual(r) => &mut r.change_date,
+            TopLevelRecord::Submitter(r) => &mut r.change_date,
+            TopLevelRecord::Submission(r) => &mut r.change_date,
+            TopLevelRecord::Family(r) => &mut r.change_date,
+            TopLevelRecord::Source(r) => &mut r.change_date,
+        };
+
+        match change_date {
+            Some(change_date) => change_date.update(clock),
+            None => *change_date = Some(ChangeDate::now(clock)),
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq)]
 pub enum NameType {
     Aka,
@@ -612,6 +760,78 @@ pub enum RestrictionNotice {
     Privacy,
 }
 
+/// The role of an individual in an event recorded by a source (`SOUR.EVEN.ROLE`).
+///
+/// GEDCOM 5.5.1 defines the roles `CHIL`, `HUSB`, `WIFE`, `MOTH`, `FATH`,
+/// and `SPOU`; the others are from GEDCOM 7.0, but are also used by 5.5.1 files.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Role {
+    Child,
+    Clergy,
+    Father,
+    Friend,
+    Godparent,
+    Husband,
+    Mother,
+    Multiple,
+    Neighbor,
+    Officiator,
+    Parent,
+    Spouse,
+    Wife,
+    Witness,
+    Other,
+    /// A role described in words, which GEDCOM 5.5.1 writes in
+    /// parentheses; e.g. `(Best man)` is `UserDefined("Best man")`.
+    UserDefined(String),
+}
+
+impl From<&str> for Role {
+    fn from(value: &str) -> Self {
+        match value {
+            "CHIL" => Role::Child,
+            "CLERGY" => Role::Clergy,
+            "FATH" => Role::Father,
+            "FRIEND" => Role::Friend,
+            "GODP" => Role::Godparent,
+            "HUSB" => Role::Husband,
+            "MOTH" => Role::Mother,
+            "MULTIPLE" => Role::Multiple,
+            "NGHBR" => Role::Neighbor,
+            "OFFICIATOR" => Role::Officiator,
+            "PARENT" => Role::Parent,
+            "SPOU" => Role::Spouse,
+            "WIFE" => Role::Wife,
+            "WITN" => Role::Witness,
+            "OTHER" => Role::Other,
+            _ => {
+                let description = value
+                    .strip_prefix('(')
+                    .and_then(|value| value.strip_suffix(')'))
+                    .unwrap_or(value)
+                    .trim();
+
+                // 5.5.1 has no role for witnesses, so they are usually described in words
+                if description.eq_ignore_ascii_case("witness") {
+                    Role::Witness
+                } else {
+                    Role::UserDefined(description.to_string())
+                }
+            }
+        }
+    }
+}
+
+impl Deduplicate for Role {}
+
+impl TryFrom<Sourced<RawRecord<'_>>> for Role {
+    type Error = SchemaError;
+
+    fn try_from(source: Sourced<RawRecord<'_>>) -> Result<Self, Self::Error> {
+        Ok(Role::from(String::try_from(source)?.as_str()))
+    }
+}
+
 define_record!(
     "SOUR" Source {
         "DATA" data: SourceData {0:1},
@@ -657,47 +877,14 @@ define_record!(
     }
 );
 
-/*
-struct Source {}
-
-impl<'a> TryFrom<RawRecord<'a>> for Header {
-    type Error = InvalidStandardTag;
-
-    fn try_from(value: RawRecord<'a>) -> Result<Self, Self::Error> {
-        if !value.line.tag.value.eq("HEAD") {
-            todo!()
-        }
-
-        if value.line.line_value.is_some() {
-            todo!();
-        }
-
-        if value.line.xref.is_some() {
-            todo!();
-        }
-
-        for record in value.records {
-            let tag: Sourced<Tag> = record
-                .line
-                .tag
-                .try_map(|t| Tag::from_raw(t.as_str()).into())?;
-
-            match tag {
-                Tag::Standard(_) => todo!(),
-                Tag::UserDefined(_) => todo!(),
-            }
-        }
-
-        Ok()
-    }
-}
-*/
 #[cfg(test)]
 mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
     use miette::SourceSpan;
 
     use super::*;
-    use crate::reader::Reader;
+    use crate::{reader::Reader, schemas::changes::FixedClock};
 
     #[test]
     fn basic_header() -> miette::Result<()> {
@@ -831,4 +1018,95 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn touch_updates_change_date() -> miette::Result<()> {
+        let lines = "\
+        0 HEAD\n\
+        1 GEDC\n\
+        2 VERS 5.5.1\n\
+        0 @I1@ INDI\n\
+        1 NAME John /Smith/\n\
+        1 CHAN\n\
+        2 DATE 1 JAN 2000\n\
+        2 NOTE keep me\n\
+        0 @I2@ INDI\n\
+        1 NAME Jane /Smith/";
+
+        // 2024-02-29 13:14:15 UTC
+        let clock = FixedClock(UNIX_EPOCH + Duration::from_secs(1_709_212_455));
+
+        let records = Reader::default().raw_records(&lines)?;
+        let mut records = records
+            .into_iter()
+            .skip(1) // HEAD
+            .map(TopLevelRecord::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for record in &mut records {
+            record.touch(&clock);
+        }
+
+        let expected = DateTime {
+            date: "29 FEB 2024".to_string(),
+            time: Some("13:14:15".to_string()),
+        };
+
+        let updated = records[0].change_date().unwrap();
+        assert_eq!(updated.date, expected);
+        assert_eq!(updated.note, vec!["keep me".to_string()]);
+
+        let inserted = records[1].change_date().unwrap();
+        assert_eq!(inserted.date, expected);
+        assert!(inserted.note.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn event_accessors() -> miette::Result<()> {
+        let lines = "\
+        0 HEAD\n\
+        1 GEDC\n\
+        2 VERS 5.5.1\n\
+        0 @I1@ INDI\n\
+        1 BIRT\n\
+        2 DATE 1 JAN 1900\n\
+        1 BIRT\n\
+        2 DATE 2 JAN 1900\n\
+        1 OCCU Farmer\n\
+        1 DEAT Y\n\
+        0 @F1@ FAM\n\
+        1 MARR\n\
+        2 DATE 1 JUN 1920\n\
+        1 DIV Y\n\
+        1 MARR\n\
+        2 DATE 1 JUN 1930";
+
+        let records = Reader::default().raw_records(&lines)?;
+        let mut records = records.into_iter().skip(1); // HEAD
+        let individual = Individual::try_from(records.next().unwrap())?;
+        let family = Family::try_from(records.next().unwrap())?;
+
+        let birth_date = |birth: &Birth| birth.detail.as_ref()?.detail.date.clone();
+        assert_eq!(
+            individual.birth().and_then(birth_date),
+            Some("1 JAN 1900".to_string())
+        );
+        assert_eq!(individual.births().count(), 2);
+        assert_eq!(individual.death().unwrap().y, Some("Y".to_string()));
+        assert!(individual.burial().is_none());
+        assert_eq!(
+            individual
+                .attributes_of::<Occupation>()
+                .map(|o| o.occupation.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Farmer"]
+        );
+
+        assert_eq!(family.marriages().count(), 2);
+        assert_eq!(family.divorces().count(), 1);
+
+        Ok(())
+    }
 }
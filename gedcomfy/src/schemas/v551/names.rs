@@ -0,0 +1,143 @@
+//! An index of the surnames and given names used in a file.
+
+use std::collections::BTreeMap;
+
+use super::{File, Individual, Name, TopLevelRecord};
+use crate::schemas::years_of;
+
+/// How often each surname and given name is used in a file.
+///
+/// Only the first (preferred) name of each individual is counted.
+/// Given names are counted separately, so `John Paul` counts as
+/// one use of `John` and one use of `Paul`.
+#[derive(Debug, Default)]
+pub struct NameIndex {
+    surnames: BTreeMap<String, usize>,
+    /// Given names, by the decade in which the individual was born (if known).
+    given_names: BTreeMap<Option<i32>, BTreeMap<String, usize>>,
+}
+
+impl NameIndex {
+    pub fn new(file: &File) -> Self {
+        let mut index = Self::default();
+        for record in &file.records {
+            if let TopLevelRecord::Individual(individual) = record {
+                index.add(individual);
+            }
+        }
+
+        index
+    }
+
+    fn add(&mut self, individual: &Individual) {
+        let Some(name) = individual.names.first() else {
+            return;
+        };
+
+        if let Some(surname) = surname(name) {
+            *self.surnames.entry(surname.to_string()).or_default() += 1;
+        }
+
+        let decade = birth_year(individual).map(|year| year - year.rem_euclid(10));
+        let given_names = self.given_names.entry(decade).or_default();
+        for given_name in given_names_of(name) {
+            *given_names.entry(given_name.to_string()).or_default() += 1;
+        }
+    }
+
+    /// All surnames and how often they are used, most frequent first.
+    pub fn surnames(&self) -> Vec<(&str, usize)> {
+        by_frequency(
+            self.surnames
+                .iter()
+                .map(|(name, count)| (name.as_str(), *count)),
+        )
+    }
+
+    /// All given names and how often they are used, most frequent first.
+    pub fn given_names(&self) -> Vec<(&str, usize)> {
+        let mut totals = BTreeMap::<&str, usize>::new();
+        for names in self.given_names.values() {
+            for (name, count) in names {
+                *totals.entry(name.as_str()).or_default() += count;
+            }
+        }
+
+        by_frequency(totals.into_iter())
+    }
+
+    /// The decades in which individuals with given names were born, in order;
+    /// e.g. `1900` for births from 1900 to 1909.
+    pub fn decades(&self) -> impl Iterator<Item = i32> + '_ {
+        self.given_names.keys().flatten().copied()
+    }
+
+    /// The given names of individuals born in the decade (or those whose
+    /// birth year is unknown, for `None`), most frequent first.
+    pub fn given_names_in_decade(&self, decade: Option<i32>) -> Vec<(&str, usize)> {
+        self.given_names
+            .get(&decade)
+            .map(|names| by_frequency(names.iter().map(|(name, count)| (name.as_str(), *count))))
+            .unwrap_or_default()
+    }
+
+    /// Surnames which start with the prefix (ignoring case), most frequent first.
+    pub fn surnames_with_prefix(&self, prefix: &str) -> Vec<(&str, usize)> {
+        with_prefix(self.surnames(), prefix)
+    }
+
+    /// Given names which start with the prefix (ignoring case), most frequent first.
+    pub fn given_names_with_prefix(&self, prefix: &str) -> Vec<(&str, usize)> {
+        with_prefix(self.given_names(), prefix)
+    }
+}
+
+fn by_frequency<'a>(names: impl Iterator<Item = (&'a str, usize)>) -> Vec<(&'a str, usize)> {
+    let mut result: Vec<(&str, usize)> = names.collect();
+
+    // names are already in order, so a stable sort keeps ties alphabetical
+    result.sort_by(|(_, l), (_, r)| r.cmp(l));
+    result
+}
+
+fn with_prefix<'a>(names: Vec<(&'a str, usize)>, prefix: &str) -> Vec<(&'a str, usize)> {
+    let prefix = prefix.to_lowercase();
+    names
+        .into_iter()
+        .filter(|(name, _)| name.to_lowercase().starts_with(&prefix))
+        .collect()
+}
+
+/// The surname, from the `SURN` piece if there is one,
+/// or otherwise the part of the name between slashes (`John /Smith/`).
+fn surname(name: &Name) -> Option<&str> {
+    let surname = match name.pieces.as_ref().and_then(|p| p.surname.as_deref()) {
+        Some(surname) => surname,
+        None => name.personal_name.split('/').nth(1)?,
+    };
+
+    Some(surname.trim()).filter(|s| !s.is_empty())
+}
+
+/// The given names, from the `GIVN` piece if there is one,
+/// or otherwise the part of the name before the surname.
+fn given_names_of(name: &Name) -> impl Iterator<Item = &str> {
+    let given = match name.pieces.as_ref().and_then(|p| p.given.as_deref()) {
+        Some(given) => given,
+        None => name.personal_name.split('/').next().unwrap_or_default(),
+    };
+
+    // given names may be separated by commas in GIVN
+    given.split([' ', ',']).filter(|given| !given.is_empty())
+}
+
+fn birth_year(individual: &Individual) -> Option<i32> {
+    let date = individual
+        .birth()?
+        .detail
+        .as_ref()?
+        .detail
+        .date
+        .as_deref()?;
+    years_of(date).next()
+}
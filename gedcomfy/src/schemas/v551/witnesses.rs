@@ -0,0 +1,83 @@
+//! Finding the individuals who witnessed an event.
+//!
+//! GEDCOM 5.5.1 has no direct link from an event to its witnesses. Instead, a witness
+//! cites the same source as the event, naming the type of event (`SOUR.EVEN`) and
+//! their role in it (`SOUR.EVEN.ROLE`):
+//!
+//! ```text
+//! 0 @I3@ INDI
+//! 1 SOUR @S1@
+//! 2 EVEN MARR
+//! 3 ROLE (Witness)
+//! ```
+
+use super::{EventDetail, FamilyEvent, File, Individual, IndividualEvent, Role, TopLevelRecord};
+
+/// Events which can have witnesses; that is, [`IndividualEvent`] and [`FamilyEvent`].
+pub trait Event {
+    /// The tag of the event; e.g. `MARR`.
+    fn tag(&self) -> &'static str;
+
+    /// The details of the event, if it has any.
+    fn event_detail(&self) -> Option<&EventDetail>;
+}
+
+impl Event for IndividualEvent {
+    fn tag(&self) -> &'static str {
+        IndividualEvent::tag(self)
+    }
+
+    fn event_detail(&self) -> Option<&EventDetail> {
+        self.detail().as_ref().map(|detail| &detail.detail)
+    }
+}
+
+impl Event for FamilyEvent {
+    fn tag(&self) -> &'static str {
+        FamilyEvent::tag(self)
+    }
+
+    fn event_detail(&self) -> Option<&EventDetail> {
+        self.detail()
+            .as_ref()
+            .and_then(|detail| detail.detail.as_ref())
+    }
+}
+
+impl File {
+    /// The individuals who witnessed the event, in the order they appear in the file.
+    ///
+    /// These are the individuals who cite one of the sources cited by the event,
+    /// for an event of the same type, with the role [`Role::Witness`].
+    pub fn witnesses_of<'a>(
+        &'a self,
+        event: &'a impl Event,
+    ) -> impl Iterator<Item = &'a Individual> {
+        let tag = event.tag();
+        let sources: Vec<&str> = event
+            .event_detail()
+            .into_iter()
+            .flat_map(|detail| &detail.sources)
+            .filter_map(|citation| citation.source.xref())
+            .collect();
+
+        self.records
+            .iter()
+            .filter_map(|record| match record {
+                TopLevelRecord::Individual(individual) => Some(individual),
+                _ => None,
+            })
+            .filter(move |individual| {
+                individual.source_citations.iter().any(|citation| {
+                    citation
+                        .source
+                        .xref()
+                        .is_some_and(|xref| sources.contains(&xref))
+                        && citation.event.as_ref().is_some_and(|event| {
+                            event.event_type_cited_from == tag
+                                && event.role_in_event == Some(Role::Witness)
+                        })
+                })
+            })
+    }
+}
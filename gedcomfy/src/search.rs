@@ -0,0 +1,111 @@
+//! Searching the values of records for text.
+
+use miette::SourceSpan;
+
+use crate::reader::{Sourced, lines::LineValue, records::RawRecord};
+
+/// What to search for.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// The exact text.
+    Literal(String),
+    /// Text matching a regular expression.
+    Regex(regex::Regex),
+}
+
+#[derive(thiserror::Error, derive_more::Display, Debug, miette::Diagnostic)]
+#[display("Invalid regular expression")]
+#[diagnostic(code(gedcom::search::invalid_pattern))]
+pub struct InvalidPattern {
+    source: regex::Error,
+}
+
+impl Pattern {
+    pub fn literal(text: impl Into<String>) -> Self {
+        Pattern::Literal(text.into())
+    }
+
+    pub fn regex(pattern: &str) -> Result<Self, InvalidPattern> {
+        regex::Regex::new(pattern)
+            .map(Pattern::Regex)
+            .map_err(|source| InvalidPattern { source })
+    }
+
+    /// The ranges of all the (non-overlapping) matches in the text.
+    fn find_all<'a>(&'a self, text: &'a str) -> Box<dyn Iterator<Item = (usize, usize)> + 'a> {
+        match self {
+            // an empty literal would match everywhere, so it matches nowhere instead
+            Pattern::Literal(literal) if literal.is_empty() => Box::new(std::iter::empty()),
+            Pattern::Literal(literal) => Box::new(
+                text.match_indices(literal.as_str())
+                    .map(|(start, m)| (start, m.len())),
+            ),
+            Pattern::Regex(regex) => Box::new(
+                regex
+                    .find_iter(text)
+                    .filter(|m| !m.is_empty())
+                    .map(|m| (m.start(), m.len())),
+            ),
+        }
+    }
+}
+
+/// A place where the pattern was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch<'i> {
+    /// The xref of the top-level record containing the match, if it has one.
+    pub xref: Option<&'i str>,
+    /// The tags of the records from the top-level record down to
+    /// the record containing the match, e.g. `["INDI", "BIRT", "PLAC"]`.
+    pub path: Vec<&'i str>,
+    /// Where the matching text is in the source.
+    pub span: SourceSpan,
+}
+
+impl SearchMatch<'_> {
+    /// The path of tags, joined by dots (e.g. `INDI.BIRT.PLAC`).
+    pub fn path_string(&self) -> String {
+        self.path.join(".")
+    }
+}
+
+/// Finds all the places in the values of the records (and their subrecords) where
+/// the pattern occurs, in the order that they appear. Pointers are not searched.
+pub fn search<'i>(records: &[Sourced<RawRecord<'i>>], pattern: &Pattern) -> Vec<SearchMatch<'i>> {
+    let mut matches = Vec::new();
+    for record in records {
+        let xref = record.line.xref.as_ref().map(|xref| xref.sourced_value);
+        search_record(record, pattern, xref, &mut Vec::new(), &mut matches);
+    }
+
+    matches
+}
+
+fn search_record<'i>(
+    record: &Sourced<RawRecord<'i>>,
+    pattern: &Pattern,
+    xref: Option<&'i str>,
+    path: &mut Vec<&'i str>,
+    matches: &mut Vec<SearchMatch<'i>>,
+) {
+    path.push(record.line.tag.sourced_value.as_str());
+
+    if let LineValue::Str(value) = record.line.value.sourced_value {
+        // the value might not include all of the span (if it began with an escaped `@@`)
+        let span = record.line.value.span;
+        let value_offset = span.offset() + (span.len() - value.len());
+        for (start, len) in pattern.find_all(value) {
+            matches.push(SearchMatch {
+                xref,
+                path: path.clone(),
+                span: SourceSpan::from((value_offset + start, len)),
+            });
+        }
+    }
+
+    for child in &record.records {
+        search_record(child, pattern, xref, path, matches);
+    }
+
+    path.pop();
+}
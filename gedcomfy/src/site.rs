@@ -0,0 +1,537 @@
+//! Exporting a page for each individual, in Markdown or HTML, for publishing
+//! with a static site generator.
+//!
+//! Each page gives the facts of the individual (their events and attributes,
+//! with dates and places), links to the pages of their parents, siblings,
+//! spouses, and children, and the media linked to them. The citations of each
+//! fact are given as footnotes. The pages link to each other by relative paths,
+//! so they should be published together in one directory, along with the index
+//! page which lists every individual. Markdown pages start with front matter
+//! giving their title.
+
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{
+    graph::Person,
+    normalize::EVENT_TAGS,
+    reader::{Sourced, records::RawRecord},
+    schemas::tags::tag_description,
+};
+
+/// How the pages are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PageFormat {
+    /// Markdown, with the citations as footnotes.
+    #[default]
+    Markdown,
+    /// Standalone HTML documents.
+    Html,
+}
+
+impl PageFormat {
+    /// The extension of the file names of the pages.
+    pub fn extension(self) -> &'static str {
+        match self {
+            PageFormat::Markdown => "md",
+            PageFormat::Html => "html",
+        }
+    }
+}
+
+/// A page of the site.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    /// The file name of the page, relative to the directory of the site.
+    pub file_name: String,
+    pub content: String,
+}
+
+/// Writes the page of each individual, in the order they appear in the file,
+/// followed by the index page (`index.md` or `index.html`).
+pub fn pages(records: &[Sourced<RawRecord>], format: PageFormat) -> Vec<Page> {
+    let site = Site::new(records, format);
+    let mut pages = Vec::from_iter(site.individuals.iter().map(|&(xref, record)| Page {
+        file_name: site.file_name(xref),
+        content: site.individual_page(xref, record),
+    }));
+
+    pages.push(Page {
+        file_name: format!("index.{}", format.extension()),
+        content: site.index_page(),
+    });
+
+    pages
+}
+
+struct Site<'r, 'i> {
+    format: PageFormat,
+    /// The records with xrefs.
+    index: HashMap<&'i str, &'r RawRecord<'i>>,
+    /// The individuals, in file order.
+    individuals: Vec<(&'i str, &'r RawRecord<'i>)>,
+    /// The families in which each individual is a partner, in file order.
+    partner_in: HashMap<&'i str, Vec<&'r RawRecord<'i>>>,
+    /// The families in which each individual is a child, in file order.
+    child_in: HashMap<&'i str, Vec<&'r RawRecord<'i>>>,
+}
+
+impl<'r, 'i> Site<'r, 'i> {
+    fn new(records: &'r [Sourced<RawRecord<'i>>], format: PageFormat) -> Self {
+        let mut site = Self {
+            format,
+            index: HashMap::new(),
+            individuals: Vec::new(),
+            partner_in: HashMap::new(),
+            child_in: HashMap::new(),
+        };
+
+        for record in records {
+            let Some(xref) = &record.line.xref else {
+                continue;
+            };
+
+            let record = &record.sourced_value;
+            site.index.insert(xref.sourced_value, record);
+            match record.line.tag.as_str() {
+                "INDI" => site.individuals.push((xref.sourced_value, record)),
+                "FAM" => {
+                    for partner in partners(record) {
+                        site.partner_in.entry(partner).or_default().push(record);
+                    }
+
+                    for child in record.subrecords("CHIL").filter_map(RawRecord::pointer) {
+                        site.child_in.entry(child).or_default().push(record);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        site
+    }
+
+    /// The file name of the page of the individual. Any characters of the xref
+    /// other than uppercase letters, digits, and `-` are escaped as `_` followed
+    /// by the hex of each of their bytes, so different xrefs always have different
+    /// names (even on case-insensitive file systems), and no individual’s page
+    /// can be named `index`.
+    fn file_name(&self, xref: &str) -> String {
+        let mut stem = String::with_capacity(xref.len());
+        for b in xref.bytes() {
+            match b {
+                b'A'..=b'Z' | b'0'..=b'9' | b'-' => stem.push(char::from(b)),
+                _ => _ = write!(stem, "_{b:02X}"),
+            }
+        }
+
+        format!("{stem}.{}", self.format.extension())
+    }
+
+    fn individual(&self, xref: &str) -> Option<&'r RawRecord<'i>> {
+        self.index
+            .get(xref)
+            .copied()
+            .filter(|record| record.line.tag.as_str() == "INDI")
+    }
+
+    fn name(&self, xref: &'i str) -> String {
+        self.individual(xref)
+            .and_then(|record| Person::new(xref, record).name)
+            .unwrap_or_else(|| "(unnamed)".to_string())
+    }
+
+    /// The name of the individual, linking to their page.
+    fn link(&self, xref: &'i str) -> String {
+        if self.individual(xref).is_none() {
+            return self.escape(&format!("@{xref}@"));
+        }
+
+        let name = self.escape(&self.name(xref));
+        let file_name = self.file_name(xref);
+        match self.format {
+            PageFormat::Markdown => format!("[{name}]({file_name})"),
+            PageFormat::Html => format!("<a href=\"{file_name}\">{name}</a>"),
+        }
+    }
+
+    fn escape(&self, text: &str) -> String {
+        escape(self.format, text)
+    }
+
+    fn individual_page(&self, xref: &'i str, record: &RawRecord<'i>) -> String {
+        let mut page = PageWriter::new(self.format, &self.name(xref));
+
+        let facts = self.facts(&mut page, record);
+        if !facts.is_empty() {
+            page.heading(2, "Facts");
+            page.list(&facts);
+        }
+
+        let parent_families = self.child_in.get(xref).map_or(&[][..], Vec::as_slice);
+        let partner_families = self.partner_in.get(xref).map_or(&[][..], Vec::as_slice);
+        if !parent_families.is_empty() || !partner_families.is_empty() {
+            page.heading(2, "Family");
+        }
+
+        let parents = Vec::from_iter(
+            parent_families
+                .iter()
+                .flat_map(|family| partners(family))
+                .map(|parent| self.link(parent)),
+        );
+        if !parents.is_empty() {
+            page.heading(3, "Parents");
+            page.list(&parents);
+        }
+
+        let siblings = Vec::from_iter(
+            parent_families
+                .iter()
+                .flat_map(|family| family.subrecords("CHIL").filter_map(RawRecord::pointer))
+                .filter(|sibling| *sibling != xref)
+                .map(|sibling| self.link(sibling)),
+        );
+        if !siblings.is_empty() {
+            page.heading(3, "Siblings");
+            page.list(&siblings);
+        }
+
+        for family in partner_families {
+            match partners(family).find(|partner| *partner != xref) {
+                Some(spouse) => page.heading(3, &format!("Family with {}", self.link(spouse))),
+                None => page.heading(3, "Family"),
+            }
+
+            let facts = self.facts(&mut page, family);
+            page.list(&facts);
+
+            let children = Vec::from_iter(
+                family
+                    .subrecords("CHIL")
+                    .filter_map(RawRecord::pointer)
+                    .map(|child| self.link(child)),
+            );
+            if !children.is_empty() {
+                page.heading(4, "Children");
+                page.list(&children);
+            }
+        }
+
+        let media = self.media(record);
+        if !media.is_empty() {
+            page.heading(2, "Media");
+            page.list(&media);
+        }
+
+        page.finish()
+    }
+
+    /// Describes each event and attribute of the record, with the markers of
+    /// their citations.
+    fn facts(&self, page: &mut PageWriter, record: &RawRecord) -> Vec<String> {
+        let mut facts = Vec::new();
+        for event in &record.records {
+            let tag = event.line.tag.as_str();
+            if !EVENT_TAGS.contains(&tag) {
+                continue;
+            }
+
+            // generic events and facts are labelled with their type
+            let label = event
+                .subrecords("TYPE")
+                .find_map(RawRecord::string_value)
+                .filter(|_| matches!(tag, "EVEN" | "FACT"))
+                .or_else(|| tag_description(tag).and_then(|d| d.split(':').next()))
+                .unwrap_or(tag);
+
+            let details = Vec::from_iter(
+                event
+                    .string_value()
+                    .filter(|value| *value != "Y")
+                    .into_iter()
+                    .chain(event.subrecords("DATE").find_map(RawRecord::string_value))
+                    .chain(event.subrecords("PLAC").find_map(RawRecord::string_value)),
+            );
+
+            let mut fact = self.escape(label);
+            if !details.is_empty() {
+                _ = write!(fact, ": {}", self.escape(&details.join(", ")));
+            }
+
+            for citation in event.subrecords("SOUR") {
+                let text = self.citation(citation);
+                let number = page.footnote(text);
+                match self.format {
+                    PageFormat::Markdown => _ = write!(fact, "[^{number}]"),
+                    PageFormat::Html => {
+                        _ = write!(fact, "<sup><a href=\"#source-{number}\">{number}</a></sup>")
+                    }
+                }
+            }
+
+            facts.push(fact);
+        }
+
+        facts
+    }
+
+    /// The text of the footnote for the citation: the title of the source,
+    /// followed by the page.
+    fn citation(&self, citation: &RawRecord) -> String {
+        let mut text = match citation.pointer() {
+            Some(source) => self
+                .index
+                .get(source)
+                .and_then(|source| source.subrecords("TITL").find_map(RawRecord::string_value))
+                .map_or_else(|| format!("@{source}@"), str::to_string),
+            None => citation.string_value().unwrap_or_default().to_string(),
+        };
+
+        if let Some(page) = citation
+            .subrecords("PAGE")
+            .find_map(RawRecord::string_value)
+        {
+            _ = write!(text, ", {page}");
+        }
+
+        text
+    }
+
+    /// Links to the files of the multimedia objects of the record, whether they
+    /// are given inline or as pointers to `OBJE` records.
+    fn media(&self, record: &RawRecord) -> Vec<String> {
+        let mut media = Vec::new();
+        for object in record.subrecords("OBJE") {
+            let object = match object.pointer() {
+                Some(xref) => match self.index.get(xref) {
+                    Some(object) => *object,
+                    None => continue,
+                },
+                None => object,
+            };
+
+            for file in object.subrecords("FILE") {
+                let Some(path) = file.string_value() else {
+                    continue;
+                };
+
+                let title = file
+                    .subrecords("TITL")
+                    .chain(object.subrecords("TITL"))
+                    .find_map(RawRecord::string_value)
+                    .unwrap_or(path);
+
+                let Some(url) = media_url(path) else {
+                    // not something we can safely link to, so just show it
+                    media.push(if title == path {
+                        self.escape(path)
+                    } else {
+                        format!("{} ({})", self.escape(title), self.escape(path))
+                    });
+                    continue;
+                };
+
+                let title = self.escape(title);
+                media.push(match self.format {
+                    PageFormat::Markdown => format!("[{title}]({url})"),
+                    PageFormat::Html => format!("<a href=\"{}\">{title}</a>", self.escape(&url)),
+                });
+            }
+        }
+
+        media
+    }
+
+    /// Lists every individual, with their years of birth and death.
+    fn index_page(&self) -> String {
+        let mut page = PageWriter::new(self.format, "Individuals");
+        let entries = Vec::from_iter(self.individuals.iter().map(|&(xref, record)| {
+            let person = Person::new(xref, record);
+            let mut entry = self.link(xref);
+            match (person.birth_year, person.death_year) {
+                (None, None) => {}
+                (birth, death) => {
+                    let year = |year: Option<i32>| year.map(|y| y.to_string()).unwrap_or_default();
+                    _ = write!(entry, " ({}–{})", year(birth), year(death));
+                }
+            }
+
+            entry
+        }));
+
+        page.list(&entries);
+        page.finish()
+    }
+}
+
+/// The partners (`HUSB` and `WIFE`) of the family.
+fn partners<'r, 'i>(family: &'r RawRecord<'i>) -> impl Iterator<Item = &'i str> + use<'r, 'i> {
+    ["HUSB", "WIFE"]
+        .into_iter()
+        .filter_map(|tag| family.subrecords(tag).find_map(RawRecord::pointer))
+}
+
+/// The URL to link to for the file of a multimedia object, if it is a relative
+/// path or an `http` or `https` URL. Anything else (other schemes, or absolute
+/// paths on the machine the file was made on) is not linked to.
+///
+/// Characters which could end the link early or are not allowed in URLs are
+/// percent-encoded, so the result can be used as-is in Markdown or HTML.
+fn media_url(path: &str) -> Option<String> {
+    let scheme_end = path.find([':', '/', '?', '#']);
+    let allowed = match scheme_end {
+        Some(ix) if path[ix..].starts_with(':') => {
+            let scheme = &path[..ix];
+            scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+        }
+        _ => !path.starts_with(['/', '\\']),
+    };
+
+    if !allowed || path.is_empty() {
+        return None;
+    }
+
+    let mut url = String::with_capacity(path.len());
+    let bytes = path.as_bytes();
+    for (ix, &b) in bytes.iter().enumerate() {
+        let escaped = b == b'%'
+            && bytes
+                .get(ix + 1..ix + 3)
+                .is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit));
+        if b.is_ascii_alphanumeric() || b"-._~/?#&=+;,:@!$".contains(&b) || escaped {
+            url.push(char::from(b));
+        } else {
+            _ = write!(url, "%{b:02X}");
+        }
+    }
+
+    Some(url)
+}
+
+/// Escapes the text so that it is shown as-is.
+fn escape(format: PageFormat, text: &str) -> String {
+    match format {
+        PageFormat::Markdown => {
+            let mut result = String::with_capacity(text.len());
+            for c in text.chars() {
+                if matches!(c, '\\' | '*' | '_' | '[' | ']' | '<' | '>' | '`') {
+                    result.push('\\');
+                }
+
+                result.push(c);
+            }
+
+            result
+        }
+        PageFormat::Html => text
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;"),
+    }
+}
+
+/// Writes the blocks of a page. The text given to it must already be escaped,
+/// apart from the title and the footnotes.
+struct PageWriter {
+    format: PageFormat,
+    output: String,
+    /// The text of each footnote, in order.
+    footnotes: Vec<String>,
+}
+
+impl PageWriter {
+    fn new(format: PageFormat, title: &str) -> Self {
+        let mut output = String::new();
+        match format {
+            PageFormat::Markdown => {
+                let quoted = title.replace('\\', "\\\\").replace('"', "\\\"");
+                _ = write!(output, "---\ntitle: \"{quoted}\"\n---\n");
+            }
+            PageFormat::Html => {
+                _ = write!(
+                    output,
+                    "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+                    <title>{}</title>\n</head>\n<body>\n",
+                    escape(format, title),
+                );
+            }
+        }
+
+        let mut writer = Self { format, output, footnotes: Vec::new() };
+        writer.heading(1, &escape(format, title));
+        writer
+    }
+
+    fn heading(&mut self, level: usize, text: &str) {
+        match self.format {
+            PageFormat::Markdown => {
+                _ = write!(self.output, "\n{} {text}\n", "#".repeat(level));
+            }
+            PageFormat::Html => _ = writeln!(self.output, "<h{level}>{text}</h{level}>"),
+        }
+    }
+
+    fn list(&mut self, items: &[String]) {
+        if items.is_empty() {
+            return;
+        }
+
+        match self.format {
+            PageFormat::Markdown => {
+                _ = writeln!(self.output);
+                for item in items {
+                    _ = writeln!(self.output, "- {item}");
+                }
+            }
+            PageFormat::Html => {
+                _ = writeln!(self.output, "<ul>");
+                for item in items {
+                    _ = writeln!(self.output, "<li>{item}</li>");
+                }
+
+                _ = writeln!(self.output, "</ul>");
+            }
+        }
+    }
+
+    /// The number of the footnote, adding it if it is new.
+    fn footnote(&mut self, text: String) -> usize {
+        match self.footnotes.iter().position(|footnote| *footnote == text) {
+            Some(ix) => ix + 1,
+            None => {
+                self.footnotes.push(text);
+                self.footnotes.len()
+            }
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if !self.footnotes.is_empty() {
+            match self.format {
+                PageFormat::Markdown => _ = writeln!(self.output),
+                PageFormat::Html => _ = write!(self.output, "<h2>Sources</h2>\n<ol>\n"),
+            }
+
+            for (ix, footnote) in self.footnotes.iter().enumerate() {
+                let footnote = escape(self.format, footnote);
+                match self.format {
+                    PageFormat::Markdown => _ = writeln!(self.output, "[^{}]: {footnote}", ix + 1),
+                    PageFormat::Html => {
+                        _ = writeln!(self.output, "<li id=\"source-{}\">{footnote}</li>", ix + 1)
+                    }
+                }
+            }
+
+            if self.format == PageFormat::Html {
+                _ = writeln!(self.output, "</ol>");
+            }
+        }
+
+        if self.format == PageFormat::Html {
+            _ = write!(self.output, "</body>\n</html>\n");
+        }
+
+        self.output
+    }
+}
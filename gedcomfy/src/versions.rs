@@ -22,6 +22,31 @@ pub(crate) struct FileVersion {
     patch: u8,
 }
 
+impl FileVersion {
+    /// The legacy version of GEDCOM that this is, if it predates GEDCOM 5.x.
+    pub(crate) fn legacy(&self) -> Option<LegacyVersion> {
+        match self.major {
+            0..=2 => Some(LegacyVersion::V2),
+            3 => Some(LegacyVersion::V3),
+            4 => Some(LegacyVersion::V4),
+            _ => None,
+        }
+    }
+}
+
+/// A version of GEDCOM from before 5.x.
+///
+/// These are not supported, but are detected so that a helpful error can be reported.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, derive_more::Display)]
+pub enum LegacyVersion {
+    #[display("2.x")]
+    V2,
+    #[display("3.0")]
+    V3,
+    #[display("4.x")]
+    V4,
+}
+
 #[derive(thiserror::Error, derive_more::Display, Debug, miette::Diagnostic)]
 #[display("GEDCOM version {version} is not supported by the `gedcomfy` library")]
 pub struct UnsupportedGEDCOMVersionError {
@@ -50,6 +75,16 @@ pub enum KnownVersion {
     V7_0,
 }
 
+impl KnownVersion {
+    /// Every version which can be read.
+    pub const ALL: &'static [KnownVersion] = &[
+        KnownVersion::V5_5,
+        KnownVersion::V5_5_1,
+        KnownVersion::V5_5_5,
+        KnownVersion::V7_0,
+    ];
+}
+
 impl From<KnownVersion> for FileVersion {
     fn from(version: KnownVersion) -> Self {
         match version {
@@ -132,7 +167,13 @@ impl MaybeSourced<KnownVersion> {
             KnownVersion::V5_5 | // TODO: this is kinda fake
             KnownVersion::V5_5_1 |
             KnownVersion::V5_5_5 => {
-                let encoding = head.subrecord_optional("CHAR").expect("TODO better error");
+                let Some(encoding) = head.subrecord_optional("CHAR") else {
+                    return Err(EncodingError::MissingEncoding {
+                        version: self.value,
+                        span: head.line.span,
+                    });
+                };
+
                 let line_data = match encoding.line.value {
                     Sourced{ sourced_value: LineValue::None | LineValue::Ptr(_), ..} =>
                         return Err(EncodingError::InvalidHeader{}),
@@ -201,20 +242,25 @@ impl MaybeSourced<KnownVersion> {
                                 encoding_span: line_data.span,
                                 assumed_version: version})?;
                         } else {
-                            // error out, the user forced an incompatible version
-                            todo!()
+                            // the user forced an incompatible version
+                            return Err(EncodingError::VersionEncodingNotPermitted {
+                                version: self.value,
+                                version_span: None,
+                                encoding,
+                                encoding_span: line_data.span,
+                            });
                         }
                         tracing::debug!(version = %version, "updating version because of encoding");
                         self.value = version;
                         self.span = None;
                     }
                     EncodingSupport::NotPermitted => {
-                        if self.span.is_none() {
-                            todo!("user forced an incompatible version")
-                        } else {
-                            todo!("file encoding is not permitted by the version")
-                        }
-
+                        return Err(EncodingError::VersionEncodingNotPermitted {
+                            version: self.value,
+                            version_span: self.span,
+                            encoding,
+                            encoding_span: line_data.span,
+                        });
                     }
                 }
 
@@ -0,0 +1,414 @@
+//! Writing GEDCOM lines, following the conventions expected by the program
+//! which will read them.
+//!
+//! Most programs accept any line terminator and a trailing line break, but some
+//! older ones require `CRLF`, no line break after the trailer, or the GEDCOM 5.5
+//! rule that every `@` in a value is doubled. An [`OutputProfile`] names a set of
+//! [`WriteOptions`] for such programs, which can then be adjusted further:
+//!
+//! ```
+//! # use gedcomfy::writer::{LineTerminator, OutputProfile, WriteOptions};
+//! let options = OutputProfile::Legacy.options().final_newline(true);
+//! assert_eq!(options.line_terminator, LineTerminator::CrLf);
+//! ```
+
+use std::fmt::Write;
+
+/// The longest line allowed by GEDCOM 5.5.1, in characters.
+const MAX_LINE_LENGTH: usize = 255;
+
+/// The characters written at the end of each line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineTerminator {
+    #[default]
+    Lf,
+    CrLf,
+    Cr,
+}
+
+impl LineTerminator {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineTerminator::Lf => "\n",
+            LineTerminator::CrLf => "\r\n",
+            LineTerminator::Cr => "\r",
+        }
+    }
+}
+
+/// How the xref delimiter (`@`) is escaped when it appears in a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AtSignEscaping {
+    /// Only a leading `@` is doubled, as in GEDCOM 5.5.1 and 7.0.
+    #[default]
+    Leading,
+    /// Every `@` is doubled, as in GEDCOM 5.5.
+    All,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteOptions {
+    pub line_terminator: LineTerminator,
+    pub final_newline: bool,
+    pub at_sign_escaping: AtSignEscaping,
+    pub max_line_length: Option<usize>,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        OutputProfile::default().options()
+    }
+}
+
+impl WriteOptions {
+    pub fn line_terminator(self, line_terminator: LineTerminator) -> Self {
+        Self { line_terminator, ..self }
+    }
+
+    /// Whether the last line (the trailer) is followed by a line terminator.
+    pub fn final_newline(self, final_newline: bool) -> Self {
+        Self { final_newline, ..self }
+    }
+
+    pub fn at_sign_escaping(self, at_sign_escaping: AtSignEscaping) -> Self {
+        Self { at_sign_escaping, ..self }
+    }
+
+    /// The longest a line written by [`GedcomWriter::write_text`] can be, in characters
+    /// (not including the line terminator), before it is split using `CONC` records.
+    /// This is 255 by default, as required by GEDCOM 5.5.1; `None` never splits lines.
+    pub fn max_line_length(self, max_line_length: impl Into<Option<usize>>) -> Self {
+        Self { max_line_length: max_line_length.into(), ..self }
+    }
+}
+
+/// A named set of [`WriteOptions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputProfile {
+    /// `LF` line terminators, a final line break, and only leading `@` doubled.
+    #[default]
+    Standard,
+    /// For older programs: `CRLF` line terminators, no final line break,
+    /// and every `@` doubled.
+    Legacy,
+}
+
+impl OutputProfile {
+    pub fn options(self) -> WriteOptions {
+        match self {
+            OutputProfile::Standard => WriteOptions {
+                line_terminator: LineTerminator::Lf,
+                final_newline: true,
+                at_sign_escaping: AtSignEscaping::Leading,
+                max_line_length: Some(MAX_LINE_LENGTH),
+            },
+            OutputProfile::Legacy => WriteOptions {
+                line_terminator: LineTerminator::CrLf,
+                final_newline: false,
+                at_sign_escaping: AtSignEscaping::All,
+                max_line_length: Some(MAX_LINE_LENGTH),
+            },
+        }
+    }
+}
+
+/// The value of a line being written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineText<'a> {
+    /// A pointer to the xref, or `@VOID@` if there is none.
+    Pointer(Option<&'a str>),
+    /// A value, which is escaped as needed.
+    Text(&'a str),
+}
+
+/// Writes GEDCOM lines into a string.
+#[derive(Debug, Default)]
+pub struct GedcomWriter {
+    options: WriteOptions,
+    output: String,
+}
+
+impl GedcomWriter {
+    pub fn new(options: WriteOptions) -> Self {
+        Self { options, output: String::new() }
+    }
+
+    /// Writes a line. The xref is given without its surrounding `@`.
+    ///
+    /// Text values are written as they are, apart from escaping; to write
+    /// text which may be long or contain line breaks, use [`write_text`](Self::write_text).
+    pub fn write_line(
+        &mut self,
+        level: usize,
+        xref: Option<&str>,
+        tag: &str,
+        value: Option<LineText>,
+    ) {
+        self.write_prefix(level, xref, tag);
+
+        match value {
+            None => {}
+            Some(LineText::Pointer(Some(xref))) => _ = write!(self.output, " @{xref}@"),
+            Some(LineText::Pointer(None)) => _ = write!(self.output, " @VOID@"),
+            Some(LineText::Text(value)) => {
+                self.output.push(' ');
+                self.write_value(value, true);
+            }
+        }
+
+        self.output.push_str(self.options.line_terminator.as_str());
+    }
+
+    /// Writes a line with a text value, continuing it with a `CONT` record for each
+    /// line break, and with `CONC` records wherever a line would otherwise be longer
+    /// than the [maximum length](WriteOptions::max_line_length).
+    ///
+    /// Lines are not split next to a space, since some programs trim the values of
+    /// `CONC` records, unless there is no other way to keep within the maximum length.
+    pub fn write_text(&mut self, level: usize, xref: Option<&str>, tag: &str, text: &str) {
+        let text = text.replace("\r\n", "\n");
+        let mut lines = text.split(['\r', '\n']);
+
+        // UNWRAP: split always returns at least one part
+        self.write_segments(level, xref, tag, lines.next().unwrap(), level + 1);
+        for line in lines {
+            self.write_segments(level + 1, None, "CONT", line, level + 1);
+        }
+    }
+
+    /// Writes a line, and as many `CONC` records (at `conc_level`)
+    /// as are needed for the rest of its value.
+    fn write_segments(
+        &mut self,
+        level: usize,
+        xref: Option<&str>,
+        tag: &str,
+        value: &str,
+        conc_level: usize,
+    ) {
+        let (mut line_level, mut xref, mut tag) = (level, xref, tag);
+        let mut rest = value;
+        loop {
+            let first = tag != "CONC";
+            let end = match self.options.max_line_length {
+                Some(max) => {
+                    let prefix = line_level.to_string().len()
+                        + xref.map_or(0, |xref| xref.chars().count() + 3)
+                        + tag.chars().count()
+                        + 2;
+                    self.split_point(rest, max.saturating_sub(prefix), first)
+                }
+                None => rest.len(),
+            };
+
+            let (segment, remainder) = rest.split_at(end);
+            self.write_prefix(line_level, xref, tag);
+            // an empty value is only left out of continuation lines
+            if !segment.is_empty() || !matches!(tag, "CONT" | "CONC") {
+                self.output.push(' ');
+                self.write_value(segment, first);
+            }
+
+            self.output.push_str(self.options.line_terminator.as_str());
+
+            if remainder.is_empty() {
+                return;
+            }
+
+            rest = remainder;
+            (line_level, xref, tag) = (conc_level, None, "CONC");
+        }
+    }
+
+    /// Finds where to split the value so that the first part (once escaped)
+    /// fits into `room` characters, returning its length in bytes.
+    fn split_point(&self, value: &str, room: usize, allow_escape: bool) -> usize {
+        let mut escape = if allow_escape {
+            escape_length(value)
+        } else {
+            0
+        };
+
+        // an escape which does not fit is split up, so it is written as text
+        if value[..escape].chars().count() > room {
+            escape = 0;
+        }
+        let mut width = 0;
+        let mut fits = None;
+        let mut best = None;
+        let mut chars = value.char_indices().peekable();
+        while let Some((ix, c)) = chars.next() {
+            let doubled = c == '@'
+                && ix >= escape
+                && (ix == 0 || self.options.at_sign_escaping == AtSignEscaping::All);
+            width += if doubled { 2 } else { 1 };
+            if width > room {
+                break;
+            }
+
+            let end = ix + c.len_utf8();
+            match chars.peek() {
+                None => return end,
+                Some(&(_, next)) => {
+                    fits = Some(end);
+                    if end >= escape && c != ' ' && next != ' ' {
+                        best = Some(end);
+                    }
+                }
+            }
+        }
+
+        // at least one character must be written, to make progress
+        best.or(fits)
+            .unwrap_or_else(|| value.chars().next().map_or(0, char::len_utf8))
+    }
+
+    fn write_prefix(&mut self, level: usize, xref: Option<&str>, tag: &str) {
+        _ = write!(self.output, "{level}");
+
+        if let Some(xref) = xref {
+            _ = write!(self.output, " @{xref}@");
+        }
+
+        _ = write!(self.output, " {tag}");
+    }
+
+    /// Writes a value, doubling the `@`s in it as needed. If `allow_escape` is set,
+    /// an escape (`@#...@`) at the start of the value is left as it is.
+    fn write_value(&mut self, value: &str, allow_escape: bool) {
+        let escape = if allow_escape {
+            escape_length(value)
+        } else {
+            0
+        };
+        let (escape, rest) = value.split_at(escape);
+
+        self.output.push_str(escape);
+        match self.options.at_sign_escaping {
+            AtSignEscaping::Leading => {
+                if escape.is_empty() && rest.starts_with('@') {
+                    self.output.push('@');
+                }
+
+                self.output.push_str(rest);
+            }
+            AtSignEscaping::All => self.output.push_str(&rest.replace('@', "@@")),
+        }
+    }
+
+    /// Returns the text that has been written.
+    pub fn finish(mut self) -> String {
+        if !self.options.final_newline {
+            let terminator = self.options.line_terminator.as_str();
+            if self.output.ends_with(terminator) {
+                self.output.truncate(self.output.len() - terminator.len());
+            }
+        }
+
+        self.output
+    }
+}
+
+/// The length in bytes of the escape (`@#...@`) at the start of the value, if any.
+fn escape_length(value: &str) -> usize {
+    value
+        .strip_prefix("@#")
+        .and_then(|rest| rest.find('@'))
+        .map_or(0, |end| end + 3)
+}
+
+#[cfg(test)]
+mod test {
+    use bolero::check;
+
+    use super::*;
+    use crate::reader::Reader;
+
+    fn write(options: WriteOptions, values: &[&str]) -> String {
+        let mut writer = GedcomWriter::new(options);
+        writer.write_line(0, Some("N1"), "NOTE", None);
+        for value in values {
+            writer.write_line(1, None, "CONT", Some(LineText::Text(value)));
+        }
+
+        writer.write_line(0, None, "TRLR", None);
+        writer.finish()
+    }
+
+    #[test]
+    fn profiles() {
+        let values = ["@home", "a@b", "@#DJULIAN@ 1700 @x"];
+        insta::assert_debug_snapshot!(write(WriteOptions::default(), &values), @r#""0 @N1@ NOTE\n1 CONT @@home\n1 CONT a@b\n1 CONT @#DJULIAN@ 1700 @x\n0 TRLR\n""#);
+        insta::assert_debug_snapshot!(write(OutputProfile::Legacy.options(), &values), @r#""0 @N1@ NOTE\r\n1 CONT @@home\r\n1 CONT a@@b\r\n1 CONT @#DJULIAN@ 1700 @@x\r\n0 TRLR""#);
+    }
+
+    #[test]
+    fn options() {
+        let options = WriteOptions::default()
+            .line_terminator(LineTerminator::Cr)
+            .final_newline(false);
+        insta::assert_debug_snapshot!(write(options, &[]), @r#""0 @N1@ NOTE\r0 TRLR""#);
+    }
+
+    #[test]
+    fn splits_long_lines() {
+        let mut writer = GedcomWriter::new(WriteOptions::default().max_line_length(20));
+        writer.write_text(
+            1,
+            None,
+            "NOTE",
+            "The quick brown fox jumps\nover the  lazy dog @home\n\n@#DJULIAN@ 1700",
+        );
+        insta::assert_snapshot!(writer.finish(), @r#"
+        1 NOTE The quick bro
+        2 CONC wn fox jumps
+        2 CONT over the  laz
+        2 CONC y dog @home
+        2 CONT
+        2 CONT @#DJULIAN@ 17
+        2 CONC 00
+        "#);
+    }
+
+    #[test]
+    fn split_long_escape() {
+        let mut writer = GedcomWriter::new(WriteOptions::default().max_line_length(12));
+        writer.write_text(0, None, "NOTE", "x\n@#not an escape@");
+        insta::assert_snapshot!(writer.finish(), @r#"
+        0 NOTE x
+        1 CONT @@#no
+        1 CONC t a
+        1 CONC n esc
+        1 CONC ape@
+        "#);
+    }
+
+    #[test]
+    fn split_text_reassembles() {
+        // a small alphabet, so that spaces, @s and line breaks are common
+        const CHARS: &[char] = &['a', 'é', ' ', '@', '#', '\n', '\r'];
+
+        check!()
+            .with_type::<(Vec<u8>, u8)>()
+            .for_each(|(text, max)| {
+                let text: String = text
+                    .iter()
+                    .map(|c| CHARS[usize::from(*c) % CHARS.len()])
+                    .collect();
+
+                let max = 20 + usize::from(*max) % 60;
+                let mut writer = GedcomWriter::new(WriteOptions::default().max_line_length(max));
+                writer.write_text(0, Some("N1"), "NOTE", &text);
+                writer.write_line(0, None, "TRLR", None);
+                let output = writer.finish();
+
+                assert!(output.lines().all(|line| line.chars().count() <= max));
+
+                let input = format!("0 HEAD\n1 GEDC\n2 VERS 5.5.1\n1 CHAR UTF-8\n{output}");
+                let input = input.as_str();
+                let records = Reader::default().raw_records(&input).unwrap();
+                let note = String::try_from(records.into_iter().nth(1).unwrap()).unwrap();
+                assert_eq!(note, text.replace("\r\n", "\n").replace('\r', "\n"));
+            });
+    }
+}
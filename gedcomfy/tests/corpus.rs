@@ -0,0 +1,64 @@
+//! Checks the results of reading each file in `tests/corpus` against snapshots;
+//! see `tests/corpus/README.md`.
+
+use std::path::{Path, PathBuf};
+
+use gedcomfy::reader::{Reader, from_kdl};
+use rstest::*;
+
+mod shared;
+
+#[rstest]
+fn corpus(#[files("tests/corpus/**/*.ged")] path: PathBuf) {
+    let data = std::fs::read(&path).unwrap();
+
+    // e.g. `gramps__family.ged`
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let name = path.strip_prefix(corpus).unwrap();
+    let name = name
+        .iter()
+        .map(|part| part.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("__");
+
+    insta::with_settings!({
+        // provide GEDCOM source alongside output
+        description => String::from_utf8_lossy(&data),
+        snapshot_path => "corpus/snapshots",
+        snapshot_suffix => name,
+        prepend_module_to_snapshot => false,
+    }, {
+        let reader = Reader::default();
+        let result = reader
+            .decode_borrowed(data.as_slice())
+            .and_then(|input| Ok((reader.validate(&input)?, reader.parse_kdl(&input)?)));
+
+        let (validation, kdl) = match result {
+            Ok(result) => result,
+            Err(err) => {
+                insta::assert_snapshot!("validation", shared::render(&err));
+                return;
+            }
+        };
+
+        let mut summary = format!(
+            "{:?}, {} records\n",
+            validation.validity, validation.record_count
+        );
+
+        for error in validation.errors {
+            let report = miette::Report::new(error).with_source_code(data.clone());
+            summary.push('\n');
+            summary.push_str(&shared::render(report.as_ref()));
+        }
+
+        insta::assert_snapshot!("validation", summary);
+
+        insta::assert_snapshot!("kdl", kdl);
+
+        let gedcom = from_kdl::document_to_gedcom(&kdl).unwrap();
+        let reparsed = reader.parse_kdl(&gedcom.as_str()).unwrap();
+        assert_eq!(kdl.to_string(), reparsed.to_string());
+        insta::assert_snapshot!("round_trip", gedcom);
+    });
+}
@@ -0,0 +1,96 @@
+//! Checks that reading arbitrary input never panics.
+//!
+//! This runs a limited number of random inputs as part of `cargo test`;
+//! for proper fuzzing, use `cargo bolero test --profile fuzz --test fuzz_test main`.
+
+use bolero::{TypeGenerator, check};
+use gedcomfy::reader::{Reader, options::ParseOptions};
+
+/// Completely random bytes rarely get past encoding detection,
+/// so most inputs are built from lines which are likely to be valid.
+#[derive(Debug, TypeGenerator)]
+enum Input {
+    Bytes(Vec<u8>),
+    Lines {
+        version: u8,
+        charset: u8,
+        lines: Vec<Line>,
+    },
+}
+
+#[derive(Debug, TypeGenerator)]
+struct Line {
+    level: u8,
+    xref: Option<u8>,
+    tag: u8,
+    value: Option<Value>,
+    line_break: u8,
+}
+
+#[derive(Debug, TypeGenerator)]
+enum Value {
+    Pointer(u8),
+    Text(String),
+}
+
+const TAGS: &[&str] = &[
+    "HEAD", "TRLR", "INDI", "FAM", "SOUR", "NOTE", "NAME", "BIRT", "DATE", "PLAC", "CONT", "CONC",
+    "GEDC", "VERS", "CHAR", "SUBM", "FAMC", "HUSB", "_USER",
+];
+
+const VERSIONS: &[&str] = &["5.5", "5.5.1", "5.5.5", "7.0", "", "x"];
+
+const CHARSETS: &[&str] = &["UTF-8", "ANSEL", "ASCII", "UNICODE", "ANSI", ""];
+
+impl Input {
+    fn to_bytes(&self) -> Vec<u8> {
+        let (version, charset, lines) = match self {
+            Input::Bytes(bytes) => return bytes.clone(),
+            Input::Lines { version, charset, lines } => (version, charset, lines),
+        };
+
+        let version = VERSIONS[usize::from(*version) % VERSIONS.len()];
+        let charset = CHARSETS[usize::from(*charset) % CHARSETS.len()];
+        let mut output = format!("0 HEAD\n1 GEDC\n2 VERS {version}\n1 CHAR {charset}\n");
+        for line in lines {
+            output.push_str(&(line.level % 8).to_string());
+            if let Some(xref) = line.xref {
+                output.push_str(&format!(" @X{xref}@"));
+            }
+
+            output.push(' ');
+            output.push_str(TAGS[usize::from(line.tag) % TAGS.len()]);
+            match &line.value {
+                Some(Value::Pointer(xref)) => output.push_str(&format!(" @X{xref}@")),
+                Some(Value::Text(text)) => {
+                    output.push(' ');
+                    output.push_str(text);
+                }
+                None => {}
+            }
+
+            output.push_str(["\n", "\r\n", "\r"][usize::from(line.line_break) % 3]);
+        }
+
+        output.into_bytes()
+    }
+}
+
+fn main() {
+    check!().with_type::<Input>().for_each(|input| {
+        let input = input.to_bytes();
+        let repairing = ParseOptions::default()
+            .repair_line_breaks(true)
+            .recover_invalid_levels(true)
+            .skip_repeated_bom(true)
+            .trim_trailing_nuls(true);
+
+        for reader in [Reader::default(), Reader::with_options(repairing)] {
+            if let Ok(decoded) = reader.decode_borrowed(&input) {
+                _ = reader.raw_records(&decoded);
+                _ = reader.validate(&decoded);
+                _ = reader.parse(&decoded);
+            }
+        }
+    });
+}
@@ -1,5 +1,7 @@
 // cSpell:ignore GEDC VERS xref
-use gedcomfy::reader::{Reader, ReaderError, WithSourceCode};
+use gedcomfy::reader::{
+    Reader, ReaderError, WithSourceCode, encodings::Encoding, options::ParseOptions,
+};
 use indoc::indoc;
 use kdl::KdlDocument;
 
@@ -260,6 +262,70 @@ fn bad_skipped_level() {
     ");
 }
 
+#[test]
+fn recover_invalid_levels() {
+    let input: &[u8] = indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR ASCII
+        0 @I1@ INDI
+        2 NAME John /Smith/
+        1 BIRT
+        3 DATE 1900
+    "};
+
+    let reader = Reader::with_options(ParseOptions::default().recover_invalid_levels(true));
+    let decoded = reader.decode_borrowed(input).unwrap();
+    insta::assert_snapshot!(reader.parse_kdl(&decoded).unwrap(), @r#"
+    HEAD {
+        GEDC {
+            VERS "5.5.1"
+        }
+        CHAR "ASCII"
+    }
+    INDI xref="I1" {
+        NAME "John /Smith/"
+        BIRT {
+            DATE "1900"
+        }
+    }
+    "#);
+
+    let result = reader.validate(&decoded).unwrap();
+    let errors: Vec<String> = result
+        .errors
+        .into_iter()
+        .map(|error| {
+            let report = miette::Report::new(error).with_source_code(input);
+            shared::render(report.as_ref())
+        })
+        .collect();
+
+    insta::assert_snapshot!(errors.join("\n"), @r#"
+    gedcom::record_error::invalid_child_level
+
+      × Invalid child level 2, expected 1 or less
+       ╭─[6:1]
+     5 │ 0 @I1@ INDI
+     6 │ 2 NAME John /Smith/
+       · ┬
+       · ╰── this should be less than or equal to 1
+     7 │ 1 BIRT
+       ╰────
+
+    gedcom::record_error::invalid_child_level
+
+      × Invalid child level 3, expected 2 or less
+       ╭─[8:1]
+     7 │ 1 BIRT
+     8 │ 3 DATE 1900
+       · ┬
+       · ╰── this should be less than or equal to 2
+       ╰────
+    "#);
+}
+
 #[test]
 fn bad_no_tag() {
     let input: &[u8] = indoc! {b"
@@ -366,3 +432,308 @@ fn warn_no_children_or_value() {
     TAG
     "###);
 }
+
+#[test]
+fn repair_line_breaks() {
+    let input: &[u8] = indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR ASCII
+        0 NOTE first line
+        second line
+        third line
+        1 CONT last line
+    "};
+
+    let reader = Reader::with_options(ParseOptions::default().repair_line_breaks(true));
+    let decoded = reader.decode_borrowed(input).unwrap();
+    insta::assert_snapshot!(reader.parse_kdl(&decoded).unwrap(), @r#"
+    HEAD {
+        GEDC {
+            VERS "5.5.1"
+        }
+        CHAR "ASCII"
+    }
+    NOTE "first line\nsecond line\nthird line" {
+        CONT "last line"
+    }
+    "#);
+
+    let result = reader.validate(&decoded).unwrap();
+    let warnings: Vec<String> = result
+        .errors
+        .into_iter()
+        .map(|warning| {
+            let report = miette::Report::new(warning).with_source_code(input);
+            shared::render(report.as_ref())
+        })
+        .collect();
+
+    insta::assert_snapshot!(warnings.join("\n"), @r#"
+    gedcom::parse_error::line_break_in_value
+
+      ⚠ Line break inside a value
+       ╭─[5:8]
+     4 │ 1 CHAR ASCII
+     5 │ 0 NOTE first line
+       ·        ─────┬────
+       ·             ╰── this value
+     6 │ second line
+       · ─────┬─────
+       ·      ╰── is continued on this line
+     7 │ third line
+       ╰────
+      help: the line has been joined onto the value; line breaks in values should
+            be written using CONT records
+
+    gedcom::parse_error::line_break_in_value
+
+      ⚠ Line break inside a value
+       ╭─[5:8]
+     4 │     1 CHAR ASCII
+     5 │ ╭─▶ 0 NOTE first line
+     6 │ ├─▶ second line
+       · ╰──── this value
+     7 │     third line
+       ·     ─────┬────
+       ·          ╰── is continued on this line
+     8 │     1 CONT last line
+       ╰────
+      help: the line has been joined onto the value; line breaks in values should
+            be written using CONT records
+    "#);
+}
+
+#[test]
+fn input_limits() {
+    let input: &[u8] = indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR ASCII
+        0 @I1@ INDI
+        1 BIRT
+        2 PLAC Somewhere with a long name
+    "};
+
+    let read = |options: ParseOptions| {
+        let reader = Reader::with_options(options);
+        let decoded = reader
+            .decode_borrowed(input)
+            .map_err(|e| shared::render(&e))?;
+        reader.parse_kdl(&decoded).map_err(|e| shared::render(&e))
+    };
+
+    assert!(read(ParseOptions::default()).is_ok());
+
+    let err = read(ParseOptions::default().max_level(1)).unwrap_err();
+    insta::assert_snapshot!(err, @r#"
+    gedcomfy::error
+
+      × A problem was found in the GEDCOM file
+      ╰─▶ gedcom::limit::level_too_deep
+          
+            × Level 2 is deeper than the maximum of 1
+             ╭─[3:1]
+           2 │ 1 GEDC
+           3 │ 2 VERS 5.5.1
+             · ┬
+             · ╰── this level is too deep
+           4 │ 1 CHAR ASCII
+             ╰────
+    "#);
+
+    let err = read(ParseOptions::default().max_line_length(20)).unwrap_err();
+    insta::assert_snapshot!(err, @r#"
+    gedcomfy::error
+
+      × A problem was found in the GEDCOM file
+      ╰─▶ gedcom::limit::line_too_long
+          
+            × Line is longer than the maximum of 20 bytes
+             ╭─[7:1]
+           6 │ 1 BIRT
+           7 │ 2 PLAC Somewhere with a long name
+             · ────────────────┬────────────────
+             ·                 ╰── this line is 33 bytes long
+             ╰────
+    "#);
+
+    let err = read(ParseOptions::default().max_records(6)).unwrap_err();
+    insta::assert_snapshot!(err, @r#"
+    gedcomfy::error
+
+      × A problem was found in the GEDCOM file
+      ╰─▶ gedcom::limit::too_many_records
+          
+            × The file contains more than the maximum of 6 records
+             ╭─[7:1]
+           6 │ 1 BIRT
+           7 │ 2 PLAC Somewhere with a long name
+             · ────────────────┬────────────────
+             ·                 ╰── this record is over the limit
+             ╰────
+    "#);
+
+    let err = read(ParseOptions::default().max_decoded_size(64)).unwrap_err();
+    insta::assert_snapshot!(err, @r#"
+    gedcomfy::error
+
+      × A problem was found in the GEDCOM file
+      ╰─▶ gedcom::limit::input_too_large
+          
+            × The decoded file could be larger than the maximum of 64 bytes
+            help: the file is 93 bytes in ASCII, which could decode to as many as
+                  93 bytes
+    "#);
+
+    // a file which must be transcoded is rejected before it is decoded,
+    // if it could become too large
+    let windows1252 = ParseOptions::default().force_encoding(Encoding::Windows1252);
+    assert!(read(windows1252.clone().max_decoded_size(279)).is_ok());
+    let err = read(windows1252.max_decoded_size(278)).unwrap_err();
+    insta::assert_snapshot!(err, @r#"
+    gedcomfy::error
+
+      × A problem was found in the GEDCOM file
+      ╰─▶ gedcom::limit::input_too_large
+          
+            × The decoded file could be larger than the maximum of 278 bytes
+            help: the file is 93 bytes in Windows-1252, which could decode to as
+                  many as 279 bytes
+    "#);
+}
+
+#[test]
+fn bad_legacy_versions() {
+    let v2: &[u8] = indoc! {b"
+        0 HEAD
+        1 SOUR PAF
+        0 TRLR
+    "};
+
+    let err = test(v2).unwrap_err();
+    insta::assert_snapshot!(err, @r#"
+    gedcomfy::error
+
+      × A problem was found in the GEDCOM file
+      ╰─▶ gedcom::version::too_old
+          
+            × GEDCOM 2.x files are too old to be read
+             ╭─[2:1]
+           1 │ 0 HEAD
+           2 │ 1 SOUR PAF
+             · ─────┬────
+             ·      ╰── this identifies the file as GEDCOM 2.x
+           3 │ 0 TRLR
+             ╰────
+            help: convert the file to GEDCOM 5.5.1 first; most genealogy programs
+                  (such as PAF or Gramps) can import older GEDCOM files and export
+                  them as GEDCOM 5.5.1
+    "#);
+
+    let v3: &[u8] = indoc! {b"
+        0 HEAD
+        1 SOUR PAF
+        2 VERS 2.2
+        0 TRLR
+    "};
+
+    let err = test(v3).unwrap_err();
+    insta::assert_snapshot!(err, @r#"
+    gedcomfy::error
+
+      × A problem was found in the GEDCOM file
+      ╰─▶ gedcom::version::too_old
+          
+            × GEDCOM 3.0 files are too old to be read
+             ╭─[3:1]
+           2 │ 1 SOUR PAF
+           3 │ 2 VERS 2.2
+             · ─────┬────
+             ·      ╰── this identifies the file as GEDCOM 3.0
+           4 │ 0 TRLR
+             ╰────
+            help: convert the file to GEDCOM 5.5.1 first; most genealogy programs
+                  (such as PAF or Gramps) can import older GEDCOM files and export
+                  them as GEDCOM 5.5.1
+    "#);
+
+    let v4: &[u8] = indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 4.0
+        0 TRLR
+    "};
+
+    let err = test(v4).unwrap_err();
+    insta::assert_snapshot!(err, @r#"
+    gedcomfy::error
+
+      × A problem was found in the GEDCOM file
+      ╰─▶ gedcom::version::too_old
+          
+            × GEDCOM 4.x files are too old to be read
+             ╭─[3:8]
+           2 │ 1 GEDC
+           3 │ 2 VERS 4.0
+             ·        ─┬─
+             ·         ╰── this identifies the file as GEDCOM 4.x
+           4 │ 0 TRLR
+             ╰────
+            help: convert the file to GEDCOM 5.5.1 first; most genealogy programs
+                  (such as PAF or Gramps) can import older GEDCOM files and export
+                  them as GEDCOM 5.5.1
+    "#);
+}
+
+#[test]
+fn raw_offsets() {
+    // "é" is a single byte in Windows-1252, but two bytes once decoded
+    let input: &[u8] = b"0 HEAD\n1 GEDC\n2 VERS 5.5.1\n0 @N1@ NOTE \xE9\xE9\xE9\n1 x y\n";
+
+    let read = |options: ParseOptions| {
+        let reader = Reader::with_options(options.force_encoding(Encoding::Windows1252));
+        let decoded = reader
+            .decode_borrowed(input)
+            .map_err(|e| shared::render(&e))?;
+        reader.parse_kdl(&decoded).map_err(|e| shared::render(&e))
+    };
+
+    let err = read(ParseOptions::default()).unwrap_err();
+    insta::assert_snapshot!(err, @r#"
+    gedcomfy::error
+
+      × A problem was found in the GEDCOM file
+      ╰─▶ gedcom::parse_error::invalid_tag
+          
+            × Invalid character in tag
+             ╭─[5:3]
+           4 │ 0 @N1@ NOTE ééé
+           5 │ 1 x y
+             ·   ┬
+             ·   ╰── this character is not permitted in a tag
+             ╰────
+            help: tag names must begin with either an uppercase letter or
+                  underscore, followed by letters or numbers
+    "#);
+
+    let err = read(ParseOptions::default().raw_offsets(true)).unwrap_err();
+    insta::assert_snapshot!(err, @r#"
+    gedcomfy::error
+
+      × A problem was found in the GEDCOM file
+      ╰─▶ gedcom::parse_error::invalid_tag
+          
+            × Invalid character in tag
+             ╭─[5:3]
+           4 │ 0 @N1@ NOTE ???
+           5 │ 1 x y
+             ·   ┬
+             ·   ╰── this character is not permitted in a tag
+             ╰────
+            help: tag names must begin with either an uppercase letter or
+                  underscore, followed by letters or numbers
+    "#);
+}
@@ -1,7 +1,35 @@
 // cSpell:ignore allged
-use std::path::PathBuf;
+use std::{ops::Range, path::PathBuf, sync::Arc};
 
-use gedcomfy::reader::{Reader, decoding::detect_external_encoding, input::File};
+use gedcomfy::{
+    ages::event_ages,
+    citations::{event_support, supported_only_by},
+    graph::FamilyGraph,
+    households::Households,
+    normalize::{self, XRefRenumbering},
+    reader::{
+        Reader, Sourced,
+        arena::Records,
+        decoding::detect_external_encoding,
+        from_kdl,
+        incremental::Edit,
+        input::File,
+        lines::LineValue,
+        options::{DuplicateSubrecords, ParseOptions},
+        producers::ProducerReport,
+        records::RawRecord,
+        visitors::{Annotation, RecordVisitor, Visit},
+    },
+    reports::{ReportFormat, descendants},
+    schemas::{
+        AnyFileVersion,
+        places::{Gazetteer, HistoricalJurisdictions},
+        quality::CertaintyAssessment,
+        v551::{Role, TopLevelRecord, names::NameIndex},
+    },
+    search::{Pattern, search},
+    site::{self, PageFormat},
+};
 use rstest::*;
 
 #[macro_use]
@@ -31,6 +59,33 @@ fn can_parse_allged_fully() -> miette::Result<()> {
     Ok(())
 }
 
+#[test]
+fn record_arena_matches_raw_records() -> miette::Result<()> {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("tests/external/others/allged.ged");
+
+    let reader = Reader::default();
+    let file = reader.decode_file(path)?;
+    let records = reader.raw_records(&file)?;
+    let arena = reader.record_arena(&file)?;
+
+    fn assert_same(records: &[Sourced<RawRecord>], arena: Records) {
+        let arena: Vec<_> = arena.collect();
+        assert_eq!(records.len(), arena.len());
+        for (record, arena_record) in records.iter().zip(arena) {
+            assert_eq!(record.span, arena_record.span);
+            assert_eq!(record.line.span, arena_record.line.span);
+            assert_eq!(record.line.tag, arena_record.line.tag);
+            assert_eq!(record.line.xref, arena_record.line.xref);
+            assert_eq!(record.line.value, arena_record.line.value);
+            assert_same(&record.records, arena_record.subrecords());
+        }
+    }
+
+    assert_same(&records, arena.records());
+    Ok(())
+}
+
 #[test]
 fn produces_expected_allged_tree() -> miette::Result<()> {
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -115,3 +170,2099 @@ fn assess_ged() {
     let kdl = reader.parse_kdl(&decoded).unwrap();
     insta::assert_snapshot!(kdl);
 }
+
+#[test]
+fn json_export() {
+    let reader = Reader::default();
+    let data = b"0 HEAD\n1 GEDC\n2 VERS 5.5.1\n1 CHAR UTF-8\n0 @I1@ INDI\n1 NAME John /Smith/\n1 FAMS @F1@\n0 TRLR\n";
+    let decoded = reader.decode_borrowed(data.as_slice()).unwrap();
+    let json = reader.parse_json(&decoded).unwrap();
+    insta::assert_snapshot!(json, @r#"
+    [{"tag":"HEAD","records":[{"tag":"GEDC","records":[{"tag":"VERS","value":"5.5.1"}]},{"tag":"CHAR","value":"UTF-8"}]},{"tag":"INDI","xref":"I1","records":[{"tag":"NAME","value":"John /Smith/"},{"tag":"FAMS","pointer":"F1"}]},{"tag":"TRLR"}]
+    "#);
+}
+
+/// Rewrites `_MAIL` to `EMAIL` (as some producers use the former), and drops
+/// `_PLAC` records, noting each one that it drops.
+struct Normalizer;
+
+impl RecordVisitor for Normalizer {
+    fn visit(
+        &self,
+        record: &mut Sourced<RawRecord<'_>>,
+        annotations: &mut Vec<Annotation>,
+    ) -> Visit {
+        if record.line.tag.as_str() == "_PLAC" {
+            annotations.push(Annotation::new("dropped place record", record.span));
+            return Visit::Drop;
+        }
+
+        for subrecord in &mut record.sourced_value.records {
+            if subrecord.line.tag.as_str() == "_MAIL" {
+                let email = ascii::AsciiStr::from_ascii("EMAIL").unwrap();
+                subrecord.sourced_value.line.sourced_value.set_tag(email);
+            }
+        }
+
+        Visit::Keep
+    }
+}
+
+#[test]
+fn record_visitors() {
+    let reader = Reader::with_options(ParseOptions::default().add_visitor(Arc::new(Normalizer)));
+    let data = b"0 HEAD\n1 GEDC\n2 VERS 5.5.1\n1 CHAR UTF-8\n0 @P1@ _PLAC Paris\n0 @I1@ INDI\n1 _MAIL j@example.com\n0 TRLR\n";
+    let decoded = reader.decode_borrowed(data.as_slice()).unwrap();
+    let json = reader.parse_json(&decoded).unwrap();
+    insta::assert_snapshot!(json, @r#"
+    [{"tag":"HEAD","records":[{"tag":"GEDC","records":[{"tag":"VERS","value":"5.5.1"}]},{"tag":"CHAR","value":"UTF-8"}]},{"tag":"INDI","xref":"I1","records":[{"tag":"EMAIL","value":"j@example.com"}]},{"tag":"TRLR"}]
+    "#);
+
+    let result = reader.validate(&decoded).unwrap();
+    let advice: Vec<String> = result.errors.iter().map(ToString::to_string).collect();
+    insta::assert_snapshot!(advice.join("\n"), @"dropped place record");
+}
+
+#[test]
+fn graph_export() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @I1@ INDI
+        1 NAME John /Smith/
+        1 SEX M
+        1 BIRT
+        2 DATE ABT 1850
+        1 DEAT
+        2 DATE 3 MAR 1901
+        0 @I2@ INDI
+        1 NAME Mary /Jones & Co/
+        1 SEX F
+        0 @I3@ INDI
+        1 NAME Ada /Smith/
+        1 ASSO @I4@
+        2 RELA Godmother
+        0 @I4@ INDI
+        0 @F1@ FAM
+        1 HUSB @I1@
+        1 WIFE @I2@
+        1 CHIL @I3@
+        1 CHIL @I9@
+        0 TRLR
+    "};
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let records = reader.raw_records(&decoded).unwrap();
+    let graph = FamilyGraph::new(&records);
+    insta::assert_snapshot!(graph.to_graphml(), @r#"
+    <?xml version="1.0" encoding="UTF-8"?>
+    <graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+      <key id="name" for="node" attr.name="name" attr.type="string"/>
+      <key id="sex" for="node" attr.name="sex" attr.type="string"/>
+      <key id="birth_year" for="node" attr.name="birth_year" attr.type="int"/>
+      <key id="death_year" for="node" attr.name="death_year" attr.type="int"/>
+      <key id="type" for="edge" attr.name="type" attr.type="string"/>
+      <key id="relation" for="edge" attr.name="relation" attr.type="string"/>
+      <graph id="G" edgedefault="directed">
+        <node id="I1">
+          <data key="name">John Smith</data>
+          <data key="sex">M</data>
+          <data key="birth_year">1850</data>
+          <data key="death_year">1901</data>
+        </node>
+        <node id="I2">
+          <data key="name">Mary Jones &amp; Co</data>
+          <data key="sex">F</data>
+        </node>
+        <node id="I3">
+          <data key="name">Ada Smith</data>
+        </node>
+        <node id="I4">
+        </node>
+        <edge id="e0" source="I3" target="I4" directed="true">
+          <data key="type">association</data>
+          <data key="relation">Godmother</data>
+        </edge>
+        <edge id="e1" source="I1" target="I2" directed="false">
+          <data key="type">spouse</data>
+        </edge>
+        <edge id="e2" source="I1" target="I3" directed="true">
+          <data key="type">parent-child</data>
+        </edge>
+        <edge id="e3" source="I2" target="I3" directed="true">
+          <data key="type">parent-child</data>
+        </edge>
+      </graph>
+    </graphml>
+    "#);
+    insta::assert_snapshot!(graph.to_gexf(), @r#"
+    <?xml version="1.0" encoding="UTF-8"?>
+    <gexf xmlns="http://gexf.net/1.3" version="1.3">
+      <graph mode="static" defaultedgetype="directed">
+        <attributes class="node">
+          <attribute id="sex" title="sex" type="string"/>
+          <attribute id="birth_year" title="birth_year" type="integer"/>
+          <attribute id="death_year" title="death_year" type="integer"/>
+        </attributes>
+        <attributes class="edge">
+          <attribute id="relation" title="relation" type="string"/>
+        </attributes>
+        <nodes>
+          <node id="I1" label="John Smith">
+            <attvalues>
+              <attvalue for="sex" value="M"/>
+              <attvalue for="birth_year" value="1850"/>
+              <attvalue for="death_year" value="1901"/>
+            </attvalues>
+          </node>
+          <node id="I2" label="Mary Jones &amp; Co">
+            <attvalues>
+              <attvalue for="sex" value="F"/>
+            </attvalues>
+          </node>
+          <node id="I3" label="Ada Smith"/>
+          <node id="I4" label="I4"/>
+        </nodes>
+        <edges>
+          <edge id="0" source="I3" target="I4" type="directed" kind="association">
+            <attvalues>
+              <attvalue for="relation" value="Godmother"/>
+            </attvalues>
+          </edge>
+          <edge id="1" source="I1" target="I2" type="undirected" kind="spouse"/>
+          <edge id="2" source="I1" target="I3" type="directed" kind="parent-child"/>
+          <edge id="3" source="I2" target="I3" type="directed" kind="parent-child"/>
+        </edges>
+      </graph>
+    </gexf>
+    "#);
+}
+
+#[test]
+fn households() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @I1@ INDI
+        1 NAME John /Smith/
+        1 BIRT
+        2 DATE 1840
+        0 @I2@ INDI
+        1 NAME Mary /Jones/
+        1 BIRT
+        2 DATE 1845
+        0 @I3@ INDI
+        1 NAME Ada /Smith/
+        1 BIRT
+        2 DATE 1866
+        0 @I4@ INDI
+        1 NAME Tom /Smith/
+        1 BIRT
+        2 DATE 1870
+        1 RESI
+        2 DATE BET 1878 AND 1885
+        2 PLAC Manchester
+        0 @I5@ INDI
+        1 NAME George /Brown/
+        1 CENS
+        2 DATE 1881
+        2 PLAC York
+        0 @I6@ INDI
+        1 NAME Grace /Jones/
+        1 DEAT
+        2 DATE 1875
+        1 RESI
+        2 DATE 1881
+        2 PLAC Leeds
+        0 @I7@ INDI
+        1 NAME Walter /Lodge/
+        1 CENS
+        2 DATE 1881
+        2 PLAC Leeds
+        0 @I8@ INDI
+        1 NAME Edith /Brown/
+        1 BIRT
+        2 DATE 1890
+        0 @F1@ FAM
+        1 HUSB @I1@
+        1 WIFE @I2@
+        1 CHIL @I3@
+        1 CHIL @I4@
+        1 MARR
+        2 DATE 1865
+        1 RESI
+        2 DATE 1881
+        2 PLAC Leeds
+        0 @F2@ FAM
+        1 HUSB @I5@
+        1 WIFE @I3@
+        1 CHIL @I8@
+        1 MARR
+        2 DATE 1880
+        0 TRLR
+    "};
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let records = reader.raw_records(&decoded).unwrap();
+    let households = Households::new(&records, 1881);
+    let listing: Vec<String> = households
+        .places
+        .iter()
+        .flat_map(|(place, households)| {
+            households.iter().map(move |household| {
+                let members: Vec<String> = household
+                    .members
+                    .iter()
+                    .map(|member| {
+                        format!("{} {} {:?}", member.person.xref, member.role, member.age)
+                    })
+                    .collect();
+                format!("{place} {:?}: {}", household.family, members.join(", "))
+            })
+        })
+        .collect();
+
+    insta::assert_snapshot!(listing.join("\n"), @r#"
+    Leeds Some("F1"): I1 Husband Some(41), I2 Wife Some(36)
+    Leeds None: I7 Individual None
+    Manchester Some("F1"): I4 Child Some(11)
+    York Some("F2"): I5 Husband None, I3 Wife Some(15)
+    "#);
+}
+
+#[test]
+fn yaml_export() {
+    let reader = Reader::default();
+    let data = b"0 HEAD\n1 GEDC\n2 VERS 5.5.1\n1 CHAR UTF-8\n0 @I1@ INDI\n1 NAME John \"Jack\" /Smith/\n1 SEX N\n1 FAMS @VOID@\n0 TRLR\n";
+    let decoded = reader.decode_borrowed(data.as_slice()).unwrap();
+    let yaml = serde_yaml::to_string(&reader.parse_yaml(&decoded).unwrap()).unwrap();
+    insta::assert_snapshot!(yaml, @r#"
+    - tag: HEAD
+      records:
+      - tag: GEDC
+        records:
+        - tag: VERS
+          value: 5.5.1
+      - tag: CHAR
+        value: UTF-8
+    - tag: INDI
+      xref: I1
+      records:
+      - tag: NAME
+        value: John "Jack" /Smith/
+      - tag: SEX
+        value: N
+      - tag: FAMS
+        pointer: null
+    - tag: TRLR
+    "#);
+}
+
+#[test]
+fn yaml_round_trip() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @I1@ INDI
+        1 NAME Key: value # not a comment
+        1 NOTE - not a list
+        2 CONT * not an alias
+        2 CONT & not an anchor
+        2 CONT
+        1 SEX no
+        1 OCCU yes
+        1 EDUC 1e3
+        1 _NULL null
+        1 _TILDE ~
+        1 _QUOTES \"double\" and 'single'
+        1 _BRACES {not: a map} [or a list]
+        1 _TRAILING trailing space 
+        1 FAMS @VOID@
+        0 TRLR
+    "};
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let yaml = reader.parse_yaml(&decoded).unwrap();
+    let text = serde_yaml::to_string(&yaml).unwrap();
+
+    // reading the output gives the same structure as the JSON mode
+    let read: serde_json::Value = serde_yaml::from_str(&text).unwrap();
+    assert_eq!(read, reader.parse_json(&decoded).unwrap());
+    assert_eq!(
+        serde_yaml::from_str::<serde_yaml::Value>(&text).unwrap(),
+        yaml
+    );
+}
+
+#[test]
+fn toml_export() {
+    let reader = Reader::default();
+    let data = b"0 HEAD\n1 GEDC\n2 VERS 5.5.1\n1 CHAR UTF-8\n0 @I1@ INDI\n1 NAME John \"Jack\" /Smith/\n1 FAMS @F1@\n1 FAMC @VOID@\n0 TRLR\n";
+    let decoded = reader.decode_borrowed(data.as_slice()).unwrap();
+    let toml = reader.parse_toml(&decoded).unwrap();
+    insta::assert_snapshot!(toml, @r#"
+    [[records]]
+    tag = "HEAD"
+
+    [[records.records]]
+    tag = "GEDC"
+
+    [[records.records.records]]
+    tag = "VERS"
+    value = "5.5.1"
+
+    [[records.records]]
+    tag = "CHAR"
+    value = "UTF-8"
+
+    [[records]]
+    tag = "INDI"
+    xref = "I1"
+
+    [[records.records]]
+    tag = "NAME"
+    value = 'John "Jack" /Smith/'
+
+    [[records.records]]
+    tag = "FAMS"
+    pointer = "F1"
+
+    [[records.records]]
+    tag = "FAMC"
+    pointer = "VOID"
+
+    [[records]]
+    tag = "TRLR"
+    "#);
+}
+
+#[rstest]
+#[case("tests/external/others/allged.ged")]
+#[case("tests/external/others/assess.ged")]
+fn kdl_round_trip(#[case] path: &str) {
+    let reader = Reader::default();
+    let decoded = reader.decode_file(path).unwrap();
+    let kdl = reader.parse_kdl(&decoded).unwrap();
+
+    let gedcom = from_kdl::document_to_gedcom(&kdl).unwrap();
+    let reparsed = reader.parse_kdl(&gedcom.as_str()).unwrap();
+    assert_eq!(kdl.to_string(), reparsed.to_string());
+}
+
+#[test]
+fn kdl_import() {
+    let kdl = indoc::indoc! {r#"
+        INDI xref="I1" {
+            NAME "John /Smith/"
+            NOTE "@home\nmore" {
+                SOUR see=null
+            }
+        }
+    "#};
+
+    insta::assert_snapshot!(from_kdl::to_gedcom(kdl).unwrap(), @r#"
+    0 @I1@ INDI
+    1 NAME John /Smith/
+    1 NOTE @@home
+    2 CONT more
+    2 SOUR @VOID@
+    "#);
+
+    let kdl = r#"INDI xref="I1" { NAME "John" "Smith"; }"#;
+    let err = from_kdl::to_gedcom(kdl).unwrap_err();
+    let report = miette::Report::new(err).with_source_code(kdl);
+    insta::assert_snapshot!(shared::render(report.as_ref()), @r#"
+    gedcom::kdl::unexpected_entry
+
+      × Unexpected entry in KDL node
+       ╭────
+     1 │ INDI xref="I1" { NAME "John" "Smith"; }
+       ·                              ───┬───
+       ·                                 ╰── this entry cannot be converted to GEDCOM
+       ╰────
+      help: a node can have an `xref` property, and either a single string
+            argument or a `see` property
+    "#);
+}
+
+#[test]
+fn measure_phases() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 SOUR test
+        1 SUBM @SUBM@
+        1 GEDC
+        2 VERS 5.5.1
+        2 FORM LINEAGE-LINKED
+        1 CHAR UTF-8
+        0 @SUBM@ SUBM
+        1 NAME Not Given
+        0 @I1@ INDI
+        1 NAME John /Smith/
+        0 TRLR
+    "};
+
+    let metrics = reader.measure(data).unwrap();
+    assert_eq!(metrics.decode.count.as_usize(), data.len());
+    assert_eq!(metrics.line_parse.count.as_usize(), 12);
+    assert_eq!(metrics.record_build.count.as_usize(), 4);
+    assert_eq!(metrics.schema_conversion.count.as_usize(), 4);
+}
+
+#[test]
+fn scan_dir() {
+    let dir = std::env::temp_dir().join(format!("gedcomfy-scan-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+    let valid = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n2 FORM LINEAGE-LINKED\n1 CHAR UTF-8\n1 SOUR test\n1 SUBM @U@\n0 @U@ SUBM\n1 NAME Someone\n0 TRLR\n";
+    std::fs::write(dir.join("valid.ged"), valid).unwrap();
+    std::fs::write(dir.join("sub/invalid.GED"), "not GEDCOM").unwrap();
+    std::fs::write(dir.join("ignored.txt"), "not GEDCOM").unwrap();
+
+    let mut results: Vec<_> = Reader::default()
+        .scan_dir(&dir)
+        .map(|result| match result {
+            Ok(summary) => (summary.path, Some(summary.validation.validity)),
+            Err(err) => (err.path().to_owned(), None),
+        })
+        .collect();
+
+    results.sort_by(|(a, _), (b, _)| a.cmp(b));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        results,
+        vec![
+            (dir.join("sub/invalid.GED"), None),
+            (
+                dir.join("valid.ged"),
+                Some(gedcomfy::reader::Validity::Valid)
+            ),
+        ]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn scan_dir_symlink_loop() {
+    let dir = std::env::temp_dir().join(format!("gedcomfy-scan-loop-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+    std::fs::write(dir.join("sub/file.ged"), "not GEDCOM").unwrap();
+    std::os::unix::fs::symlink(&dir, dir.join("sub/parent")).unwrap();
+
+    // the link back to the parent directory is not followed again
+    let paths: Vec<_> = Reader::default()
+        .scan_dir(&dir)
+        .map(|result| result.map_or_else(|err| err.path().to_owned(), |s| s.path))
+        .collect();
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(paths, vec![dir.join("sub/file.ged")]);
+}
+
+#[cfg(feature = "gedzip")]
+#[test]
+fn gedzip_size_limit() {
+    use gedcomfy::reader::input::{FileLoadError, GedZip};
+
+    let path = std::env::temp_dir().join(format!("gedcomfy-gedzip-{}.gdz", std::process::id()));
+    let mut archive = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+    archive
+        .start_file(GedZip::GEDCOM_FILE_NAME, zip::write::SimpleFileOptions::default())
+        .unwrap();
+    std::io::Write::write_all(&mut archive, &[b'x'; 1000]).unwrap();
+    archive.finish().unwrap();
+
+    let within = GedZip::load_with_limit(path.clone(), Some(1000));
+    let exceeded = GedZip::load_with_limit(path.clone(), Some(999));
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(within.is_ok());
+    assert!(matches!(
+        exceeded,
+        Err(FileLoadError::ArchiveTooLarge { max_size: 999, .. })
+    ));
+}
+
+#[test]
+fn producer_report_unreadable() {
+    let dir = std::env::temp_dir().join(format!("gedcomfy-producers-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let broken = "0 HEAD\n1 GEDC\n2 VERS 5.5.1\n2 FORM LINEAGE-LINKED\n1 CHAR UTF-8\n1 SOUR Exporter\n0 @I1@ INDI\nx\n0 TRLR\n";
+    std::fs::write(dir.join("broken.ged"), broken).unwrap();
+    std::fs::write(dir.join("garbage.ged"), "not GEDCOM").unwrap();
+
+    // files which cannot be read are counted for their producer, if it is known
+    let mut report = ProducerReport::default();
+    for result in Reader::default().scan_dir(&dir) {
+        report.add_scan_error(&result.unwrap_err());
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let producers: Vec<_> = report
+        .producers()
+        .map(|(producer, stats)| (producer, stats.unreadable_count, stats.problems()))
+        .collect();
+
+    insta::assert_debug_snapshot!(producers, @r#"
+    [
+        (
+            None,
+            1,
+            [
+                (
+                    "Input file does not appear to be valid GEDCOM",
+                    ProblemCount {
+                        files: 1,
+                        occurrences: 1,
+                    },
+                ),
+            ],
+        ),
+        (
+            Some(
+                "Exporter",
+            ),
+            1,
+            [
+                (
+                    "gedcom::parse_error::no_tag",
+                    ProblemCount {
+                        files: 1,
+                        occurrences: 1,
+                    },
+                ),
+            ],
+        ),
+    ]
+    "#);
+}
+
+#[test]
+fn producer_report() {
+    let reader = Reader::default();
+    let files: [&[u8]; 3] = [
+        indoc::indoc! {b"
+            0 HEAD
+            1 SOUR Exporter
+            1 SUBM @NOBODY@
+            1 GEDC
+            2 VERS 5.5.1
+            2 FORM LINEAGE-LINKED
+            1 CHAR UTF-8
+            0 TRLR
+        "},
+        indoc::indoc! {b"
+            0 HEAD
+            1 SOUR Exporter
+            1 SUBM @NOBODY@
+            1 GEDC
+            2 VERS 5.5.1
+            2 FORM LINEAGE-LINKED
+            1 CHAR UTF-8
+            0 @I1@ INDI
+            1 BAPL
+            2 TEMP NOWHERE
+            0 TRLR
+        "},
+        indoc::indoc! {b"
+            0 HEAD
+            1 GEDC
+            2 VERS 5.5.1
+            2 FORM LINEAGE-LINKED
+            1 CHAR UTF-8
+            1 SUBM @U@
+            0 @U@ SUBM
+            1 NAME Someone
+            0 TRLR
+        "},
+    ];
+
+    let mut report = ProducerReport::default();
+    for data in files {
+        let decoded = reader.decode_borrowed(data).unwrap();
+        let validation = reader.validate(&decoded).unwrap();
+        report.add(reader.producer(&decoded).as_deref(), &validation);
+    }
+
+    let producers: Vec<_> = report
+        .producers()
+        .map(|(producer, stats)| (producer, stats.file_count, stats.problems()))
+        .collect();
+
+    insta::assert_debug_snapshot!(producers, @r#"
+    [
+        (
+            None,
+            1,
+            [],
+        ),
+        (
+            Some(
+                "Exporter",
+            ),
+            2,
+            [
+                (
+                    "gedcom::schema::missing_submitter",
+                    ProblemCount {
+                        files: 2,
+                        occurrences: 2,
+                    },
+                ),
+                (
+                    "gedcom::schema::unknown_temple_code",
+                    ProblemCount {
+                        files: 1,
+                        occurrences: 1,
+                    },
+                ),
+            ],
+        ),
+    ]
+    "#);
+}
+
+#[rstest]
+#[case::within_record("John", "Johnny", 2..4)]
+#[case::insert_record("0 @I3@", "0 @I4@ INDI\n1 SEX F\n0 @I3@", 3..6)]
+#[case::delete_record("0 @I2@ INDI\n1 NAME John /Doe/\n", "", 2..4)]
+#[case::change_level("0 @I3@", "1 @I3@", 3..4)]
+fn reparse_after_edit(#[case] from: &str, #[case] to: &str, #[case] reparsed: Range<usize>) {
+    let old_source = indoc::indoc! {"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        2 FORM LINEAGE-LINKED
+        1 CHAR UTF-8
+        1 SUBM @U1@
+        0 @U1@ SUBM
+        1 NAME Someone
+        0 @I1@ INDI
+        1 NAME Jane /Doe/
+        0 @I2@ INDI
+        1 NAME John /Doe/
+        0 @I3@ INDI
+        1 NAME Baby /Doe/
+        0 TRLR
+    "};
+
+    let start = old_source.find(from).unwrap();
+    let edit = Edit::new(start..start + from.len(), to.len());
+    let new_source = old_source.replacen(from, to, 1);
+    let new_source = new_source.as_str();
+
+    let reader = Reader::default();
+    let previous = reader.raw_records(&old_source).unwrap();
+    let result = reader
+        .reparse(old_source, &previous, &edit, new_source)
+        .unwrap();
+
+    // the result should be the same as reading the whole file again
+    let expected = reader.raw_records(&new_source).unwrap();
+    assert_eq!(format!("{:?}", result.records), format!("{expected:?}"));
+    assert_eq!(result.reparsed, reparsed);
+}
+
+#[test]
+fn reparse_with_visitor() {
+    let old_source = indoc::indoc! {"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @I1@ INDI
+        1 _MAIL j@example.com
+        0 @P1@ _PLAC Paris
+        0 @I2@ INDI
+        1 NAME John /Doe/
+        0 @I3@ INDI
+        1 _MAIL b@example.com
+        0 TRLR
+    "};
+
+    let start = old_source.find("John").unwrap();
+    let edit = Edit::new(start..start + 4, 6);
+    let new_source = old_source.replacen("John", "Johnny", 1);
+    let new_source = new_source.as_str();
+
+    // the visitor rewrites tags to text which is not in the source,
+    // so those records cannot simply be moved to the new source
+    let reader = Reader::with_options(ParseOptions::default().add_visitor(Arc::new(Normalizer)));
+    let previous = reader.raw_records(&old_source).unwrap();
+    let result = reader
+        .reparse(old_source, &previous, &edit, new_source)
+        .unwrap();
+
+    let expected = reader.raw_records(&new_source).unwrap();
+    assert_eq!(format!("{:?}", result.records), format!("{expected:?}"));
+}
+
+#[rstest]
+#[case::wrong_length(Edit::new(10..14, 3))]
+#[case::beyond_end(Edit::new(1000..1004, 6))]
+#[case::backwards(Edit::new(Range { start: 14, end: 10 }, 2))]
+#[case::wrong_position(Edit::new(0..4, 6))]
+fn reparse_after_inconsistent_edit(#[case] edit: Edit) {
+    let old_source = indoc::indoc! {"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @I1@ INDI
+        1 NAME John /Doe/
+        0 TRLR
+    "};
+
+    let new_source = old_source.replacen("John", "Johnny", 1);
+    let new_source = new_source.as_str();
+
+    // an edit which does not describe the change reads the whole file again
+    let reader = Reader::default();
+    let previous = reader.raw_records(&old_source).unwrap();
+    let result = reader
+        .reparse(old_source, &previous, &edit, new_source)
+        .unwrap();
+
+    let expected = reader.raw_records(&new_source).unwrap();
+    assert_eq!(format!("{:?}", result.records), format!("{expected:?}"));
+    assert_eq!(result.reparsed, 0..expected.len());
+}
+
+#[test]
+fn reparse_after_overflowing_edit() {
+    let old_source = indoc::indoc! {"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @I1@ INDI
+        1 NAME John /Doe/
+        0 TRLR
+    "};
+
+    let new_source = old_source.replacen("John", "Jon", 1);
+    let new_source = new_source.as_str();
+
+    // the new length wraps around to make the source one byte shorter,
+    // but the edit is still inconsistent with the change
+    let edit = Edit::new(0..0, usize::MAX);
+    let reader = Reader::default();
+    let previous = reader.raw_records(&old_source).unwrap();
+    let result = reader
+        .reparse(old_source, &previous, &edit, new_source)
+        .unwrap();
+
+    let expected = reader.raw_records(&new_source).unwrap();
+    assert_eq!(format!("{:?}", result.records), format!("{expected:?}"));
+    assert_eq!(result.reparsed, 0..expected.len());
+}
+
+#[test]
+fn missing_submitter() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 SOUR test
+        1 SUBM @NOBODY@
+        1 GEDC
+        2 VERS 5.5.1
+        2 FORM LINEAGE-LINKED
+        1 CHAR UTF-8
+        0 TRLR
+    "};
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let err = reader.parse(&decoded).unwrap_err();
+    insta::assert_snapshot!(shared::render(&err), @r#"
+    gedcomfy::error
+
+      × A problem was found in the GEDCOM file
+      ╰─▶ gedcom::schema::missing_submitter
+          
+            × Pointer to missing submitter @NOBODY@
+             ╭─[3:8]
+           2 │ 1 SOUR test
+           3 │ 1 SUBM @NOBODY@
+             ·        ────┬───
+             ·            ╰── there is no SUBM record with this xref
+           4 │ 1 GEDC
+             ╰────
+    "#);
+}
+
+#[rstest]
+#[case::unsupported_version("7.0", "", "Parsing GEDCOM 7.0 records is not yet supported")]
+#[case::pointer_in_text(
+    "5.5.1",
+    "0 @I1@ INDI\n1 NOTE text\n2 CONT @I1@",
+    "Error reading data for record CONT"
+)]
+#[case::text_for_pointer(
+    "5.5.1",
+    "0 @I1@ INDI\n1 FAMC family",
+    "Error reading data for record FAMC"
+)]
+#[case::value_with_subrecords(
+    "5.5.1",
+    "0 @I1@ INDI\n1 FAMS @F1@\n2 DATE 1900\n0 @F1@ FAM",
+    "Unexpected subrecord DATE"
+)]
+fn parse_errors(#[case] version: &str, #[case] records: &str, #[case] message: &str) {
+    let data = format!(
+        "0 HEAD\n1 SOUR test\n1 SUBM @U1@\n1 GEDC\n2 VERS {version}\n2 FORM LINEAGE-LINKED\n\
+        1 CHAR UTF-8\n0 @U1@ SUBM\n1 NAME Someone\n{records}\n0 TRLR\n"
+    );
+
+    let reader = Reader::default();
+    let decoded = reader.decode_borrowed(data.as_bytes()).unwrap();
+    let err = reader.parse(&decoded).unwrap_err();
+    assert_eq!(err.source.to_string(), message);
+}
+
+#[rstest]
+#[case::first(DuplicateSubrecords::KeepFirst, "1850")]
+#[case::last(DuplicateSubrecords::KeepLast, "1851")]
+fn duplicate_subrecords(#[case] keep: DuplicateSubrecords, #[case] date: &str) {
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 SOUR test
+        1 SUBM @SUBM@
+        1 GEDC
+        2 VERS 5.5.1
+        2 FORM LINEAGE-LINKED
+        1 CHAR UTF-8
+        0 @SUBM@ SUBM
+        1 NAME Submitter
+        0 @I1@ INDI
+        1 SEX M
+        1 BIRT
+        2 DATE 1850
+        2 DATE 1851
+        0 TRLR
+    "};
+
+    let reader = Reader::default();
+    let decoded = reader.decode_borrowed(data).unwrap();
+    assert!(reader.parse(&decoded).is_err());
+
+    let reader = Reader::with_options(ParseOptions::default().duplicate_subrecords(keep));
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let result = reader.parse(&decoded).unwrap();
+    let warnings: Vec<String> = result.non_fatals.iter().map(ToString::to_string).collect();
+    assert_eq!(warnings, ["Duplicate subrecord DATE"]);
+
+    let records = reader.raw_records(&decoded).unwrap();
+    let birth = &records[2].records[1];
+    assert_eq!(birth.records.len(), 1);
+    assert_eq!(
+        birth.records[0].line.value.sourced_value,
+        LineValue::Str(date)
+    );
+}
+
+#[test]
+fn unreferenced_submitter() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 SOUR test
+        1 SUBM @SUBM@
+        1 GEDC
+        2 VERS 5.5.1
+        2 FORM LINEAGE-LINKED
+        1 CHAR UTF-8
+        0 @SUBM@ SUBM
+        1 NAME Used
+        0 @UNUSED@ SUBM
+        1 NAME Unused
+        0 TRLR
+    "};
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let result = reader.validate(&decoded).unwrap();
+    assert_eq!(
+        result.validity,
+        gedcomfy::reader::Validity::ValidWithWarnings
+    );
+    let [warning] = result.errors.as_slice() else {
+        panic!("expected one warning: {:?}", result.errors);
+    };
+
+    insta::assert_snapshot!(warning, @"Submitter @UNUSED@ is not referred to by any record");
+}
+
+#[test]
+fn associations() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 SOUR test
+        1 SUBM @SUBM@
+        1 GEDC
+        2 VERS 5.5.1
+        2 FORM LINEAGE-LINKED
+        1 CHAR UTF-8
+        0 @SUBM@ SUBM
+        1 NAME Someone
+        0 @I1@ INDI
+        1 ASSO @I2@
+        2 RELA Godfather
+        2 NOTE Named in the baptism record
+        0 @I2@ INDI
+        0 TRLR
+    "};
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let AnyFileVersion::V551(file) = reader.parse(&decoded).unwrap().file;
+    let TopLevelRecord::Individual(individual) = &file.records[1] else {
+        panic!("expected an individual");
+    };
+
+    let [association] = individual.associations.as_slice() else {
+        panic!("expected one association");
+    };
+
+    assert_eq!(association.individual.xref(), Some("I2"));
+    assert_eq!(association.relation, "Godfather");
+    assert_eq!(
+        association.notes,
+        vec!["Named in the baptism record".to_string()]
+    );
+}
+
+#[test]
+fn missing_associate() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @I1@ INDI
+        1 ASSO @NOBODY@
+        2 RELA Witness
+        0 TRLR
+    "};
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let err = reader.parse(&decoded).unwrap_err();
+    insta::assert_snapshot!(shared::render(&err), @r#"
+    gedcomfy::error
+
+      × A problem was found in the GEDCOM file
+      ╰─▶ gedcom::schema::missing_associate
+          
+            × Pointer to missing associate @NOBODY@
+             ╭─[6:8]
+           5 │ 0 @I1@ INDI
+           6 │ 1 ASSO @NOBODY@
+             ·        ────┬───
+             ·            ╰── there is no record with this xref
+           7 │ 2 RELA Witness
+             ╰────
+    "#);
+}
+
+#[test]
+fn unknown_temple_code() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 SOUR test
+        1 SUBM @SUBM@
+        1 SUBN @SUBN@
+        1 GEDC
+        2 VERS 5.5.1
+        2 FORM LINEAGE-LINKED
+        1 CHAR UTF-8
+        0 @SUBM@ SUBM
+        1 NAME Someone
+        0 @SUBN@ SUBN
+        1 SUBM @SUBM@
+        1 TEMP SLAKE
+        0 @I1@ INDI
+        1 BAPL
+        2 TEMP NOWHERE
+        0 TRLR
+    "};
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let result = reader.validate(&decoded).unwrap();
+    assert_eq!(result.validity, gedcomfy::reader::Validity::Valid);
+    let [advice] = result.errors.as_slice() else {
+        panic!("expected one piece of advice: {:?}", result.errors);
+    };
+
+    insta::assert_snapshot!(advice, @"Unknown temple code NOWHERE");
+}
+
+#[test]
+fn renumber_xrefs() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 SUBM @ME@
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @ME@ SUBM
+        0 @DAD@ INDI
+        1 FAMS @MARRIAGE@
+        0 @MARRIAGE@ FAM
+        1 HUSB @DAD@
+        1 CHIL @KID@
+        1 CHIL @VOID@
+        0 @KID@ INDI
+        1 FAMC @MARRIAGE@
+        1 ASSO @NOBODY@
+        0 TRLR
+    "};
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let records = reader.raw_records(&decoded).unwrap();
+    let renumbering = XRefRenumbering::new(&records);
+    assert_eq!(renumbering.get("KID"), Some("I2"));
+
+    let out = render_records(&renumbering.apply(records));
+    insta::assert_snapshot!(out, @r#"
+    0 HEAD
+    1 SUBM @U1@
+    1 GEDC
+    2 VERS 5.5.1
+    1 CHAR UTF-8
+    0 @U1@ SUBM
+    0 @I1@ INDI
+    1 FAMS @F1@
+    0 @F1@ FAM
+    1 HUSB @I1@
+    1 CHIL @I2@
+    1 CHIL @VOID@
+    0 @I2@ INDI
+    1 FAMC @F1@
+    1 ASSO @NOBODY@
+    0 TRLR
+    "#);
+}
+
+/// Writes out the records in GEDCOM format.
+fn render_records(records: &[Sourced<RawRecord>]) -> String {
+    fn render(record: &RawRecord, level: usize, out: &mut String) {
+        let xref = record
+            .line
+            .xref
+            .as_ref()
+            .map(|x| format!("@{}@ ", x.sourced_value));
+        let value = match record.line.value.sourced_value {
+            LineValue::Ptr(Some(p)) => format!(" @{p}@"),
+            LineValue::Ptr(None) => " @VOID@".to_string(),
+            LineValue::Str(s) => format!(" {s}"),
+            LineValue::None => String::new(),
+        };
+
+        let tag = record.line.tag.as_str();
+        out.push_str(&format!(
+            "{level} {}{tag}{value}\n",
+            xref.unwrap_or_default()
+        ));
+        for child in &record.records {
+            render(child, level + 1, out);
+        }
+    }
+
+    let mut out = String::new();
+    for record in records {
+        render(record, 0, &mut out);
+    }
+
+    out
+}
+
+#[test]
+fn sort_records() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 CHAR UTF-8
+        1 GEDC
+        2 VERS 5.5.1
+        1 SUBM @U1@
+        0 @F1@ FAM
+        1 CHIL @I10@
+        1 CHIL @I2@
+        1 HUSB @I1@
+        0 @I10@ INDI
+        1 NOTE Youngest
+        1 DEAT
+        2 PLAC Wellington
+        2 DATE 1990
+        1 BIRT
+        2 DATE 1920
+        1 SEX F
+        1 NAME Ada /Smith/
+        0 @S1@ SOUR
+        1 TITL Parish register
+        0 @I2@ INDI
+        1 NAME Bob /Smith/
+        1 _NICKNAME Bobby
+        1 FAMC @F1@
+        0 @U1@ SUBM
+        1 NAME Someone
+        0 @I1@ INDI
+        1 NAME Carl /Smith/
+        0 TRLR
+    "};
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let mut records = reader.raw_records(&decoded).unwrap();
+    normalize::sort_records(&mut records);
+    insta::assert_snapshot!(render_records(&records), @r#"
+    0 HEAD
+    1 SUBM @U1@
+    1 GEDC
+    2 VERS 5.5.1
+    1 CHAR UTF-8
+    0 @U1@ SUBM
+    1 NAME Someone
+    0 @I1@ INDI
+    1 NAME Carl /Smith/
+    0 @I2@ INDI
+    1 NAME Bob /Smith/
+    1 FAMC @F1@
+    1 _NICKNAME Bobby
+    0 @I10@ INDI
+    1 NAME Ada /Smith/
+    1 SEX F
+    1 DEAT
+    2 DATE 1990
+    2 PLAC Wellington
+    1 BIRT
+    2 DATE 1920
+    1 NOTE Youngest
+    0 @F1@ FAM
+    1 HUSB @I1@
+    1 CHIL @I10@
+    1 CHIL @I2@
+    0 @S1@ SOUR
+    1 TITL Parish register
+    0 TRLR
+    "#);
+}
+
+#[test]
+fn sort_records_without_xrefs() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 CHAR UTF-8
+        1 GEDC
+        2 VERS 5.5.1
+        0 @X10@ _LOC
+        0 _ROOT first
+        0 @X2@ _LOC
+        0 _ROOT second
+        0 @X1@ _LOC
+        0 TRLR
+    "};
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let mut records = reader.raw_records(&decoded).unwrap();
+    normalize::sort_records(&mut records);
+    insta::assert_snapshot!(render_records(&records), @r#"
+    0 HEAD
+    1 GEDC
+    2 VERS 5.5.1
+    1 CHAR UTF-8
+    0 _ROOT first
+    0 _ROOT second
+    0 @X1@ _LOC
+    0 @X2@ _LOC
+    0 @X10@ _LOC
+    0 TRLR
+    "#);
+}
+
+#[test]
+fn sort_records_keeps_text_in_order() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @N1@ NOTE First line
+        1 CONT Second
+        1 CONC  line
+        1 SOUR @S1@
+        1 CONT Third line
+        1 CONC  continued
+        0 TRLR
+    "};
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let mut records = reader.raw_records(&decoded).unwrap();
+    normalize::sort_records(&mut records);
+    insta::assert_snapshot!(render_records(&records), @r#"
+    0 HEAD
+    1 GEDC
+    2 VERS 5.5.1
+    1 CHAR UTF-8
+    0 @N1@ NOTE First line
+    1 CONT Second
+    1 CONC  line
+    1 CONT Third line
+    1 CONC  continued
+    1 SOUR @S1@
+    0 TRLR
+    "#);
+}
+
+#[test]
+fn tolerate_garbage() {
+    let data: &[u8] =
+        b"\xEF\xBB\xBF\xEF\xBB\xBF0 HEAD\n1 GEDC\n2 VERS 5.5.1\n1 CHAR UTF-8\n0 TRLR\n\0\0\0\0";
+
+    let reader = Reader::default();
+    assert!(reader.decode_borrowed(data).is_err());
+
+    let reader = Reader::with_options(
+        ParseOptions::default()
+            .skip_repeated_bom(true)
+            .trim_trailing_nuls(true),
+    );
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let result = reader.validate(&decoded).unwrap();
+    assert_eq!(
+        result.validity,
+        gedcomfy::reader::Validity::ValidWithWarnings
+    );
+    let text = decoded.as_ref().to_string();
+    let warnings = Vec::from_iter(result.errors.into_iter().map(|warning| {
+        let report = miette::Report::new(warning).with_source_code(text.clone());
+        shared::render(report.as_ref())
+    }));
+    insta::assert_snapshot!(warnings.join("\n"), @r#"
+    gedcom::encoding::repeated_bom
+
+      ⚠ Repeated byte order mark
+       ╭─[1:1]
+     1 │ 0 HEAD
+       · ▲
+       · ╰── the byte order mark was repeated before this
+     2 │ 1 GEDC
+       ╰────
+
+    gedcom::encoding::trailing_nuls
+
+      ⚠ Trailing NUL characters
+       ╭─[5:7]
+     4 │ 1 CHAR UTF-8
+     5 │ 0 TRLR
+       ·       ▲
+       ·       ╰── the NUL characters after this line were ignored
+       ╰────
+      help: the file might have been exported with padding up to a fixed size
+    "#);
+}
+
+#[test]
+fn place_anachronism() {
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @I1@ INDI
+        1 BIRT
+        2 DATE 12 MAR 1900
+        2 PLAC Prague, Czechoslovakia
+        1 DEAT
+        2 DATE BET 1917 AND 1920
+        2 PLAC Brno, Czechoslovakia
+        0 TRLR
+    "};
+
+    let gazetteer = HistoricalJurisdictions::well_known();
+    let reader = Reader::with_options(
+        ParseOptions::default().gazetteer(Arc::new(gazetteer) as Arc<dyn Gazetteer>),
+    );
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let result = reader.validate(&decoded).unwrap();
+    let advice: Vec<String> = result
+        .errors
+        .into_iter()
+        .map(|advice| {
+            let report = miette::Report::new(advice).with_source_code(data);
+            shared::render(report.as_ref())
+        })
+        .collect();
+
+    insta::assert_snapshot!(advice.join("\n"), @r#"
+    gedcom::schema::place_anachronism
+
+      ☞ Czechoslovakia did not exist at the date of the event
+       ╭─[7:8]
+     6 │ 1 BIRT
+     7 │ 2 DATE 12 MAR 1900
+       ·        ─────┬─────
+       ·             ╰── but the event happened at this date
+     8 │ 2 PLAC Prague, Czechoslovakia
+       ·        ───────────┬──────────
+       ·                   ╰── this place is in Czechoslovakia
+     9 │ 1 DEAT
+       ╰────
+    "#);
+}
+
+#[test]
+fn impossible_family_structures() {
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @I1@ INDI
+        1 NAME Ada /Smith/
+        1 FAMC @F1@
+        1 FAMC @F2@
+        2 PEDI adopted
+        1 FAMC @F3@
+        0 @I2@ INDI
+        1 NAME Bob /Smith/
+        0 @I3@ INDI
+        1 NAME Cat /Jones/
+        0 @F1@ FAM
+        1 HUSB @I2@
+        1 WIFE @I2@
+        1 CHIL @I1@
+        0 @F2@ FAM
+        1 HUSB @I2@
+        1 WIFE @I3@
+        1 WIFE @I1@
+        0 @F3@ FAM
+        1 HUSB @I3@
+        1 WIFE @I1@
+        1 CHIL @I2@
+        0 @F4@ FAM
+        1 HUSB @I2@
+        1 WIFE @I1@
+        1 WIFE @I3@
+        1 _POLY Y
+        0 TRLR
+    "};
+
+    let reader = Reader::default();
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let result = reader.validate(&decoded).unwrap();
+    assert_eq!(
+        result.validity,
+        gedcomfy::reader::Validity::ValidWithWarnings
+    );
+    let warnings: Vec<String> = result
+        .errors
+        .into_iter()
+        .map(|warning| {
+            let report = miette::Report::new(warning).with_source_code(data);
+            shared::render(report.as_ref())
+        })
+        .collect();
+
+    insta::assert_snapshot!(warnings.join("\n"), @r#"
+    gedcom::schema::multiple_birth_families
+
+      ⚠ Individual is a child of more than one birth family
+        ╭─[7:1]
+      6 │ 1 NAME Ada /Smith/
+      7 │ 1 FAMC @F1@
+        · ─────┬─────
+        ·      ╰── conflicts with this one
+      8 │ 1 FAMC @F2@
+      9 │ 2 PEDI adopted
+     10 │ 1 FAMC @F3@
+        · ─────┬─────
+        ·      ╰── this family link
+     11 │ 0 @I2@ INDI
+        ╰────
+      help: if the individual was adopted or fostered, add a PEDI record to the
+            FAMC link to say so
+
+    gedcom::schema::duplicate_spouse
+
+      ⚠ Family has @I2@ as more than one spouse
+        ╭─[16:8]
+     15 │ 0 @F1@ FAM
+     16 │ 1 HUSB @I2@
+        ·        ──┬─
+        ·          ╰── is the same individual as this one
+     17 │ 1 WIFE @I2@
+        ·        ──┬─
+        ·          ╰── this spouse
+     18 │ 1 CHIL @I1@
+        ╰────
+
+    gedcom::schema::too_many_spouses
+
+      ⚠ Family has 3 spouses
+        ╭─[22:1]
+     21 │ 1 WIFE @I3@
+     22 │ 1 WIFE @I1@
+        · ─────┬─────
+        ·      ╰── this spouse is one too many
+     23 │ 0 @F3@ FAM
+        ╰────
+      help: a family has at most one husband and one wife; if this is intended,
+            add a _POLY record to the family
+
+    gedcom::schema::ancestry_cycle
+
+      ⚠ @I1@ is their own ancestor
+        ╭─[26:8]
+     25 │ 1 WIFE @I1@
+     26 │ 1 CHIL @I2@
+        ·        ──┬─
+        ·          ╰── this child link completes the cycle
+     27 │ 0 @F4@ FAM
+        ╰────
+      help: following the parents of @I1@ leads back to them, so one of the links
+            between these families is probably wrong
+    "#);
+}
+
+#[test]
+fn improbable_ages() {
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @I1@ INDI
+        1 NAME Ada /Smith/
+        1 BIRT
+        2 DATE 12 MAR 1800
+        1 DEAT
+        2 DATE 1870
+        1 BURI
+        2 DATE BEF 1869
+        0 @I2@ INDI
+        1 NAME Bob /Smith/
+        1 BIRT
+        2 DATE ABT 1790
+        0 @I3@ INDI
+        1 NAME Cat /Smith/
+        1 BIRT
+        2 DATE 1812
+        0 @I4@ INDI
+        1 NAME Dan /Smith/
+        1 BIRT
+        2 DATE 1870
+        0 @F1@ FAM
+        1 HUSB @I2@
+        1 WIFE @I1@
+        1 CHIL @I3@
+        1 CHIL @I4@
+        1 MARR
+        2 DATE 1814
+        0 TRLR
+    "};
+
+    let reader = Reader::with_options(ParseOptions::default().min_marriage_age(16));
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let result = reader.validate(&decoded).unwrap();
+    assert_eq!(
+        result.validity,
+        gedcomfy::reader::Validity::ValidWithWarnings
+    );
+    let warnings: Vec<String> = result
+        .errors
+        .into_iter()
+        .map(|warning| {
+            let report = miette::Report::new(warning).with_source_code(data);
+            shared::render(report.as_ref())
+        })
+        .collect();
+
+    insta::assert_snapshot!(warnings.join("\n"), @r#"
+    gedcom::schema::burial_before_death
+
+      ⚠ Burial before death
+        ╭─[10:8]
+      9 │ 1 DEAT
+     10 │ 2 DATE 1870
+        ·        ──┬─
+        ·          ╰── but died at this date
+     11 │ 1 BURI
+     12 │ 2 DATE BEF 1869
+        ·        ────┬───
+        ·            ╰── the individual was buried at this date
+     13 │ 0 @I2@ INDI
+        ╰────
+
+    gedcom::schema::mother_too_young
+
+      ⚠ Mother was at most 12 years old at the birth of her child
+       ╭─[8:8]
+     7 │ 1 BIRT
+     8 │ 2 DATE 12 MAR 1800
+       ·        ─────┬─────
+       ·             ╰── the mother was born at this date
+     9 │ 1 DEAT
+       ╰────
+        ╭─[20:8]
+     19 │ 1 BIRT
+     20 │ 2 DATE 1812
+        ·        ──┬─
+        ·          ╰── and the child at this date
+     21 │ 0 @I4@ INDI
+        ╰────
+      help: check the dates of both births, and that the child is in the right
+            family
+
+    gedcom::schema::mother_too_old
+
+      ⚠ Mother was at least 69 years old at the birth of her child
+       ╭─[8:8]
+     7 │ 1 BIRT
+     8 │ 2 DATE 12 MAR 1800
+       ·        ─────┬─────
+       ·             ╰── the mother was born at this date
+     9 │ 1 DEAT
+       ╰────
+        ╭─[24:8]
+     23 │ 1 BIRT
+     24 │ 2 DATE 1870
+        ·        ──┬─
+        ·          ╰── and the child at this date
+     25 │ 0 @F1@ FAM
+        ╰────
+      help: check the dates of both births, and that the child is in the right
+            family
+
+    gedcom::schema::married_too_young
+
+      ☞ Spouse was at most 14 years old when married, which is younger than 16
+       ╭─[8:8]
+     7 │ 1 BIRT
+     8 │ 2 DATE 12 MAR 1800
+       ·        ─────┬─────
+       ·             ╰── the spouse was born at this date
+     9 │ 1 DEAT
+       ╰────
+        ╭─[31:8]
+     30 │ 1 MARR
+     31 │ 2 DATE 1814
+        ·        ──┬─
+        ·          ╰── and married at this date
+     32 │ 0 TRLR
+        ╰────
+    "#);
+
+    let records = reader.raw_records(&decoded).unwrap();
+    let ages: Vec<String> = event_ages(&records)
+        .into_iter()
+        .map(|age| {
+            format!(
+                "{} {} {:?}..={:?}",
+                age.xref, age.event, age.age.min, age.age.max
+            )
+        })
+        .collect();
+
+    insta::assert_snapshot!(ages.join("\n"), @r#"
+    I1 DEAT Some(69)..=Some(70)
+    I1 BURI None..=Some(68)
+    "#);
+}
+
+#[test]
+fn citation_quality() {
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @S1@ SOUR
+        1 TITL Family bible
+        0 @I1@ INDI
+        1 BIRT
+        2 DATE 1850
+        2 SOUR @S1@
+        3 QUAY 0
+        1 DEAT
+        2 DATE 1900
+        2 SOUR @S1@
+        3 QUAY 0
+        2 SOUR @S1@
+        3 QUAY 3
+        1 BURI
+        2 DATE 1900
+        0 TRLR
+    "};
+
+    let reader = Reader::with_options(
+        ParseOptions::default().min_citation_quality(CertaintyAssessment::Secondary),
+    );
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let result = reader.validate(&decoded).unwrap();
+    let advice: Vec<String> = result.errors.iter().map(ToString::to_string).collect();
+    insta::assert_snapshot!(advice.join("\n"), @"BIRT has no citations assessed as at least 2");
+
+    let records = reader.raw_records(&decoded).unwrap();
+    let support: Vec<String> = event_support(&records)
+        .into_iter()
+        .map(|support| {
+            format!(
+                "{} {} {:?}",
+                support.xref,
+                support.event,
+                support.best_quality()
+            )
+        })
+        .collect();
+    insta::assert_snapshot!(support.join("\n"), @r#"
+    I1 BIRT Some(Unreliable)
+    I1 DEAT Some(Primary)
+    I1 BURI None
+    "#);
+
+    let unreliable: Vec<&str> = supported_only_by(&records, CertaintyAssessment::Unreliable)
+        .into_iter()
+        .map(|support| support.event)
+        .collect();
+    assert_eq!(unreliable, ["BIRT"]);
+}
+
+#[test]
+fn descendant_report() {
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @S1@ SOUR
+        1 TITL Parish register
+        0 @I1@ INDI
+        1 NAME John /Smith/
+        1 BIRT
+        2 DATE 12 MAR 1850
+        2 PLAC Paris
+        2 SOUR @S1@
+        3 PAGE p. 12
+        1 DEAT
+        2 DATE 3 MAR 1901
+        0 @I2@ INDI
+        1 NAME Mary /Jones/
+        1 BIRT
+        2 DATE 1852
+        0 @I3@ INDI
+        1 NAME Ada /Smith/
+        0 @I4@ INDI
+        1 NAME Bob /Smith/
+        1 BIRT
+        2 DATE 1878
+        2 SOUR @S1@
+        3 PAGE p. 40
+        0 @I5@ INDI
+        1 NAME Dora /Brown/
+        0 @F1@ FAM
+        1 HUSB @I1@
+        1 WIFE @I2@
+        1 MARR
+        2 DATE 1875
+        2 PLAC London
+        1 CHIL @I3@
+        1 CHIL @I4@
+        0 @F2@ FAM
+        1 WIFE @I3@
+        1 CHIL @I5@
+        0 TRLR
+    "};
+
+    let reader = Reader::default();
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let records = reader.raw_records(&decoded).unwrap();
+    assert_eq!(descendants(&records, "I9", ReportFormat::Text), None);
+
+    let text = descendants(&records, "I1", ReportFormat::Text).unwrap();
+    insta::assert_snapshot!(text, @r#"
+    Descendants of John Smith
+
+    Generation 1
+
+    1. John Smith, born 12 MAR 1850 in Paris[1] and died 3 MAR 1901.
+       John Smith married Mary Jones (born 1852), 1875 in London.
+       Children of John Smith and Mary Jones:
+       + 2      i. Ada Smith
+         3     ii. Bob Smith, born 1878[2]
+
+    Generation 2
+
+    2. Ada Smith.
+       Children of Ada Smith:
+         4      i. Dora Brown
+
+    Sources
+
+    [1] Parish register, p. 12
+    [2] Parish register, p. 40
+    "#);
+
+    let markdown = descendants(&records, "I1", ReportFormat::Markdown).unwrap();
+    insta::assert_snapshot!(markdown, @r#"
+    # Descendants of John Smith
+
+    ## Generation 1
+
+    **1. John Smith**, born 12 MAR 1850 in Paris[^1] and died 3 MAR 1901.
+
+    John Smith married Mary Jones (born 1852), 1875 in London.
+
+    Children of John Smith and Mary Jones:
+
+    - \+ 2\. i. Ada Smith
+    - 3\. ii. Bob Smith, born 1878[^2]
+
+    ## Generation 2
+
+    **2. Ada Smith**.
+
+    Children of Ada Smith:
+
+    - 4\. i. Dora Brown
+
+    [^1]: Parish register, p. 12
+    [^2]: Parish register, p. 40
+    "#);
+}
+
+#[test]
+fn site_pages() {
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @S1@ SOUR
+        1 TITL Parish register
+        0 @M1@ OBJE
+        1 FILE photos/john smith.jpg
+        2 FORM jpg
+        1 TITL Portrait of John
+        0 @I1@ INDI
+        1 NAME John /Smith/
+        1 BIRT
+        2 DATE 12 MAR 1850
+        2 PLAC Paris
+        2 SOUR @S1@
+        3 PAGE p. 12
+        1 OCCU Farmer <head>
+        2 DATE 1881
+        1 EVEN
+        2 TYPE Emigrated*
+        2 DATE 1890
+        1 OBJE @M1@
+        1 OBJE
+        2 FILE https://example.com/photos/wedding (1875).jpg
+        2 FILE javascript:alert(1)
+        2 FILE C:\\Photos\\john.jpg
+        2 TITL Wedding
+        0 @I2@ INDI
+        1 NAME Mary /Jones/
+        0 @I3@ INDI
+        1 NAME Ada /Smith/
+        0 @I4@ INDI
+        1 NAME Bob /Smith/
+        0 @F1@ FAM
+        1 HUSB @I1@
+        1 WIFE @I2@
+        1 MARR
+        2 DATE 1875
+        2 SOUR @S1@
+        3 PAGE p. 12
+        1 CHIL @I3@
+        1 CHIL @I4@
+        0 TRLR
+    "};
+
+    let reader = Reader::default();
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let records = reader.raw_records(&decoded).unwrap();
+
+    let pages = site::pages(&records, PageFormat::Markdown);
+    let file_names = Vec::from_iter(pages.iter().map(|page| page.file_name.as_str()));
+    assert_eq!(file_names, ["I1.md", "I2.md", "I3.md", "I4.md", "index.md"]);
+    insta::assert_snapshot!(pages[0].content, @r#"
+    ---
+    title: "John Smith"
+    ---
+
+    # John Smith
+
+    ## Facts
+
+    - Birth: 12 MAR 1850, Paris[^1]
+    - Occupation: Farmer \<head\>, 1881
+    - Emigrated\*: 1890
+
+    ## Family
+
+    ### Family with [Mary Jones](I2.md)
+
+    - Marriage: 1875[^1]
+
+    #### Children
+
+    - [Ada Smith](I3.md)
+    - [Bob Smith](I4.md)
+
+    ## Media
+
+    - [Portrait of John](photos/john%20smith.jpg)
+    - [Wedding](https://example.com/photos/wedding%20%281875%29.jpg)
+    - Wedding (javascript:alert(1))
+    - Wedding (C:\\Photos\\john.jpg)
+
+    [^1]: Parish register, p. 12
+    "#);
+
+    let pages = site::pages(&records, PageFormat::Html);
+    assert_eq!(pages[4].file_name, "index.html");
+    assert!(
+        pages[2]
+            .content
+            .contains("<li><a href=\"I4.html\">Bob Smith</a></li>")
+    );
+    assert!(
+        pages[0]
+            .content
+            .contains("<li>Occupation: Farmer &lt;head&gt;, 1881</li>")
+    );
+    let media = &pages[0].content;
+    assert!(media.contains("<a href=\"photos/john%20smith.jpg\">Portrait of John</a>"));
+    assert!(media.contains("<li>Wedding (javascript:alert(1))</li>"));
+}
+
+#[test]
+fn site_page_names() {
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @I1@ INDI
+        0 @i1@ INDI
+        0 @I.1@ INDI
+        0 @I_1@ INDI
+        0 @index@ INDI
+        0 TRLR
+    "};
+
+    let reader = Reader::default();
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let records = reader.raw_records(&decoded).unwrap();
+
+    let pages = site::pages(&records, PageFormat::Markdown);
+    let file_names = Vec::from_iter(pages.iter().map(|page| page.file_name.as_str()));
+    assert_eq!(
+        file_names,
+        [
+            "I1.md",
+            "_691.md",
+            "I_2E1.md",
+            "I_5F1.md",
+            "_69_6E_64_65_78.md",
+            "index.md"
+        ]
+    );
+}
+
+#[test]
+fn name_index() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 SOUR test
+        1 SUBM @SUBM@
+        1 GEDC
+        2 VERS 5.5.1
+        2 FORM LINEAGE-LINKED
+        1 CHAR UTF-8
+        0 @SUBM@ SUBM
+        1 NAME Someone
+        0 @I1@ INDI
+        1 NAME John Paul /Smith/
+        1 BIRT
+        2 DATE 3 JAN 1901
+        0 @I2@ INDI
+        1 NAME Mary /Smith/
+        1 BIRT
+        2 DATE ABT 1925
+        0 @I3@ INDI
+        1 NAME Jack /Jones/
+        2 GIVN John
+        2 SURN Smithers
+        1 BIRT
+        2 DATE 1909
+        0 @I4@ INDI
+        1 NAME Unknown /Jones/
+        0 TRLR
+    "};
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let AnyFileVersion::V551(file) = reader.parse(&decoded).unwrap().file;
+    let index = NameIndex::new(&file);
+
+    assert_eq!(
+        index.surnames(),
+        vec![("Smith", 2), ("Jones", 1), ("Smithers", 1)]
+    );
+    assert_eq!(
+        index.surnames_with_prefix("smi"),
+        vec![("Smith", 2), ("Smithers", 1)]
+    );
+    assert_eq!(index.given_names_with_prefix("J"), vec![("John", 2)]);
+    assert_eq!(index.decades().collect::<Vec<_>>(), vec![1900, 1920]);
+    assert_eq!(
+        index.given_names_in_decade(Some(1900)),
+        vec![("John", 2), ("Paul", 1)]
+    );
+    assert_eq!(index.given_names_in_decade(None), vec![("Unknown", 1)]);
+}
+
+#[test]
+fn event_witnesses() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 SOUR test
+        1 SUBM @SUBM@
+        1 GEDC
+        2 VERS 5.5.1
+        2 FORM LINEAGE-LINKED
+        1 CHAR UTF-8
+        0 @SUBM@ SUBM
+        1 NAME Someone
+        0 @S1@ SOUR
+        1 TITL Parish register
+        0 @S2@ SOUR
+        1 TITL Census
+        0 @I1@ INDI
+        1 NAME John /Smith/
+        0 @I2@ INDI
+        1 NAME Mary /Jones/
+        0 @I3@ INDI
+        1 NAME Ann /Brown/
+        1 SOUR @S1@
+        2 EVEN MARR
+        3 ROLE (Witness)
+        0 @I4@ INDI
+        1 NAME Tom /Green/
+        1 SOUR @S1@
+        2 EVEN MARR
+        3 ROLE WITN
+        0 @I5@ INDI
+        1 NAME Bill /White/
+        1 SOUR @S1@
+        2 EVEN MARR
+        3 ROLE (Best man)
+        0 @I6@ INDI
+        1 NAME Jane /Black/
+        1 SOUR @S2@
+        2 EVEN MARR
+        3 ROLE WITN
+        0 @F1@ FAM
+        1 HUSB @I1@
+        1 WIFE @I2@
+        1 MARR
+        2 DATE 1 JUN 1850
+        2 SOUR @S1@
+        0 TRLR
+    "};
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let AnyFileVersion::V551(file) = reader.parse(&decoded).unwrap().file;
+    let Some(TopLevelRecord::Family(family)) = file.records.last() else {
+        panic!("expected a family");
+    };
+
+    let witnesses: Vec<&str> = file
+        .witnesses_of(&family.events[0])
+        .map(|individual| individual.names[0].personal_name.as_str())
+        .collect();
+
+    assert_eq!(witnesses, vec!["Ann /Brown/", "Tom /Green/"]);
+
+    let roles: Vec<Option<Role>> = file
+        .records
+        .iter()
+        .filter_map(|record| match record {
+            TopLevelRecord::Individual(individual) => individual.source_citations.first(),
+            _ => None,
+        })
+        .map(|citation| citation.event.as_ref().unwrap().role_in_event.clone())
+        .collect();
+
+    assert_eq!(
+        roles,
+        vec![
+            Some(Role::Witness),
+            Some(Role::Witness),
+            Some(Role::UserDefined("Best man".to_string())),
+            Some(Role::Witness),
+        ]
+    );
+}
+
+#[test]
+fn search_values() {
+    let reader = Reader::default();
+    let data: &[u8] = indoc::indoc! {b"
+        0 HEAD
+        1 GEDC
+        2 VERS 5.5.1
+        1 CHAR UTF-8
+        0 @I1@ INDI
+        1 NAME Ada /Smith/
+        1 BIRT
+        2 PLAC Smithfield
+        0 @N1@ NOTE The Smiths
+        1 CONT @@Smithy
+        0 TRLR
+    "};
+
+    let decoded = reader.decode_borrowed(data).unwrap();
+    let records = reader.raw_records(&decoded).unwrap();
+    let source = decoded.as_ref();
+    let found = |pattern: &Pattern| -> Vec<String> {
+        search(&records, pattern)
+            .into_iter()
+            .map(|m| {
+                let text = &source[m.span.offset()..m.span.offset() + m.span.len()];
+                format!("{:?} {} {text}", m.xref, m.path_string())
+            })
+            .collect()
+    };
+
+    insta::assert_snapshot!(found(&Pattern::literal("Smith")).join("\n"), @r#"
+    Some("I1") INDI.NAME Smith
+    Some("I1") INDI.BIRT.PLAC Smith
+    Some("N1") NOTE Smith
+    Some("N1") NOTE.CONT Smith
+    "#);
+    insta::assert_snapshot!(found(&Pattern::regex(r"Smith\w+").unwrap()).join("\n"), @r#"
+    Some("I1") INDI.BIRT.PLAC Smithfield
+    Some("N1") NOTE Smiths
+    Some("N1") NOTE.CONT Smithy
+    "#);
+    assert!(Pattern::regex("(").is_err());
+}
@@ -0,0 +1,75 @@
+//! Timing of each phase of reading a GEDCOM file, for `mdf gedcom bench`.
+
+use std::{fmt::Write, num::NonZeroU32, time::Duration};
+
+use complex_indifference::Rate;
+use fancy_duration::FancyDuration;
+use gedcomfy::reader::{
+    Reader, ReaderError, WithSourceCode,
+    metrics::{Byte, Metrics, Phase},
+};
+
+/// Reads the file `iterations` times, returning the
+/// mean time taken by each phase.
+pub fn bench(
+    reader: &Reader,
+    data: &[u8],
+    iterations: NonZeroU32,
+) -> Result<Metrics, WithSourceCode<'static, ReaderError>> {
+    reader
+        .measure_mean(data, iterations)
+        .map_err(WithSourceCode::into_owned)
+}
+
+/// Formats the timings as a table. The rate of each phase is in its own
+/// units, and its throughput is in bytes of input.
+pub fn table(metrics: &Metrics, iterations: u32) -> String {
+    let mut table = Table { metrics, text: String::new() };
+    _ = writeln!(table.text, "Mean of {iterations} iteration(s):");
+    _ = writeln!(
+        table.text,
+        "{:<18} {:>16} {:>12} {:>20} {:>20}",
+        "Phase", "Count", "Time", "Rate", "Throughput (bytes)"
+    );
+
+    table.row("Decode", &metrics.decode, "bytes");
+    table.row("(Line split)", &metrics.line_split, "lines");
+    table.row("Line parse", &metrics.line_parse, "lines");
+    table.row("Record build", &metrics.record_build, "records");
+    table.row("Schema conversion", &metrics.schema_conversion, "records");
+    table.row("(Arena build)", &metrics.arena_build, "records");
+
+    let total: Duration = metrics.decode.elapsed
+        + metrics.line_parse.elapsed
+        + metrics.record_build.elapsed
+        + metrics.schema_conversion.elapsed;
+    _ = writeln!(
+        table.text,
+        "{:<18} {:>16} {:>12}",
+        "Total",
+        "",
+        FancyDuration(total).truncate(2).to_string()
+    );
+
+    table.text
+}
+
+struct Table<'m> {
+    metrics: &'m Metrics,
+    text: String,
+}
+
+impl Table<'_> {
+    fn row<T>(&mut self, name: &str, phase: &Phase<T>, unit: &str) {
+        let throughput: Rate<Byte> = self.metrics.throughput(phase);
+        _ = writeln!(
+            self.text,
+            "{:<18} {:>16} {:>12} {:>20} {:>20}",
+            name,
+            format!("{} {unit}", phase.count),
+            FancyDuration(phase.elapsed).truncate(2).to_string(),
+            phase.rate().to_string(),
+            throughput.to_string(),
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! The errors which `mdf` can exit with.
+//!
+//! Each kind of failure has its own exit code, so that scripts can tell them apart:
+//! - 0: success
+//! - 1: warnings were found, and `--deny-warnings` was given
+//! - 2: the GEDCOM file is invalid (or a KDL file could not be converted to GEDCOM)
+//! - 3: the file could not be read, the output could not be written,
+//!   the requested record (or code or tag to explain) does not exist, the command line
+//!   (or search pattern) was invalid, or the language server could not communicate with its client
+
+use std::{path::PathBuf, process::ExitCode};
+
+use gedcomfy::{
+    reader::{ReaderError, WithSourceCode, from_kdl::KdlImportError, input::FileLoadError},
+    search::InvalidPattern,
+};
+use miette::NamedSource;
+
+use crate::show::RecordSelector;
+
+#[derive(Debug, derive_more::Display, errful::Error)]
+pub enum MdfError {
+    #[display("invalid command line")]
+    #[error(exit_code = 3)]
+    Usage { source: clap::Error },
+
+    #[display("the file could not be read")]
+    #[error(exit_code = 3)]
+    Io { source: FileLoadError },
+
+    #[display("the input could not be read from {}", path.display())]
+    #[error(exit_code = 3)]
+    Input {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+
+    #[display("the output could not be written to {}", path.display())]
+    #[error(exit_code = 3)]
+    Output {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+
+    #[display("the language server could not communicate with its client")]
+    #[error(exit_code = 3)]
+    Lsp { source: std::io::Error },
+
+    #[display("the search pattern is invalid")]
+    #[error(exit_code = 3)]
+    InvalidPattern { source: InvalidPattern },
+
+    #[display("no {selector} was found in the file")]
+    #[error(exit_code = 3)]
+    RecordNotFound { selector: RecordSelector },
+
+    #[display("there is no diagnostic code or tag named {name}")]
+    #[error(exit_code = 3)]
+    UnknownExplanation { name: String },
+
+    #[display("the file could not be decoded")]
+    #[error(exit_code = 2)]
+    Decoding { source: FileLoadError },
+
+    #[display("the GEDCOM file is invalid")]
+    #[error(exit_code = 2)]
+    Read {
+        source: WithSourceCode<'static, ReaderError>,
+    },
+
+    #[display("the KDL file could not be converted to GEDCOM")]
+    #[error(exit_code = 2)]
+    KdlImport {
+        source: KdlImportError,
+        path: PathBuf,
+        text: String,
+    },
+
+    #[display("validation found errors")]
+    #[error(exit_code = 2)]
+    Invalid,
+
+    #[display("validation found warnings, and --deny-warnings was given")]
+    #[error(exit_code = 1)]
+    DeniedWarnings,
+}
+
+impl MdfError {
+    /// Prints the error to stderr, returning the exit code for it.
+    ///
+    /// Problems in GEDCOM files are rendered by `miette`, so that they
+    /// include the relevant source code.
+    pub fn report(self) -> ExitCode {
+        let exit_code = std::error::request_value::<ExitCode>(&self).unwrap_or(ExitCode::FAILURE);
+        match self {
+            MdfError::Usage { source } => {
+                _ = source.print();
+            }
+            MdfError::Io { source } | MdfError::Decoding { source } => {
+                eprintln!("{:?}", miette::Report::new(source));
+            }
+            MdfError::Input { ref source, .. }
+            | MdfError::Output { ref source, .. }
+            | MdfError::Lsp { ref source } => {
+                eprintln!("{self}: {source}");
+            }
+            MdfError::Read { source } => {
+                eprintln!("{:?}", miette::Report::new(source));
+            }
+            MdfError::KdlImport { source, path, text } => {
+                let source_code = NamedSource::new(path.display().to_string(), text);
+                eprintln!(
+                    "{:?}",
+                    miette::Report::new(source).with_source_code(source_code)
+                );
+            }
+            MdfError::InvalidPattern { source } => {
+                eprintln!("{:?}", miette::Report::new(source));
+            }
+            MdfError::RecordNotFound { .. }
+            | MdfError::UnknownExplanation { .. }
+            | MdfError::Invalid
+            | MdfError::DeniedWarnings => {
+                eprintln!("{self}");
+            }
+        }
+
+        exit_code
+    }
+}
+
+impl From<FileLoadError> for MdfError {
+    fn from(source: FileLoadError) -> Self {
+        match source {
+            FileLoadError::IO { .. } => MdfError::Io { source },
+            FileLoadError::Archive { .. }
+            | FileLoadError::ArchiveTooLarge { .. }
+            | FileLoadError::Decoding { .. } => MdfError::Decoding { source },
+        }
+    }
+}
+
+impl From<WithSourceCode<'static, ReaderError>> for MdfError {
+    fn from(source: WithSourceCode<'static, ReaderError>) -> Self {
+        MdfError::Read { source }
+    }
+}
@@ -0,0 +1,46 @@
+//! Documentation for diagnostic codes and GEDCOM tags, for `mdf gedcom explain`.
+
+use std::fmt::Write;
+
+use gedcomfy::{codes::CodeDocumentation, schemas::tags::tag_description};
+
+/// Explains the diagnostic code (e.g. `gedcom::encoding::invalid_bom`)
+/// or tag (e.g. `BIRT`), if it is known.
+pub fn explain(name: &str) -> Option<String> {
+    if name.contains("::") {
+        gedcomfy::codes::explain(name).map(code)
+    } else {
+        let tag = name.to_ascii_uppercase();
+        tag_description(&tag).map(|description| self::tag(&tag, description))
+    }
+}
+
+fn code(doc: &CodeDocumentation) -> String {
+    let mut output = String::new();
+    _ = writeln!(output, "{}: {}", doc.code, doc.summary);
+    _ = writeln!(output);
+    _ = writeln!(output, "{}", doc.explanation);
+
+    if let Some(example) = doc.example {
+        _ = writeln!(output);
+        _ = writeln!(output, "For example:");
+        for line in example.lines() {
+            _ = writeln!(output, "  {line}");
+        }
+    }
+
+    if let Some(specification) = doc.specification {
+        _ = writeln!(output);
+        _ = writeln!(output, "See {specification}.");
+    }
+
+    output
+}
+
+fn tag(tag: &str, description: &str) -> String {
+    let mut output = String::new();
+    _ = writeln!(output, "{tag}: {description}");
+    _ = writeln!(output);
+    _ = writeln!(output, "See GEDCOM 5.5.1, Appendix A: Tag Definitions.");
+    output
+}
@@ -0,0 +1,41 @@
+//! Listing the households of a census year, for `mdf gedcom households`.
+
+use std::fmt::Write;
+
+use gedcomfy::households::Households;
+
+/// Lists the households in each place, with the role and age of each member.
+pub fn listing(households: &Households) -> String {
+    let mut output = String::new();
+    if households.places.is_empty() {
+        _ = writeln!(output, "No households were found in {}", households.year);
+        return output;
+    }
+
+    _ = writeln!(output, "Households in {}:", households.year);
+    for (place, households) in &households.places {
+        _ = writeln!(output, "{place}");
+        for household in households {
+            match household.family {
+                Some(family) => _ = writeln!(output, "  Family @{family}@"),
+                None => _ = writeln!(output, "  No family"),
+            }
+
+            for member in &household.members {
+                let name = member.person.name.as_deref().unwrap_or("(unnamed)");
+                _ = write!(
+                    output,
+                    "    @{}@ {name}: {}",
+                    member.person.xref, member.role
+                );
+                if let Some(age) = member.age {
+                    _ = write!(output, ", age {age}");
+                }
+
+                _ = writeln!(output);
+            }
+        }
+    }
+
+    output
+}
@@ -0,0 +1,335 @@
+//! A language server for GEDCOM files, for `mdf gedcom lsp`.
+//!
+//! This implements the parts of the Language Server Protocol (over stdin and stdout)
+//! which are needed to provide diagnostics, documentation for tags on hover,
+//! go-to-definition for pointers, and an outline of the top-level records.
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+    sync::Arc,
+};
+
+use gedcomfy::{
+    reader::{Reader, lines::LineValue},
+    schemas::tags::tag_description,
+};
+use miette::SourceSpan;
+use serde_json::{Value, json};
+
+use crate::lsp::document::Document;
+
+mod document;
+
+/// The JSON-RPC error code for unknown methods.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// The JSON-RPC error code for requests with missing or invalid parameters.
+const INVALID_PARAMS: i64 = -32602;
+
+/// Runs the language server until the client tells it to exit.
+pub fn serve(reader: Reader) -> io::Result<()> {
+    let mut input = io::stdin().lock();
+    let mut output = io::stdout().lock();
+    let mut server = Server { reader, documents: HashMap::new() };
+    while let Some(message) = read_message(&mut input)? {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        match message.get("id") {
+            Some(id) => {
+                let response = match server.request(method, params) {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    Err((code, message)) => json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": code, "message": message },
+                    }),
+                };
+
+                write_message(&mut output, &response)?;
+            }
+            None if method == "exit" => break,
+            None => {
+                for notification in server.notify(method, params) {
+                    write_message(&mut output, &notification)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a message, which is preceded by headers giving its length.
+/// Returns `None` when the input has ended.
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<Value>> {
+    loop {
+        let mut length = None;
+        loop {
+            let mut header = String::new();
+            if input.read_line(&mut header)? == 0 {
+                return Ok(None);
+            }
+
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("Content-Length") {
+                    length = value.trim().parse::<usize>().ok();
+                }
+            }
+        }
+
+        let Some(length) = length else {
+            tracing::warn!("ignoring message without a Content-Length header");
+            continue;
+        };
+
+        let mut body = vec![0; length];
+        input.read_exact(&mut body)?;
+        match serde_json::from_slice(&body) {
+            Ok(message) => return Ok(Some(message)),
+            Err(err) => tracing::warn!(%err, "ignoring message which is not valid JSON"),
+        }
+    }
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush()
+}
+
+struct Server {
+    reader: Reader,
+    documents: HashMap<String, Document>,
+}
+
+type RequestResult = Result<Value, (i64, String)>;
+
+impl Server {
+    fn request(&mut self, method: &str, params: &Value) -> RequestResult {
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    // open and close notifications, and incremental changes
+                    "textDocumentSync": { "openClose": true, "change": 2, "save": true },
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "documentSymbolProvider": true,
+                },
+                "serverInfo": { "name": "mdf", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "shutdown" => Ok(Value::Null),
+            "textDocument/hover" => self.hover(params),
+            "textDocument/definition" => self.definition(params),
+            "textDocument/documentSymbol" => self.symbols(params),
+            _ => Err((METHOD_NOT_FOUND, format!("unsupported method: {method}"))),
+        }
+    }
+
+    /// Handles a notification, returning any notifications to send in response.
+    fn notify(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                let document = Document::new(&self.reader, Arc::from(text));
+                self.documents.insert(uri.clone(), document);
+            }
+            "textDocument/didChange" => {
+                let Some(document) = self.documents.get_mut(&uri) else {
+                    return Vec::new();
+                };
+
+                let changes = params["contentChanges"].as_array();
+                for change in changes.into_iter().flatten() {
+                    let range = change.get("range").map(|range| {
+                        let start = offset(document, &range["start"]);
+                        let end = offset(document, &range["end"]);
+                        start..end.max(start)
+                    });
+
+                    let text = change["text"].as_str().unwrap_or_default();
+                    document.edit(&self.reader, range, text);
+                }
+            }
+            "textDocument/didSave" => {
+                let Some(document) = self.documents.get_mut(&uri) else {
+                    return Vec::new();
+                };
+
+                document.revalidate(&self.reader);
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                return vec![diagnostics(&uri, Vec::new())];
+            }
+            _ => return Vec::new(),
+        }
+
+        let Some(document) = self.documents.get(&uri) else {
+            return Vec::new();
+        };
+
+        let problems = document
+            .problems()
+            .iter()
+            .map(|problem| {
+                let severity = match problem.severity {
+                    None | Some(miette::Severity::Error) => 1,
+                    Some(miette::Severity::Warning) => 2,
+                    Some(miette::Severity::Advice) => 3,
+                };
+
+                json!({
+                    "range": range(document, problem.span),
+                    "severity": severity,
+                    "code": problem.code,
+                    "source": "mdf",
+                    "message": problem.message,
+                })
+            })
+            .collect();
+
+        vec![diagnostics(&uri, problems)]
+    }
+
+    fn document(&self, params: &Value) -> Result<&Document, (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        self.documents
+            .get(uri)
+            .ok_or_else(|| (INVALID_PARAMS, format!("unknown document: {uri}")))
+    }
+
+    /// Shows the documentation for the tag under the cursor.
+    fn hover(&self, params: &Value) -> RequestResult {
+        let document = self.document(params)?;
+        let offset = offset(document, &params["position"]);
+        let Some(line) = document.line_at(offset) else {
+            return Ok(Value::Null);
+        };
+
+        let tag = line.tag.as_str();
+        if !contains(line.tag.span, offset) {
+            return Ok(Value::Null);
+        }
+
+        let Some(description) = tag_description(tag) else {
+            return Ok(Value::Null);
+        };
+
+        Ok(json!({
+            "contents": { "kind": "markdown", "value": format!("**{tag}**: {description}") },
+            "range": range(document, line.tag.span),
+        }))
+    }
+
+    /// Finds the record which the pointer under the cursor refers to.
+    fn definition(&self, params: &Value) -> RequestResult {
+        let document = self.document(params)?;
+        let offset = offset(document, &params["position"]);
+        let Some(line) = document.line_at(offset) else {
+            return Ok(Value::Null);
+        };
+
+        let LineValue::Ptr(Some(pointer)) = line.value.sourced_value else {
+            return Ok(Value::Null);
+        };
+
+        if !contains(line.value.span, offset) {
+            return Ok(Value::Null);
+        }
+
+        let target = document.records().iter().find(|record| {
+            record
+                .line
+                .xref
+                .as_ref()
+                .is_some_and(|xref| xref.sourced_value == pointer)
+        });
+
+        Ok(match target {
+            Some(record) => json!({
+                "uri": params["textDocument"]["uri"],
+                "range": range(document, record.line.span),
+            }),
+            None => Value::Null,
+        })
+    }
+
+    /// Lists the top-level records.
+    fn symbols(&self, params: &Value) -> RequestResult {
+        let document = self.document(params)?;
+        let symbols: Vec<Value> = document
+            .records()
+            .iter()
+            .map(|record| {
+                let tag = record.line.tag.as_str();
+                let name = match &record.line.xref {
+                    Some(xref) => format!("@{}@ {tag}", xref.sourced_value),
+                    None => tag.to_string(),
+                };
+
+                // records are described by their name or title, if they have one
+                let detail = record
+                    .records
+                    .iter()
+                    .find(|child| matches!(child.line.tag.as_str(), "NAME" | "TITL"))
+                    .and_then(|child| match child.line.value.sourced_value {
+                        LineValue::Str(value) => Some(value),
+                        LineValue::Ptr(_) | LineValue::None => None,
+                    });
+
+                json!({
+                    "name": name,
+                    "detail": detail,
+                    // ‘object’
+                    "kind": 19,
+                    "range": range(document, record.span),
+                    "selectionRange": range(document, record.line.span),
+                })
+            })
+            .collect();
+
+        Ok(Value::Array(symbols))
+    }
+}
+
+fn diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+fn offset(document: &Document, position: &Value) -> usize {
+    let line = position["line"].as_u64().unwrap_or_default();
+    let character = position["character"].as_u64().unwrap_or_default();
+    document.offset(line as usize, character as usize)
+}
+
+fn range(document: &Document, span: SourceSpan) -> Value {
+    let position = |offset| {
+        let (line, character) = document.position(offset);
+        json!({ "line": line, "character": character })
+    };
+
+    json!({
+        "start": position(span.offset()),
+        "end": position(span.offset() + span.len()),
+    })
+}
+
+/// Whether the offset is within the span (or just after it).
+fn contains(span: SourceSpan, offset: usize) -> bool {
+    span.offset() <= offset && offset <= span.offset() + span.len()
+}
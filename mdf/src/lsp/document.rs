@@ -0,0 +1,232 @@
+//! The state of each open document, which is kept up to date as it is edited.
+
+use std::{ops::Range, sync::Arc};
+
+use gedcomfy::reader::{
+    Reader, ReaderError, Sourced, incremental::Edit, lines::RawLine, records::RawRecord,
+};
+use miette::{Diagnostic, SourceSpan};
+use yoke::{Yoke, Yokeable};
+
+use super::contains;
+
+#[derive(Yokeable)]
+struct Records<'a>(Vec<Sourced<RawRecord<'a>>>);
+
+/// A problem found in the document.
+pub struct Problem {
+    pub span: SourceSpan,
+    pub severity: Option<miette::Severity>,
+    pub code: Option<String>,
+    pub message: String,
+}
+
+impl From<&ReaderError> for Problem {
+    fn from(error: &ReaderError) -> Self {
+        let span = error
+            .labels()
+            .and_then(|mut labels| labels.next())
+            .map_or_else(|| SourceSpan::from((0, 0)), |label| *label.inner());
+
+        let message = match error.help() {
+            Some(help) => format!("{error}\n{help}"),
+            None => error.to_string(),
+        };
+
+        Problem {
+            span,
+            severity: error.severity(),
+            code: error.code().map(|code| code.to_string()),
+            message,
+        }
+    }
+}
+
+pub struct Document {
+    text: Arc<str>,
+    /// The byte offset of the start of each line.
+    line_starts: Vec<usize>,
+    /// The records in the text, if it could be read.
+    records: Option<Yoke<Records<'static>, Arc<str>>>,
+    problems: Vec<Problem>,
+}
+
+impl Document {
+    /// Reads and validates the whole text.
+    pub fn new(reader: &Reader, text: Arc<str>) -> Self {
+        let problems = match reader.validate(&text.as_ref()) {
+            Ok(validation) => validation.errors.iter().map(Problem::from).collect(),
+            Err(err) => vec![Problem::from(&err.source)],
+        };
+
+        let records = Yoke::try_attach_to_cart(text.clone(), |text| {
+            reader
+                .parse_incremental(text)
+                .map(|parsed| Records(parsed.records))
+        })
+        .ok();
+
+        Self {
+            line_starts: line_starts(&text),
+            text,
+            records,
+            problems,
+        }
+    }
+
+    /// Reads and validates the whole text again, including checks
+    /// (such as for missing records) which are skipped by [`Document::edit`].
+    pub fn revalidate(&mut self, reader: &Reader) {
+        *self = Self::new(reader, self.text.clone());
+    }
+
+    /// Replaces the text in the range, or the whole text if there is no range,
+    /// reparsing only the records which were affected.
+    pub fn edit(&mut self, reader: &Reader, range: Option<Range<usize>>, replacement: &str) {
+        let Some(range) = range else {
+            *self = Self::new(reader, replacement.into());
+            return;
+        };
+
+        let text: Arc<str> = [
+            &self.text[..range.start],
+            replacement,
+            &self.text[range.end..],
+        ]
+        .concat()
+        .into();
+
+        let Some(records) = &self.records else {
+            // there is nothing to reuse
+            *self = Self::new(reader, text);
+            return;
+        };
+
+        let edit = Edit::new(range.clone(), replacement.len());
+        let mut region = 0..0;
+        let mut warnings = Vec::new();
+        let reparsed = Yoke::try_attach_to_cart(text.clone(), |text| {
+            let reparsed = reader.reparse(&self.text, &records.get().0, &edit, text)?;
+            region = reparsed.region;
+            warnings = reparsed.warnings;
+            Ok::<_, ReaderError>(Records(reparsed.records))
+        });
+
+        match reparsed {
+            Ok(records) => {
+                // keep the problems outside of the reparsed region, moving those after it
+                let shift = replacement.len() as isize - range.len() as isize;
+                let old_region_end = (region.end as isize - shift) as usize;
+                self.problems.retain_mut(|problem| {
+                    let offset = problem.span.offset();
+                    if offset >= old_region_end {
+                        let offset = (offset as isize + shift) as usize;
+                        problem.span = SourceSpan::from((offset, problem.span.len()));
+                        true
+                    } else {
+                        offset < region.start
+                    }
+                });
+
+                self.problems.extend(warnings.iter().map(Problem::from));
+                self.problems.sort_by_key(|problem| problem.span.offset());
+                self.records = Some(records);
+            }
+            Err(err) => {
+                self.problems = vec![Problem::from(&err)];
+                self.records = None;
+            }
+        }
+
+        self.line_starts = line_starts(&text);
+        self.text = text;
+    }
+
+    pub fn problems(&self) -> &[Problem] {
+        &self.problems
+    }
+
+    /// The top-level records, if the text could be read.
+    pub fn records(&self) -> &[Sourced<RawRecord<'_>>] {
+        match &self.records {
+            Some(records) => &records.get().0,
+            None => &[],
+        }
+    }
+
+    /// The innermost line containing the offset.
+    pub fn line_at(&self, offset: usize) -> Option<&Sourced<RawLine<'_, str>>> {
+        fn find<'a, 'i>(
+            records: &'a [Sourced<RawRecord<'i>>],
+            offset: usize,
+        ) -> Option<&'a Sourced<RawLine<'i, str>>> {
+            let record = records
+                .iter()
+                .find(|record| contains(record.span, offset))?;
+            if contains(record.line.span, offset) {
+                Some(&record.line)
+            } else {
+                find(&record.records, offset)
+            }
+        }
+
+        find(self.records(), offset)
+    }
+
+    /// Converts a position (a line number, and an offset within
+    /// the line in UTF-16 code units) into a byte offset.
+    pub fn offset(&self, line: usize, character: usize) -> usize {
+        let Some(&start) = self.line_starts.get(line) else {
+            return self.text.len();
+        };
+
+        let mut offset = start;
+        let mut units = 0;
+        for char in self.text[start..].chars() {
+            if units >= character || char == '\n' || char == '\r' {
+                break;
+            }
+
+            units += char.len_utf16();
+            offset += char.len_utf8();
+        }
+
+        offset
+    }
+
+    /// Converts a byte offset into a position (a line number, and an
+    /// offset within the line in UTF-16 code units).
+    ///
+    /// The offset might not be valid for the current text (for example, the span of
+    /// a problem which was found before an edit), so it is clamped to the end of the
+    /// text, and moved back to the start of the character which contains it.
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let mut offset = offset.min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.line_starts[line];
+        let character = self
+            .text
+            .get(start..offset)
+            .map_or(0, |text| text.encode_utf16().count());
+        (line, character)
+    }
+}
+
+fn line_starts(text: &str) -> Vec<usize> {
+    let mut starts = vec![0];
+    let bytes = text.as_bytes();
+    for (ix, byte) in bytes.iter().enumerate() {
+        match byte {
+            b'\n' => starts.push(ix + 1),
+            // a CR is a line break on its own unless it is followed by LF
+            b'\r' if bytes.get(ix + 1) != Some(&b'\n') => starts.push(ix + 1),
+            _ => {}
+        }
+    }
+
+    starts
+}
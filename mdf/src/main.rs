@@ -1,20 +1,98 @@
+#![feature(error_generic_member_access)]
+
 use std::{
-    io::{stdout, IsTerminal},
-    path::PathBuf,
+    io::{IsTerminal, stderr},
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::Arc,
     time::Instant,
 };
 
 use fancy_duration::FancyDuration;
 use gedcomfy::{
-    reader::{encodings::Encoding, options::ParseOptions, Reader},
+    graph::FamilyGraph,
+    households::Households,
+    reader::{
+        Reader, Validity,
+        encodings::Encoding,
+        from_kdl,
+        input::File,
+        options::{DuplicateSubrecords, ParseOptions},
+        producers::ProducerReport,
+    },
+    reports,
+    schemas::{
+        AnyFileVersion,
+        places::{Gazetteer, HistoricalJurisdictions},
+        quality::CertaintyAssessment,
+        v551::names::NameIndex,
+    },
+    search::{Pattern, search},
+    site,
     versions::KnownVersion,
+    writer::{LineTerminator, OutputProfile},
+};
+
+use crate::{
+    bench::{bench, table},
+    errors::MdfError,
+    output::{GraphFormat, OutputFormat, PageFormat, ReportFormat, summary, write_output},
+    show::RecordSelector,
 };
 
+mod bench;
 mod components;
+mod errors;
+mod explain;
+mod households;
+mod lsp;
+mod output;
+mod show;
+mod stats;
+mod version;
 
 #[derive(clap::Parser)]
-enum MdfArgs {
+struct MdfArgs {
+    #[command(flatten)]
+    verbosity: VerbosityArgs,
+
+    #[command(subcommand)]
+    command: MdfCommands,
+}
+
+#[derive(clap::Subcommand)]
+enum MdfCommands {
     Gedcom(GedcomArgs),
+    /// Print the version of mdf; with `--verbose`, also the versions of
+    /// its libraries and the GEDCOM versions and encodings they support
+    Version {
+        /// Print everything as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(clap::Args)]
+struct VerbosityArgs {
+    /// Only log errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Log more detail (-v for debug, -vv for trace)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+}
+
+impl VerbosityArgs {
+    fn level(&self) -> tracing::Level {
+        match (self.quiet, self.verbose) {
+            (true, _) => tracing::Level::ERROR,
+            (false, 0) => tracing::Level::INFO,
+            (false, 1) => tracing::Level::DEBUG,
+            (false, _) => tracing::Level::TRACE,
+        }
+    }
 }
 
 #[derive(clap::Args)]
@@ -29,17 +107,195 @@ enum GedcomCommands {
         path: PathBuf,
         #[command(flatten)]
         parse_options: ParseOptionsArgs,
+
+        /// How to format the parsed file
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
+
+        /// Write the output to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
     },
     Validate {
+        /// The file to validate, or a directory containing `.ged` or `.gdz` files
         path: PathBuf,
         #[command(flatten)]
         parse_options: ParseOptionsArgs,
+
+        /// Fail if any warnings are found
+        #[arg(long)]
+        deny_warnings: bool,
     },
     Kdl {
         path: PathBuf,
         #[command(flatten)]
         parse_options: ParseOptionsArgs,
     },
+    /// Print the records of the file as YAML
+    Yaml {
+        path: PathBuf,
+        #[command(flatten)]
+        parse_options: ParseOptionsArgs,
+    },
+    /// Print the records of the file as TOML
+    Toml {
+        path: PathBuf,
+        #[command(flatten)]
+        parse_options: ParseOptionsArgs,
+    },
+    /// Export the individuals and their relationships as a graph
+    Graph {
+        path: PathBuf,
+        #[command(flatten)]
+        parse_options: ParseOptionsArgs,
+
+        /// How to format the graph
+        #[arg(long, value_enum, default_value_t)]
+        format: GraphFormat,
+
+        /// Write the output to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// List the households in each place for a census year, from
+    /// residence and census events and the links between families
+    Households {
+        path: PathBuf,
+        #[command(flatten)]
+        parse_options: ParseOptionsArgs,
+
+        /// The year of the census, e.g. `1881`
+        #[arg(long)]
+        year: i32,
+    },
+    /// Write a report about the individuals in the file
+    Report {
+        #[command(subcommand)]
+        report: ReportCommands,
+    },
+    /// Write a page for each individual into a directory, with links between
+    /// them, for publishing with a static site generator
+    Site {
+        path: PathBuf,
+        #[command(flatten)]
+        parse_options: ParseOptionsArgs,
+
+        /// How to format the pages
+        #[arg(long, value_enum, default_value_t)]
+        format: PageFormat,
+
+        /// The directory to write the pages into, which is created if needed
+        #[arg(long, short)]
+        output: PathBuf,
+    },
+    /// Convert a KDL file (in the shape produced by `kdl`) back into GEDCOM
+    FromKdl {
+        path: PathBuf,
+
+        /// Which conventions to write the GEDCOM with
+        #[arg(long, default_value = "standard")]
+        profile: Profile,
+
+        /// Override the line terminator of the profile
+        #[arg(long)]
+        line_terminator: Option<Terminator>,
+
+        /// Override whether the profile ends the file with a line terminator
+        #[arg(long)]
+        final_newline: Option<bool>,
+
+        /// Write the output to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Show a single record from the file, with its location
+    Show {
+        path: PathBuf,
+        #[command(flatten)]
+        parse_options: ParseOptionsArgs,
+
+        /// The xref of the record to show, e.g. `@I123@`
+        #[arg(required_unless_present = "tag", conflicts_with = "tag")]
+        xref: Option<String>,
+
+        /// Show a record with this tag instead, e.g. `INDI`
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Which of the records with the tag to show, counting from 1
+        #[arg(long, requires = "tag", default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+        nth: u64,
+    },
+    /// Search the values of records, showing where each match is
+    Grep {
+        /// The text to search for
+        pattern: String,
+        path: PathBuf,
+        #[command(flatten)]
+        parse_options: ParseOptionsArgs,
+
+        /// Treat the pattern as a regular expression
+        #[arg(long, short = 'e')]
+        regex: bool,
+    },
+    /// Show statistics about the records in the file
+    Stats {
+        /// The file, or (with `--by-producer`) a directory containing `.ged` or `.gdz` files
+        path: PathBuf,
+        #[command(flatten)]
+        parse_options: ParseOptionsArgs,
+
+        /// How many of the most common names (or problems) to show
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+
+        /// Show the problems found in the files, grouped by the product which produced them
+        #[arg(long)]
+        by_producer: bool,
+    },
+    /// Explain a diagnostic code (e.g. `gedcom::encoding::invalid_bom`) or a tag (e.g. `BIRT`)
+    Explain {
+        /// The code or tag to explain
+        name: String,
+    },
+    /// Run a language server for GEDCOM files, communicating over stdin and stdout
+    Lsp {
+        #[command(flatten)]
+        parse_options: ParseOptionsArgs,
+    },
+    /// Time each phase of reading the file
+    Bench {
+        path: PathBuf,
+        #[command(flatten)]
+        parse_options: ParseOptionsArgs,
+
+        /// How many times to read the file
+        #[arg(long, default_value = "10")]
+        iterations: NonZeroU32,
+    },
+}
+
+#[derive(clap::Subcommand)]
+enum ReportCommands {
+    /// A narrative of the descendants of an individual, by generation,
+    /// in the style of the NEHGS Register
+    Descendants {
+        path: PathBuf,
+        #[command(flatten)]
+        parse_options: ParseOptionsArgs,
+
+        /// The xref of the individual whose descendants to report, e.g. `@I1@`
+        #[arg(long)]
+        root: String,
+
+        /// How to format the report
+        #[arg(long, value_enum, default_value_t)]
+        format: ReportFormat,
+
+        /// Write the output to a file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
 }
 
 #[derive(clap::Args)]
@@ -50,6 +306,59 @@ struct ParseOptionsArgs {
 
     #[arg(long)]
     force_version: Option<ForcedVersion>,
+
+    /// Join lines which cannot be parsed onto the value of the previous line
+    #[arg(long)]
+    repair_line_breaks: bool,
+
+    /// Report lines whose level is too deep, but keep reading the file
+    #[arg(long)]
+    recover_invalid_levels: bool,
+
+    /// Ignore byte order marks repeated at the start of the file
+    #[arg(long)]
+    skip_repeated_bom: bool,
+
+    /// Ignore NUL characters at the end of the file
+    #[arg(long)]
+    trim_trailing_nuls: bool,
+
+    /// Report problems at their byte offsets in the original file,
+    /// rather than in the decoded text
+    #[arg(long)]
+    raw_offsets: bool,
+
+    /// Keep one of a repeated subrecord which may only appear once, rather than failing
+    #[arg(long)]
+    duplicate_subrecords: Option<Duplicates>,
+
+    /// Check that the places of events existed at the time of the events
+    #[arg(long)]
+    check_places: bool,
+
+    /// Report individuals who married younger than this age
+    #[arg(long)]
+    min_marriage_age: Option<u32>,
+
+    /// Report events which are only cited by sources with a lower QUAY than this
+    #[arg(long)]
+    min_citation_quality: Option<Quality>,
+
+    /// Fail if any line has a level deeper than this
+    #[arg(long)]
+    max_level: Option<usize>,
+
+    /// Fail if any line is longer than this many bytes
+    #[arg(long)]
+    max_line_length: Option<usize>,
+
+    /// Fail if the file contains more than this many records, including subrecords
+    #[arg(long)]
+    max_records: Option<usize>,
+
+    /// Fail if the file could be larger than this many bytes, once decoded
+    #[arg(long)]
+    max_decoded_size: Option<usize>,
 }
 
 impl From<ParseOptionsArgs> for ParseOptions {
@@ -57,6 +366,26 @@ impl From<ParseOptionsArgs> for ParseOptions {
         ParseOptions::default()
             .force_encoding(args.force_encoding.map(Into::into))
             .force_version(args.force_version.map(Into::into))
+            .repair_line_breaks(args.repair_line_breaks)
+            .recover_invalid_levels(args.recover_invalid_levels)
+            .skip_repeated_bom(args.skip_repeated_bom)
+            .trim_trailing_nuls(args.trim_trailing_nuls)
+            .raw_offsets(args.raw_offsets)
+            .duplicate_subrecords(
+                args.duplicate_subrecords
+                    .map(Into::into)
+                    .unwrap_or_default(),
+            )
+            .gazetteer(
+                args.check_places
+                    .then(|| Arc::new(HistoricalJurisdictions::well_known()) as Arc<dyn Gazetteer>),
+            )
+            .min_marriage_age(args.min_marriage_age)
+            .min_citation_quality(args.min_citation_quality.map(Into::into))
+            .max_level(args.max_level)
+            .max_line_length(args.max_line_length)
+            .max_records(args.max_records)
+            .max_decoded_size(args.max_decoded_size)
     }
 }
 
@@ -77,6 +406,45 @@ pub enum ForcedVersion {
     V7,
 }
 
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum Duplicates {
+    /// Keep the first of the subrecords
+    First,
+    /// Keep the last of the subrecords
+    Last,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum Quality {
+    /// Unreliable evidence or estimated data
+    #[clap(name = "0")]
+    Unreliable,
+    /// Questionable reliability of evidence
+    #[clap(name = "1")]
+    Questionable,
+    /// Secondary evidence
+    #[clap(name = "2")]
+    Secondary,
+    /// Direct and primary evidence
+    #[clap(name = "3")]
+    Primary,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum Profile {
+    /// LF line terminators and a final line terminator
+    Standard,
+    /// CRLF line terminators, no final line terminator, and every `@` in values doubled
+    Legacy,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum Terminator {
+    Lf,
+    Crlf,
+    Cr,
+}
+
 impl From<ForcedEncoding> for Encoding {
     fn from(value: ForcedEncoding) -> Encoding {
         match value {
@@ -86,6 +454,45 @@ impl From<ForcedEncoding> for Encoding {
     }
 }
 
+impl From<Profile> for OutputProfile {
+    fn from(value: Profile) -> OutputProfile {
+        match value {
+            Profile::Standard => OutputProfile::Standard,
+            Profile::Legacy => OutputProfile::Legacy,
+        }
+    }
+}
+
+impl From<Terminator> for LineTerminator {
+    fn from(value: Terminator) -> LineTerminator {
+        match value {
+            Terminator::Lf => LineTerminator::Lf,
+            Terminator::Crlf => LineTerminator::CrLf,
+            Terminator::Cr => LineTerminator::Cr,
+        }
+    }
+}
+
+impl From<Duplicates> for DuplicateSubrecords {
+    fn from(value: Duplicates) -> DuplicateSubrecords {
+        match value {
+            Duplicates::First => DuplicateSubrecords::KeepFirst,
+            Duplicates::Last => DuplicateSubrecords::KeepLast,
+        }
+    }
+}
+
+impl From<Quality> for CertaintyAssessment {
+    fn from(value: Quality) -> CertaintyAssessment {
+        match value {
+            Quality::Unreliable => CertaintyAssessment::Unreliable,
+            Quality::Questionable => CertaintyAssessment::Questionable,
+            Quality::Secondary => CertaintyAssessment::Secondary,
+            Quality::Primary => CertaintyAssessment::Primary,
+        }
+    }
+}
+
 impl From<ForcedVersion> for KnownVersion {
     fn from(value: ForcedVersion) -> KnownVersion {
         match value {
@@ -96,15 +503,29 @@ impl From<ForcedVersion> for KnownVersion {
     }
 }
 
-fn main() -> miette::Result<()> {
-    let args = <MdfArgs as clap::Parser>::parse();
+fn main() -> ExitCode {
+    let args = match <MdfArgs as clap::Parser>::try_parse() {
+        Ok(args) => args,
+        // requests for help or the version are not errors
+        Err(err) if !err.use_stderr() => {
+            _ = err.print();
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => return MdfError::Usage { source: err }.report(),
+    };
+
+    // logs go to stderr so that they don't interfere with command output
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(args.verbosity.level())
+        .with_writer(stderr);
 
-    if stdout().is_terminal() {
-        // TODO
+    if stderr().is_terminal() {
+        // interactive, format log messages compactly for humans
+        subscriber.compact().init();
     } else {
-        // non-interactive, format log messages using default `fmt`
+        // non-interactive, format log messages as JSON for processing
+        subscriber.json().init();
     }
-    tracing_subscriber::fmt::init();
 
     miette::set_hook(Box::new(|_| {
         Box::new(
@@ -112,35 +533,210 @@ fn main() -> miette::Result<()> {
                 .with_syntax_highlighting(gedcomfy::highlighting::GEDCOMHighlighter {})
                 .build(),
         )
-    }))?;
+    }))
+    .expect("the hook is only set once");
 
-    match args {
-        MdfArgs::Gedcom(args) => match args.command {
-            GedcomCommands::Kdl {
-                path,
-                parse_options,
-            } => {
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => err.report(),
+    }
+}
+
+fn run(args: MdfArgs) -> Result<(), MdfError> {
+    match args.command {
+        // `--verbose` is the global verbosity flag
+        MdfCommands::Version { json: true } => println!("{:#}", version::json()),
+        MdfCommands::Version { json: false } => {
+            print!("{}", version::text(args.verbosity.verbose > 0))
+        }
+        MdfCommands::Gedcom(args) => match args.command {
+            GedcomCommands::Kdl { path, parse_options } => {
                 let reader = Reader::with_options(parse_options.into());
                 let input = reader.decode_file(path)?;
                 let result = reader.parse_kdl(&input)?;
                 println!("{result}");
             }
-            GedcomCommands::Parse {
-                path,
-                parse_options,
+            GedcomCommands::Yaml { path, parse_options } => {
+                let reader = Reader::with_options(parse_options.into());
+                let input = reader.decode_file(path)?;
+                let result = reader.parse_yaml(&input)?;
+                // UNWRAP: a YAML value can always be serialized
+                print!("{}", serde_yaml::to_string(&result).unwrap());
+            }
+            GedcomCommands::Toml { path, parse_options } => {
+                let reader = Reader::with_options(parse_options.into());
+                let input = reader.decode_file(path)?;
+                let result = reader.parse_toml(&input)?;
+                print!("{result}");
+            }
+            GedcomCommands::Graph { path, parse_options, format, output } => {
+                let reader = Reader::with_options(parse_options.into());
+                let input = reader.decode_file(path)?;
+                let records = reader.raw_records(&input)?;
+                let graph = FamilyGraph::new(&records);
+                let rendered = match format {
+                    GraphFormat::Graphml => graph.to_graphml(),
+                    GraphFormat::Gexf => graph.to_gexf(),
+                };
+
+                write_output(output.as_deref(), &rendered)?;
+            }
+            GedcomCommands::Households { path, parse_options, year } => {
+                let reader = Reader::with_options(parse_options.into());
+                let input = reader.decode_file(path)?;
+                let records = reader.raw_records(&input)?;
+                print!("{}", households::listing(&Households::new(&records, year)));
+            }
+            GedcomCommands::Report {
+                report: ReportCommands::Descendants { path, parse_options, root, format, output },
             } => {
                 let reader = Reader::with_options(parse_options.into());
                 let input = reader.decode_file(path)?;
-                let result = reader.parse(&input)?;
-                // TODO: print warnings
-                println!("{:#?}", result.file);
+                let records = reader.raw_records(&input)?;
+                let root = root.trim_matches('@');
+                let Some(report) = reports::descendants(&records, root, format.into()) else {
+                    let selector = RecordSelector::Xref(root.to_owned());
+                    return Err(MdfError::RecordNotFound { selector });
+                };
+
+                write_output(output.as_deref(), &report)?;
+            }
+            GedcomCommands::Site { path, parse_options, format, output } => {
+                let reader = Reader::with_options(parse_options.into());
+                let input = reader.decode_file(path)?;
+                let records = reader.raw_records(&input)?;
+                std::fs::create_dir_all(&output)
+                    .map_err(|source| MdfError::Output { source, path: output.clone() })?;
+
+                for page in site::pages(&records, format.into()) {
+                    write_output(Some(&output.join(page.file_name)), &page.content)?;
+                }
             }
-            GedcomCommands::Validate {
+            GedcomCommands::FromKdl {
                 path,
-                parse_options,
+                profile,
+                line_terminator,
+                final_newline,
+                output,
             } => {
+                let text = std::fs::read_to_string(&path)
+                    .map_err(|source| MdfError::Input { source, path: path.clone() })?;
+
+                let mut options = OutputProfile::from(profile).options();
+                if let Some(line_terminator) = line_terminator {
+                    options = options.line_terminator(line_terminator.into());
+                }
+
+                if let Some(final_newline) = final_newline {
+                    options = options.final_newline(final_newline);
+                }
+
+                let gedcom = from_kdl::to_gedcom_with_options(&text, options)
+                    .map_err(|source| MdfError::KdlImport { source, path, text })?;
+
+                // make sure that the result can be read back; schema problems are
+                // left for `validate`, so that invalid files can still be edited
+                Reader::default()
+                    .raw_records(&gedcom.as_str())
+                    .map_err(|err| err.into_owned())?;
+
+                write_output(output.as_deref(), &gedcom)?;
+            }
+            GedcomCommands::Show { path, parse_options, xref, tag, nth } => {
+                let selector = match (xref, tag) {
+                    (Some(xref), _) => RecordSelector::Xref(xref.trim_matches('@').to_owned()),
+                    (None, Some(tag)) => RecordSelector::Tag { tag, nth: nth as usize },
+                    (None, None) => unreachable!("clap requires one of xref or --tag"),
+                };
+
+                let reader = Reader::with_options(parse_options.into());
+                let input = reader.decode_file(&path)?;
+                let records = reader.raw_records(&input)?;
+                let Some(record) = selector.find(&records) else {
+                    return Err(MdfError::RecordNotFound { selector });
+                };
+
+                let source_name = path.display().to_string();
+                print!("{}", show::render(input.as_ref(), &source_name, record));
+            }
+            GedcomCommands::Grep { pattern, path, parse_options, regex } => {
+                let pattern = if regex {
+                    Pattern::regex(&pattern)
+                        .map_err(|source| MdfError::InvalidPattern { source })?
+                } else {
+                    Pattern::literal(pattern)
+                };
+
+                let reader = Reader::with_options(parse_options.into());
+                let input = reader.decode_file(&path)?;
+                let records = reader.raw_records(&input)?;
+                let source_name = path.display().to_string();
+                for found in search(&records, &pattern) {
+                    let message = match found.xref {
+                        Some(xref) => format!("@{xref}@ {}", found.path_string()),
+                        None => found.path_string(),
+                    };
+
+                    print!(
+                        "{}",
+                        show::render_span(input.as_ref(), &source_name, found.span, message)
+                    );
+                }
+            }
+            GedcomCommands::Stats { path, parse_options, top, by_producer } => {
+                let reader = Reader::with_options(parse_options.into());
+                if by_producer {
+                    return stats_by_producer(&reader, &path, top);
+                }
+
+                let input = reader.decode_file(path)?;
+                let result = reader.parse(&input)?;
+                let AnyFileVersion::V551(file) = &result.file;
+                print!("{}", summary(&result));
+                print!("{}", stats::names(&NameIndex::new(file), top));
+            }
+            GedcomCommands::Explain { name } => {
+                let Some(explanation) = explain::explain(&name) else {
+                    return Err(MdfError::UnknownExplanation { name });
+                };
+
+                print!("{explanation}");
+            }
+            GedcomCommands::Lsp { parse_options } => {
+                let reader = Reader::with_options(parse_options.into());
+                lsp::serve(reader).map_err(|source| MdfError::Lsp { source })?;
+            }
+            GedcomCommands::Bench { path, parse_options, iterations } => {
+                let reader = Reader::with_options(parse_options.into());
+                let input = File::load(path)?;
+                let metrics = bench(&reader, input.as_ref(), iterations)?;
+                print!("{}", table(&metrics, iterations.get()));
+            }
+            GedcomCommands::Parse { path, parse_options, format, output } => {
+                let reader = Reader::with_options(parse_options.into());
+                let input = reader.decode_file(path)?;
+                let rendered = match format {
+                    // TODO: print warnings
+                    OutputFormat::Debug => format!("{:#?}\n", reader.parse(&input)?.file),
+                    OutputFormat::Json => format!("{:#}\n", reader.parse_json(&input)?),
+                    OutputFormat::Kdl => format!("{}\n", reader.parse_kdl(&input)?),
+                    OutputFormat::Summary => summary(&reader.parse(&input)?),
+                    OutputFormat::Toml => reader.parse_toml(&input)?.to_string(),
+                    // UNWRAP: a YAML value can always be serialized
+                    OutputFormat::Yaml => {
+                        serde_yaml::to_string(&reader.parse_yaml(&input)?).unwrap()
+                    }
+                };
+
+                write_output(output.as_deref(), &rendered)?;
+            }
+            GedcomCommands::Validate { path, parse_options, deny_warnings } => {
                 let start_time = Instant::now();
                 let reader = Reader::with_options(parse_options.into());
+                if path.is_dir() {
+                    return validate_dir(&reader, &path, deny_warnings);
+                }
+
                 let input = reader.decode_file(&path)?;
 
                 println!("File loaded: {}", path.display());
@@ -153,10 +749,80 @@ fn main() -> miette::Result<()> {
                     FancyDuration(start_time.elapsed()).truncate(2)
                 );
 
+                let validity = result.validity;
                 println!("{:?}", miette::Report::new(result));
+
+                match validity {
+                    Validity::Valid => {}
+                    Validity::ValidWithWarnings if !deny_warnings => {}
+                    Validity::ValidWithWarnings => return Err(MdfError::DeniedWarnings),
+                    Validity::Invalid => return Err(MdfError::Invalid),
+                }
             }
         },
     }
 
     Ok(())
 }
+
+/// Reports the problems found in the file (or every GEDCOM file
+/// in the directory), grouped by the product which produced them.
+fn stats_by_producer(reader: &Reader, path: &Path, top: usize) -> Result<(), MdfError> {
+    let mut report = ProducerReport::default();
+    if path.is_dir() {
+        for result in reader.scan_dir(path) {
+            match result {
+                Ok(summary) => report.add_summary(&summary),
+                Err(err) => {
+                    report.add_scan_error(&err);
+                    eprintln!("{:?}", miette::Report::new(err));
+                }
+            }
+        }
+    } else {
+        let input = reader.decode_file(path)?;
+        let validation = reader.validate(&input)?;
+        report.add(reader.producer(&input).as_deref(), &validation);
+    }
+
+    print!("{}", stats::producers(&report, top));
+    Ok(())
+}
+
+/// Validates every GEDCOM file in the directory, failing
+/// according to the worst result among them.
+fn validate_dir(reader: &Reader, path: &Path, deny_warnings: bool) -> Result<(), MdfError> {
+    let start_time = Instant::now();
+    println!("Validating files in: {}", path.display());
+
+    let (mut valid, mut with_warnings, mut invalid) = (0, 0, 0);
+    for result in reader.scan_dir(path) {
+        match result {
+            Ok(summary) => {
+                println!("{}: {}", summary.path.display(), summary.validation);
+                match summary.validation.validity {
+                    Validity::Valid => valid += 1,
+                    Validity::ValidWithWarnings => with_warnings += 1,
+                    Validity::Invalid => invalid += 1,
+                }
+            }
+            Err(err) => {
+                eprintln!("{:?}", miette::Report::new(err));
+                invalid += 1;
+            }
+        }
+    }
+
+    println!(
+        "Completed in {}: {valid} valid, {with_warnings} valid with warnings, {invalid} invalid",
+        FancyDuration(start_time.elapsed()).truncate(2)
+    );
+
+    if invalid > 0 {
+        Err(MdfError::Invalid)
+    } else if with_warnings > 0 && deny_warnings {
+        Err(MdfError::DeniedWarnings)
+    } else {
+        Ok(())
+    }
+}
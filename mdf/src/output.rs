@@ -0,0 +1,126 @@
+//! Formats for the output of `mdf gedcom parse`.
+
+use std::{fmt::Write, path::Path};
+
+use gedcomfy::{
+    reader::ParseResult,
+    reports,
+    schemas::{AnyFileVersion, v551::TopLevelRecord},
+    site,
+};
+
+use crate::errors::MdfError;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default)]
+pub enum OutputFormat {
+    /// The parsed file, as a Rust debug representation
+    #[default]
+    Debug,
+    /// The records of the file, as JSON
+    Json,
+    /// The records of the file, as KDL
+    Kdl,
+    /// A human-readable summary of the file
+    Summary,
+    /// The records of the file, as TOML
+    Toml,
+    /// The records of the file, as YAML
+    Yaml,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default)]
+pub enum GraphFormat {
+    /// GraphML, e.g. for Cytoscape
+    #[default]
+    Graphml,
+    /// GEXF, e.g. for Gephi
+    Gexf,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default)]
+pub enum ReportFormat {
+    /// Plain text
+    #[default]
+    Text,
+    /// Markdown, with the citations as footnotes
+    #[clap(name = "md", alias = "markdown")]
+    Markdown,
+}
+
+impl From<ReportFormat> for reports::ReportFormat {
+    fn from(value: ReportFormat) -> reports::ReportFormat {
+        match value {
+            ReportFormat::Text => reports::ReportFormat::Text,
+            ReportFormat::Markdown => reports::ReportFormat::Markdown,
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default)]
+pub enum PageFormat {
+    /// Markdown, with front matter for static site generators
+    #[default]
+    #[clap(name = "md", alias = "markdown")]
+    Markdown,
+    /// Standalone HTML pages
+    Html,
+}
+
+impl From<PageFormat> for site::PageFormat {
+    fn from(value: PageFormat) -> site::PageFormat {
+        match value {
+            PageFormat::Markdown => site::PageFormat::Markdown,
+            PageFormat::Html => site::PageFormat::Html,
+        }
+    }
+}
+
+/// Summarizes the records in a parsed file.
+pub fn summary(result: &ParseResult) -> String {
+    let AnyFileVersion::V551(file) = &result.file;
+
+    let mut counts: [(&str, usize); 5] = [
+        ("Individuals", 0),
+        ("Families", 0),
+        ("Sources", 0),
+        ("Submitters", 0),
+        ("Submissions", 0),
+    ];
+
+    for record in &file.records {
+        let ix = match record {
+            TopLevelRecord::Individual(_) => 0,
+            TopLevelRecord::Family(_) => 1,
+            TopLevelRecord::Source(_) => 2,
+            TopLevelRecord::Submitter(_) => 3,
+            TopLevelRecord::Submission(_) => 4,
+        };
+
+        counts[ix].1 += 1;
+    }
+
+    let mut summary = String::new();
+    _ = writeln!(
+        summary,
+        "GEDCOM 5.5.1 file with {} records",
+        file.records.len()
+    );
+    for (name, count) in counts {
+        _ = writeln!(summary, "  {name}: {count}");
+    }
+
+    _ = writeln!(summary, "Warnings: {}", result.non_fatals.len());
+    summary
+}
+
+/// Writes the output to the given file, or to stdout if there is none.
+pub fn write_output(path: Option<&Path>, output: &str) -> Result<(), MdfError> {
+    match path {
+        Some(path) => std::fs::write(path, output)
+            .map_err(|source| MdfError::Output { source, path: path.to_owned() }),
+        None => {
+            print!("{output}");
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Finding and displaying a single record, for `mdf gedcom show`
+//! (and displaying matches, for `mdf gedcom grep`).
+
+use std::io::{IsTerminal, stdout};
+
+use gedcomfy::{
+    highlighting::GEDCOMHighlighter,
+    reader::{Sourced, records::RawRecord},
+};
+use miette::SourceSpan;
+use snippets::{LabelRenderer, Severity, Span, Theme};
+use vec1::Vec1;
+
+/// How to pick out a record from the file.
+#[derive(Debug, derive_more::Display)]
+pub enum RecordSelector {
+    /// The record with the given xref.
+    #[display("@{_0}@")]
+    Xref(String),
+    /// The nth record (counting from 1) with the given tag.
+    #[display("{tag} record #{nth}")]
+    Tag { tag: String, nth: usize },
+}
+
+impl RecordSelector {
+    /// Finds the selected record amongst the top-level records.
+    pub fn find<'r, 'i>(
+        &self,
+        records: &'r [Sourced<RawRecord<'i>>],
+    ) -> Option<&'r Sourced<RawRecord<'i>>> {
+        match self {
+            RecordSelector::Xref(xref) => records.iter().find(|record| {
+                record
+                    .line
+                    .xref
+                    .as_ref()
+                    .is_some_and(|x| x.sourced_value == xref.as_str())
+            }),
+            RecordSelector::Tag { tag, nth } => records
+                .iter()
+                .filter(|record| record.line.tag.as_str() == tag)
+                .nth(nth.checked_sub(1)?),
+        }
+    }
+}
+
+/// Renders the record along with the surrounding source code and its line numbers.
+pub fn render(source_code: &str, source_name: &str, record: &Sourced<RawRecord>) -> String {
+    let message = match &record.line.xref {
+        Some(xref) => format!(
+            "{} record @{}@",
+            record.line.tag.as_str(),
+            xref.sourced_value
+        ),
+        None => format!("{} record", record.line.tag.as_str()),
+    };
+
+    render_span(source_code, source_name, record.span, message)
+}
+
+/// Renders the source code around the span, with the span labelled by the message.
+pub fn render_span(
+    source_code: &str,
+    source_name: &str,
+    span: SourceSpan,
+    message: String,
+) -> String {
+    let span = Span::new(span.offset().into(), span.len().into());
+    let label = snippets::Label::new(span, message.into(), owo_colors::Style::new())
+        .with_severity(Severity::Info);
+
+    let renderer = LabelRenderer::new(source_code, Some(source_name));
+    let renderer = if stdout().is_terminal() {
+        renderer
+            .with_theme(Theme::detect())
+            .with_highlighter(&GEDCOMHighlighter {})
+    } else {
+        renderer
+    };
+
+    renderer.render_to_string(Vec1::new(label))
+}
@@ -0,0 +1,80 @@
+//! Statistics about the contents of files, for `mdf gedcom stats`.
+
+use std::fmt::Write;
+
+use gedcomfy::{reader::producers::ProducerReport, schemas::v551::names::NameIndex};
+
+/// Lists the most common surnames, and the most common given names
+/// for each decade of birth.
+pub fn names(index: &NameIndex, top: usize) -> String {
+    let mut output = String::new();
+    _ = writeln!(output, "Most common surnames:");
+    for (surname, count) in index.surnames().into_iter().take(top) {
+        _ = writeln!(output, "  {surname}: {count}");
+    }
+
+    _ = writeln!(output, "Most common given names, by decade of birth:");
+    let decades = index.decades().map(Some).chain([None]);
+    for decade in decades {
+        let given_names = index.given_names_in_decade(decade);
+        if given_names.is_empty() {
+            continue;
+        }
+
+        let given_names: Vec<String> = given_names
+            .into_iter()
+            .take(top)
+            .map(|(name, count)| format!("{name} ({count})"))
+            .collect();
+
+        let decade = match decade {
+            Some(decade) => format!("{decade}s"),
+            None => "unknown".to_string(),
+        };
+
+        _ = writeln!(output, "  {decade}: {}", given_names.join(", "));
+    }
+
+    output
+}
+
+/// Lists the most common problems in the files from each producer.
+pub fn producers(report: &ProducerReport, top: usize) -> String {
+    let mut output = String::new();
+    for (producer, stats) in report.producers() {
+        let files = plural(stats.file_count, "file");
+        let unreadable = match stats.unreadable_count {
+            0 => String::new(),
+            count => format!(", {count} unreadable"),
+        };
+
+        _ = writeln!(
+            output,
+            "{} ({files}{unreadable}):",
+            producer.unwrap_or("Unknown producer")
+        );
+
+        let problems = stats.problems();
+        if problems.is_empty() {
+            _ = writeln!(output, "  no problems found");
+        }
+
+        for (code, count) in problems.into_iter().take(top) {
+            _ = writeln!(
+                output,
+                "  {code}: in {} of {files} ({})",
+                count.files,
+                plural(count.occurrences, "occurrence")
+            );
+        }
+    }
+
+    output
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("{count} {noun}"),
+        _ => format!("{count} {noun}s"),
+    }
+}
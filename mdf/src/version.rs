@@ -0,0 +1,61 @@
+//! Version information, for `mdf version`.
+
+use std::fmt::{Display, Write};
+
+use gedcomfy::{capabilities, capabilities::Capabilities};
+use serde_json::{Value, json};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The version of `mdf`; with `verbose`, also the versions of the libraries
+/// it uses and what they support, for including in bug reports.
+pub fn text(verbose: bool) -> String {
+    let mut output = String::new();
+    _ = writeln!(output, "mdf {VERSION}");
+    if !verbose {
+        return output;
+    }
+
+    let Capabilities {
+        version,
+        features,
+        versions,
+        encodings,
+        export_modes,
+    } = capabilities();
+    _ = writeln!(
+        output,
+        "  gedcomfy {version} (features: {})",
+        features.join(", ")
+    );
+    _ = writeln!(output, "  errful {}", errful::VERSION);
+    _ = writeln!(output, "  snippets {}", snippets::VERSION);
+    _ = writeln!(output, "GEDCOM versions: {}", names(versions).join(", "));
+    _ = writeln!(output, "Encodings: {}", names(encodings).join(", "));
+    _ = writeln!(output, "Export modes: {}", names(&export_modes).join(", "));
+    output
+}
+
+/// Everything shown by `text` with `verbose`, as JSON.
+pub fn json() -> Value {
+    let Capabilities {
+        version,
+        features,
+        versions,
+        encodings,
+        export_modes,
+    } = capabilities();
+    json!({
+        "mdf": VERSION,
+        "gedcomfy": { "version": version, "features": features },
+        "errful": errful::VERSION,
+        "snippets": snippets::VERSION,
+        "gedcom_versions": names(versions),
+        "encodings": names(encodings),
+        "export_modes": names(&export_modes),
+    })
+}
+
+fn names(items: &[impl Display]) -> Vec<String> {
+    Vec::from_iter(items.iter().map(ToString::to_string))
+}
@@ -0,0 +1,33 @@
+use std::borrow::Cow;
+
+use owo_colors::Style;
+
+/// A decoration shown in the gutter next to a line, independent of any labels;
+/// e.g. a breakpoint, a severity icon, or a changed-line marker.
+///
+/// Marks are only shown for lines which are already part of the snippet
+/// (either because they are labelled or are shown for context).
+#[derive(Debug, Clone)]
+pub struct GutterMark<'a> {
+    pub(crate) line_number: usize,
+    pub(crate) text: Cow<'a, str>,
+    pub(crate) style: Style,
+}
+
+impl<'a> GutterMark<'a> {
+    /// Creates a mark for the given (one-based) line number.
+    #[inline(always)]
+    pub fn new(line_number: usize, text: Cow<'a, str>, style: Style) -> Self {
+        Self { line_number, text, style }
+    }
+
+    #[inline(always)]
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    #[inline(always)]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
@@ -0,0 +1,16 @@
+use owo_colors::Styled;
+
+/// Provides syntax highlighting for the source code shown in a snippet.
+///
+/// The styles returned here are applied to the source text first, and then
+/// label styles are layered on top: wherever a label with a non-plain style
+/// covers the text, the label style takes precedence.
+pub trait SourceHighlighter {
+    /// Highlights a single line of source (without its line terminator).
+    ///
+    /// The returned pieces must be in order and, when concatenated, form a
+    /// prefix of the line. Any part of the line which is not covered is
+    /// rendered without highlighting, as is everything from the first piece
+    /// which would end past the line or within a character.
+    fn highlight_line<'s>(&self, line: &'s str) -> Vec<Styled<&'s str>>;
+}
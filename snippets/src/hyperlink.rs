@@ -0,0 +1,30 @@
+//! Support for OSC 8 terminal hyperlinks.
+//!
+//! See: <https://gist.github.com/egmontkob/eb114294efbcd5adb1944c9f3cb5feda>
+
+/// The sequence which terminates a hyperlink.
+pub(crate) const CLOSE: &str = "\x1b]8;;\x1b\\";
+
+/// Produces the sequence which opens a hyperlink to the given URL.
+///
+/// Returns `None` if the URL cannot be safely embedded in the
+/// escape sequence; the spec only permits bytes in the range 32–126,
+/// and we additionally reject spaces and empty URLs. Anything else
+/// (notably `ESC` and `BEL`) could terminate the sequence early and
+/// allow the URL to inject arbitrary escapes into the output.
+pub(crate) fn open(url: &str) -> Option<String> {
+    if url.is_empty() || !url.bytes().all(|b| b.is_ascii_graphic()) {
+        return None;
+    }
+
+    Some(format!("\x1b]8;;{url}\x1b\\"))
+}
+
+/// Wraps the text in a hyperlink to the given URL,
+/// or returns it unchanged if the URL is not safe to embed.
+pub(crate) fn wrap(url: &str, text: &str) -> String {
+    match open(url) {
+        Some(open) => format!("{open}{text}{CLOSE}"),
+        None => text.to_string(),
+    }
+}
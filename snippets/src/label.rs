@@ -2,19 +2,48 @@ use std::borrow::Cow;
 
 use complex_indifference::{Count, Index, Span};
 use owo_colors::Style;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::theme::Severity;
 
 #[derive(Debug, Clone)]
 pub struct Label<'a> {
     pub(crate) span: Span<u8>,
     pub(crate) message: Cow<'a, str>,
     pub(crate) style: Style,
+    pub(crate) url: Option<Cow<'a, str>>,
+    pub(crate) suggestion: Option<Cow<'a, str>>,
+    pub(crate) severity: Option<Severity>,
+    // the position of the label in the list provided by the caller
+    pub(crate) order: usize,
     pub(crate) is_multiline_end: bool,
 }
 
 impl<'a> Label<'a> {
     #[inline(always)]
     pub fn new(span: Span<u8>, message: Cow<'a, str>, style: Style) -> Self {
-        Self { span, message, style, is_multiline_end: false }
+        Self {
+            span,
+            message,
+            style,
+            url: None,
+            suggestion: None,
+            severity: None,
+            order: 0,
+            is_multiline_end: false,
+        }
+    }
+
+    /// Creates a label from a span measured in some other unit than bytes
+    /// (such as [`char`]s or [`Grapheme`]s), converting it using the source
+    /// code which the label refers to.
+    pub fn in_source<U: SpanUnit>(
+        source_code: &str,
+        span: Span<U>,
+        message: Cow<'a, str>,
+        style: Style,
+    ) -> Self {
+        Self::new(U::byte_span(source_code, span), message, style)
     }
 
     #[inline(always)]
@@ -22,11 +51,47 @@ impl<'a> Label<'a> {
         Self { style, ..self }
     }
 
+    /// Sets the severity of the label; if the label has no style of
+    /// its own, the style for the severity is taken from the theme.
+    #[inline(always)]
+    pub fn with_severity(self, severity: Severity) -> Self {
+        Self { severity: Some(severity), ..self }
+    }
+
+    /// Attaches a URL to the label; when hyperlinks are enabled on the renderer,
+    /// the label message will be emitted as an OSC 8 hyperlink to it.
+    #[inline(always)]
+    pub fn with_url(self, url: Cow<'a, str>) -> Self {
+        Self { url: Some(url), ..self }
+    }
+
+    /// Attaches a suggested replacement for the labelled text; the renderer
+    /// will show the affected lines as a `-`/`+` patch after the snippet.
+    #[inline(always)]
+    pub fn with_suggestion(self, suggestion: Cow<'a, str>) -> Self {
+        Self { suggestion: Some(suggestion), ..self }
+    }
+
     #[inline(always)]
     pub fn message(&self) -> &str {
         &self.message
     }
 
+    #[inline(always)]
+    pub fn severity(&self) -> Option<Severity> {
+        self.severity
+    }
+
+    #[inline(always)]
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    #[inline(always)]
+    pub fn suggestion(&self) -> Option<&str> {
+        self.suggestion.as_deref()
+    }
+
     #[inline(always)]
     pub fn start(&self) -> Index<u8> {
         self.span.start()
@@ -44,3 +109,45 @@ impl<'a> Label<'a> {
         self
     }
 }
+
+/// A unit in which the position of a label can be given; see [`Label::in_source`].
+pub trait SpanUnit {
+    /// Where the unit at `index` starts in `source_code`; indices
+    /// past the end of the source code are moved to its end.
+    fn byte_index(source_code: &str, index: Index<Self>) -> Index<u8>;
+
+    fn byte_span(source_code: &str, span: Span<Self>) -> Span<u8> {
+        let start = Self::byte_index(source_code, span.start());
+        let end = Self::byte_index(source_code, span.end());
+        Span::try_from_indices(start, end).unwrap() // UNWRAP: guaranteed, units are in order
+    }
+}
+
+impl SpanUnit for u8 {
+    #[inline(always)]
+    fn byte_index(_source_code: &str, index: Index<u8>) -> Index<u8> {
+        index
+    }
+
+    #[inline(always)]
+    fn byte_span(_source_code: &str, span: Span<u8>) -> Span<u8> {
+        span
+    }
+}
+
+impl SpanUnit for char {
+    fn byte_index(source_code: &str, index: Index<char>) -> Index<u8> {
+        let offset = source_code.char_indices().nth(index.as_usize());
+        Index::new(offset.map_or(source_code.len(), |(offset, _)| offset))
+    }
+}
+
+/// An extended grapheme cluster (a user-perceived character).
+pub enum Grapheme {}
+
+impl SpanUnit for Grapheme {
+    fn byte_index(source_code: &str, index: Index<Grapheme>) -> Index<u8> {
+        let offset = source_code.grapheme_indices(true).nth(index.as_usize());
+        Index::new(offset.map_or(source_code.len(), |(offset, _)| offset))
+    }
+}
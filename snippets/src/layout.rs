@@ -0,0 +1,97 @@
+//! The structured model produced by the layout pass.
+//!
+//! This can be used by consumers which want to draw snippets themselves
+//! (e.g. in a GUI) while reusing the label placement logic.
+
+use std::borrow::Cow;
+
+use owo_colors::Style;
+
+use crate::{Theme, hyperlink};
+
+/// The result of laying out a snippet, prior to rendering it as text.
+#[derive(Debug, Clone)]
+pub struct SnippetLayout<'a> {
+    pub source_name: Option<&'a str>,
+    /// The number of columns needed to display the largest line number.
+    pub line_number_width: usize,
+    /// The number of columns needed to display the widest gutter mark;
+    /// zero if there are no marks.
+    pub mark_width: usize,
+    pub rows: Vec<Row<'a>>,
+}
+
+/// A single row of output.
+#[derive(Debug, Clone)]
+pub struct Row<'a> {
+    pub kind: RowKind,
+    /// The (one-based) line number to show in the gutter, if any.
+    pub line_number: Option<usize>,
+    /// The number of multi-line labels which are open during this row.
+    pub multiline_depth: usize,
+    /// The box-drawing characters shown between the gutter and the content.
+    pub ruler: &'static str,
+    /// The ruler to use if the content has to be wrapped onto more rows.
+    pub continuation: &'static str,
+    /// The gutter mark shown next to the line number, if any.
+    pub mark: Option<StyledRun<'a>>,
+    pub runs: Vec<StyledRun<'a>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowKind {
+    /// A source line which has labels pointing into it.
+    Source,
+    /// A source line shown only for context.
+    Context,
+    /// Marks a place where source lines have been skipped.
+    Elision,
+    /// Indicates the labelled portions of the preceding source line.
+    Indicator,
+    /// A label message.
+    Message,
+    /// The message of a label which spans multiple lines.
+    MultilineMessage,
+    /// Separates a suggested patch from the preceding rows.
+    PatchSeparator,
+    /// A line of the source as it currently is.
+    PatchRemoved,
+    /// A line of the source as it would be after applying a suggestion.
+    PatchAdded,
+    /// Underlines the changed portion of the preceding patch line.
+    PatchUnderline,
+}
+
+/// A piece of text with a uniform style.
+#[derive(Debug, Clone)]
+pub struct StyledRun<'a> {
+    pub text: Cow<'a, str>,
+    pub style: Style,
+    /// The URL of the label that this text belongs to, if any.
+    pub url: Option<Cow<'a, str>>,
+}
+
+impl<'a> StyledRun<'a> {
+    pub fn new(text: impl Into<Cow<'a, str>>, style: Style) -> Self {
+        Self { text: text.into(), style, url: None }
+    }
+
+    pub fn with_url(self, url: Option<Cow<'a, str>>) -> Self {
+        Self { url, ..self }
+    }
+}
+
+/// Formats the runs as text, using ANSI escapes for styles
+/// and (optionally) OSC 8 escapes for URLs.
+pub(crate) fn format_runs(runs: &[StyledRun], theme: &Theme, hyperlinks: bool) -> String {
+    let styled = Vec::from_iter(runs.iter().map(|run| {
+        let text = match run.url.as_deref() {
+            Some(url) if hyperlinks => Cow::Owned(hyperlink::wrap(url, &run.text)),
+            _ => Cow::Borrowed(run.text.as_ref()),
+        };
+
+        theme.apply(run.style).style(text)
+    }));
+
+    format!("{}", owo_colors::StyledList::from(styled))
+}
@@ -4,31 +4,46 @@
 pub use complex_indifference::Span;
 use vec1::Vec1;
 
+mod gutter;
+mod highlighter;
+mod hyperlink;
 pub mod label;
+pub mod layout;
+mod line_index;
 mod linelighter;
+pub mod markup;
 mod renderer;
+mod suggestion;
+pub mod theme;
 
-pub use label::Label;
-use renderer::LabelRenderer;
+pub use gutter::GutterMark;
+pub use highlighter::SourceHighlighter;
+pub use label::{Grapheme, Label, SpanUnit};
+pub use layout::SnippetLayout;
+pub use line_index::LineIndex;
+pub use renderer::{LabelRenderer, MessageOrder};
+pub use theme::{ColorLevel, Severity, Theme};
+
+/// The version of the `snippets` crate.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub fn render_labels<W: std::fmt::Write>(
     source_code: &str,
     source_name: Option<&str>,
-    mut labels: Vec1<Label>,
+    labels: Vec1<Label>,
     destination: &mut W,
 ) -> Result<(), std::fmt::Error> {
-    // ensure that all labels indices are valid
-    // - we do not want to panic because of a bug in the caller,
-    //   because snippets could be rendered during panic rendering
-    for label in &mut labels {
-        let span = label.span;
-        let start_ix = source_code.floor_char_boundary(span.start().as_usize());
-        let end_ix = source_code.ceil_char_boundary(span.end().as_usize());
-        // UNWRAP: since span is already ordered, we know that start_ix <= end_ix
-        label.span = Span::try_from_indices(start_ix.into(), end_ix.into()).unwrap();
-    }
-
-    LabelRenderer::new(source_code, source_name).render_spans(labels.into(), destination)
+    LabelRenderer::new(source_code, source_name).render(labels, destination)
+}
+
+/// Computes the layout of the labelled snippet without rendering it,
+/// for consumers which want to draw the result themselves.
+pub fn compute_layout<'a>(
+    source_code: &'a str,
+    source_name: Option<&'a str>,
+    labels: Vec1<Label<'a>>,
+) -> SnippetLayout<'a> {
+    LabelRenderer::new(source_code, source_name).layout(labels)
 }
 
 pub fn render_labels_to_string(
@@ -36,11 +51,7 @@ pub fn render_labels_to_string(
     source_name: Option<&str>,
     labels: Vec1<Label>,
 ) -> String {
-    let mut result = String::new();
-    // UNWRAP: writing to the String should never fail
-    // this is checked by the fuzz testing
-    render_labels(source_code, source_name, labels, &mut result).unwrap();
-    result
+    LabelRenderer::new(source_code, source_name).render_to_string(labels)
 }
 
 #[cfg(test)]
@@ -49,7 +60,10 @@ mod test {
     use insta::assert_snapshot;
     use owo_colors::Style;
 
-    use super::{Label, render_labels_to_string};
+    use super::{
+        ColorLevel, Grapheme, GutterMark, Label, LabelRenderer, LineIndex, MessageOrder, Severity,
+        SourceHighlighter, Theme, render_labels_to_string,
+    };
     use crate::renderer::sort_labels;
 
     fn span_of(source: &str, word: &str) -> Span<u8> {
@@ -711,6 +725,25 @@ mod test {
         "#);
     }
 
+    #[test]
+    fn multi_line_after_context() {
+        let source_code = "\
+        one\ntwo\nhello,\nworld!\n\
+        ";
+
+        let result = highlight_many(source_code, &[("hello,\nworld!", "the label")]);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 │ one
+        2 │ two
+        3 ┢╸hello,
+        4 ┃ world!
+          ┡━╸the label
+          └
+        "#);
+    }
+
     #[test]
     fn partway_multi() {
         let source_code = "\
@@ -829,4 +862,546 @@ mod test {
           └
         "#);
     }
+
+    #[test]
+    fn hyperlink_label() {
+        let source_code = "hello, world!";
+        let labels = vec1::vec1![
+            make_label(source_code, "hello", "greeting")
+                .with_url("https://example.com/greeting".into())
+        ];
+
+        let result = LabelRenderer::new(source_code, None)
+            .with_hyperlinks(true)
+            .render_to_string(labels);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 │ hello, world!
+          │ ├───┘
+          │ └╴]8;;https://example.com/greeting\greeting]8;;\
+          └
+        "#);
+    }
+
+    #[test]
+    fn hyperlink_label_disabled() {
+        let source_code = "hello, world!";
+        let labels = vec1::vec1![
+            make_label(source_code, "hello", "greeting")
+                .with_url("https://example.com/greeting".into())
+        ];
+
+        let result = render_labels_to_string(source_code, None, labels);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 │ hello, world!
+          │ ├───┘
+          │ └╴greeting
+          └
+        "#);
+    }
+
+    #[test]
+    fn hyperlink_label_unsafe_url() {
+        let source_code = "hello, world!";
+        let labels = vec1::vec1![
+            make_label(source_code, "hello", "greeting")
+                .with_url("https://example.com/\x1b[31m".into())
+        ];
+
+        let result = LabelRenderer::new(source_code, None)
+            .with_hyperlinks(true)
+            .render_to_string(labels);
+
+        assert!(!result.contains("\x1b"));
+    }
+
+    struct WordHighlighter;
+
+    impl SourceHighlighter for WordHighlighter {
+        fn highlight_line<'s>(&self, line: &'s str) -> Vec<owo_colors::Styled<&'s str>> {
+            // alternate colours for each word
+            let mut result = Vec::new();
+            let mut rest = line;
+            let mut styles = [Style::new().blue(), Style::new().green()]
+                .into_iter()
+                .cycle();
+            while let Some((word, after)) = rest.split_once(' ') {
+                result.push(styles.next().unwrap().style(word));
+                result.push(Style::new().style(" "));
+                rest = after;
+            }
+
+            result.push(styles.next().unwrap().style(rest));
+            result
+        }
+    }
+
+    #[test]
+    fn syntax_highlighting_plain_label() {
+        let source_code = "line 1\nhello, world!\nline 3";
+        let labels = vec1::vec1![make_label(source_code, "lo, wo", "here")];
+
+        let result = LabelRenderer::new(source_code, None)
+            .with_highlighter(&WordHighlighter)
+            .render_to_string(labels);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 │ [34mline[0m [32m1[0m
+        2 │ [34mhello,[0m [32mworld![0m
+          │    ├────┘
+          │    └╴here
+        3 │ [34mline[0m [32m3[0m
+          └
+        "#);
+    }
+
+    #[test]
+    fn syntax_highlighting_styled_label() {
+        let source_code = "hello, world!";
+        let labels =
+            vec1::vec1![make_label(source_code, "lo, wo", "here").with_style(Style::new().red())];
+
+        let result = LabelRenderer::new(source_code, None)
+            .with_highlighter(&WordHighlighter)
+            .render_to_string(labels);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 │ [34mhel[31mlo, wo[32mrld![0m
+          │    [31m├────┘[0m
+          │ [31m   └╴here[0m
+          └
+        "#);
+    }
+
+    #[test]
+    fn syntax_highlighting_off_char_boundary() {
+        struct ByteHighlighter;
+
+        impl SourceHighlighter for ByteHighlighter {
+            fn highlight_line<'s>(&self, line: &'s str) -> Vec<owo_colors::Styled<&'s str>> {
+                // one byte per character, which is wrong for multi-byte characters
+                Vec::from_iter(line.chars().map(|_| Style::new().blue().style("x")))
+            }
+        }
+
+        let source_code = "ab\nhéllo\ncd";
+        let labels = vec1::vec1![make_label(source_code, "llo", "here")];
+
+        let result = LabelRenderer::new(source_code, None)
+            .with_highlighter(&ByteHighlighter)
+            .render_to_string(labels);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 │ [34mab[0m
+        2 │ [34mh[0méllo
+          │   ├─┘
+          │   └╴here
+        3 │ [34mcd[0m
+          └
+        "#);
+    }
+
+    #[test]
+    fn suggestion_replace() {
+        let source_code = "line 1\nhello, world!\nline 3";
+        let labels = vec1::vec1![
+            make_label(source_code, "hello", "unfriendly greeting").with_suggestion("howdy".into())
+        ];
+
+        let result = render_labels_to_string(source_code, None, labels);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 │ line 1
+        2 │ hello, world!
+          │ ├───┘
+          │ └╴unfriendly greeting
+        3 │ line 3
+          │
+        2 - hello, world!
+          │ -----
+        2 + howdy, world!
+          │ +++++
+          └
+        "#);
+    }
+
+    #[test]
+    fn suggestion_delete() {
+        let source_code = "hello, world!";
+        let labels = vec1::vec1![
+            make_label(source_code, ", world", "unnecessary").with_suggestion("".into())
+        ];
+
+        let result = render_labels_to_string(source_code, None, labels);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 │ hello, world!
+          │      ├─────┘
+          │      └╴unnecessary
+          │
+        1 - hello, world!
+          │      -------
+        1 + hello!
+          └
+        "#);
+    }
+
+    #[test]
+    fn suggestion_multiple_lines() {
+        let source_code = "hello,\nworld!\nline 3";
+        let labels = vec1::vec1![
+            make_label(source_code, "world", "wrong planet").with_suggestion("mars".into()),
+            make_label(source_code, "lo,", "should be one line")
+                .with_suggestion("lo, big\nwide".into()),
+        ];
+
+        let result = render_labels_to_string(source_code, None, labels);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 │ hello,
+          │    ├─┘
+          │    └╴should be one line
+        2 │ world!
+          │ ├───┘
+          │ └╴wrong planet
+        3 │ line 3
+          │
+        1 - hello,
+          │    ---
+        1 + hello, big
+          │    +++++++
+        2 + wide
+          │ ++++
+          │
+        2 - world!
+          │ -----
+        2 + mars!
+          │ ++++
+          └
+        "#);
+    }
+
+    #[test]
+    fn layout_rows() {
+        use crate::layout::RowKind;
+
+        let source_code = "line 1\nhello, world!\nline 3";
+        let labels = vec1::vec1![make_label(source_code, "world", "here")];
+
+        let layout = super::compute_layout(source_code, None, labels);
+
+        assert_eq!(layout.line_number_width, 1);
+        assert_eq!(
+            Vec::from_iter(layout.rows.iter().map(|r| (r.kind, r.line_number))),
+            [
+                (RowKind::Context, Some(1)),
+                (RowKind::Source, Some(2)),
+                (RowKind::Indicator, None),
+                (RowKind::Message, None),
+                (RowKind::Context, Some(3)),
+            ]
+        );
+
+        let source_row = &layout.rows[1];
+        assert_eq!(
+            Vec::from_iter(source_row.runs.iter().map(|r| r.text.as_ref())),
+            ["hello, ", "world", "!"]
+        );
+    }
+
+    #[test]
+    fn theme_severity() {
+        let source_code = "hello, world!";
+        let labels = vec1::vec1![
+            make_label(source_code, "hello", "bad").with_severity(Severity::Error),
+            make_label(source_code, "world", "meh").with_severity(Severity::Warning),
+        ];
+
+        let result = LabelRenderer::new(source_code, None)
+            .with_theme(Theme::dark(ColorLevel::Ansi16))
+            .render_to_string(labels);
+
+        assert_snapshot!(result, @r#"
+        [90m  ┌[0m
+        [90m1 │ [0m[91mhello[0m, [93mworld[0m!
+        [90m  │ [0m[91m├───┘[0m  [93m├───┘[0m
+        [90m  │ [0m[91m└╴bad[0m  [93m│[0m
+        [90m  │ [0m[93m       └╴meh[0m
+        [90m  └[0m
+        "#);
+    }
+
+    #[test]
+    fn theme_message() {
+        let source_code = "hello,\nworld!";
+        let labels = vec1::vec1![
+            make_label(source_code, "hello", "single"),
+            make_label(source_code, "lo,\nwor", "multi"),
+        ];
+
+        // unstyled messages of single- and multi-line labels are styled alike
+        let result = LabelRenderer::new(source_code, None)
+            .with_theme(Theme::dark(ColorLevel::Ansi16))
+            .render_to_string(labels);
+
+        assert_snapshot!(result, @r#"
+        [90m  ┌[0m
+        [90m1 ┢╸[0mhello,
+        [90m  ┃ [0m[97m├───┘[0m
+        [90m  ┃ [0m[97m└╴single[0m
+        [90m2 ┃ [0mworld!
+        [90m  ┡━╸[0m[97mmulti[0m
+        [90m  └[0m
+        "#);
+    }
+
+    #[test]
+    fn theme_no_color() {
+        let source_code = "hello, world!";
+        let labels = vec1::vec1![
+            make_label(source_code, "hello", "greeting").with_style(Style::new().red())
+        ];
+
+        let result = LabelRenderer::new(source_code, None)
+            .with_theme(Theme::no_color())
+            .render_to_string(labels);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 │ hello, world!
+          │ ├───┘
+          │ └╴greeting
+          └
+        "#);
+    }
+
+    #[test]
+    fn insertion_message_order() {
+        let source_code = "hello, world!";
+        let labels = vec1::vec1![
+            make_label(source_code, "world", "primary"),
+            make_label(source_code, "hello", "secondary"),
+            make_label(source_code, "!", "tertiary"),
+        ];
+
+        let result = LabelRenderer::new(source_code, None)
+            .with_message_order(MessageOrder::Insertion)
+            .render_to_string(labels);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 │ hello, world!
+          │ ├───┘  ├───┘╿
+          │ │      └╴primary
+          │ └╴secondary │
+          │             └╴tertiary
+          └
+        "#);
+    }
+
+    #[test]
+    fn symbolic_styles() {
+        let source_code = "hello, world!";
+        let labels = vec1::vec1![
+            make_label(source_code, "hello, world!", "outer").with_style(Style::new().red()),
+            make_label(source_code, "hello", "inner").with_style(Style::new().blue().bold()),
+        ];
+
+        let result = LabelRenderer::new(source_code, None)
+            .with_symbolic_styles(true)
+            .render_to_string(labels);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 │ <blue><bold>hello</bold></blue><red>, world!</red>
+          │ <blue><bold>├───┘</bold></blue><red>╶──────┘</red>
+          │ <blue><bold>└╴inner</bold></blue>
+          │ <red>└╴outer</red>
+          └
+        "#);
+    }
+
+    #[test]
+    fn reused_line_index() {
+        let source_code = "first line\nsecond line\nthird line\nfourth line\n";
+        let line_index = LineIndex::new(source_code);
+        let renderer = LabelRenderer::new(source_code, None).with_line_index(&line_index);
+
+        for (target, message) in [
+            ("second", "here"),
+            ("fourth", "there"),
+            ("first", "anywhere"),
+        ] {
+            let labels = vec1::vec1![make_label(source_code, target, message)];
+            assert_eq!(
+                renderer.render_to_string(labels.clone()),
+                LabelRenderer::new(source_code, None).render_to_string(labels),
+            );
+        }
+
+        let result =
+            renderer.render_to_string(vec1::vec1![make_label(source_code, "third", "middle")]);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 │ first line
+        2 │ second line
+        3 │ third line
+          │ ├───┘
+          │ └╴middle
+        4 │ fourth line
+          └
+        "#);
+    }
+
+    #[test]
+    fn mismatched_line_index() {
+        let source_code = "first line\nsecond line\nthird line\n";
+        let line_index = LineIndex::new("some other\nsource code");
+        let labels = vec1::vec1![make_label(source_code, "third", "here")];
+
+        // the index does not fit the source code, so it is rebuilt
+        assert_eq!(
+            LabelRenderer::new(source_code, None)
+                .with_line_index(&line_index)
+                .render_to_string(labels.clone()),
+            LabelRenderer::new(source_code, None).render_to_string(labels),
+        );
+    }
+
+    #[test]
+    fn gutter_marks() {
+        let source_code =
+            "first line\nsecond line\nthird line\nfourth line\nfifth line\nsixth line\n";
+        let marks = [
+            GutterMark::new(2, ">>".into(), Style::new()),
+            GutterMark::new(3, "●".into(), Style::new()),
+            // not shown, since the line is not part of the snippet
+            GutterMark::new(6, "!!".into(), Style::new()),
+        ];
+
+        let result = LabelRenderer::new(source_code, None)
+            .with_gutter_marks(&marks)
+            .render_to_string(vec1::vec1![make_label(source_code, "third", "here")]);
+
+        assert_snapshot!(result, @r#"
+             ┌
+        1    │ first line
+        2 >> │ second line
+        3 ●  │ third line
+             │ ├───┘
+             │ └╴here
+        4    │ fourth line
+        5    │ fifth line
+             └
+        "#);
+    }
+
+    #[test]
+    fn message_wrapping() {
+        let source_code = "let value = compute(first, second);";
+        let labels = vec1::vec1![
+            make_label(
+                source_code,
+                "value",
+                "this name is never used anywhere in the program"
+            ),
+            make_label(source_code, "second", "second argument"),
+        ];
+
+        let result = LabelRenderer::new(source_code, None)
+            .with_message_width(26)
+            .render_to_string(labels);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 │ let value = compute(first, second);
+          │     ├───┘                  ├────┘
+          │     └╴this name is never   │
+          │       used anywhere in the │
+          │       program              │
+          │                            └╴second argument
+          └
+        "#);
+    }
+
+    #[test]
+    fn message_wrapping_multiline() {
+        let source_code = "start {\n  body\n}\n";
+        let labels = vec1::vec1![Label::new(
+            span_of(source_code, "{\n  body\n}"),
+            "this block spans several lines\nand has a long message".into(),
+            Style::new()
+        )];
+
+        let result = LabelRenderer::new(source_code, None)
+            .with_message_width(20)
+            .render_to_string(labels);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 ┢╸start {
+        2 ┃   body
+        3 ┃ }
+          ┡━╸this block spans
+          │  several lines
+          │  and has a long
+          │  message
+          └
+        "#);
+    }
+
+    #[test]
+    fn char_and_grapheme_spans() {
+        let source_code = "naïve café, vraiment";
+        let by_bytes = highlight(source_code, "café", "here");
+
+        let chars = Span::<char>::new(6.into(), 4.into());
+        let label = Label::in_source(source_code, chars, "here".into(), Style::new());
+        let by_chars = render_labels_to_string(source_code, None, vec1::vec1![label]);
+        assert_eq!(by_bytes, by_chars);
+
+        // “e” followed by a combining acute accent is one grapheme but two chars
+        let source_code = "cafe\u{301}s and teas";
+        let by_bytes = highlight(source_code, "teas", "here");
+
+        let graphemes = Span::<Grapheme>::new(10.into(), 4.into());
+        let label = Label::in_source(source_code, graphemes, "here".into(), Style::new());
+        let by_graphemes = render_labels_to_string(source_code, None, vec1::vec1![label]);
+        assert_eq!(by_bytes, by_graphemes);
+    }
+
+    #[test]
+    fn max_labels_per_line() {
+        let source_code = "a b c d e f";
+        let labels =
+            Vec::from_iter(["f", "e", "d", "c", "b", "a"].map(|target| {
+                Label::new(span_of(source_code, target), target.into(), Style::new())
+            }))
+            .try_into()
+            .unwrap();
+
+        let result = LabelRenderer::new(source_code, None)
+            .with_max_labels_per_line(2)
+            .render_to_string(labels);
+
+        assert_snapshot!(result, @r#"
+          ┌
+        1 │ a b c d e f
+          │         ╿ ╿
+          │         └╴e
+          │           └╴f
+          │ … and 4 more
+          └
+        "#);
+    }
 }
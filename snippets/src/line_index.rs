@@ -0,0 +1,116 @@
+use complex_indifference::{Index, Span};
+
+/// An index of the line boundaries in a source document.
+///
+/// Building the index requires a scan of the whole source, so when the
+/// same source is rendered repeatedly with different labels (e.g. in an
+/// editor, where the diagnostics change more often than the document),
+/// construct it once and pass it to
+/// [`LabelRenderer::with_line_index`](crate::LabelRenderer::with_line_index).
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    // the start of each line; always contains at least one entry
+    line_starts: Vec<Index<u8>>,
+    source_len: usize,
+}
+
+impl LineIndex {
+    pub fn new(source_code: &str) -> Self {
+        let line_starts = Vec::from_iter(line_starts(source_code));
+        LineIndex { line_starts, source_len: source_code.len() }
+    }
+
+    /// The length of the source that this index was built for.
+    pub fn source_len(&self) -> usize {
+        self.source_len
+    }
+
+    /// Whether this index could have been built for the source code;
+    /// that is, whether the source code has the same length and line starts.
+    pub fn fits(&self, source_code: &str) -> bool {
+        self.source_len == source_code.len()
+            && self
+                .line_starts
+                .iter()
+                .copied()
+                .eq(line_starts(source_code))
+    }
+
+    /// The number of lines, including the (empty) line following
+    /// a trailing newline, if any.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// The (zero-based) number of the line containing the offset.
+    pub fn line_number(&self, offset: Index<u8>) -> usize {
+        // the first entry is 0, so there is always a line start <= offset
+        self.line_starts.partition_point(|start| *start <= offset) - 1
+    }
+
+    /// The span of the given line, including its terminator.
+    ///
+    /// Returns `None` if the line does not exist.
+    pub fn line_span(&self, line_number: usize) -> Option<Span<u8>> {
+        let start = *self.line_starts.get(line_number)?;
+        let end = self
+            .line_starts
+            .get(line_number + 1)
+            .copied()
+            .unwrap_or(Index::from(self.source_len));
+
+        Span::try_from_indices(start, end)
+    }
+}
+
+/// The start of each line: the start of the source code, and after each `\n`.
+fn line_starts(source_code: &str) -> impl Iterator<Item = Index<u8>> + '_ {
+    std::iter::once(0)
+        .chain(
+            source_code
+                .bytes()
+                .enumerate()
+                .filter_map(|(ix, b)| (b == b'\n').then_some(ix + 1)),
+        )
+        .map(Index::from)
+}
+
+#[cfg(test)]
+mod test {
+    use complex_indifference::Span;
+
+    use super::LineIndex;
+
+    #[test]
+    fn lines() {
+        let index = LineIndex::new("ab\ncd\n");
+        assert_eq!(index.line_count(), 3);
+        assert_eq!(index.line_number(0.into()), 0);
+        assert_eq!(index.line_number(2.into()), 0);
+        assert_eq!(index.line_number(3.into()), 1);
+        assert_eq!(index.line_number(6.into()), 2);
+        assert_eq!(index.line_span(1), Span::try_from(3..6usize).ok());
+        assert_eq!(index.line_span(2), Span::try_from(6..6usize).ok());
+        assert_eq!(index.line_span(3), None);
+    }
+
+    #[test]
+    fn fits() {
+        let index = LineIndex::new("ab\ncd\n");
+        assert!(index.fits("ab\ncd\n"));
+        assert!(index.fits("xy\nzw\n"));
+        assert!(!index.fits("ab\ncd"));
+        assert!(!index.fits("ab\ncde"));
+        assert!(!index.fits("abc\nd\ne"));
+        // the same length and last line, but different lines before it
+        assert!(!index.fits("a\nbcd\n"));
+    }
+
+    #[test]
+    fn empty() {
+        let index = LineIndex::new("");
+        assert_eq!(index.line_count(), 1);
+        assert_eq!(index.line_number(0.into()), 0);
+        assert_eq!(index.line_span(0), Span::try_from(0..0usize).ok());
+    }
+}
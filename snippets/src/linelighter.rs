@@ -1,41 +1,174 @@
-use std::{borrow::Cow, cmp::min, mem::take};
-
-use complex_indifference::Span;
-use owo_colors::{Style, Styled};
+use std::{
+    borrow::Cow,
+    cmp::{max, min},
+    mem::take,
+};
+
+use complex_indifference::{Count, RelativeSpan, Span};
+use owo_colors::Style;
 use unicode_width::UnicodeWidthStr;
 
-use crate::label::Label;
+use crate::{SourceHighlighter, Theme, label::Label, layout::StyledRun, renderer::MessageOrder};
 
 pub struct LineHighlighter<'a> {
     source_code: &'a str,
-    line: Vec<StyledString<'a>>,
-    indicator_line: Vec<StyledString<'a>>,
-    messages: Vec<Vec<StyledString<'a>>>,
+    theme: Theme,
+    highlighter: Option<&'a dyn SourceHighlighter>,
+    message_order: MessageOrder,
+    message_width: Option<usize>,
+    // syntax highlighting runs for the current line, in order
+    syntax: Vec<(Span<u8>, Style)>,
+    line: Vec<StyledRun<'a>>,
+    indicator_line: Vec<StyledRun<'a>>,
+    messages: Vec<Vec<StyledRun<'a>>>,
+}
+
+pub struct LitLine<'a> {
+    pub line: Vec<StyledRun<'a>>,
+    pub indicator_line: Vec<StyledRun<'a>>,
+    pub messages: Vec<Vec<StyledRun<'a>>>,
+}
+
+/// Produces the syntax highlighting runs for the given line,
+/// as absolute spans into the source code.
+fn syntax_runs(
+    source_code: &str,
+    highlighter: &dyn SourceHighlighter,
+    line_span: Span<u8>,
+) -> Vec<(Span<u8>, Style)> {
+    let line = line_span.str(source_code).trim_ascii_end();
+    let mut start = Count::ZERO;
+    let mut result = Vec::new();
+    for piece in highlighter.highlight_line(line) {
+        let relative = RelativeSpan::new(start, piece.inner().len().into());
+        // ignore anything the highlighter produced past the end of the line,
+        // or from a piece which does not end on a character boundary
+        if !line.is_char_boundary(relative.end().as_usize()) {
+            break;
+        }
+
+        let Some(span) = relative.within(line_span) else {
+            break;
+        };
+
+        result.push((span, piece.style));
+        start = relative.end();
+    }
+
+    result
+}
+
+// messages are never wrapped narrower than this,
+// so that they remain readable for labels far to the right
+const MIN_MESSAGE_WIDTH: usize = 20;
+
+/// Splits a message into lines at any newlines, and wraps each
+/// line which is wider than the given width (if any).
+pub fn wrap_message(message: &str, width: Option<usize>) -> Vec<Cow<'_, str>> {
+    let mut result = Vec::new();
+    for line in message.split('\n') {
+        match width {
+            Some(width) if line.width() > width => result.extend(textwrap::wrap(line, width)),
+            _ => result.push(Cow::Borrowed(line)),
+        }
+    }
+
+    result
 }
 
-type StyledString<'a> = Styled<Cow<'a, str>>;
-type StyledList<'a> = owo_colors::StyledList<Vec<StyledString<'a>>, StyledString<'a>>;
+/// Lays out a line which has no labels, applying only syntax highlighting.
+pub fn highlight_plain<'a>(
+    source_code: &'a str,
+    theme: Theme,
+    highlighter: Option<&'a dyn SourceHighlighter>,
+    line_span: Span<u8>,
+) -> Vec<StyledRun<'a>> {
+    let line = line_span.str(source_code).trim_ascii_end();
+    let mut lighter = LineHighlighter::new(source_code, theme, highlighter, MessageOrder::Source);
+    if let Some(highlighter) = highlighter {
+        lighter.syntax = syntax_runs(source_code, highlighter, line_span);
+    }
 
-pub struct LitLine {
-    pub line: String,
-    pub indicator_line: String,
-    pub messages: Vec<String>,
+    lighter.push_source(line_span.with_len(line.len().into()), Style::new());
+    lighter.line
 }
 
-impl LineHighlighter<'_> {
-    pub fn new(source_code: &str) -> LineHighlighter<'_> {
+impl<'a> LineHighlighter<'a> {
+    pub fn new(
+        source_code: &'a str,
+        theme: Theme,
+        highlighter: Option<&'a dyn SourceHighlighter>,
+        message_order: MessageOrder,
+    ) -> LineHighlighter<'a> {
         LineHighlighter {
             source_code,
+            theme,
+            highlighter,
+            message_order,
+            message_width: None,
+            syntax: Vec::new(),
             line: Vec::new(),
             indicator_line: Vec::new(),
             messages: Vec::new(),
         }
     }
 
+    pub fn with_message_width(self, message_width: Option<usize>) -> Self {
+        Self { message_width, ..self }
+    }
+
+    /// Emits a portion of the source line with the given (label) style.
+    ///
+    /// If the style is plain then any syntax highlighting is used instead.
+    fn push_source(&mut self, span: Span<u8>, style: Style) {
+        if !style.is_plain() || self.syntax.is_empty() {
+            self.line
+                .push(StyledRun::new(span.str(self.source_code), style));
+            return;
+        }
+
+        let mut up_to = span.start();
+        for &(run, run_style) in &self.syntax {
+            let start = max(run.start(), up_to);
+            let end = min(run.end(), span.end());
+            let Some(overlap) = Span::try_from_indices(start, end) else {
+                continue;
+            };
+
+            if overlap.is_empty() {
+                continue;
+            }
+
+            if let Some(gap) = up_to.span_until(start).filter(|s| !s.is_empty()) {
+                self.line
+                    .push(StyledRun::new(gap.str(self.source_code), style));
+            }
+
+            self.line
+                .push(StyledRun::new(overlap.str(self.source_code), run_style));
+            up_to = end;
+        }
+
+        if let Some(rest) = up_to.span_until(span.end()).filter(|s| !s.is_empty()) {
+            self.line
+                .push(StyledRun::new(rest.str(self.source_code), style));
+        }
+    }
+
+    /// The style for the indicator of a label; unstyled labels use the theme.
+    fn underline_style(&self, style: Style) -> Style {
+        if style.is_plain() {
+            self.theme.underline
+        } else {
+            style
+        }
+    }
+
     fn fill_indicator(&mut self, continuing: bool, continues: bool, value: &str, style: &Style) {
+        let style = &self.underline_style(*style);
         let width = value.width();
         if width == 0 {
-            self.indicator_line.push(style.style("│".into()));
+            self.indicator_line.push(StyledRun::new("│", *style));
         } else if width == 1 {
             let v = match (continuing, continues) {
                 (true, true) => "╌",
@@ -44,24 +177,27 @@ impl LineHighlighter<'_> {
                 (false, false) => "╿",
             };
 
-            self.indicator_line.push(style.style(v.into()));
+            self.indicator_line.push(StyledRun::new(v, *style));
         } else {
-            self.indicator_line.push(
-                style.style(
-                    format!(
-                        "{}{:─<width$}{}",
-                        if continuing { "╶" } else { "├" },
-                        "",
-                        if continues { "╴" } else { "┘" },
-                        width = width - 2
-                    )
-                    .into(),
+            self.indicator_line.push(StyledRun::new(
+                format!(
+                    "{}{:─<width$}{}",
+                    if continuing { "╶" } else { "├" },
+                    "",
+                    if continues { "╴" } else { "┘" },
+                    width = width - 2
                 ),
-            );
+                *style,
+            ));
         }
     }
 
-    fn emit_message(&mut self, line_span: Span<u8>, label: &Label, other_labels: &[&Label]) {
+    fn emit_message(
+        &mut self,
+        line_span: Span<u8>,
+        label: &Label<'a>,
+        other_labels: &[&Label<'a>],
+    ) {
         let line_start = line_span.start();
         let no_style = Style::new();
 
@@ -70,7 +206,9 @@ impl LineHighlighter<'_> {
         // with ones that come later
         let fill_holes = |line_offset: usize,
                           msg: &str,
-                          out: &mut Vec<Styled<Cow<str>>>,
+                          out: &mut Vec<StyledRun<'a>>,
+                          style: Style,
+                          url: Option<&Cow<'a, str>>,
                           bright: bool,
                           char: &'static str| {
             // walk through all spaces in the string
@@ -96,19 +234,20 @@ impl LineHighlighter<'_> {
                         .width();
 
                         if offset_from_start == offset_to_space {
-                            Some(&l.style)
+                            Some(self.underline_style(l.style))
                         } else {
                             None
                         }
                     }) {
-                        out.push(label.style.style(take(&mut building).into()));
-                        out.push(if bright {
-                            other_style.style(char.into())
-                        } else if !other_style.is_plain() {
-                            other_style.dimmed().style(char.into())
-                        } else {
-                            other_style.style(char.into())
-                        });
+                        out.push(StyledRun::new(take(&mut building), style).with_url(url.cloned()));
+                        out.push(StyledRun::new(
+                            char,
+                            if bright || other_style.is_plain() {
+                                other_style
+                            } else {
+                                other_style.dimmed()
+                            },
+                        ));
 
                         continue;
                     }
@@ -118,7 +257,7 @@ impl LineHighlighter<'_> {
             }
 
             if !building.is_empty() {
-                out.push(label.style.style(building.into()));
+                out.push(StyledRun::new(building, style).with_url(url.cloned()));
             }
         };
 
@@ -131,55 +270,87 @@ impl LineHighlighter<'_> {
         // 2 chars at start of messages: "└╴"
         const MSG_PREFIX_WIDTH: usize = 2;
 
-        let mut out: Vec<Styled<Cow<str>>> = Vec::new();
-
-        let indent = " ".repeat(indent_width);
-        fill_holes(0, &indent, &mut out, true, "│");
-
-        out.push(label.style.style("└╴".into()));
+        let message_lines = wrap_message(
+            &label.message,
+            self.message_width.map(|w| {
+                max(
+                    w.saturating_sub(indent_width + MSG_PREFIX_WIDTH),
+                    MIN_MESSAGE_WIDTH,
+                )
+            }),
+        );
 
         // if we're on the first row we can use full brightness
         // where it connects to the indicator line, otherwise we dim
-        let bright = self.messages.is_empty();
-        fill_holes(
-            indent_width + MSG_PREFIX_WIDTH,
-            &label.message,
-            &mut out,
-            bright,
-            "╵",
-        );
+        let first_row = self.messages.is_empty();
+
+        let mut rows = Vec::new();
+        for (i, message_line) in message_lines.iter().enumerate() {
+            let mut out: Vec<StyledRun<'a>> = Vec::new();
+            let bright = first_row && i == 0;
+
+            if i == 0 {
+                let indent = " ".repeat(indent_width);
+                fill_holes(0, &indent, &mut out, label.style, None, true, "│");
+                out.push(StyledRun::new("└╴", self.underline_style(label.style)));
+            } else {
+                // subsequent lines hang below the start of the message
+                let indent = " ".repeat(indent_width + MSG_PREFIX_WIDTH);
+                fill_holes(0, &indent, &mut out, label.style, None, bright, "│");
+            }
 
-        // draw in any others that come after
-        let mut total_width = indent_width + MSG_PREFIX_WIDTH + label.message.width();
-        for l in other_labels {
-            // ↓ line_start
-            // -------------------------------------------
-            //         msg ... ]
-            // |← total_width →|← len? →|
-            //                          [l.start]-------
-            // |←   offset_from_start  →|
-            let offset_from_start = self.source_code[line_start
-                .span_until(l.start())
-                .expect("l.start >= line_start")]
-            .width();
-            if let Some(len) = offset_from_start.checked_sub(total_width) {
-                if len > 0 {
-                    out.push(no_style.style(" ".repeat(len).into()));
-                }
+            fill_holes(
+                indent_width + MSG_PREFIX_WIDTH,
+                message_line,
+                &mut out,
+                if label.style.is_plain() {
+                    self.theme.message
+                } else {
+                    label.style
+                },
+                label.url.as_ref(),
+                bright,
+                "╵",
+            );
+
+            // draw in any others that come after
+            let mut total_width = indent_width + MSG_PREFIX_WIDTH + message_line.width();
+            for l in other_labels {
+                // ↓ line_start
+                // -------------------------------------------
+                //         msg ... ]
+                // |← total_width →|← len? →|
+                //                          [l.start]-------
+                // |←   offset_from_start  →|
+                let offset_from_start = self.source_code[line_start
+                    .span_until(l.start())
+                    .expect("l.start >= line_start")]
+                .width();
+                if let Some(len) = offset_from_start.checked_sub(total_width) {
+                    if len > 0 {
+                        out.push(StyledRun::new(" ".repeat(len), no_style));
+                    }
 
-                out.push(l.style.style("│".into()));
-                // 'len' spaces and one pipe
-                total_width += len + 1;
+                    out.push(StyledRun::new("│", self.underline_style(l.style)));
+                    // 'len' spaces and one pipe
+                    total_width += len + 1;
+                }
             }
+
+            rows.push(out);
         }
 
-        self.messages.push(out);
+        self.messages.extend(rows);
     }
 
-    pub fn highlight_line(mut self, line_span: Span<u8>, labels: &[Label]) -> LitLine {
+    pub fn highlight_line(mut self, line_span: Span<u8>, labels: &[Label<'a>]) -> LitLine<'a> {
         let no_style = Style::new();
 
-        let mut stack: Vec<&Label> = Vec::new();
+        if let Some(highlighter) = self.highlighter {
+            self.syntax = syntax_runs(self.source_code, highlighter, line_span);
+        }
+
+        let mut stack: Vec<&Label<'a>> = Vec::new();
         let mut message_order = Vec::new();
 
         let mut up_to = line_span.start();
@@ -195,10 +366,9 @@ impl LineHighlighter<'_> {
 
                     // emit highlighted portion of line
                     // UNWRAP: since start is > up_to, end must be as well
-                    let value = Span::try_from_indices(up_to, end)
-                        .unwrap()
-                        .str(self.source_code);
-                    self.line.push(outer_label.style.style(value.into()));
+                    let span = Span::try_from_indices(up_to, end).unwrap();
+                    let value = span.str(self.source_code);
+                    self.push_source(span, outer_label.style);
 
                     // emit indicator line
                     let continuing = outer_label.start() < up_to;
@@ -227,10 +397,10 @@ impl LineHighlighter<'_> {
                     if let Some(slice) = up_to.span_until(label.start()) {
                         // emit unhighlighted characters
                         let value = &self.source_code[slice];
-                        self.line.push(no_style.style(value.into()));
+                        self.push_source(slice, no_style);
                         // space indicator line wide enough
                         self.indicator_line
-                            .push(no_style.style(" ".repeat(value.width()).into()));
+                            .push(StyledRun::new(" ".repeat(value.width()), no_style));
 
                         up_to = label.start();
                     }
@@ -250,7 +420,7 @@ impl LineHighlighter<'_> {
                 let value = &self.source_code[slice];
                 let continuing = label.start() < up_to;
                 self.fill_indicator(continuing, false, value, &label.style);
-                self.line.push(label.style.style(value.into()));
+                self.push_source(slice, label.style);
                 message_order.push(label);
                 up_to = end;
             }
@@ -262,12 +432,16 @@ impl LineHighlighter<'_> {
             if let Some(slice) = up_to.span_until(line_span.end()) {
                 // emit unhighlighted characters
                 let value = self.source_code[slice].trim_ascii_end();
-                self.line.push(no_style.style(value.into()));
+                self.push_source(slice.with_len(value.len().into()), no_style);
                 // indicator line doesn't need spacing
             }
         }
 
         // emit all messages now that we know the full order
+        if self.message_order == MessageOrder::Insertion {
+            message_order.sort_by_key(|l| l.order);
+        }
+
         let mut message_order = message_order.into_iter();
         while let Some(label) = message_order.next() {
             self.emit_message(line_span, label, message_order.as_slice());
@@ -276,15 +450,11 @@ impl LineHighlighter<'_> {
         self.result()
     }
 
-    fn result(self) -> LitLine {
+    fn result(self) -> LitLine<'a> {
         LitLine {
-            line: format!("{}", StyledList::from(self.line)),
-            indicator_line: format!("{}", StyledList::from(self.indicator_line)),
-            messages: self
-                .messages
-                .into_iter()
-                .map(|m| format!("{}", StyledList::from(m)))
-                .collect(),
+            line: self.line,
+            indicator_line: self.indicator_line,
+            messages: self.messages,
         }
     }
 }
@@ -0,0 +1,382 @@
+//! Conversion between ANSI escape sequences and a symbolic markup.
+//!
+//! The markup represents each change in style as a tag such as `<red>`,
+//! `</red>`, or `<bold>`, which makes rendered output much easier to review
+//! in snapshot tests than raw escape codes. A literal `<` in the text is
+//! written as `<<`.
+//!
+//! Tags describe state changes rather than a tree, so they need not nest:
+//! `<red><bold>a</red>b</bold>` is valid. However, [`ansi_to_markup`]
+//! always closes tags in the reverse order of opening them, and so produces
+//! `<red><bold>a</bold></red><bold>b</bold>` instead.
+
+const COLORS: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+const EFFECTS: [(u8, &str, u8); 8] = [
+    (1, "bold", 22),
+    (2, "dimmed", 22),
+    (3, "italic", 23),
+    (4, "underline", 24),
+    (5, "blink", 25),
+    (7, "reversed", 27),
+    (8, "hidden", 28),
+    (9, "strikethrough", 29),
+];
+
+#[derive(Default)]
+struct State {
+    fg: Option<String>,
+    bg: Option<String>,
+    effects: Vec<&'static str>,
+    link: Option<String>,
+}
+
+impl State {
+    /// Applies the parameters of an SGR (`ESC [ ... m`) sequence.
+    fn apply_sgr(&mut self, params: &str) {
+        let mut codes = params.split(';').map(|p| {
+            if p.is_empty() {
+                Some(0)
+            } else {
+                p.parse::<u8>().ok()
+            }
+        });
+
+        while let Some(code) = codes.next() {
+            let Some(code) = code else {
+                continue;
+            };
+
+            match code {
+                0 => *self = State { link: self.link.take(), ..State::default() },
+                30..=37 => self.fg = Some(COLORS[usize::from(code - 30)].to_string()),
+                90..=97 => self.fg = Some(format!("bright_{}", COLORS[usize::from(code - 90)])),
+                40..=47 => self.bg = Some(format!("on_{}", COLORS[usize::from(code - 40)])),
+                100..=107 => {
+                    self.bg = Some(format!("on_bright_{}", COLORS[usize::from(code - 100)]))
+                }
+                39 => self.fg = None,
+                49 => self.bg = None,
+                38 | 48 => {
+                    let color = match codes.next().flatten() {
+                        Some(5) => codes.next().flatten().map(|n| format!("ansi:{n}")),
+                        Some(2) => {
+                            let r = codes.next().flatten().unwrap_or_default();
+                            let g = codes.next().flatten().unwrap_or_default();
+                            let b = codes.next().flatten().unwrap_or_default();
+                            Some(format!("#{r:02x}{g:02x}{b:02x}"))
+                        }
+                        _ => None,
+                    };
+
+                    if code == 38 {
+                        self.fg = color;
+                    } else {
+                        self.bg = color.map(|c| format!("on_{c}"));
+                    }
+                }
+                _ => {
+                    if let Some(&(_, name, _)) = EFFECTS.iter().find(|(on, _, _)| *on == code) {
+                        if !self.effects.contains(&name) {
+                            self.effects.push(name);
+                        }
+                    } else if EFFECTS.iter().any(|(_, _, off)| *off == code) {
+                        self.effects.retain(|name| {
+                            !EFFECTS.iter().any(|(_, n, off)| n == name && *off == code)
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    /// The tags which are open in this state.
+    fn tags(&self) -> Vec<String> {
+        let mut tags = Vec::from_iter(self.fg.iter().chain(&self.bg).cloned());
+        tags.extend(self.effects.iter().map(|effect| effect.to_string()));
+        tags.extend(self.link.iter().map(|link| format!("link={link}")));
+        tags
+    }
+}
+
+/// Writes the tags needed to move from the open tags to the given state.
+///
+/// Tags are closed in the reverse order of opening them, so any tags opened
+/// after one that is closed are closed as well, and then reopened.
+fn transition(open: &mut Vec<String>, next: &State, out: &mut String) {
+    let tags = next.tags();
+    let keep = open.iter().take_while(|tag| tags.contains(tag)).count();
+    for tag in open.drain(keep..).rev() {
+        let name = if tag.starts_with("link=") {
+            "link"
+        } else {
+            &tag
+        };
+        out.push_str(&format!("</{name}>"));
+    }
+
+    for tag in tags {
+        if !open.contains(&tag) {
+            out.push_str(&format!("<{tag}>"));
+            open.push(tag);
+        }
+    }
+}
+
+/// Converts ANSI SGR and OSC 8 escape sequences into symbolic markup.
+///
+/// Any other escape sequences are passed through unchanged.
+pub fn ansi_to_markup(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut state = State::default();
+    // the tags are only written before the text they apply to,
+    // so that consecutive escape sequences become a single change
+    let mut open = Vec::new();
+    let mut rest = input;
+
+    while let Some(ix) = rest.find(['\x1b', '<']) {
+        if ix > 0 {
+            transition(&mut open, &state, &mut out);
+            out.push_str(&rest[..ix]);
+        }
+
+        rest = &rest[ix..];
+
+        if let Some(after) = rest.strip_prefix('<') {
+            transition(&mut open, &state, &mut out);
+            out.push_str("<<");
+            rest = after;
+            continue;
+        }
+
+        if let Some(csi) = rest.strip_prefix("\x1b[") {
+            // parameters are followed by a single final byte in @..~
+            let end = csi.find(|c: char| ('@'..='~').contains(&c));
+            match end {
+                Some(end) if csi[end..].starts_with('m') => {
+                    state.apply_sgr(&csi[..end]);
+                    rest = &csi[end + 1..];
+                }
+                _ => {
+                    transition(&mut open, &state, &mut out);
+                    out.push('\x1b');
+                    rest = &rest[1..];
+                    continue;
+                }
+            }
+        } else if let Some(osc) = rest.strip_prefix("\x1b]8;") {
+            // OSC 8 ; params ; URL ST
+            let Some((body, after)) = osc.split_once("\x1b\\") else {
+                transition(&mut open, &state, &mut out);
+                out.push('\x1b');
+                rest = &rest[1..];
+                continue;
+            };
+
+            let url = body.split_once(';').map_or("", |(_, url)| url);
+            state.link = (!url.is_empty()).then(|| url.to_string());
+            rest = after;
+        } else {
+            transition(&mut open, &state, &mut out);
+            out.push('\x1b');
+            rest = &rest[1..];
+            continue;
+        }
+    }
+
+    transition(&mut open, &state, &mut out);
+    out.push_str(rest);
+    out
+}
+
+/// Converts symbolic markup (as produced by [`ansi_to_markup`])
+/// back into ANSI escape sequences, for display.
+///
+/// Unrecognized tags are left as they are.
+pub fn markup_to_ansi(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut effects = Vec::new();
+    let mut rest = input;
+
+    while let Some(ix) = rest.find('<') {
+        out.push_str(&rest[..ix]);
+        rest = &rest[ix..];
+
+        if let Some(after) = rest.strip_prefix("<<") {
+            out.push('<');
+            rest = after;
+            continue;
+        }
+
+        let escape = rest
+            .find('>')
+            .and_then(|end| Some((tag_to_ansi(&rest[1..end])?, end)));
+
+        match escape {
+            Some((escape, end)) => {
+                out.push_str(&escape);
+                restore_effects(&rest[1..end], &mut effects, &mut out);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('<');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Keeps track of the effects which are on. Some effects are turned off
+/// by the same code (`</bold>` also ends `<dimmed>`), so when one of them
+/// is closed, the others which are still open are turned back on.
+fn restore_effects(tag: &str, effects: &mut Vec<&'static str>, out: &mut String) {
+    let (closing, name) = match tag.strip_prefix('/') {
+        Some(name) => (true, name),
+        None => (false, tag),
+    };
+
+    let Some(&(_, name, off)) = EFFECTS.iter().find(|(_, n, _)| *n == name) else {
+        return;
+    };
+
+    if !closing {
+        if !effects.contains(&name) {
+            effects.push(name);
+        }
+
+        return;
+    }
+
+    effects.retain(|effect| *effect != name);
+    for (on, other, _) in EFFECTS.iter().filter(|(_, _, o)| *o == off) {
+        if effects.contains(other) {
+            out.push_str(&format!("\x1b[{on}m"));
+        }
+    }
+}
+
+fn tag_to_ansi(tag: &str) -> Option<String> {
+    if let Some(url) = tag.strip_prefix("link=") {
+        return Some(format!("\x1b]8;;{url}\x1b\\"));
+    }
+
+    if tag == "/link" {
+        return Some("\x1b]8;;\x1b\\".to_string());
+    }
+
+    let (closing, name) = match tag.strip_prefix('/') {
+        Some(name) => (true, name),
+        None => (false, tag),
+    };
+
+    if let Some(&(on, _, off)) = EFFECTS.iter().find(|(_, n, _)| *n == name) {
+        return Some(format!("\x1b[{}m", if closing { off } else { on }));
+    }
+
+    let (background, color) = match name.strip_prefix("on_") {
+        Some(color) => (true, color),
+        None => (false, name),
+    };
+
+    let base = if background { 40 } else { 30 };
+    if closing {
+        return color_code(color, base).map(|_| format!("\x1b[{}m", base + 9));
+    }
+
+    color_code(color, base).map(|code| format!("\x1b[{code}m"))
+}
+
+fn color_code(color: &str, base: u8) -> Option<String> {
+    if let Some(n) = color.strip_prefix("ansi:") {
+        let n: u8 = n.parse().ok()?;
+        return Some(format!("{};5;{n}", base + 8));
+    }
+
+    if let Some(hex) = color.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        let (r, g, b) = (channel(0)?, channel(2)?, channel(4)?);
+        return Some(format!("{};2;{r};{g};{b}", base + 8));
+    }
+
+    let (offset, color) = match color.strip_prefix("bright_") {
+        Some(color) => (60, color),
+        None => (0, color),
+    };
+
+    let ix = COLORS.iter().position(|c| *c == color)?;
+    Some((base + offset + ix as u8).to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ansi_to_markup, markup_to_ansi};
+
+    #[test]
+    fn converts_colors() {
+        let input = "\x1b[34mhello\x1b[31m, world!\x1b[0m";
+        assert_eq!(
+            ansi_to_markup(input),
+            "<blue>hello</blue><red>, world!</red>"
+        );
+    }
+
+    #[test]
+    fn converts_effects_and_rgb() {
+        let input = "\x1b[1;38;2;255;0;16mhi\x1b[22m there\x1b[0m";
+        assert_eq!(
+            ansi_to_markup(input),
+            "<#ff0010><bold>hi</bold> there</#ff0010>"
+        );
+    }
+
+    #[test]
+    fn escapes_angle_brackets() {
+        assert_eq!(ansi_to_markup("a < b"), "a << b");
+        assert_eq!(markup_to_ansi("a << b"), "a < b");
+    }
+
+    #[test]
+    fn converts_links() {
+        let input = "\x1b]8;;https://example.com\x1b\\text\x1b]8;;\x1b\\";
+        let markup = ansi_to_markup(input);
+        assert_eq!(markup, "<link=https://example.com>text</link>");
+        assert_eq!(markup_to_ansi(&markup), input);
+    }
+
+    #[test]
+    fn closes_in_reverse_order() {
+        let input = "\x1b[34m\x1b[1mhello\x1b[0m";
+        assert_eq!(ansi_to_markup(input), "<blue><bold>hello</bold></blue>");
+
+        let markup = "<red>a<bold>b</red>c</bold>";
+        assert_eq!(
+            ansi_to_markup(&markup_to_ansi(markup)),
+            "<red>a<bold>b</bold></red><bold>c</bold>"
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let markup = "<red>a<bold>b</bold>c</red> <on_bright_blue>d</on_bright_blue>";
+        assert_eq!(ansi_to_markup(&markup_to_ansi(markup)), markup);
+    }
+
+    #[test]
+    fn round_trips_bold_and_dimmed() {
+        let markup = "<dimmed>a<bold>b</bold>c</dimmed>";
+        assert_eq!(
+            markup_to_ansi(markup),
+            "\x1b[2ma\x1b[1mb\x1b[22m\x1b[2mc\x1b[22m"
+        );
+        assert_eq!(ansi_to_markup(&markup_to_ansi(markup)), markup);
+    }
+}
@@ -1,17 +1,49 @@
-use std::{borrow::Cow, cmp::min};
+use std::cmp::min;
 
-use complex_indifference::{Count, Index, Indexable, Span};
+use complex_indifference::{Count, Span};
+use owo_colors::Style;
+use unicode_width::UnicodeWidthStr;
+use vec1::Vec1;
 
 use crate::{
+    GutterMark, LineIndex, SourceHighlighter, Theme,
     label::Label,
-    linelighter::{LineHighlighter, LitLine},
+    layout::{Row, RowKind, SnippetLayout, StyledRun, format_runs},
+    linelighter::{LineHighlighter, LitLine, highlight_plain, wrap_message},
+    markup::ansi_to_markup,
+    suggestion::patch_rows,
 };
 
+type OutputLine<'a> = (usize, RowKind, Vec<StyledRun<'a>>, usize);
+
 pub struct LabelRenderer<'a> {
     source_code: &'a str,
     source_name: Option<&'a str>,
     context_lines: usize,
     max_width: usize,
+    hyperlinks: bool,
+    highlighter: Option<&'a dyn SourceHighlighter>,
+    theme: Theme,
+    message_order: MessageOrder,
+    message_width: Option<usize>,
+    symbolic_styles: bool,
+    line_index: Option<&'a LineIndex>,
+    gutter_marks: &'a [GutterMark<'a>],
+    max_labels_per_line: usize,
+}
+
+/// Determines the order in which label messages are listed below a line.
+///
+/// This does not affect the position of the indicators,
+/// which always follow the source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MessageOrder {
+    /// Messages are ordered by the position of their labels in the source.
+    #[default]
+    Source,
+    /// Messages are ordered as the labels were provided by the caller;
+    /// e.g. to show a primary label before any secondary ones.
+    Insertion,
 }
 
 // sorts labels by increasing order (in reverse for popping)
@@ -33,29 +65,88 @@ impl<'a> LabelRenderer<'a> {
             source_name,
             context_lines: 2,
             max_width: usize::MAX,
+            hyperlinks: false,
+            highlighter: None,
+            theme: Theme::plain(),
+            message_order: MessageOrder::Source,
+            message_width: None,
+            symbolic_styles: false,
+            line_index: None,
+            gutter_marks: &[],
+            max_labels_per_line: 20,
         }
     }
 
-    fn line_containing_start_of(&self, span: Span<u8>) -> Span<u8> {
-        // start of line is after the last newline, or at start of string
-        let start_of_line: Index<u8> = self
-            .source_code
-            .slice_until(span.start())
-            .rfind('\n')
-            .map(|x| x + 1)
-            .unwrap_or(0)
-            .into();
-
-        // end of line is after the next newline, or at end of string
-        let end_of_line: Index<u8> = self
-            .source_code
-            .slice_from(span.start())
-            .find('\n')
-            .map(|x| x + span.start().as_usize() + 1)
-            .unwrap_or(self.source_code.len())
-            .into();
-
-        let line_span = Span::try_from((start_of_line, end_of_line)).unwrap();
+    /// Sets marks to be shown in the gutter alongside the line numbers.
+    ///
+    /// If there is more than one mark for a line, the first is used.
+    pub fn with_gutter_marks(self, gutter_marks: &'a [GutterMark<'a>]) -> Self {
+        Self { gutter_marks, ..self }
+    }
+
+    /// Sets the maximum number of labels shown on a single line (20 by default).
+    ///
+    /// Any further labels which start and end on the line are summarized
+    /// as “… and N more”, in favour of those which were provided first.
+    /// This keeps the output readable (and bounds the work done) when
+    /// very many labels point into the same line.
+    pub fn with_max_labels_per_line(self, max_labels_per_line: usize) -> Self {
+        Self { max_labels_per_line, ..self }
+    }
+
+    /// Uses a precomputed index of the lines in the source code.
+    ///
+    /// Otherwise, an index is built each time the snippet is rendered.
+    /// The index should have been built from the same source code; if it
+    /// plainly was not (see [`LineIndex::fits`]), it is ignored and rebuilt.
+    pub fn with_line_index(self, line_index: &'a LineIndex) -> Self {
+        Self { line_index: Some(line_index), ..self }
+    }
+
+    /// Emits styles as symbolic markup (e.g. `<red>`…`</red>`) rather than
+    /// as ANSI escape sequences. This is intended for snapshot testing;
+    /// use [`markup_to_ansi`](crate::markup::markup_to_ansi) to convert
+    /// the result for display.
+    pub fn with_symbolic_styles(self, symbolic_styles: bool) -> Self {
+        Self { symbolic_styles, ..self }
+    }
+
+    /// Sets the order in which label messages are listed.
+    pub fn with_message_order(self, message_order: MessageOrder) -> Self {
+        Self { message_order, ..self }
+    }
+
+    /// Wraps label messages so that they fit within the given number
+    /// of columns (not including the gutter). Wrapped lines are indented
+    /// to align with the start of the message.
+    pub fn with_message_width(self, message_width: usize) -> Self {
+        Self { message_width: Some(message_width), ..self }
+    }
+
+    /// Sets the theme used for the parts of the snippet not styled by labels.
+    pub fn with_theme(self, theme: Theme) -> Self {
+        Self { theme, ..self }
+    }
+
+    /// Sets a syntax highlighter to be applied to the source lines.
+    pub fn with_highlighter(self, highlighter: &'a dyn SourceHighlighter) -> Self {
+        Self { highlighter: Some(highlighter), ..self }
+    }
+
+    /// Enables emitting OSC 8 hyperlinks for labels which have a URL.
+    ///
+    /// This is off by default, since the escape sequences are only
+    /// meaningful when the output is going to a terminal that supports them.
+    pub fn with_hyperlinks(self, hyperlinks: bool) -> Self {
+        Self { hyperlinks, ..self }
+    }
+
+    fn line_containing_start_of(line_index: &LineIndex, span: Span<u8>) -> Span<u8> {
+        // UNWRAP: the line number comes from the index, so the line exists
+        let line_span = line_index
+            .line_span(line_index.line_number(span.start()))
+            .unwrap();
+
         debug_assert!(
             line_span.contains_offset(span.start())
                 || (span.len() == Count::ZERO && span.start() == line_span.end())
@@ -64,27 +155,134 @@ impl<'a> LabelRenderer<'a> {
         line_span
     }
 
-    pub fn render_spans<W: std::fmt::Write>(
+    /// Lays out a line of the source code without any labels.
+    fn context_line(&self, line_span: Span<u8>) -> Vec<StyledRun<'a>> {
+        highlight_plain(self.source_code, self.theme, self.highlighter, line_span)
+    }
+
+    /// Computes the layout of the snippet without rendering it to text.
+    pub fn layout(&self, mut labels: Vec1<Label<'a>>) -> SnippetLayout<'a> {
+        // ensure that all labels indices are valid
+        // - we do not want to panic because of a bug in the caller,
+        //   because snippets could be rendered during panic rendering
+        for (order, label) in labels.iter_mut().enumerate() {
+            label.order = order;
+
+            let span = label.span;
+            let start_ix = self
+                .source_code
+                .floor_char_boundary(span.start().as_usize());
+            let end_ix = self.source_code.ceil_char_boundary(span.end().as_usize());
+            // UNWRAP: since span is already ordered, we know that start_ix <= end_ix
+            label.span = Span::try_from_indices(start_ix.into(), end_ix.into()).unwrap();
+
+            // unstyled labels take their style from the theme, based on severity
+            if let Some(severity) = label.severity.filter(|_| label.style.is_plain()) {
+                label.style = self.theme.severity(severity);
+            }
+        }
+
+        let mut labels = Vec::from(labels);
+        sort_labels(labels.as_mut_slice());
+
+        // patches are shown in source order, after the snippet
+        let patches = Vec::from_iter(labels.iter().rev().flat_map(|label| {
+            label
+                .suggestion
+                .as_deref()
+                .map_or_else(Vec::new, |suggestion| {
+                    patch_rows(self.source_code, label.span, suggestion, label.style)
+                })
+        }));
+
+        // an index which was built for some other source code is not used
+        let owned_index;
+        let line_index = match self.line_index {
+            Some(line_index) if line_index.fits(self.source_code) => line_index,
+            _ => {
+                owned_index = LineIndex::new(self.source_code);
+                &owned_index
+            }
+        };
+
+        let mut rows = self.generate_output_lines(line_index, labels);
+        rows.extend(patches);
+
+        for row in &mut rows {
+            if matches!(row.kind, RowKind::Source | RowKind::Context) {
+                row.mark = self
+                    .gutter_marks
+                    .iter()
+                    .find(|mark| Some(mark.line_number) == row.line_number)
+                    .map(|mark| StyledRun::new(mark.text.clone(), mark.style));
+            }
+        }
+
+        let mark_width = rows
+            .iter()
+            .filter_map(|row| row.mark.as_ref())
+            .map(|mark| mark.text.width())
+            .max()
+            .unwrap_or_default();
+
+        // the indent width is one more than the number of digits in the highest line number
+        let line_number_width = rows
+            .iter()
+            .filter_map(|row| row.line_number)
+            .max()
+            .unwrap_or_default()
+            // count digits
+            .checked_ilog10()
+            .unwrap_or_default() // 0 when 0
+            as usize
+            + 1;
+
+        SnippetLayout {
+            source_name: self.source_name,
+            line_number_width,
+            mark_width,
+            rows,
+        }
+    }
+
+    pub fn render<W: std::fmt::Write>(
         &self,
-        mut labels: Vec<Label>,
+        labels: Vec1<Label<'a>>,
         destination: &mut W,
     ) -> Result<(), std::fmt::Error> {
-        sort_labels(labels.as_mut_slice());
-        let output_lines = self.generate_output_lines(labels);
-        self.generate_output(output_lines, destination)
+        let layout = self.layout(labels);
+        if self.symbolic_styles {
+            let mut output = String::new();
+            self.generate_output(layout, &mut output)?;
+            destination.write_str(&ansi_to_markup(&output))
+        } else {
+            self.generate_output(layout, destination)
+        }
+    }
+
+    pub fn render_to_string(&self, labels: Vec1<Label<'a>>) -> String {
+        let mut result = String::new();
+        // UNWRAP: writing to the String should never fail
+        // this is checked by the fuzz testing
+        self.render(labels, &mut result).unwrap();
+        result
     }
 
     fn generate_output_lines(
         &self,
+        line_index: &LineIndex,
         mut labels: Vec<Label<'a>>,
-    ) -> Vec<(usize, Cow<'a, str>, usize)> {
+    ) -> Vec<Row<'a>> {
         let mut multi_count = 0; // active spans which cover multiple lines
 
         let mut last_line: Option<usize> = None; // the last line number we rendered
-        let mut output_lines: Vec<(usize, Cow<'a, str>, usize)> = Vec::new(); // lines we've rendered
+        let mut output_lines: Vec<OutputLine<'a>> = Vec::new(); // lines we've rendered
         let mut context_after = Vec::new(); // the context lines after the last line we rendered
 
         while let Some(label) = labels.pop() {
+            // context before the line is not covered by multi-line labels starting on it
+            let multis_before = multi_count;
+
             // all labels which are on the same line
             let mut line_labels = vec![];
 
@@ -93,10 +291,10 @@ impl<'a> LabelRenderer<'a> {
 
             let line_span: Span<u8>;
             if label.is_multiline_end {
-                line_span = self.line_containing_start_of(label.span);
+                line_span = Self::line_containing_start_of(line_index, label.span);
                 ending_multis.push(label);
             } else {
-                line_span = self.line_containing_start_of(label.span);
+                line_span = Self::line_containing_start_of(line_index, label.span);
                 let is_multiline = label.end() > line_span.end();
                 if is_multiline {
                     multi_count += 1;
@@ -107,15 +305,7 @@ impl<'a> LabelRenderer<'a> {
                 }
             }
 
-            // TODO(PERF): count only since last line
-            // TODO(PERF): allow line number to be supplied with label, so we don't need to count it
-            // TODO(PERF): allow line number printing to be disabled
-            let line_number = self
-                .source_code
-                .slice_until(line_span.start())
-                .bytes()
-                .filter(|c| *c == b'\n')
-                .count();
+            let line_number = line_index.line_number(line_span.start());
 
             // We are going to generate the output like this:
             //  0. context-before
@@ -127,9 +317,9 @@ impl<'a> LabelRenderer<'a> {
 
             // 5. context-after:
             //    first, output any context between this and the previous line
-            for (num, line, multi_count) in context_after.drain(..) {
+            for (num, kind, line, multi_count) in context_after.drain(..) {
                 if num < line_number {
-                    output_lines.push((num, line, multi_count));
+                    output_lines.push((num, kind, line, multi_count));
                     last_line = Some(num);
                 }
             }
@@ -141,7 +331,12 @@ impl<'a> LabelRenderer<'a> {
                     (line_number - last_line).saturating_sub(1),
                 );
                 if line_number > last_line + self.context_lines {
-                    output_lines.push((usize::MAX, Cow::Borrowed("…"), multi_count));
+                    output_lines.push((
+                        usize::MAX,
+                        RowKind::Elision,
+                        vec![StyledRun::new("…", Style::new())],
+                        multi_count,
+                    ));
                 }
             } else {
                 before_context_lines = self.context_lines;
@@ -163,52 +358,58 @@ impl<'a> LabelRenderer<'a> {
                 }
             }
 
+            // labels beyond the maximum are only counted; keep those provided first
+            let mut hidden_labels = 0;
+            if line_labels.len() > self.max_labels_per_line {
+                line_labels.sort_by_key(|l| l.order);
+                hidden_labels = line_labels.len() - self.max_labels_per_line;
+                line_labels.truncate(self.max_labels_per_line);
+                // restore source order
+                sort_labels(line_labels.as_mut_slice());
+                line_labels.reverse();
+            }
+
             // 0. context-before:
             //    get the N lines before the current line
-            let mut context_before = Vec::from_iter(
-                self.source_code
-                    .slice_until(line_span.start())
-                    .split_inclusive('\n')
-                    .rev()
-                    .take(before_context_lines)
-                    .enumerate()
-                    .map(|(i, line)| {
-                        (
-                            line_number - i - 1,
-                            Cow::Borrowed(line.trim_ascii_end()),
-                            multi_count,
-                        )
-                    }),
-            );
-
-            context_before.reverse();
-            output_lines.extend(context_before);
+            let first_before = line_number.saturating_sub(before_context_lines);
+            output_lines.extend((first_before..line_number).filter_map(|num| {
+                let span = line_index.line_span(num)?;
+                Some((
+                    num,
+                    RowKind::Context,
+                    self.context_line(span),
+                    multis_before,
+                ))
+            }));
 
             // 5: context-after
             //    store the N lines after the current line
             let multis_after = multi_count - ending_multis.len();
             context_after.extend(
-                self.source_code
-                    .slice_from(line_span.end())
-                    .split_inclusive('\n')
+                (line_number + 1..)
                     .take(self.context_lines)
-                    .enumerate()
-                    .map(|(i, line)| {
-                        (
-                            line_number + i + 1,
-                            Cow::Borrowed(line.trim_ascii_end()),
-                            multis_after,
-                        )
+                    // the empty 'line' after a trailing newline is not shown
+                    .map_while(|num| {
+                        Some((num, line_index.line_span(num).filter(|s| !s.is_empty())?))
+                    })
+                    .map(|(num, span)| {
+                        (num, RowKind::Context, self.context_line(span), multis_after)
                     }),
             );
 
             // invoke the line-lighter to indicate the portions of the line that the labels are pointing at
             // as well as the indicator line and any messages
-            let LitLine { line, indicator_line, messages } =
-                LineHighlighter::new(self.source_code).highlight_line(line_span, &line_labels);
+            let LitLine { line, indicator_line, messages } = LineHighlighter::new(
+                self.source_code,
+                self.theme,
+                self.highlighter,
+                self.message_order,
+            )
+            .with_message_width(self.message_width)
+            .highlight_line(line_span, &line_labels);
 
             // 1. the line itself
-            output_lines.push((line_number, line.into(), multi_count));
+            output_lines.push((line_number, RowKind::Source, line, multi_count));
 
             // line number can 'never' be usize::MAX (since it must be offset by 1, which would overflow)
             // so we reuse it here to mark augmented lines
@@ -216,118 +417,204 @@ impl<'a> LabelRenderer<'a> {
             // 2. the 'indicator' line:
             //    this contains just box-drawing chars
             if !indicator_line.is_empty() {
-                output_lines.push((usize::MAX, indicator_line.into(), multi_count));
+                output_lines.push((usize::MAX, RowKind::Indicator, indicator_line, multi_count));
             }
 
             // 3. the 'messages' lines:
             //    these are the messages from the labels
             for message in messages {
-                output_lines.push((usize::MAX, message.into(), multi_count));
+                output_lines.push((usize::MAX, RowKind::Message, message, multi_count));
+            }
+
+            if hidden_labels > 0 {
+                output_lines.push((
+                    usize::MAX,
+                    RowKind::Message,
+                    vec![StyledRun::new(
+                        format!("… and {hidden_labels} more"),
+                        self.theme.message,
+                    )],
+                    multi_count,
+                ));
             }
 
             // we also need to render all multi-line labels that end on or before this line
             // TODO: those that end before need to be rendered before the line
+            if self.message_order == MessageOrder::Insertion {
+                ending_multis.sort_by_key(|l| l.order);
+            }
+
             for ending_multi in ending_multis {
                 multi_count -= 1;
-                output_lines.push((usize::MAX, ending_multi.message, multi_count));
+                // as for single-line labels, an unstyled message takes its style from the theme
+                let message_style = if ending_multi.style.is_plain() {
+                    self.theme.message
+                } else {
+                    ending_multi.style
+                };
+
+                for (i, message_line) in wrap_message(&ending_multi.message, self.message_width)
+                    .into_iter()
+                    .enumerate()
+                {
+                    let message = StyledRun::new(message_line.into_owned(), message_style)
+                        .with_url(ending_multi.url.clone());
+
+                    // the first row has a wider ruler ("┡━╸"), so align the rest with it
+                    let runs = if i == 0 {
+                        vec![message]
+                    } else {
+                        vec![StyledRun::new(" ", Style::new()), message]
+                    };
+
+                    output_lines.push((usize::MAX, RowKind::MultilineMessage, runs, multi_count));
+                }
             }
         }
 
         // 5. output any context-after we had stored after the last label
         output_lines.extend(context_after);
-        output_lines
-    }
 
-    fn generate_output<W: std::fmt::Write>(
-        &self,
-        output_lines: Vec<(usize, Cow<str>, usize)>,
-        destination: &mut W,
-    ) -> Result<(), std::fmt::Error> {
         // all line numbers (which are present) should be in order
         debug_assert!(
             output_lines
                 .iter()
-                .filter_map(|(n, _, _)| (*n != usize::MAX).then_some(n))
+                .filter_map(|(n, _, _, _)| (*n != usize::MAX).then_some(n))
                 .is_sorted()
         );
 
-        // the indent width is one more than the number of digits in the highest line number
-        let indent_width = output_lines
-            // find highest line number, which is the last non-MAX one
-            .iter()
-            .rev()
-            .find(|(n, _, _)| *n != usize::MAX)
-            .unwrap()
-            .0
-            // count digits
-            .checked_ilog10()
-            .unwrap_or_default() // 0 when 0
-            as usize
-            + 1;
+        // now that we know the order, we can determine the rulers
+        let mut last_multi_count = 0;
+        Vec::from_iter(
+            output_lines
+                .into_iter()
+                .map(|(ix, kind, runs, multi_count)| {
+                    let (ruler, continuation) = match (last_multi_count, multi_count) {
+                        (0, 0) => ("│ ", "│ "),
+                        (0, _) => ("┢╸", "┃ "),
+                        (_, 0) => ("┡━╸", "│  "),
+                        (x, y) => match x.cmp(&y) {
+                            std::cmp::Ordering::Less => ("┣╸", "┃ "),
+                            std::cmp::Ordering::Equal => ("┃ ", "┃ "),
+                            std::cmp::Ordering::Greater => ("┣━╸", "┃  "),
+                        },
+                    };
+
+                    last_multi_count = multi_count;
+
+                    Row {
+                        kind,
+                        line_number: (ix != usize::MAX).then(|| ix + 1),
+                        multiline_depth: multi_count,
+                        ruler,
+                        continuation,
+                        mark: None,
+                        runs,
+                    }
+                }),
+        )
+    }
 
-        if let Some(source_name) = self.source_name {
+    fn generate_output<W: std::fmt::Write>(
+        &self,
+        layout: SnippetLayout,
+        destination: &mut W,
+    ) -> Result<(), std::fmt::Error> {
+        let line_number_width = layout.line_number_width;
+        let mark_width = layout.mark_width;
+        // the frame and continuation lines are aligned with the rulers,
+        // so they are indented past both the line numbers and the marks
+        let indent_width = match mark_width {
+            0 => line_number_width,
+            _ => line_number_width + 1 + mark_width,
+        };
+
+        let gutter = self.theme.apply(self.theme.gutter);
+
+        if let Some(source_name) = layout.source_name {
             let name_len = source_name.len();
             writeln!(
                 destination,
-                "{:>indent_width$} ┌─{:─<name_len$}─┐",
-                " ", // no line number - this is a supplementary line
-                "",
+                "{}",
+                gutter.style(format_args!(
+                    "{:>indent_width$} ┌─{:─<name_len$}─┐",
+                    " ", // no line number - this is a supplementary line
+                    "",
+                )),
             )?;
             writeln!(
                 destination,
-                "{:>indent_width$} │ {source_name} │",
-                " ", // no line number - this is a supplementary line
+                "{}",
+                gutter.style(format_args!(
+                    "{:>indent_width$} │ {source_name} │",
+                    " ", // no line number - this is a supplementary line
+                )),
             )?;
             writeln!(
                 destination,
-                "{:>indent_width$} ├─{:─<name_len$}─╯",
-                " ", // no line number - this is a supplementary line
-                "",
+                "{}",
+                gutter.style(format_args!(
+                    "{:>indent_width$} ├─{:─<name_len$}─╯",
+                    " ", // no line number - this is a supplementary line
+                    "",
+                )),
             )?;
         } else {
             writeln!(
                 destination,
-                "{:>indent_width$} ┌",
-                " ", // no line number - this is a supplementary line
+                "{}",
+                gutter.style(format_args!(
+                    "{:>indent_width$} ┌",
+                    " ", // no line number - this is a supplementary line
+                )),
             )?;
         }
 
-        let mut last_multi_count = 0;
-        for (ix, line, multi_count) in output_lines {
-            let (ruler, continuation) = match (last_multi_count, multi_count) {
-                (0, 0) => ("│ ", "│ "),
-                (0, _) => ("┢╸", "┃ "),
-                (_, 0) => ("┡━╸", "│  "),
-                (x, y) => match x.cmp(&y) {
-                    std::cmp::Ordering::Less => ("┣╸", "┃ "),
-                    std::cmp::Ordering::Equal => ("┃ ", "┃ "),
-                    std::cmp::Ordering::Greater => ("┣━╸", "┃  "),
-                },
+        for row in layout.rows {
+            let line = format_runs(&row.runs, &self.theme, self.hyperlinks);
+            let line_number = match row.line_number {
+                Some(n) => n.to_string(),
+                None => " ".to_string(), // no line number - this is a supplementary line
             };
 
-            last_multi_count = multi_count;
-
-            let initial_indent = if ix == usize::MAX {
+            let initial_indent = if mark_width == 0 {
                 format!(
-                    "{:>indent_width$} {}",
-                    " ", // no line number - this is a supplementary line
-                    ruler,
-                    indent_width = indent_width
+                    "{}",
+                    gutter.style(format_args!("{line_number:>indent_width$} {}", row.ruler))
                 )
             } else {
+                let (mark, mark_len) = match &row.mark {
+                    Some(mark) => (
+                        format_runs(std::slice::from_ref(mark), &self.theme, false),
+                        mark.text.width(),
+                    ),
+                    None => (String::new(), 0),
+                };
+
                 format!(
-                    "{:>indent_width$} {}",
-                    ix + 1,
-                    ruler,
-                    indent_width = indent_width
+                    "{} {mark}{:pad$}{}",
+                    gutter.style(format_args!("{line_number:>line_number_width$}")),
+                    "",
+                    gutter.style(format_args!(" {}", row.ruler)),
+                    pad = mark_width - mark_len,
                 )
             };
 
+            if matches!(
+                row.kind,
+                RowKind::PatchSeparator
+                    | RowKind::PatchRemoved
+                    | RowKind::PatchAdded
+                    | RowKind::PatchUnderline
+            ) {
+                // patches show the source verbatim, so are never wrapped
+                writeln!(destination, "{initial_indent}{line}")?;
+                continue;
+            }
+
             let subsequent_indent = format!(
-                "{:>indent_width$} {}",
-                " ",
-                continuation,
-                indent_width = indent_width
+                "{}",
+                gutter.style(format_args!("{:>indent_width$} {}", " ", row.continuation))
             );
 
             let wrap_opts = textwrap::Options::new(self.max_width)
@@ -341,9 +628,11 @@ impl<'a> LabelRenderer<'a> {
 
         writeln!(
             destination,
-            "{:>indent_width$} └",
-            " ", // no line number - this is a supplementary line
-            indent_width = indent_width
+            "{}",
+            gutter.style(format_args!(
+                "{:>indent_width$} └",
+                " ", // no line number - this is a supplementary line
+            )),
         )?;
 
         Ok(())
@@ -0,0 +1,122 @@
+use complex_indifference::{Index, Indexable, Span};
+use owo_colors::Style;
+use unicode_width::UnicodeWidthStr;
+
+use crate::layout::{Row, RowKind, StyledRun};
+
+/// Builds the `-`/`+` patch showing the effect of replacing
+/// the given span of the source code with the suggestion.
+///
+/// The span must lie on character boundaries.
+pub fn patch_rows<'a>(
+    source_code: &str,
+    span: Span<u8>,
+    suggestion: &str,
+    style: Style,
+) -> Vec<Row<'a>> {
+    // expand the span out to cover full lines
+    let first_line_start: Index<u8> = source_code
+        .slice_until(span.start())
+        .rfind('\n')
+        .map(|x| x + 1)
+        .unwrap_or(0)
+        .into();
+
+    let last_line_end: Index<u8> = source_code
+        .slice_from(span.end())
+        .find('\n')
+        .map(|x| x + span.end().as_usize())
+        .unwrap_or(source_code.len())
+        .into();
+
+    let first_line_number = source_code
+        .slice_until(first_line_start)
+        .bytes()
+        .filter(|c| *c == b'\n')
+        .count();
+
+    // UNWRAP: the expanded span always contains the original span
+    let before = &source_code[first_line_start.span_until(span.start()).unwrap()];
+    let removed = &source_code[span];
+    let after = &source_code[span.end().span_until(last_line_end).unwrap()];
+
+    let mut result = vec![Row {
+        kind: RowKind::PatchSeparator,
+        line_number: None,
+        multiline_depth: 0,
+        ruler: "│",
+        continuation: "│",
+        mark: None,
+        runs: Vec::new(),
+    }];
+
+    push_lines(
+        &mut result,
+        first_line_number,
+        RowKind::PatchRemoved,
+        [before, removed, after],
+        style,
+    );
+    push_lines(
+        &mut result,
+        first_line_number,
+        RowKind::PatchAdded,
+        [before, suggestion, after],
+        style,
+    );
+    result
+}
+
+/// Splits the concatenation of `[before, changed, after]` into lines,
+/// underlining the portion of each line that comes from `changed`.
+fn push_lines(
+    result: &mut Vec<Row>,
+    first_line_number: usize,
+    kind: RowKind,
+    [before, changed, after]: [&str; 3],
+    style: Style,
+) {
+    let (ruler, marker) = match kind {
+        RowKind::PatchRemoved => ("- ", "-"),
+        _ => ("+ ", "+"),
+    };
+
+    let text = format!("{before}{changed}{after}");
+    let changed_start = before.len();
+    let changed_end = changed_start + changed.len();
+
+    let mut offset = 0;
+    for (i, line) in text.split('\n').enumerate() {
+        let line_start = offset;
+        offset += line.len() + 1;
+
+        let line = line.trim_ascii_end();
+        let start = changed_start.clamp(line_start, line_start + line.len()) - line_start;
+        let end = changed_end.clamp(line_start, line_start + line.len()) - line_start;
+
+        result.push(Row {
+            kind,
+            line_number: Some(first_line_number + i + 1),
+            multiline_depth: 0,
+            ruler,
+            continuation: "│ ",
+            mark: None,
+            runs: vec![StyledRun::new(line.to_string(), Style::new())],
+        });
+
+        if start < end {
+            result.push(Row {
+                kind: RowKind::PatchUnderline,
+                line_number: None,
+                multiline_depth: 0,
+                ruler: "│ ",
+                continuation: "│ ",
+                mark: None,
+                runs: vec![
+                    StyledRun::new(" ".repeat(line[..start].width()), Style::new()),
+                    StyledRun::new(marker.repeat(line[start..end].width()), style),
+                ],
+            });
+        }
+    }
+}
@@ -0,0 +1,366 @@
+//! Themes control the styles used for the parts of a snippet
+//! which are not determined by the labels themselves.
+
+use owo_colors::{AnsiColors, Rgb, Style};
+
+/// The severity of a label, used to pick its style from the [`Theme`]
+/// when the label itself has no style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// How much color the output device supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorLevel {
+    /// No styling at all.
+    None,
+    /// The basic 16 ANSI colors.
+    Ansi16,
+    /// 24-bit color.
+    TrueColor,
+}
+
+impl ColorLevel {
+    /// Determines the color level from the environment.
+    ///
+    /// This respects `NO_COLOR` and `FORCE_COLOR`/`CLICOLOR_FORCE`,
+    /// and otherwise uses `TERM` and `COLORTERM` to guess at
+    /// the capabilities of the terminal.
+    pub fn detect() -> Self {
+        Self::from_env(|name| std::env::var(name).ok())
+    }
+
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        let set = |name| var(name).is_some_and(|v| !v.is_empty() && v != "0");
+
+        // https://no-color.org/
+        if set("NO_COLOR") {
+            return ColorLevel::None;
+        }
+
+        let truecolor = var("COLORTERM").is_some_and(|v| v == "truecolor" || v == "24bit");
+        let term = var("TERM");
+        if set("FORCE_COLOR") || set("CLICOLOR_FORCE") {
+            return if truecolor {
+                ColorLevel::TrueColor
+            } else {
+                ColorLevel::Ansi16
+            };
+        }
+
+        match term.as_deref() {
+            None | Some("") | Some("dumb") => ColorLevel::None,
+            Some(_) if truecolor => ColorLevel::TrueColor,
+            Some(_) => ColorLevel::Ansi16,
+        }
+    }
+}
+
+/// The styles used when rendering a snippet.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    /// The color level that the theme was built for. At [`ColorLevel::None`]
+    /// all styles are suppressed, including those provided by labels.
+    pub level: ColorLevel,
+    /// Line numbers, rulers, and the frame around the snippet.
+    pub gutter: Style,
+    /// The indicator line for labels which have no style of their own.
+    pub underline: Style,
+    /// The message of labels which have no style of their own.
+    pub message: Style,
+    pub info: Style,
+    pub warning: Style,
+    pub error: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::plain()
+    }
+}
+
+impl Theme {
+    /// A theme which applies no styles of its own;
+    /// any styles set on labels are still used.
+    pub const fn plain() -> Self {
+        Theme {
+            level: ColorLevel::TrueColor,
+            gutter: Style::new(),
+            underline: Style::new(),
+            message: Style::new(),
+            info: Style::new(),
+            warning: Style::new(),
+            error: Style::new(),
+        }
+    }
+
+    /// A theme which suppresses all styling.
+    pub const fn no_color() -> Self {
+        Theme { level: ColorLevel::None, ..Self::plain() }
+    }
+
+    /// A theme suited to terminals with a dark background.
+    pub fn dark(level: ColorLevel) -> Self {
+        match level {
+            ColorLevel::None => Self::no_color(),
+            ColorLevel::Ansi16 => Theme {
+                level,
+                gutter: Style::new().bright_black(),
+                underline: Style::new().bright_white(),
+                message: Style::new().bright_white(),
+                info: Style::new().bright_blue(),
+                warning: Style::new().bright_yellow(),
+                error: Style::new().bright_red(),
+            },
+            ColorLevel::TrueColor => Theme {
+                level,
+                gutter: Style::new().color(Rgb(0x88, 0x88, 0x88)),
+                underline: Style::new().color(Rgb(0xDD, 0xDD, 0xDD)),
+                message: Style::new().color(Rgb(0xDD, 0xDD, 0xDD)),
+                info: Style::new().color(Rgb(0x77, 0xAA, 0xDD)),
+                warning: Style::new().color(Rgb(0xEE, 0xDD, 0x88)),
+                error: Style::new().color(Rgb(0xEE, 0x66, 0x77)),
+            },
+        }
+    }
+
+    /// A theme suited to terminals with a light background.
+    pub fn light(level: ColorLevel) -> Self {
+        match level {
+            ColorLevel::None => Self::no_color(),
+            ColorLevel::Ansi16 => Theme {
+                level,
+                gutter: Style::new().black(),
+                underline: Style::new().black(),
+                message: Style::new().black(),
+                info: Style::new().blue(),
+                warning: Style::new().yellow(),
+                error: Style::new().red(),
+            },
+            ColorLevel::TrueColor => Theme {
+                level,
+                gutter: Style::new().color(Rgb(0x77, 0x77, 0x77)),
+                underline: Style::new().color(Rgb(0x22, 0x22, 0x22)),
+                message: Style::new().color(Rgb(0x22, 0x22, 0x22)),
+                info: Style::new().color(Rgb(0x00, 0x44, 0x88)),
+                warning: Style::new().color(Rgb(0x99, 0x77, 0x00)),
+                error: Style::new().color(Rgb(0xBB, 0x22, 0x22)),
+            },
+        }
+    }
+
+    /// The dark theme at the color level detected from the environment.
+    ///
+    /// To override detection, pass a specific level to [`Theme::dark`]
+    /// or [`Theme::light`] instead.
+    pub fn detect() -> Self {
+        Self::dark(ColorLevel::detect())
+    }
+
+    pub(crate) fn severity(&self, severity: Severity) -> Style {
+        match severity {
+            Severity::Info => self.info,
+            Severity::Warning => self.warning,
+            Severity::Error => self.error,
+        }
+    }
+
+    /// Applies the color level to a style.
+    pub(crate) fn apply(&self, style: Style) -> Style {
+        match self.level {
+            ColorLevel::None => Style::new(),
+            ColorLevel::Ansi16 => to_ansi16(style),
+            ColorLevel::TrueColor => style,
+        }
+    }
+}
+
+/// The basic ANSI colors, in the order of their codes,
+/// along with their (typical) values.
+const ANSI16: [(AnsiColors, (u8, u8, u8)); 16] = [
+    (AnsiColors::Black, (0x00, 0x00, 0x00)),
+    (AnsiColors::Red, (0xCD, 0x00, 0x00)),
+    (AnsiColors::Green, (0x00, 0xCD, 0x00)),
+    (AnsiColors::Yellow, (0xCD, 0xCD, 0x00)),
+    (AnsiColors::Blue, (0x00, 0x00, 0xEE)),
+    (AnsiColors::Magenta, (0xCD, 0x00, 0xCD)),
+    (AnsiColors::Cyan, (0x00, 0xCD, 0xCD)),
+    (AnsiColors::White, (0xE5, 0xE5, 0xE5)),
+    (AnsiColors::BrightBlack, (0x7F, 0x7F, 0x7F)),
+    (AnsiColors::BrightRed, (0xFF, 0x00, 0x00)),
+    (AnsiColors::BrightGreen, (0x00, 0xFF, 0x00)),
+    (AnsiColors::BrightYellow, (0xFF, 0xFF, 0x00)),
+    (AnsiColors::BrightBlue, (0x5C, 0x5C, 0xFF)),
+    (AnsiColors::BrightMagenta, (0xFF, 0x00, 0xFF)),
+    (AnsiColors::BrightCyan, (0x00, 0xFF, 0xFF)),
+    (AnsiColors::BrightWhite, (0xFF, 0xFF, 0xFF)),
+];
+
+/// The basic ANSI color nearest to the given color.
+fn nearest_ansi16((r, g, b): (u8, u8, u8)) -> AnsiColors {
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2);
+        d(r, r2) + d(g, g2) + d(b, b2)
+    };
+
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map_or(AnsiColors::Default, |(color, _)| *color)
+}
+
+/// The value of a color from the 256-color palette.
+fn xterm_rgb(code: u8) -> (u8, u8, u8) {
+    match code {
+        0..16 => ANSI16[usize::from(code)].1,
+        16..232 => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let n = code - 16;
+            (level(n / 36), level(n / 6 % 6), level(n % 6))
+        }
+        232.. => {
+            let gray = 8 + (code - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// Replaces any colors in the style which are not among the basic ANSI colors
+/// (such as those set by labels) with the nearest of those.
+fn to_ansi16(style: Style) -> Style {
+    // the fields of a style are private, so read them back from its escape sequence
+    let prefix = style.prefix_formatter().to_string();
+    let params = prefix
+        .strip_prefix("\x1b[")
+        .and_then(|params| params.strip_suffix('m'))
+        .unwrap_or_default();
+
+    let codes = Vec::from_iter(params.split(';').filter_map(|code| code.parse::<u8>().ok()));
+    if !codes.iter().any(|code| matches!(code, 38 | 48)) {
+        return style;
+    }
+
+    let mut result = Style::new();
+    let mut codes = codes.into_iter();
+    while let Some(code) = codes.next() {
+        result = match code {
+            1 => result.bold(),
+            2 => result.dimmed(),
+            3 => result.italic(),
+            4 => result.underline(),
+            5 => result.blink(),
+            6 => result.blink_fast(),
+            7 => result.reversed(),
+            8 => result.hidden(),
+            9 => result.strikethrough(),
+            30..=37 => result.color(ANSI16[usize::from(code - 30)].0),
+            90..=97 => result.color(ANSI16[usize::from(code - 90 + 8)].0),
+            40..=47 => result.on_color(ANSI16[usize::from(code - 40)].0),
+            100..=107 => result.on_color(ANSI16[usize::from(code - 100 + 8)].0),
+            38 | 48 => {
+                let rgb = match codes.next() {
+                    Some(5) => codes.next().map(xterm_rgb),
+                    Some(2) => match (codes.next(), codes.next(), codes.next()) {
+                        (Some(r), Some(g), Some(b)) => Some((r, g, b)),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+
+                match rgb.map(nearest_ansi16) {
+                    Some(color) if code == 38 => result.color(color),
+                    Some(color) => result.on_color(color),
+                    None => result,
+                }
+            }
+            _ => result,
+        };
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use owo_colors::{AnsiColors, Rgb, Style, XtermColors};
+
+    use super::{ColorLevel, Theme};
+
+    fn detect(vars: &[(&str, &str)]) -> ColorLevel {
+        ColorLevel::from_env(|name| {
+            vars.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.to_string())
+        })
+    }
+
+    #[test]
+    fn detect_levels() {
+        assert_eq!(detect(&[]), ColorLevel::None);
+        assert_eq!(detect(&[("TERM", "dumb")]), ColorLevel::None);
+        assert_eq!(detect(&[("TERM", "xterm")]), ColorLevel::Ansi16);
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("COLORTERM", "truecolor")]),
+            ColorLevel::TrueColor
+        );
+    }
+
+    #[test]
+    fn detect_no_color() {
+        assert_eq!(
+            detect(&[("TERM", "xterm"), ("NO_COLOR", "1")]),
+            ColorLevel::None
+        );
+        // an empty NO_COLOR is ignored
+        assert_eq!(
+            detect(&[("TERM", "xterm"), ("NO_COLOR", "")]),
+            ColorLevel::Ansi16
+        );
+    }
+
+    #[test]
+    fn detect_forced() {
+        assert_eq!(detect(&[("FORCE_COLOR", "1")]), ColorLevel::Ansi16);
+        assert_eq!(detect(&[("FORCE_COLOR", "0")]), ColorLevel::None);
+        assert_eq!(
+            detect(&[("NO_COLOR", "1"), ("FORCE_COLOR", "1")]),
+            ColorLevel::None
+        );
+    }
+
+    #[test]
+    fn ansi16_downgrades_colors() {
+        let theme = Theme::dark(ColorLevel::Ansi16);
+        assert_eq!(
+            theme.apply(
+                Style::new()
+                    .color(Rgb(0xEE, 0x11, 0x22))
+                    .on_color(Rgb(0, 0, 0x99))
+            ),
+            Style::new()
+                .color(AnsiColors::BrightRed)
+                .on_color(AnsiColors::Blue)
+        );
+        assert_eq!(
+            theme.apply(
+                Style::new()
+                    .bold()
+                    .underline()
+                    .color(XtermColors::from(244))
+            ),
+            Style::new()
+                .color(AnsiColors::BrightBlack)
+                .bold()
+                .underline()
+        );
+
+        // basic colors are left alone, as is everything at true color
+        let basic = Style::new().green().on_bright_white().italic();
+        assert_eq!(theme.apply(basic), basic);
+        let rgb = Style::new().color(Rgb(0xEE, 0x11, 0x22));
+        assert_eq!(Theme::dark(ColorLevel::TrueColor).apply(rgb), rgb);
+    }
+}
@@ -111,7 +111,7 @@ pub fn codespan_example() {
     7 ┃     _ _ => [38;2;255;165;0mnum[0m
       ┃            [38;2;255;165;0m├─┘[0m
       ┃ [38;2;255;165;0m           └╴expected `String`, found `Nat`[0m
-      ┡━╸`case` clauses have incompatible types
+      ┡━╸[38;2;255;0;0m`case` clauses have incompatible types[0m
       └
     "#);
 }
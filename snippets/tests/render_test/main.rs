@@ -1,7 +1,4 @@
-use std::{
-    cmp::{max, min},
-    ops::Deref,
-};
+use std::ops::Deref;
 
 use bolero::{TypeGenerator, check};
 use complex_indifference::Span;
@@ -9,8 +6,7 @@ use snippets::Label;
 
 #[derive(Debug, TypeGenerator)]
 struct L {
-    start: usize,
-    end: usize,
+    span: Span<u8>,
     label: String,
 }
 
@@ -21,17 +17,7 @@ fn main() {
             let style = owo_colors::Style::new();
             let labels: Vec<_> = labels
                 .iter()
-                .map(|l| {
-                    Label::new(
-                        Span::try_from_indices(
-                            min(l.start, l.end).into(),
-                            max(l.start, l.end).into(),
-                        )
-                        .unwrap(),
-                        l.label.deref().into(),
-                        style,
-                    )
-                })
+                .map(|l| Label::new(l.span, l.label.deref().into(), style))
                 .collect();
 
             if let Ok(labels) = labels.try_into() {